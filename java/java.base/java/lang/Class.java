@@ -1,5 +1,21 @@
 package java.lang;
 
+import sun.reflect.ConstantPool;
+
 public class Class<T> {
+    // Set by the VM: address of the NormalKlass this object mirrors.
+    private transient long native_klass_ptr;
+
     private Class() {}
+
+    // Binary name, e.g. "java.lang.String".
+    public native String getName();
+
+    // RuntimeVisibleAnnotations of the class, as found in the class file.
+    native byte[] getRawAnnotations();
+
+    native byte[] getRawTypeAnnotations();
+
+    // The class's constant pool, for decoding the raw annotation bytes.
+    native ConstantPool getConstantPool();
 }
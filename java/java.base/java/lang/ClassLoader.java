@@ -39,8 +39,21 @@ public abstract class ClassLoader {
         return defineClass1(name, b, off, len);
     }
 
+    public static ClassLoader getSystemClassLoader() {
+        return getSystemClassLoader0();
+    }
+
     protected native long newNativeCLD();
     protected native Class<?> defineClass1(String name, byte[] b, int off, int len);
     protected native Class<?> findLoadedClass(String name);
     protected native Class<?> findBootstrapClass(String name);
+    private static native ClassLoader getSystemClassLoader0();
+
+    // The system loader. The VM creates its only instance directly and fills in
+    // native_cld_ptr, so this constructor is never run.
+    static final class AppClassLoader extends ClassLoader {
+        private AppClassLoader() {
+            super(null);
+        }
+    }
 }
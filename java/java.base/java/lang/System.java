@@ -0,0 +1,21 @@
+package java.lang;
+
+public final class System {
+    private System() {}
+
+    public static void exit(int status) {
+        exit0(status);
+    }
+
+    public static String getProperty(String key) {
+        return getProperty0(key, null);
+    }
+
+    public static String getProperty(String key, String def) {
+        return getProperty0(key, def);
+    }
+
+    private static native void exit0(int status);
+
+    private static native String getProperty0(String key, String def);
+}
@@ -0,0 +1,73 @@
+package java.lang;
+
+public class Throwable {
+    private String detailMessage;
+
+    // cause == this means "not yet initialized", as in the reference library.
+    private Throwable cause = this;
+
+    // Filled in by the VM from the Java stack at construction time.
+    private StackTraceElement[] stackTrace;
+
+    public Throwable() {
+        fillInStackTrace();
+    }
+
+    public Throwable(String message) {
+        fillInStackTrace();
+        detailMessage = message;
+    }
+
+    public Throwable(String message, Throwable cause) {
+        fillInStackTrace();
+        detailMessage = message;
+        this.cause = cause;
+    }
+
+    public Throwable(Throwable cause) {
+        fillInStackTrace();
+        detailMessage = (cause == null ? null : cause.toString());
+        this.cause = cause;
+    }
+
+    public String getMessage() {
+        return detailMessage;
+    }
+
+    public String getLocalizedMessage() {
+        return getMessage();
+    }
+
+    public synchronized Throwable getCause() {
+        return (cause == this ? null : cause);
+    }
+
+    public synchronized Throwable initCause(Throwable cause) {
+        if (this.cause != this)
+            throw new IllegalStateException("Can't overwrite cause");
+        if (cause == this)
+            throw new IllegalArgumentException("Self-causation not permitted");
+        this.cause = cause;
+        return this;
+    }
+
+    public String toString() {
+        String s = getClass().getName();
+        String message = getLocalizedMessage();
+        return (message != null) ? (s + ": " + message) : s;
+    }
+
+    public synchronized Throwable fillInStackTrace() {
+        return fillInStackTrace(0);
+    }
+
+    public StackTraceElement[] getStackTrace() {
+        return stackTrace.clone();
+    }
+
+    // Writes the HotSpot-style report, including "Caused by:" chains, to the
+    // process's standard error.
+    public native void printStackTrace();
+
+    private native Throwable fillInStackTrace(int dummy);
+}
@@ -0,0 +1,18 @@
+package java.lang.invoke;
+
+public abstract class CallSite {
+    // Read by the VM each time the linked invokedynamic executes.
+    MethodHandle target;
+
+    CallSite(MethodHandle target) {
+        this.target = target;
+    }
+
+    public abstract MethodHandle getTarget();
+
+    public abstract void setTarget(MethodHandle newTarget);
+
+    public MethodType type() {
+        return target.type();
+    }
+}
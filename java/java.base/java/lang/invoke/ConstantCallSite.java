@@ -0,0 +1,17 @@
+package java.lang.invoke;
+
+public class ConstantCallSite extends CallSite {
+    public ConstantCallSite(MethodHandle target) {
+        super(target);
+    }
+
+    @Override
+    public final MethodHandle getTarget() {
+        return target;
+    }
+
+    @Override
+    public final void setTarget(MethodHandle ignore) {
+        throw new UnsupportedOperationException();
+    }
+}
@@ -0,0 +1,17 @@
+package java.lang.invoke;
+
+// A method handle constant: a field access or a method call, by reference kind.
+final class DirectMethodHandle extends MethodHandle {
+    // Set by the VM: JVMS 5.4.3.5 reference kind, the class declaring the
+    // member and the address of its Method or Field.
+    private final int refKind;
+    private final Class<?> declaringClass;
+    private final long native_member_ptr;
+
+    private DirectMethodHandle(MethodType type, int refKind, Class<?> declaringClass, long member) {
+        super(type);
+        this.refKind = refKind;
+        this.declaringClass = declaringClass;
+        this.native_member_ptr = member;
+    }
+}
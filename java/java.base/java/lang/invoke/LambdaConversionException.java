@@ -0,0 +1,11 @@
+package java.lang.invoke;
+
+public class LambdaConversionException extends Exception {
+    public LambdaConversionException() {
+        super();
+    }
+
+    public LambdaConversionException(String message) {
+        super(message);
+    }
+}
@@ -0,0 +1,27 @@
+package java.lang.invoke;
+
+public final class LambdaMetafactory {
+    private LambdaMetafactory() {}
+
+    // Bootstrap method of the call sites javac emits for lambda expressions
+    // and method references.  Conversions between the interface method and
+    // the implementation are limited to passing references unchanged.
+    public static CallSite metafactory(
+            MethodHandles.Lookup caller,
+            String invokedName,
+            MethodType invokedType,
+            MethodType samMethodType,
+            MethodHandle implMethod,
+            MethodType instantiatedMethodType) {
+        return new ConstantCallSite(spinInnerClass(caller, invokedName, invokedType, samMethodType, implMethod));
+    }
+
+    // Defines a class implementing the functional interface and returns a
+    // handle of type invokedType on its constructor.
+    private static native MethodHandle spinInnerClass(
+            MethodHandles.Lookup caller,
+            String samName,
+            MethodType invokedType,
+            MethodType samMethodType,
+            MethodHandle implMethod);
+}
@@ -0,0 +1,19 @@
+package java.lang.invoke;
+
+public abstract class MethodHandle {
+    private final MethodType type;
+
+    MethodHandle(MethodType type) {
+        this.type = type;
+    }
+
+    public MethodType type() {
+        return type;
+    }
+
+    // Signature polymorphic: the VM links each call by the descriptor of the
+    // call site, not by the one declared here.
+    public final native Object invokeExact(Object... args) throws Throwable;
+
+    public final native Object invoke(Object... args) throws Throwable;
+}
@@ -0,0 +1,18 @@
+package java.lang.invoke;
+
+public class MethodHandles {
+    private MethodHandles() {}
+
+    public static final class Lookup {
+        // Set by the VM: the class whose call site is being linked.
+        private final Class<?> lookupClass;
+
+        private Lookup(Class<?> lookupClass) {
+            this.lookupClass = lookupClass;
+        }
+
+        public Class<?> lookupClass() {
+            return lookupClass;
+        }
+    }
+}
@@ -0,0 +1,14 @@
+package java.lang.invoke;
+
+public final class MethodType {
+    // Set by the VM: the method descriptor, e.g. "(II)I".
+    private final String descriptor;
+
+    private MethodType(String descriptor) {
+        this.descriptor = descriptor;
+    }
+
+    public String toMethodDescriptorString() {
+        return descriptor;
+    }
+}
@@ -0,0 +1,17 @@
+package java.lang.invoke;
+
+public class MutableCallSite extends CallSite {
+    public MutableCallSite(MethodHandle target) {
+        super(target);
+    }
+
+    @Override
+    public final MethodHandle getTarget() {
+        return target;
+    }
+
+    @Override
+    public void setTarget(MethodHandle newTarget) {
+        this.target = newTarget;
+    }
+}
@@ -0,0 +1,21 @@
+package sun.reflect;
+
+// Read access to a class's constant pool for the annotation parser.
+public class ConstantPool {
+    // The java.lang.Class whose pool this is; set by Class.getConstantPool.
+    private Object constantPoolOop;
+
+    public int getSize() { return getSize0(constantPoolOop); }
+    public int getIntAt(int index) { return getIntAt0(constantPoolOop, index); }
+    public long getLongAt(int index) { return getLongAt0(constantPoolOop, index); }
+    public float getFloatAt(int index) { return getFloatAt0(constantPoolOop, index); }
+    public double getDoubleAt(int index) { return getDoubleAt0(constantPoolOop, index); }
+    public String getUTF8At(int index) { return getUTF8At0(constantPoolOop, index); }
+
+    private native int getSize0(Object constantPoolOop);
+    private native int getIntAt0(Object constantPoolOop, int index);
+    private native long getLongAt0(Object constantPoolOop, int index);
+    private native float getFloatAt0(Object constantPoolOop, int index);
+    private native double getDoubleAt0(Object constantPoolOop, int index);
+    private native String getUTF8At0(Object constantPoolOop, int index);
+}
@@ -0,0 +1,62 @@
+use std::{error::Error, fmt};
+
+use crate::assembler::build_error::BuildError;
+
+/// What is wrong with a line of assembly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsmErrorKind {
+    UnknownDirective(String),
+    UnknownInstruction(String),
+    /// An operand that is missing, extra or cannot be parsed.
+    InvalidOperand(String),
+    /// A directive that needs `.class` or `.interface` before it.
+    MissingClass,
+    /// A second `.class` or `.interface`.
+    DuplicateClass,
+    UnterminatedString,
+    /// A `.method` without `.end method`.
+    UnterminatedMethod,
+    /// A `tableswitch` or `lookupswitch` without its `default` line.
+    UnterminatedSwitch,
+    UndefinedLabel(String),
+    DuplicateLabel(String),
+    /// Code in an `abstract` or `native` method.
+    UnexpectedCode,
+    Build(BuildError),
+}
+
+/// `kind` at line `line`, counting from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub kind: AsmErrorKind,
+}
+
+pub type AsmResult<T> = Result<T, AsmError>;
+
+impl fmt::Display for AsmErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownDirective(name) => write!(f, "unknown directive {name}"),
+            Self::UnknownInstruction(name) => write!(f, "unknown instruction {name}"),
+            Self::InvalidOperand(operand) => write!(f, "invalid operand {operand:?}"),
+            Self::MissingClass => f.write_str("missing .class directive"),
+            Self::DuplicateClass => f.write_str("more than one .class directive"),
+            Self::UnterminatedString => f.write_str("unterminated string"),
+            Self::UnterminatedMethod => f.write_str("missing .end method"),
+            Self::UnterminatedSwitch => f.write_str("switch without a default label"),
+            Self::UndefinedLabel(name) => write!(f, "label {name} is not defined"),
+            Self::DuplicateLabel(name) => write!(f, "label {name} is defined twice"),
+            Self::UnexpectedCode => f.write_str("code in an abstract or native method"),
+            Self::Build(err) => err.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+impl Error for AsmError {}
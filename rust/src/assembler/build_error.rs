@@ -0,0 +1,57 @@
+use std::{error::Error, fmt};
+
+use crate::class_parser::write_error::WriteError;
+
+/// Why `ClassBuilder` cannot produce a class file.  Offsets are bytecode
+/// offsets in the method being built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// More than 65535 constant-pool slots.
+    TooManyConstants,
+    /// A field or method descriptor the builder cannot parse.
+    InvalidDescriptor(String),
+    /// A branch or exception range uses a label that was never bound.
+    UnboundLabel,
+    /// A branch at `pc` whose target is out of reach of a 16-bit offset.
+    BranchTooFar { pc: usize },
+    /// An instruction at `pc` pops more than the stack holds.
+    StackUnderflow { pc: usize },
+    /// Two paths reach `pc` with different stack depths.
+    InconsistentStack { pc: usize, depths: (u32, u32) },
+    /// Execution can run past the last instruction.
+    FallsOffEnd,
+    /// Empty code, or code longer than 65535 bytes.
+    InvalidCodeLength(usize),
+    /// More operand-stack or local-variable slots than a `u2` can count.
+    TooManySlots,
+    Write(WriteError),
+}
+
+pub type BuildResult<T> = Result<T, BuildError>;
+
+impl From<WriteError> for BuildError {
+    fn from(err: WriteError) -> Self {
+        Self::Write(err)
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyConstants => f.write_str("constant pool overflows 65535 entries"),
+            Self::InvalidDescriptor(desc) => write!(f, "illegal descriptor {desc:?}"),
+            Self::UnboundLabel => f.write_str("label used but never bound"),
+            Self::BranchTooFar { pc } => write!(f, "branch at {pc} is out of reach of its target"),
+            Self::StackUnderflow { pc } => write!(f, "operand stack underflow at {pc}"),
+            Self::InconsistentStack { pc, depths: (x, y) } => {
+                write!(f, "stack depth at {pc} is {x} on one path and {y} on another")
+            }
+            Self::FallsOffEnd => f.write_str("execution falls off the end of the code"),
+            Self::InvalidCodeLength(len) => write!(f, "invalid code length {len}"),
+            Self::TooManySlots => f.write_str("more than 65535 stack or local variable slots"),
+            Self::Write(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for BuildError {}
@@ -0,0 +1,181 @@
+use crate::{
+    assembler::{
+        build_error::{BuildError, BuildResult},
+        code_builder::CodeBuilder,
+        constant_pool::ConstantPool,
+    },
+    class_parser::{
+        attr_info::{AttrInfo, BootstrapMethodInfo},
+        class_file::ClassFile,
+        field_info::FieldInfo,
+        method_info::MethodInfo,
+    },
+    oops::acc_flags::AccFlags,
+};
+
+/// Assembles a class file member by member:
+///
+/// ```ignore
+/// let mut class = ClassBuilder::new("Max");
+/// let mut code = class.method(0x0009, "max", "(II)I");
+/// let second = code.label();
+/// code.op(0x1a).op(0x1b).branch(0xa2, second); // iload_0; iload_1; if_icmpge
+/// code.op(0x1b).op(0xac).bind(second); //          iload_1; ireturn
+/// code.op(0x1a).op(0xac); //                       iload_0; ireturn
+/// code.finish()?;
+/// let bytes = class.to_bytes()?;
+/// ```
+pub struct ClassBuilder {
+    pub(super) cp: ConstantPool,
+    major_version: u16,
+    acc_flags: u16,
+    this_class: u16,
+    super_class: u16,
+    interfaces: Vec<u16>,
+    fields: Vec<FieldInfo>,
+    pub(super) methods: Vec<MethodInfo>,
+    attrs: Vec<AttrInfo>,
+    bootstrap_methods: Vec<BootstrapMethodInfo>,
+}
+
+/// Version 49 is the last verified by type inference, so code with branches
+/// needs no `StackMapTable`, which the builder does not compute.
+const DEFAULT_MAJOR: u16 = 49;
+
+impl ClassBuilder {
+    /// A public class `name` extending `java/lang/Object`.
+    pub fn new(name: &str) -> Self {
+        let mut cp = ConstantPool::default();
+        let this_class = cp.class(name);
+        let super_class = cp.class("java/lang/Object");
+
+        Self {
+            cp,
+            major_version: DEFAULT_MAJOR,
+            acc_flags: (AccFlags::ACC_PUBLIC | AccFlags::ACC_SUPER).bits(),
+            this_class,
+            super_class,
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            attrs: Vec::new(),
+            bootstrap_methods: Vec::new(),
+        }
+    }
+
+    /// Emit class-file version `major` instead of 49.  From version 50 on,
+    /// methods with branches need a `StackMapTable` to pass verification.
+    pub fn version(&mut self, major: u16) -> &mut Self {
+        self.major_version = major;
+        self
+    }
+
+    /// Use `flags` as the class access flags instead of `ACC_PUBLIC | ACC_SUPER`.
+    pub fn flags(&mut self, flags: u16) -> &mut Self {
+        self.acc_flags = flags;
+        self
+    }
+
+    /// Make `name` the superclass instead of `java/lang/Object`.
+    pub fn extend(&mut self, name: &str) -> &mut Self {
+        self.super_class = self.cp.class(name);
+        self
+    }
+
+    pub fn implement(&mut self, name: &str) -> &mut Self {
+        let interface = self.cp.class(name);
+        self.interfaces.push(interface);
+        self
+    }
+
+    pub fn constant_pool(&mut self) -> &mut ConstantPool {
+        &mut self.cp
+    }
+
+    pub fn source_file(&mut self, name: &str) -> &mut Self {
+        self.cp.utf8("SourceFile");
+        let sourcefile_idx = self.cp.utf8(name);
+        self.attrs.push(AttrInfo::SourceFile { sourcefile_idx });
+        self
+    }
+
+    /// Add `attr` to the class attributes; its name is added to the
+    /// constant pool, the constants it refers to are not.
+    pub fn attribute(&mut self, attr: AttrInfo) -> &mut Self {
+        self.cp.utf8(attr.name());
+        self.attrs.push(attr);
+        self
+    }
+
+    /// Add an entry to the `BootstrapMethods` attribute and return its index,
+    /// for `ConstantPool::dynamic` and `ConstantPool::invoke_dynamic`.
+    /// `handle` and `args` are constant-pool indices.
+    pub fn bootstrap_method(&mut self, handle: u16, args: &[u16]) -> u16 {
+        self.cp.utf8("BootstrapMethods");
+        self.bootstrap_methods.push(BootstrapMethodInfo {
+            bootstrap_method_ref: handle,
+            bootstrap_arguments: args.to_vec(),
+        });
+        (self.bootstrap_methods.len() - 1) as u16
+    }
+
+    pub fn field(&mut self, flags: u16, name: &str, desc: &str) -> &mut Self {
+        let name_idx = self.cp.utf8(name);
+        let desc_idx = self.cp.utf8(desc);
+        self.fields.push(FieldInfo {
+            acc_flags: flags,
+            name_idx,
+            desc_idx,
+            attrs: Vec::new(),
+            raw_attrs: Vec::new(),
+        });
+        self
+    }
+
+    /// A method without code, as abstract and native methods are.
+    pub fn abstract_method(&mut self, flags: u16, name: &str, desc: &str) -> &mut Self {
+        let name_idx = self.cp.utf8(name);
+        let desc_idx = self.cp.utf8(desc);
+        self.methods.push(MethodInfo {
+            acc_flags: flags,
+            name_idx,
+            desc_idx,
+            attrs: Vec::new(),
+            raw_attrs: Vec::new(),
+        });
+        self
+    }
+
+    /// Start a method with code; `CodeBuilder::finish` adds it to the class.
+    pub fn method(&mut self, flags: u16, name: &str, desc: &str) -> CodeBuilder<'_> {
+        CodeBuilder::new(self, flags, name, desc)
+    }
+
+    pub fn build(mut self) -> BuildResult<ClassFile> {
+        if self.cp.overflowed() {
+            return Err(BuildError::TooManyConstants);
+        }
+        if !self.bootstrap_methods.is_empty() {
+            self.attrs.push(AttrInfo::BootstrapMethods(self.bootstrap_methods));
+        }
+
+        Ok(ClassFile {
+            minor_version: 0,
+            major_version: self.major_version,
+            this_class: self.this_class,
+            super_index: self.super_class,
+            acc_flags: self.acc_flags,
+            constant_pool: self.cp.into_entries(),
+            interfaces: self.interfaces,
+            fields: self.fields,
+            methods: self.methods,
+            attrs: self.attrs,
+            raw_attrs: Vec::new(),
+        })
+    }
+
+    /// The class file bytes, ready for `ClassLoaderData::define_class`.
+    pub fn to_bytes(self) -> BuildResult<Vec<u8>> {
+        Ok(self.build()?.to_bytes()?)
+    }
+}
@@ -0,0 +1,744 @@
+use std::collections::HashMap;
+
+use crate::{
+    assembler::{
+        build_error::{BuildError, BuildResult},
+        class_builder::ClassBuilder,
+        constant_pool::ConstantPool,
+    },
+    class_parser::{
+        attr_info::{AttrInfo, CodeAttrInfo, ExceptionTableEntryInfo},
+        method_info::MethodInfo,
+        stack_map_info::StackMapFrameInfo,
+    },
+    oops::acc_flags::AccFlags,
+};
+
+/// A position in the code, usable before it is bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Label(usize);
+
+/// Where control goes after an instruction.
+enum Flow {
+    Next,
+    Goto(Label),
+    /// Conditional branch: the target or the next instruction.
+    Branch(Label),
+    Switch(Vec<Label>),
+    /// `jsr`: the subroutine, with the return address pushed, and, once it
+    /// returns, the next instruction.
+    Jsr(Label),
+    /// Returns, `athrow` and `ret`.
+    Stop,
+}
+
+struct Insn {
+    pc: usize,
+    /// Operand-stack slots popped and pushed.
+    pops: u32,
+    pushes: u32,
+    flow: Flow,
+}
+
+/// A branch offset to fill in once `label` is bound.
+struct Fixup {
+    /// Offset of the instruction the branch is relative to.
+    pc: usize,
+    /// Where the offset goes.
+    at: usize,
+    label: Label,
+    /// `u4` offset, as in switches, instead of `u2`.
+    wide: bool,
+}
+
+struct Handler {
+    start: Label,
+    end: Label,
+    handler: Label,
+    catch_type: u16,
+}
+
+/// Operand-stack slots a no-operand instruction pops and pushes, and
+/// whether it ends the flow.  `None` for instructions with operands.
+pub(super) fn simple_effect(opcode: u8) -> Option<(u32, u32, bool)> {
+    let effect = match opcode {
+        0x00 => (0, 0),                         // nop
+        0x01..=0x08 | 0x0b..=0x0d => (0, 1),    // aconst_null, iconst_<i>, fconst_<f>
+        0x09 | 0x0a | 0x0e | 0x0f => (0, 2),    // lconst_<l>, dconst_<d>
+        0x1a..=0x1d | 0x22..=0x25 | 0x2a..=0x2d => (0, 1), // iload_<n>, fload_<n>, aload_<n>
+        0x1e..=0x21 | 0x26..=0x29 => (0, 2),    // lload_<n>, dload_<n>
+        0x2f | 0x31 => (2, 2),                  // laload, daload
+        0x2e..=0x35 => (2, 1),                  // other array loads
+        0x3b..=0x3e | 0x43..=0x46 | 0x4b..=0x4e => (1, 0), // istore_<n>, fstore_<n>, astore_<n>
+        0x3f..=0x42 | 0x47..=0x4a => (2, 0),    // lstore_<n>, dstore_<n>
+        0x50 | 0x52 => (4, 0),                  // lastore, dastore
+        0x4f..=0x56 => (3, 0),                  // other array stores
+        0x57 => (1, 0),                         // pop
+        0x58 => (2, 0),                         // pop2
+        0x59 => (1, 2),                         // dup
+        0x5a => (2, 3),                         // dup_x1
+        0x5b => (3, 4),                         // dup_x2
+        0x5c => (2, 4),                         // dup2
+        0x5d => (3, 5),                         // dup2_x1
+        0x5e => (4, 6),                         // dup2_x2
+        0x5f => (2, 2),                         // swap
+        // iadd ... drem: int, long, float and double in turn
+        0x60..=0x73 => match (opcode - 0x60) % 4 {
+            1 | 3 => (4, 2),
+            _ => (2, 1),
+        },
+        0x74 | 0x76 => (1, 1),                  // ineg, fneg
+        0x75 | 0x77 => (2, 2),                  // lneg, dneg
+        0x78 | 0x7a | 0x7c => (2, 1),           // ishl, ishr, iushr
+        0x79 | 0x7b | 0x7d => (3, 2),           // lshl, lshr, lushr
+        0x7e | 0x80 | 0x82 => (2, 1),           // iand, ior, ixor
+        0x7f | 0x81 | 0x83 => (4, 2),           // land, lor, lxor
+        0x85 | 0x87 | 0x8c | 0x8d => (1, 2),    // i2l, i2d, f2l, f2d
+        0x86 | 0x8b | 0x91..=0x93 => (1, 1),    // i2f, f2i, i2b, i2c, i2s
+        0x88 | 0x89 | 0x8e | 0x90 => (2, 1),    // l2i, l2f, d2i, d2f
+        0x8a | 0x8f => (2, 2),                  // l2d, d2l
+        0x94 => (4, 1),                         // lcmp
+        0x95 | 0x96 => (2, 1),                  // fcmpl, fcmpg
+        0x97 | 0x98 => (4, 1),                  // dcmpl, dcmpg
+        0xac | 0xae | 0xb0 => return Some((1, 0, true)), // ireturn, freturn, areturn
+        0xad | 0xaf => return Some((2, 0, true)), // lreturn, dreturn
+        0xb1 => return Some((0, 0, true)),      // return
+        0xbe => (1, 1),                         // arraylength
+        0xbf => return Some((1, 0, true)),      // athrow
+        0xc2 | 0xc3 => (1, 0),                  // monitorenter, monitorexit
+        _ => return None,
+    };
+
+    Some((effect.0, effect.1, false))
+}
+
+/// Slots a value of field descriptor `desc` occupies; `V` takes none.
+fn value_slots(desc: &str) -> Option<u32> {
+    match desc.as_bytes() {
+        [b'J'] | [b'D'] => Some(2),
+        [b'V'] => Some(0),
+        [b'B' | b'C' | b'F' | b'I' | b'S' | b'Z'] => Some(1),
+        [b'[', rest @ ..] if !rest.is_empty() => {
+            value_slots(&desc[1..]).filter(|&x| x != 0)?;
+            Some(1)
+        }
+        [b'L', .., b';'] if desc.len() > 2 => Some(1),
+        _ => None,
+    }
+}
+
+/// Slots taken by the parameters and by the return value of the method
+/// descriptor `desc`.
+fn method_slots(desc: &str) -> Option<(u32, u32)> {
+    let (params, ret) = desc.strip_prefix('(')?.split_once(')')?;
+
+    let mut slots = 0;
+    let mut rest = params;
+    while !rest.is_empty() {
+        let dims = rest.len() - rest.trim_start_matches('[').len();
+        let len = match *rest.as_bytes().get(dims)? {
+            b'L' => rest.find(';')? + 1,
+            _ => dims + 1,
+        };
+        let slot = value_slots(&rest[..len]).filter(|&x| x != 0)?;
+        slots += slot;
+        rest = &rest[len..];
+    }
+
+    Some((slots, value_slots(ret)?))
+}
+
+/// The code of one method.  Instructions are appended in order; branches
+/// name `Label`s, which `bind` places at the next instruction.  `finish`
+/// resolves the labels, computes `max_stack` and `max_locals`, and adds the
+/// method to its class.
+///
+/// Misusing an instruction method, e.g. `op` with an opcode that takes
+/// operands, panics; problems only `finish` can see are `BuildError`s.
+pub struct CodeBuilder<'a> {
+    class: &'a mut ClassBuilder,
+    acc_flags: u16,
+    name_idx: u16,
+    desc_idx: u16,
+    code: Vec<u8>,
+    insns: Vec<Insn>,
+    labels: Vec<Option<usize>>,
+    fixups: Vec<Fixup>,
+    handlers: Vec<Handler>,
+    frames: Option<Vec<StackMapFrameInfo>>,
+    max_locals: u32,
+    /// `max_stack` as given instead of computed.
+    stack_limit: Option<u16>,
+    locals_limit: Option<u16>,
+    /// The method descriptor, if it cannot be parsed; only `max_locals`
+    /// needs it.
+    invalid_desc: Option<String>,
+    /// The first problem found while appending instructions.
+    error: Option<BuildError>,
+}
+
+impl<'a> CodeBuilder<'a> {
+    pub(super) fn new(class: &'a mut ClassBuilder, flags: u16, name: &str, desc: &str) -> Self {
+        let name_idx = class.cp.utf8(name);
+        let desc_idx = class.cp.utf8(desc);
+
+        let receiver = flags & AccFlags::ACC_STATIC.bits() == 0;
+        let (max_locals, invalid_desc) = match method_slots(desc) {
+            Some((params, _)) => (params + receiver as u32, None),
+            None => (0, Some(desc.to_owned())),
+        };
+
+        Self {
+            class,
+            acc_flags: flags,
+            name_idx,
+            desc_idx,
+            code: Vec::new(),
+            insns: Vec::new(),
+            labels: Vec::new(),
+            fixups: Vec::new(),
+            handlers: Vec::new(),
+            frames: None,
+            max_locals,
+            stack_limit: None,
+            locals_limit: None,
+            invalid_desc,
+            error: None,
+        }
+    }
+
+    fn fail(&mut self, err: BuildError) {
+        self.error.get_or_insert(err);
+    }
+
+    /// Start an instruction at the current offset.
+    fn insn(&mut self, opcode: u8, pops: u32, pushes: u32, flow: Flow) -> &mut Self {
+        self.insns.push(Insn {
+            pc: self.code.len(),
+            pops,
+            pushes,
+            flow,
+        });
+        self.code.push(opcode);
+        self
+    }
+
+    fn u16(&mut self, x: u16) -> &mut Self {
+        self.code.extend(x.to_be_bytes());
+        self
+    }
+
+    fn touch_local(&mut self, index: u16, slots: u32) {
+        self.max_locals = self.max_locals.max(index as u32 + slots);
+    }
+
+    /// Slots of the value `desc` describes, or 0 after recording the
+    /// malformed descriptor.
+    fn value_slots(&mut self, desc: &str) -> u32 {
+        value_slots(desc).filter(|&x| x != 0).unwrap_or_else(|| {
+            self.fail(BuildError::InvalidDescriptor(desc.to_owned()));
+            0
+        })
+    }
+
+    /// Use `max` as `max_stack`.  The stack is then not analysed at all, so
+    /// code that under- or overflows it, or falls off its end, can be built.
+    pub fn max_stack(&mut self, max: u16) -> &mut Self {
+        self.stack_limit = Some(max);
+        self
+    }
+
+    /// Use `max` as `max_locals` instead of the highest local used.  The
+    /// method descriptor then need not parse, so malformed ones can be built.
+    pub fn max_locals(&mut self, max: u16) -> &mut Self {
+        self.locals_limit = Some(max);
+        self
+    }
+
+    pub fn constant_pool(&mut self) -> &mut ConstantPool {
+        &mut self.class.cp
+    }
+
+    /// A label to bind later.
+    pub fn label(&mut self) -> Label {
+        self.labels.push(None);
+        Label(self.labels.len() - 1)
+    }
+
+    /// Place `label` at the next instruction.
+    pub fn bind(&mut self, label: Label) -> &mut Self {
+        let slot = &mut self.labels[label.0];
+        assert!(slot.is_none(), "label bound twice");
+        *slot = Some(self.code.len());
+        self
+    }
+
+    /// An instruction without operands: constants, `<x>load_<n>`, array
+    /// access, stack manipulation, arithmetic, conversions, comparisons,
+    /// returns, `arraylength`, `athrow` and the monitor instructions.
+    pub fn op(&mut self, opcode: u8) -> &mut Self {
+        let Some((pops, pushes, stop)) = simple_effect(opcode) else {
+            panic!("opcode {opcode:#04x} takes operands");
+        };
+
+        match opcode {
+            0x1a..=0x2d => {
+                let slots = if (0x1e..=0x21).contains(&opcode) || (0x26..=0x29).contains(&opcode) { 2 } else { 1 };
+                self.touch_local(((opcode - 0x1a) % 4) as u16, slots);
+            }
+            0x3b..=0x4e => {
+                let slots = if (0x3f..=0x42).contains(&opcode) || (0x47..=0x4a).contains(&opcode) { 2 } else { 1 };
+                self.touch_local(((opcode - 0x3b) % 4) as u16, slots);
+            }
+            _ => {}
+        }
+
+        self.insn(opcode, pops, pushes, if stop { Flow::Stop } else { Flow::Next })
+    }
+
+    /// Push the int `value` with the shortest instruction that can.
+    pub fn iconst(&mut self, value: i32) -> &mut Self {
+        match value {
+            -1..=5 => self.op((0x03 + value) as u8),
+            -128..=127 => self.bipush(value as i8),
+            -32768..=32767 => self.sipush(value as i16),
+            _ => {
+                let index = self.class.cp.integer(value);
+                self.ldc(index, 1)
+            }
+        }
+    }
+
+    pub fn bipush(&mut self, value: i8) -> &mut Self {
+        self.insn(0x10, 0, 1, Flow::Next);
+        self.code.push(value as u8);
+        self
+    }
+
+    pub fn sipush(&mut self, value: i16) -> &mut Self {
+        self.insn(0x11, 0, 1, Flow::Next).u16(value as u16)
+    }
+
+    /// `ldc`, `ldc_w` or `ldc2_w` of the constant at `index`, which is not
+    /// checked to suit the instruction.
+    pub fn load_constant(&mut self, opcode: u8, index: u16) -> &mut Self {
+        match opcode {
+            0x12 => {
+                let Ok(index) = u8::try_from(index) else {
+                    panic!("ldc of constant {index}");
+                };
+                self.insn(0x12, 0, 1, Flow::Next);
+                self.code.push(index);
+                self
+            }
+            0x13 => self.insn(0x13, 0, 1, Flow::Next).u16(index),
+            0x14 => self.insn(0x14, 0, 2, Flow::Next).u16(index),
+            _ => panic!("opcode {opcode:#04x} does not load a constant"),
+        }
+    }
+
+    /// `ldc`, `ldc_w` or `ldc2_w` of the constant at `index`, whichever fits.
+    fn ldc(&mut self, index: u16, slots: u32) -> &mut Self {
+        match (slots, index) {
+            (2, _) => self.load_constant(0x14, index),
+            (_, 0..=255) => self.load_constant(0x12, index),
+            _ => self.load_constant(0x13, index),
+        }
+    }
+
+    pub fn ldc_int(&mut self, value: i32) -> &mut Self {
+        let index = self.class.cp.integer(value);
+        self.ldc(index, 1)
+    }
+
+    pub fn ldc_float(&mut self, value: f32) -> &mut Self {
+        let index = self.class.cp.float(value);
+        self.ldc(index, 1)
+    }
+
+    pub fn ldc_long(&mut self, value: i64) -> &mut Self {
+        let index = self.class.cp.long(value);
+        self.ldc(index, 2)
+    }
+
+    pub fn ldc_double(&mut self, value: f64) -> &mut Self {
+        let index = self.class.cp.double(value);
+        self.ldc(index, 2)
+    }
+
+    pub fn ldc_string(&mut self, text: &str) -> &mut Self {
+        let index = self.class.cp.string(text);
+        self.ldc(index, 1)
+    }
+
+    pub fn ldc_class(&mut self, name: &str) -> &mut Self {
+        let index = self.class.cp.class(name);
+        self.ldc(index, 1)
+    }
+
+    /// `<x>load`, `<x>store` or `ret` of local variable `index`, `wide` when
+    /// the index needs two bytes.
+    pub fn local(&mut self, opcode: u8, index: u16) -> &mut Self {
+        let (pops, pushes, slots, flow) = match opcode {
+            0x15 | 0x17 | 0x19 => (0, 1, 1, Flow::Next), // iload, fload, aload
+            0x16 | 0x18 => (0, 2, 2, Flow::Next),        // lload, dload
+            0x36 | 0x38 | 0x3a => (1, 0, 1, Flow::Next), // istore, fstore, astore
+            0x37 | 0x39 => (2, 0, 2, Flow::Next),        // lstore, dstore
+            0xa9 => (0, 0, 1, Flow::Stop),               // ret
+            _ => panic!("opcode {opcode:#04x} does not take a local variable"),
+        };
+        self.touch_local(index, slots);
+
+        if index > 255 {
+            self.insn(0xc4, pops, pushes, flow);
+            self.code.push(opcode);
+            self.u16(index)
+        } else {
+            self.insn(opcode, pops, pushes, flow);
+            self.code.push(index as u8);
+            self
+        }
+    }
+
+    pub fn iinc(&mut self, index: u16, delta: i16) -> &mut Self {
+        self.touch_local(index, 1);
+
+        match (u8::try_from(index), i8::try_from(delta)) {
+            (Ok(index), Ok(delta)) => {
+                self.insn(0x84, 0, 0, Flow::Next);
+                self.code.extend([index, delta as u8]);
+                self
+            }
+            _ => {
+                self.insn(0xc4, 0, 0, Flow::Next);
+                self.code.push(0x84);
+                self.u16(index).u16(delta as u16)
+            }
+        }
+    }
+
+    /// `getstatic`, `putstatic`, `getfield` or `putfield`.
+    pub fn field(&mut self, opcode: u8, class: &str, name: &str, desc: &str) -> &mut Self {
+        let slots = self.value_slots(desc);
+        let (pops, pushes) = match opcode {
+            0xb2 => (0, slots),
+            0xb3 => (slots, 0),
+            0xb4 => (1, slots),
+            0xb5 => (1 + slots, 0),
+            _ => panic!("opcode {opcode:#04x} does not access a field"),
+        };
+
+        let index = self.class.cp.field_ref(class, name, desc);
+        self.insn(opcode, pops, pushes, Flow::Next).u16(index)
+    }
+
+    fn invoke_ref(&mut self, opcode: u8, index: u16, desc: &str) -> &mut Self {
+        let (params, ret) = method_slots(desc).unwrap_or_else(|| {
+            self.fail(BuildError::InvalidDescriptor(desc.to_owned()));
+            (0, 0)
+        });
+        let receiver = match opcode {
+            0xb6 | 0xb7 | 0xb9 => 1,
+            0xb8 => 0,
+            _ => panic!("opcode {opcode:#04x} does not invoke a method"),
+        };
+
+        self.insn(opcode, params + receiver, ret, Flow::Next).u16(index);
+        if opcode == 0xb9 {
+            self.code.extend([(params + 1) as u8, 0]);
+        }
+        self
+    }
+
+    /// `invokevirtual`, `invokespecial` or `invokestatic` of a class method,
+    /// or `invokeinterface`.
+    pub fn invoke(&mut self, opcode: u8, class: &str, name: &str, desc: &str) -> &mut Self {
+        let index = match opcode {
+            0xb9 => self.class.cp.interface_method_ref(class, name, desc),
+            _ => self.class.cp.method_ref(class, name, desc),
+        };
+        self.invoke_ref(opcode, index, desc)
+    }
+
+    /// Invoke a method of `interface`: `invokeinterface`, or the
+    /// `invokestatic` and `invokespecial` forms that name an
+    /// `InterfaceMethodref`.
+    pub fn invoke_interface(&mut self, opcode: u8, interface: &str, name: &str, desc: &str) -> &mut Self {
+        let index = self.class.cp.interface_method_ref(interface, name, desc);
+        self.invoke_ref(opcode, index, desc)
+    }
+
+    /// `invokedynamic` of a call site linked by the bootstrap method at index
+    /// `bootstrap`, as `ClassBuilder::bootstrap_method` returned it.
+    pub fn invokedynamic(&mut self, bootstrap: u16, name: &str, desc: &str) -> &mut Self {
+        let (params, ret) = method_slots(desc).unwrap_or_else(|| {
+            self.fail(BuildError::InvalidDescriptor(desc.to_owned()));
+            (0, 0)
+        });
+
+        let index = self.class.cp.invoke_dynamic(bootstrap, name, desc);
+        self.insn(0xba, params, ret, Flow::Next).u16(index).u16(0)
+    }
+
+    /// `new`, `anewarray`, `checkcast` or `instanceof` of `class`.
+    pub fn class_op(&mut self, opcode: u8, class: &str) -> &mut Self {
+        let (pops, pushes) = match opcode {
+            0xbb => (0, 1),
+            0xbd | 0xc0 | 0xc1 => (1, 1),
+            _ => panic!("opcode {opcode:#04x} does not name a class"),
+        };
+
+        let index = self.class.cp.class(class);
+        self.insn(opcode, pops, pushes, Flow::Next).u16(index)
+    }
+
+    /// `newarray` of primitive type `atype`, e.g. 10 for `int`.
+    pub fn newarray(&mut self, atype: u8) -> &mut Self {
+        self.insn(0xbc, 1, 1, Flow::Next);
+        self.code.push(atype);
+        self
+    }
+
+    pub fn multianewarray(&mut self, class: &str, dimensions: u8) -> &mut Self {
+        let index = self.class.cp.class(class);
+        self.insn(0xc5, dimensions as u32, 1, Flow::Next).u16(index);
+        self.code.push(dimensions);
+        self
+    }
+
+    fn fixup(&mut self, pc: usize, label: Label, wide: bool) {
+        self.fixups.push(Fixup {
+            pc,
+            at: self.code.len(),
+            label,
+            wide,
+        });
+        self.code.extend(if wide { [0; 4].as_slice() } else { [0; 2].as_slice() });
+    }
+
+    /// `if<cond>`, `if_icmp<cond>`, `if_acmp<cond>`, `ifnull`, `ifnonnull`,
+    /// `goto` or `jsr` to `label`.
+    pub fn branch(&mut self, opcode: u8, label: Label) -> &mut Self {
+        let (pops, pushes, flow) = match opcode {
+            0x99..=0x9e | 0xc6 | 0xc7 => (1, 0, Flow::Branch(label)),
+            0x9f..=0xa6 => (2, 0, Flow::Branch(label)),
+            0xa7 => (0, 0, Flow::Goto(label)),
+            0xa8 => (0, 1, Flow::Jsr(label)),
+            _ => panic!("opcode {opcode:#04x} is not a branch"),
+        };
+
+        let pc = self.code.len();
+        self.insn(opcode, pops, pushes, flow);
+        self.fixup(pc, label, false);
+        self
+    }
+
+    /// Pad a switch at `pc` so its operands start 4-byte aligned.
+    fn align(&mut self, pc: usize) {
+        let padding = (4 - (pc + 1) % 4) % 4;
+        self.code.extend(std::iter::repeat_n(0, padding));
+    }
+
+    /// `tableswitch` jumping to `targets[key - low]`, or to `default`.
+    pub fn tableswitch(&mut self, low: i32, default: Label, targets: &[Label]) -> &mut Self {
+        let high = low.wrapping_add(targets.len() as i32).wrapping_sub(1);
+        self.tableswitch_range(low, high, default, targets)
+    }
+
+    /// `tableswitch` with `high` written as given, even when it does not
+    /// match the number of `targets`.
+    pub fn tableswitch_range(&mut self, low: i32, high: i32, default: Label, targets: &[Label]) -> &mut Self {
+        let pc = self.code.len();
+        let mut labels = vec![default];
+        labels.extend_from_slice(targets);
+        self.insn(0xaa, 1, 0, Flow::Switch(labels));
+        self.align(pc);
+
+        self.fixup(pc, default, true);
+        self.code.extend(low.to_be_bytes());
+        self.code.extend(high.to_be_bytes());
+        for target in targets {
+            self.fixup(pc, *target, true);
+        }
+        self
+    }
+
+    /// `lookupswitch` over `pairs` of key and target, in any order.
+    pub fn lookupswitch(&mut self, default: Label, pairs: &[(i32, Label)]) -> &mut Self {
+        let mut pairs = pairs.to_vec();
+        pairs.sort_by_key(|x| x.0);
+        self.lookupswitch_in_order(default, &pairs)
+    }
+
+    /// `lookupswitch` with `pairs` in the order given, sorted or not.
+    pub fn lookupswitch_in_order(&mut self, default: Label, pairs: &[(i32, Label)]) -> &mut Self {
+        let pc = self.code.len();
+        let mut labels = vec![default];
+        labels.extend(pairs.iter().map(|x| x.1));
+        self.insn(0xab, 1, 0, Flow::Switch(labels));
+        self.align(pc);
+
+        self.fixup(pc, default, true);
+        self.code.extend((pairs.len() as u32).to_be_bytes());
+        for &(key, target) in pairs {
+            self.code.extend(key.to_be_bytes());
+            self.fixup(pc, target, true);
+        }
+        self
+    }
+
+    /// An exception handler at `handler` for the code from `start` up to
+    /// `end`, catching `catch_type` or, for `None`, everything.
+    pub fn handler(&mut self, start: Label, end: Label, handler: Label, catch_type: Option<&str>) -> &mut Self {
+        let catch_type = catch_type.map_or(0, |x| self.class.cp.class(x));
+        self.handlers.push(Handler {
+            start,
+            end,
+            handler,
+            catch_type,
+        });
+        self
+    }
+
+    /// Add a `StackMapTable` with `frames`, which are written as given: the
+    /// builder does not compute frames.
+    pub fn stack_map_table(&mut self, frames: Vec<StackMapFrameInfo>) -> &mut Self {
+        self.class.cp.utf8("StackMapTable");
+        self.frames = Some(frames);
+        self
+    }
+
+    fn label_pc(&self, label: Label) -> BuildResult<usize> {
+        self.labels[label.0].ok_or(BuildError::UnboundLabel)
+    }
+
+    fn patch_branches(&mut self) -> BuildResult<()> {
+        for fixup in &self.fixups {
+            let target = self.label_pc(fixup.label)?;
+            let offset = target as i64 - fixup.pc as i64;
+            if fixup.wide {
+                self.code[fixup.at..fixup.at + 4].copy_from_slice(&(offset as i32).to_be_bytes());
+            } else {
+                let offset = i16::try_from(offset).map_err(|_| BuildError::BranchTooFar { pc: fixup.pc })?;
+                self.code[fixup.at..fixup.at + 2].copy_from_slice(&offset.to_be_bytes());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The deepest the operand stack gets, found by following every path
+    /// from the entry point and the exception handlers.
+    fn stack_depth(&self, handlers: &[ExceptionTableEntryInfo]) -> BuildResult<u32> {
+        let by_pc: HashMap<usize, usize> = self.insns.iter().enumerate().map(|(i, x)| (x.pc, i)).collect();
+        let insn_at = |pc: usize| by_pc.get(&pc).copied().ok_or(BuildError::FallsOffEnd);
+
+        let mut depths: Vec<Option<u32>> = vec![None; self.insns.len()];
+        let mut pending = vec![(0, 0)];
+        for x in handlers {
+            pending.push((insn_at(x.handler_pc as usize)?, 1));
+        }
+
+        let mut max = 0;
+        while let Some((i, depth)) = pending.pop() {
+            match depths[i] {
+                Some(known) if known == depth => continue,
+                Some(known) => {
+                    return Err(BuildError::InconsistentStack {
+                        pc: self.insns[i].pc,
+                        depths: (known, depth),
+                    });
+                }
+                None => depths[i] = Some(depth),
+            }
+
+            let insn = &self.insns[i];
+            let after = depth
+                .checked_sub(insn.pops)
+                .ok_or(BuildError::StackUnderflow { pc: insn.pc })?
+                + insn.pushes;
+            max = max.max(depth).max(after);
+
+            let next = || match self.insns.get(i + 1) {
+                Some(_) => Ok(i + 1),
+                None => Err(BuildError::FallsOffEnd),
+            };
+            match &insn.flow {
+                Flow::Next => pending.push((next()?, after)),
+                Flow::Goto(label) => pending.push((insn_at(self.label_pc(*label)?)?, after)),
+                Flow::Branch(label) => {
+                    pending.push((insn_at(self.label_pc(*label)?)?, after));
+                    pending.push((next()?, after));
+                }
+                Flow::Switch(labels) => {
+                    for label in labels {
+                        pending.push((insn_at(self.label_pc(*label)?)?, after));
+                    }
+                }
+                Flow::Jsr(label) => {
+                    pending.push((insn_at(self.label_pc(*label)?)?, after));
+                    pending.push((next()?, depth));
+                }
+                Flow::Stop => {}
+            }
+        }
+
+        Ok(max)
+    }
+
+    /// Resolve the labels, compute `max_stack` and `max_locals` and add the
+    /// method to the class.
+    pub fn finish(mut self) -> BuildResult<()> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        if let (Some(desc), None) = (self.invalid_desc.take(), self.locals_limit) {
+            return Err(BuildError::InvalidDescriptor(desc));
+        }
+        if self.code.is_empty() || self.code.len() > u16::MAX as usize {
+            return Err(BuildError::InvalidCodeLength(self.code.len()));
+        }
+
+        self.patch_branches()?;
+        let exception_table = self
+            .handlers
+            .iter()
+            .map(|x| {
+                Ok(ExceptionTableEntryInfo {
+                    start_pc: self.label_pc(x.start)? as u16,
+                    end_pc: self.label_pc(x.end)? as u16,
+                    handler_pc: self.label_pc(x.handler)? as u16,
+                    catch_type: x.catch_type,
+                })
+            })
+            .collect::<BuildResult<Vec<_>>>()?;
+
+        let max_stack = match self.stack_limit {
+            Some(max) => max,
+            None => u16::try_from(self.stack_depth(&exception_table)?).map_err(|_| BuildError::TooManySlots)?,
+        };
+        let max_locals = match self.locals_limit {
+            Some(max) => max,
+            None => u16::try_from(self.max_locals).map_err(|_| BuildError::TooManySlots)?,
+        };
+
+        self.class.cp.utf8("Code");
+        let code = CodeAttrInfo {
+            max_stack,
+            max_locals,
+            code: self.code,
+            exception_table,
+            attrs: self.frames.map(AttrInfo::StackMapTable).into_iter().collect(),
+            raw_attrs: Vec::new(),
+        };
+        self.class.methods.push(MethodInfo {
+            acc_flags: self.acc_flags,
+            name_idx: self.name_idx,
+            desc_idx: self.desc_idx,
+            attrs: vec![AttrInfo::Code(code)],
+            raw_attrs: Vec::new(),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use crate::class_parser::cp_info::ConstantPoolInfo;
+
+/// Identity of a constant, to share equal ones.  Floating-point values are
+/// compared by their bits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Key {
+    Utf8(String),
+    Integer(i32),
+    Float(u32),
+    Long(i64),
+    Double(u64),
+    Class(u16),
+    String(u16),
+    NameAndType(u16, u16),
+    /// Tag, class and name-and-type of a field or method reference.
+    Member(u8, u16, u16),
+    MethodHandle(u8, u16),
+    MethodType(u16),
+    /// Tag, bootstrap method and name-and-type of a dynamically computed
+    /// constant or call site.
+    Dynamic(u8, u16, u16),
+}
+
+/// A constant pool under construction.  Each constant is added once;
+/// adding it again returns the existing index.
+pub struct ConstantPool {
+    /// `entries[0]` is the unusable entry 0, as in `ClassFile`.
+    entries: Vec<ConstantPoolInfo>,
+    idxs: HashMap<Key, u16>,
+    /// Set once an entry no longer fits; `ClassBuilder::build` reports it.
+    overflowed: bool,
+}
+
+impl Default for ConstantPool {
+    fn default() -> Self {
+        Self {
+            entries: vec![ConstantPoolInfo::Unusable],
+            idxs: HashMap::new(),
+            overflowed: false,
+        }
+    }
+}
+
+impl ConstantPool {
+    fn add(&mut self, key: Key, entry: impl FnOnce() -> ConstantPoolInfo) -> u16 {
+        if let Some(idx) = self.idxs.get(&key) {
+            return *idx;
+        }
+
+        let idx = self.push(entry());
+        if idx != 0 {
+            self.idxs.insert(key, idx);
+        }
+        idx
+    }
+
+    fn push(&mut self, entry: ConstantPoolInfo) -> u16 {
+        let wide = matches!(entry, ConstantPoolInfo::LongInfo { .. } | ConstantPoolInfo::DoubleInfo { .. });
+        let idx = self.entries.len();
+        if idx + wide as usize >= u16::MAX as usize {
+            self.overflowed = true;
+            return 0;
+        }
+
+        self.entries.push(entry);
+        if wide {
+            self.entries.push(ConstantPoolInfo::Unusable);
+        }
+        idx as u16
+    }
+
+    /// Add `entry` as it is, even when an equal one exists.  For constants
+    /// the other methods do not make, such as malformed ones.
+    pub fn entry(&mut self, entry: ConstantPoolInfo) -> u16 {
+        self.push(entry)
+    }
+
+    /// The index the next new constant gets.
+    pub fn next_index(&self) -> u16 {
+        self.entries.len() as u16
+    }
+
+    pub fn utf8(&mut self, text: &str) -> u16 {
+        self.add(Key::Utf8(text.to_owned()), || ConstantPoolInfo::Utf8Info {
+            utf8: text.to_owned(),
+            wtf8: None,
+        })
+    }
+
+    pub fn integer(&mut self, value: i32) -> u16 {
+        self.add(Key::Integer(value), || ConstantPoolInfo::IntegerInfo { value })
+    }
+
+    pub fn float(&mut self, value: f32) -> u16 {
+        self.add(Key::Float(value.to_bits()), || ConstantPoolInfo::FloatInfo { value })
+    }
+
+    pub fn long(&mut self, value: i64) -> u16 {
+        self.add(Key::Long(value), || ConstantPoolInfo::LongInfo { value })
+    }
+
+    pub fn double(&mut self, value: f64) -> u16 {
+        self.add(Key::Double(value.to_bits()), || ConstantPoolInfo::DoubleInfo { value })
+    }
+
+    /// `CONSTANT_Class` of the class, interface or array type `name`.
+    pub fn class(&mut self, name: &str) -> u16 {
+        let name_index = self.utf8(name);
+        self.add(Key::Class(name_index), || ConstantPoolInfo::ClassInfo { name_index })
+    }
+
+    pub fn string(&mut self, text: &str) -> u16 {
+        let string_index = self.utf8(text);
+        self.add(Key::String(string_index), || ConstantPoolInfo::StringInfo { string_index })
+    }
+
+    pub fn name_and_type(&mut self, name: &str, desc: &str) -> u16 {
+        let name_index = self.utf8(name);
+        let desc_index = self.utf8(desc);
+        self.add(Key::NameAndType(name_index, desc_index), || ConstantPoolInfo::NameAndTypeInfo {
+            name_index,
+            desc_index,
+        })
+    }
+
+    fn member(&mut self, tag: u8, class: &str, name: &str, desc: &str) -> u16 {
+        let class_index = self.class(class);
+        let name_and_type_index = self.name_and_type(name, desc);
+        self.add(Key::Member(tag, class_index, name_and_type_index), || match tag {
+            9 => ConstantPoolInfo::FieldrefInfo {
+                class_index,
+                name_and_type_index,
+            },
+            10 => ConstantPoolInfo::MethodrefInfo {
+                class_index,
+                name_and_type_index,
+            },
+            _ => ConstantPoolInfo::InterfaceMethodrefInfo {
+                class_index,
+                name_and_type_index,
+            },
+        })
+    }
+
+    pub fn field_ref(&mut self, class: &str, name: &str, desc: &str) -> u16 {
+        self.member(9, class, name, desc)
+    }
+
+    pub fn method_ref(&mut self, class: &str, name: &str, desc: &str) -> u16 {
+        self.member(10, class, name, desc)
+    }
+
+    pub fn interface_method_ref(&mut self, class: &str, name: &str, desc: &str) -> u16 {
+        self.member(11, class, name, desc)
+    }
+
+    /// `CONSTANT_MethodHandle` of reference kind `kind`, 1 to 9, on the
+    /// field or method reference at `reference`.
+    pub fn method_handle(&mut self, kind: u8, reference: u16) -> u16 {
+        self.add(Key::MethodHandle(kind, reference), || ConstantPoolInfo::MethodHandleInfo {
+            reference_kind: kind,
+            reference_index: reference,
+        })
+    }
+
+    pub fn method_type(&mut self, desc: &str) -> u16 {
+        let descriptor_index = self.utf8(desc);
+        self.add(Key::MethodType(descriptor_index), || ConstantPoolInfo::MethodTypeInfo { descriptor_index })
+    }
+
+    /// `CONSTANT_Dynamic` computed by the bootstrap method at index
+    /// `bootstrap` of the `BootstrapMethods` attribute.
+    pub fn dynamic(&mut self, bootstrap: u16, name: &str, desc: &str) -> u16 {
+        let name_and_type_index = self.name_and_type(name, desc);
+        self.add(Key::Dynamic(17, bootstrap, name_and_type_index), || ConstantPoolInfo::DynamicInfo {
+            bootstrap_method_attr_index: bootstrap,
+            name_and_type_index,
+        })
+    }
+
+    /// `CONSTANT_InvokeDynamic` linked by the bootstrap method at index
+    /// `bootstrap` of the `BootstrapMethods` attribute.
+    pub fn invoke_dynamic(&mut self, bootstrap: u16, name: &str, desc: &str) -> u16 {
+        let name_and_type_index = self.name_and_type(name, desc);
+        self.add(Key::Dynamic(18, bootstrap, name_and_type_index), || ConstantPoolInfo::InvokeDynamicInfo {
+            bootstrap_method_attr_index: bootstrap,
+            name_and_type_index,
+        })
+    }
+
+    /// Has an entry been dropped because the pool was full?
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// The entries, indexed like `ClassFile::constant_pool`.
+    pub fn into_entries(self) -> Vec<ConstantPoolInfo> {
+        self.entries
+    }
+}
@@ -0,0 +1,681 @@
+//! A Jasmin-style assembler, for tests that need code javac never emits.
+//!
+//! ```text
+//! .bytecode 49.0                    ; optional, 49 by default
+//! .class public Shapes
+//! .super java/lang/Object           ; optional
+//! .field static base I
+//!
+//! .method public static pick(I)I
+//!     .limit stack 2                ; optional: computed when absent
+//!     iload_0
+//!     tableswitch 0 1
+//!         Zero
+//!         One
+//!         default : Other
+//! Zero:
+//!     bipush 10
+//!     ireturn
+//! One:
+//! Other:
+//!     getstatic Shapes/base I
+//!     ireturn
+//! .end method
+//! ```
+//!
+//! Besides `.class`, `.interface`, `.super`, `.implements`, `.source`,
+//! `.field` and `.method`, methods take `.limit stack`, `.limit locals` and
+//! `.catch <class | all> from <label> to <label> using <label>`.  Members
+//! are written as `class/name desc` for fields and `class/name(params)ret`
+//! for methods.  `ldc` takes an int, a float, a quoted string or a class
+//! name, `ldc2_w` a long or a double.  Floats need a decimal point, an
+//! exponent or an `f` (`d` for doubles) suffix.
+//!
+//! Code is kept as written where javac would normalize it:
+//! `tableswitch <low> <high>` writes `high` even when the targets do not
+//! match it, and `lookupswitch` keeps its keys in the order given.  With
+//! `.limit stack` the operand stack is not analysed, so code the verifier
+//! must reject can be assembled.
+
+use std::{collections::HashMap, iter::Peekable, str::{Chars, FromStr}};
+
+use crate::{
+    assembler::{
+        asm_error::{AsmError, AsmErrorKind, AsmResult},
+        class_builder::ClassBuilder,
+        code_builder::{CodeBuilder, Label, simple_effect},
+    },
+    oops::acc_flags::AccFlags,
+};
+
+/// Mnemonics by opcode, up to `jsr_w`.
+#[rustfmt::skip]
+const MNEMONICS: [&str; 0xca] = [
+    "nop", "aconst_null", "iconst_m1", "iconst_0", "iconst_1", "iconst_2", "iconst_3", "iconst_4",
+    "iconst_5", "lconst_0", "lconst_1", "fconst_0", "fconst_1", "fconst_2", "dconst_0", "dconst_1",
+    "bipush", "sipush", "ldc", "ldc_w", "ldc2_w", "iload", "lload", "fload",
+    "dload", "aload", "iload_0", "iload_1", "iload_2", "iload_3", "lload_0", "lload_1",
+    "lload_2", "lload_3", "fload_0", "fload_1", "fload_2", "fload_3", "dload_0", "dload_1",
+    "dload_2", "dload_3", "aload_0", "aload_1", "aload_2", "aload_3", "iaload", "laload",
+    "faload", "daload", "aaload", "baload", "caload", "saload", "istore", "lstore",
+    "fstore", "dstore", "astore", "istore_0", "istore_1", "istore_2", "istore_3", "lstore_0",
+    "lstore_1", "lstore_2", "lstore_3", "fstore_0", "fstore_1", "fstore_2", "fstore_3", "dstore_0",
+    "dstore_1", "dstore_2", "dstore_3", "astore_0", "astore_1", "astore_2", "astore_3", "iastore",
+    "lastore", "fastore", "dastore", "aastore", "bastore", "castore", "sastore", "pop",
+    "pop2", "dup", "dup_x1", "dup_x2", "dup2", "dup2_x1", "dup2_x2", "swap",
+    "iadd", "ladd", "fadd", "dadd", "isub", "lsub", "fsub", "dsub",
+    "imul", "lmul", "fmul", "dmul", "idiv", "ldiv", "fdiv", "ddiv",
+    "irem", "lrem", "frem", "drem", "ineg", "lneg", "fneg", "dneg",
+    "ishl", "lshl", "ishr", "lshr", "iushr", "lushr", "iand", "land",
+    "ior", "lor", "ixor", "lxor", "iinc", "i2l", "i2f", "i2d",
+    "l2i", "l2f", "l2d", "f2i", "f2l", "f2d", "d2i", "d2l",
+    "d2f", "i2b", "i2c", "i2s", "lcmp", "fcmpl", "fcmpg", "dcmpl",
+    "dcmpg", "ifeq", "ifne", "iflt", "ifge", "ifgt", "ifle", "if_icmpeq",
+    "if_icmpne", "if_icmplt", "if_icmpge", "if_icmpgt", "if_icmple", "if_acmpeq", "if_acmpne", "goto",
+    "jsr", "ret", "tableswitch", "lookupswitch", "ireturn", "lreturn", "freturn", "dreturn",
+    "areturn", "return", "getstatic", "putstatic", "getfield", "putfield", "invokevirtual", "invokespecial",
+    "invokestatic", "invokeinterface", "invokedynamic", "new", "newarray", "anewarray", "arraylength", "athrow",
+    "checkcast", "instanceof", "monitorenter", "monitorexit", "wide", "multianewarray", "ifnull", "ifnonnull",
+    "goto_w", "jsr_w",
+];
+
+fn opcode(mnemonic: &str) -> Option<u8> {
+    MNEMONICS.iter().position(|x| *x == mnemonic).map(|x| x as u8)
+}
+
+fn access_flag(word: &str) -> Option<AccFlags> {
+    let flag = match word {
+        "public" => AccFlags::ACC_PUBLIC,
+        "private" => AccFlags::ACC_PRIVATE,
+        "protected" => AccFlags::ACC_PROTECTED,
+        "static" => AccFlags::ACC_STATIC,
+        "final" => AccFlags::ACC_FINAL,
+        "super" => AccFlags::ACC_SUPER,
+        "synchronized" => AccFlags::ACC_SYNCHRONIZED,
+        "volatile" => AccFlags::ACC_VOLATILE,
+        "bridge" => AccFlags::ACC_BRIDGE,
+        "transient" => AccFlags::ACC_TRANSIENT,
+        "varargs" => AccFlags::ACC_VARARGS,
+        "native" => AccFlags::ACC_NATIVE,
+        "interface" => AccFlags::ACC_INTERFACE,
+        "abstract" => AccFlags::ACC_ABSTRACT,
+        "strict" => AccFlags::ACC_STRICT,
+        "synthetic" => AccFlags::ACC_SYNTHETIC,
+        "annotation" => AccFlags::ACC_ANNOTATION,
+        "enum" => AccFlags::ACC_ENUM,
+        _ => return None,
+    };
+
+    Some(flag)
+}
+
+/// `newarray` element types (JVMS 6.5).
+fn array_type(word: &str) -> Option<u8> {
+    let atype = match word {
+        "boolean" => 4,
+        "char" => 5,
+        "float" => 6,
+        "double" => 7,
+        "byte" => 8,
+        "short" => 9,
+        "int" => 10,
+        "long" => 11,
+        _ => return None,
+    };
+
+    Some(atype)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    /// A quoted string, unescaped.
+    Str(String),
+    Colon,
+}
+
+fn unescape(chars: &mut Peekable<Chars<'_>>) -> Result<String, AsmErrorKind> {
+    let mut text = String::new();
+    loop {
+        let c = match chars.next().ok_or(AsmErrorKind::UnterminatedString)? {
+            '"' => return Ok(text),
+            '\\' => match chars.next().ok_or(AsmErrorKind::UnterminatedString)? {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| AsmErrorKind::InvalidOperand(format!("\\u{hex}")))?
+                }
+                other => other,
+            },
+            other => other,
+        };
+        text.push(c);
+    }
+}
+
+/// Split a line into words, quoted strings and colons.  `;` starts a
+/// comment where a token could start, so descriptors keep theirs.
+fn tokenize(line: &str) -> Result<Vec<Token>, AsmErrorKind> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ';' => break,
+            ':' => {
+                chars.next();
+                tokens.push(Token::Colon);
+            }
+            '"' => {
+                chars.next();
+                tokens.push(Token::Str(unescape(&mut chars)?));
+            }
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == ':' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// A non-blank line.
+struct Line {
+    number: usize,
+    tokens: Vec<Token>,
+}
+
+impl Line {
+    fn error(&self, kind: AsmErrorKind) -> AsmError {
+        AsmError {
+            line: self.number,
+            kind,
+        }
+    }
+
+    fn invalid(&self, operand: &str) -> AsmError {
+        self.error(AsmErrorKind::InvalidOperand(operand.to_owned()))
+    }
+
+    fn word(&self, index: usize) -> AsmResult<&str> {
+        match self.tokens.get(index) {
+            Some(Token::Word(word)) => Ok(word),
+            Some(Token::Str(text)) => Err(self.invalid(text)),
+            Some(Token::Colon) => Err(self.invalid(":")),
+            None => Err(self.invalid("")),
+        }
+    }
+
+    /// Fail unless the line has exactly `len` tokens.
+    fn expect_len(&self, len: usize) -> AsmResult<()> {
+        match self.tokens.get(len) {
+            Some(_) => Err(self.invalid(&token_text(&self.tokens[len]))),
+            None if self.tokens.len() < len => Err(self.invalid("")),
+            None => Ok(()),
+        }
+    }
+
+    /// The integer at `index`, decimal or `0x` hexadecimal.
+    fn int<T: TryFrom<i64>>(&self, index: usize) -> AsmResult<T> {
+        let word = self.word(index)?;
+        parse_int(word).and_then(|x| T::try_from(x).ok()).ok_or_else(|| self.invalid(word))
+    }
+
+    /// A directive declaring a class or member: the access flags after the
+    /// directive, then the `names` last tokens.
+    fn declaration(&self, names: usize) -> AsmResult<(u16, &[Token])> {
+        let Some(end) = self.tokens.len().checked_sub(names).filter(|&x| x >= 1) else {
+            return Err(self.invalid(""));
+        };
+
+        let mut flags = AccFlags::empty();
+        for index in 1..end {
+            let word = self.word(index)?;
+            flags |= access_flag(word).ok_or_else(|| self.invalid(word))?;
+        }
+        Ok((flags.bits(), &self.tokens[end..]))
+    }
+}
+
+fn word_of(line: &Line, token: &Token) -> AsmResult<String> {
+    match token {
+        Token::Word(word) => Ok(word.clone()),
+        other => Err(line.invalid(&token_text(other))),
+    }
+}
+
+fn token_text(token: &Token) -> String {
+    match token {
+        Token::Word(word) => word.clone(),
+        Token::Str(text) => format!("{text:?}"),
+        Token::Colon => ":".to_owned(),
+    }
+}
+
+fn parse_int(word: &str) -> Option<i64> {
+    let (negative, digits) = match word.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, word),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i64>().ok()?,
+    };
+
+    Some(if negative { -value } else { value })
+}
+
+/// A word starting like a number: an optional `-`, then a digit, or a `.`
+/// and a digit.
+fn is_number(word: &str) -> bool {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    let digits = digits.strip_prefix('.').unwrap_or(digits);
+    digits.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// The floating-point literal `word`: a number with a decimal point, an
+/// exponent or the type `suffix`.  `NaN` and `Infinity` are names, not
+/// numbers, and an integer too large for its type stays an integer.
+fn float_literal<T: FromStr>(word: &str, suffix: char) -> Option<T> {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    if !is_number(word) || digits.starts_with("0x") {
+        return None;
+    }
+
+    match word.strip_suffix([suffix, suffix.to_ascii_uppercase()]) {
+        Some(body) => body.parse().ok(),
+        None if word.contains(['.', 'e', 'E']) => word.parse().ok(),
+        None => None,
+    }
+}
+
+/// Labels of one method by name.
+#[derive(Default)]
+struct Labels {
+    /// The label, the line it was first named on, and whether it is bound.
+    by_name: HashMap<String, (Label, usize, bool)>,
+}
+
+impl Labels {
+    fn get(&mut self, code: &mut CodeBuilder, name: &str, line: &Line) -> Label {
+        self.by_name.entry(name.to_owned()).or_insert_with(|| (code.label(), line.number, false)).0
+    }
+
+    fn bind(&mut self, code: &mut CodeBuilder, name: &str, line: &Line) -> AsmResult<()> {
+        let label = self.get(code, name, line);
+        let bound = &mut self.by_name.get_mut(name).unwrap().2;
+        if *bound {
+            return Err(line.error(AsmErrorKind::DuplicateLabel(name.to_owned())));
+        }
+        *bound = true;
+        code.bind(label);
+        Ok(())
+    }
+
+    /// The first label used but never defined.
+    fn check(&self) -> AsmResult<()> {
+        let unbound = self.by_name.iter().filter(|x| !x.1.2).min_by_key(|x| x.1.1);
+        match unbound {
+            Some((name, (_, line, _))) => Err(AsmError {
+                line: *line,
+                kind: AsmErrorKind::UndefinedLabel(name.clone()),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+type Lines<'a> = std::slice::Iter<'a, Line>;
+
+/// Assemble `source` into a class file.
+pub fn assemble(source: &str) -> AsmResult<Vec<u8>> {
+    let mut lines = Vec::new();
+    for (i, text) in source.lines().enumerate() {
+        let tokens = tokenize(text).map_err(|kind| AsmError { line: i + 1, kind })?;
+        if !tokens.is_empty() {
+            lines.push(Line { number: i + 1, tokens });
+        }
+    }
+
+    let class = assemble_class(&mut lines.iter())?;
+    let last = source.lines().count().max(1);
+    class.to_bytes().map_err(|err| AsmError {
+        line: last,
+        kind: AsmErrorKind::Build(err),
+    })
+}
+
+fn assemble_class(lines: &mut Lines) -> AsmResult<ClassBuilder> {
+    let mut class: Option<ClassBuilder> = None;
+    let mut version = None;
+    let mut source_file = None;
+
+    while let Some(line) = lines.next() {
+        let directive = line.word(0)?;
+        if let ".bytecode" | ".source" | ".class" | ".interface" = directive {
+            match directive {
+                ".bytecode" => {
+                    line.expect_len(2)?;
+                    let word = line.word(1)?;
+                    let major = word.split('.').next().and_then(|x| x.parse::<u16>().ok());
+                    version = Some(major.ok_or_else(|| line.invalid(word))?);
+                }
+                ".source" => {
+                    line.expect_len(2)?;
+                    source_file = Some(line.word(1)?.to_owned());
+                }
+                _ => {
+                    if class.is_some() {
+                        return Err(line.error(AsmErrorKind::DuplicateClass));
+                    }
+                    let (mut flags, names) = line.declaration(1)?;
+                    if directive == ".interface" {
+                        flags |= (AccFlags::ACC_INTERFACE | AccFlags::ACC_ABSTRACT).bits();
+                    } else {
+                        flags |= AccFlags::ACC_SUPER.bits();
+                    }
+                    let mut builder = ClassBuilder::new(&word_of(line, &names[0])?);
+                    builder.flags(flags);
+                    class = Some(builder);
+                }
+            }
+            continue;
+        }
+
+        let Some(class) = class.as_mut() else {
+            return Err(line.error(AsmErrorKind::MissingClass));
+        };
+        match directive {
+            ".super" => {
+                line.expect_len(2)?;
+                class.extend(line.word(1)?);
+            }
+            ".implements" => {
+                line.expect_len(2)?;
+                class.implement(line.word(1)?);
+            }
+            ".field" => {
+                let (flags, names) = line.declaration(2)?;
+                class.field(flags, &word_of(line, &names[0])?, &word_of(line, &names[1])?);
+            }
+            ".method" => assemble_method(class, line, lines)?,
+            _ => return Err(line.error(AsmErrorKind::UnknownDirective(directive.to_owned()))),
+        }
+    }
+
+    let Some(mut class) = class else {
+        return Err(AsmError {
+            line: 1,
+            kind: AsmErrorKind::MissingClass,
+        });
+    };
+    if let Some(major) = version {
+        class.version(major);
+    }
+    if let Some(name) = source_file {
+        class.source_file(&name);
+    }
+    Ok(class)
+}
+
+fn is_end_method(line: &Line) -> bool {
+    line.tokens == [Token::Word(".end".to_owned()), Token::Word("method".to_owned())]
+}
+
+fn assemble_method(class: &mut ClassBuilder, header: &Line, lines: &mut Lines) -> AsmResult<()> {
+    let (flags, names) = header.declaration(1)?;
+    let spec = word_of(header, &names[0])?;
+    let Some(paren) = spec.find('(') else {
+        return Err(header.invalid(&spec));
+    };
+    let (name, desc) = spec.split_at(paren);
+
+    let unterminated = || header.error(AsmErrorKind::UnterminatedMethod);
+    if flags & (AccFlags::ACC_ABSTRACT | AccFlags::ACC_NATIVE).bits() != 0 {
+        let line = lines.next().ok_or_else(unterminated)?;
+        if !is_end_method(line) {
+            return Err(line.error(AsmErrorKind::UnexpectedCode));
+        }
+        class.abstract_method(flags, name, desc);
+        return Ok(());
+    }
+
+    let mut code = class.method(flags, name, desc);
+    let mut labels = Labels::default();
+    let end = loop {
+        let line = lines.next().ok_or_else(unterminated)?;
+        if is_end_method(line) {
+            break line;
+        }
+
+        // `name:` defines a label at the next instruction, on this line or
+        // a later one.
+        let mut start = 0;
+        while let [Token::Word(name), Token::Colon, ..] = &line.tokens[start..] {
+            labels.bind(&mut code, name, line)?;
+            start += 2;
+        }
+        if start == line.tokens.len() {
+            continue;
+        }
+
+        match line.word(start)? {
+            ".limit" => {
+                line.expect_len(start + 3)?;
+                match line.word(start + 1)? {
+                    "stack" => code.max_stack(line.int(start + 2)?),
+                    "locals" => code.max_locals(line.int(start + 2)?),
+                    other => return Err(line.invalid(other)),
+                };
+            }
+            ".catch" => {
+                line.expect_len(start + 8)?;
+                for (index, keyword) in [(2, "from"), (4, "to"), (6, "using")] {
+                    if line.word(start + index)? != keyword {
+                        return Err(line.invalid(line.word(start + index)?));
+                    }
+                }
+                let catch_type = match line.word(start + 1)? {
+                    "all" => None,
+                    class => Some(class),
+                };
+                let from = labels.get(&mut code, line.word(start + 3)?, line);
+                let to = labels.get(&mut code, line.word(start + 5)?, line);
+                let using = labels.get(&mut code, line.word(start + 7)?, line);
+                code.handler(from, to, using, catch_type);
+            }
+            directive if directive.starts_with('.') => {
+                return Err(line.error(AsmErrorKind::UnknownDirective(directive.to_owned())));
+            }
+            _ => instruction(&mut code, &mut labels, line, start, lines)?,
+        }
+    };
+
+    labels.check()?;
+    code.finish().map_err(|err| end.error(AsmErrorKind::Build(err)))
+}
+
+/// Split `class/name` at its last `/`.
+fn member<'a>(line: &Line, spec: &'a str) -> AsmResult<(&'a str, &'a str)> {
+    spec.rsplit_once('/').ok_or_else(|| line.invalid(spec))
+}
+
+/// Split `class/name(params)ret` into class, name and descriptor.
+fn method_spec<'a>(line: &Line, spec: &'a str) -> AsmResult<(&'a str, &'a str, &'a str)> {
+    let Some(paren) = spec.find('(') else {
+        return Err(line.invalid(spec));
+    };
+    let (class, name) = member(line, &spec[..paren])?;
+    Ok((class, name, &spec[paren..]))
+}
+
+/// The constant-pool index of the `ldc` or `ldc_w` operand at `index`.
+fn single_constant(code: &mut CodeBuilder, line: &Line, index: usize) -> AsmResult<u16> {
+    let cp = code.constant_pool();
+    let constant = match &line.tokens[index] {
+        Token::Str(text) => cp.string(text),
+        Token::Word(word) => match float_literal(word, 'f') {
+            Some(value) => cp.float(value),
+            None if is_number(word) => cp.integer(line.int(index)?),
+            None => cp.class(word),
+        },
+        Token::Colon => return Err(line.invalid(":")),
+    };
+
+    Ok(constant)
+}
+
+/// The constant-pool index of the `ldc2_w` operand at `index`.
+fn double_constant(code: &mut CodeBuilder, line: &Line, index: usize) -> AsmResult<u16> {
+    let constant = match float_literal(line.word(index)?, 'd') {
+        Some(value) => code.constant_pool().double(value),
+        None => code.constant_pool().long(line.int(index)?),
+    };
+
+    Ok(constant)
+}
+
+/// The `label` or `key : label` lines of a switch, up to and including
+/// `default : label`.
+fn switch_cases(
+    code: &mut CodeBuilder,
+    labels: &mut Labels,
+    header: &Line,
+    lines: &mut Lines,
+    keyed: bool,
+) -> AsmResult<(Vec<(i32, Label)>, Label)> {
+    let mut cases = Vec::new();
+    loop {
+        let line = lines.next().ok_or_else(|| header.error(AsmErrorKind::UnterminatedSwitch))?;
+        let (key, target) = match (&line.tokens[..], keyed) {
+            ([Token::Word(key), Token::Colon, Token::Word(target)], _) if key == "default" => {
+                line.expect_len(3)?;
+                let default = labels.get(code, target, line);
+                return Ok((cases, default));
+            }
+            ([Token::Word(_), Token::Colon, Token::Word(target)], true) => (line.int(0)?, target),
+            ([Token::Word(target)], false) => (0, target),
+            _ => return Err(line.invalid(&token_text(&line.tokens[0]))),
+        };
+        cases.push((key, labels.get(code, target, line)));
+    }
+}
+
+fn instruction(code: &mut CodeBuilder, labels: &mut Labels, line: &Line, start: usize, lines: &mut Lines) -> AsmResult<()> {
+    let mnemonic = line.word(start)?;
+    let unknown = || line.error(AsmErrorKind::UnknownInstruction(mnemonic.to_owned()));
+    let opcode = opcode(mnemonic).ok_or_else(unknown)?;
+    let operand = start + 1;
+
+    if simple_effect(opcode).is_some() {
+        line.expect_len(operand)?;
+        code.op(opcode);
+        return Ok(());
+    }
+
+    match opcode {
+        0x10 => {
+            line.expect_len(operand + 1)?;
+            code.bipush(line.int(operand)?);
+        }
+        0x11 => {
+            line.expect_len(operand + 1)?;
+            code.sipush(line.int(operand)?);
+        }
+        0x12 | 0x13 => {
+            line.expect_len(operand + 1)?;
+            let index = single_constant(code, line, operand)?;
+            // 与 Jasmin 相同：放不进一个字节的 ldc 写成 ldc_w。
+            code.load_constant(if index > 255 { 0x13 } else { opcode }, index);
+        }
+        0x14 => {
+            line.expect_len(operand + 1)?;
+            let index = double_constant(code, line, operand)?;
+            code.load_constant(opcode, index);
+        }
+        0x15..=0x19 | 0x36..=0x3a | 0xa9 => {
+            line.expect_len(operand + 1)?;
+            code.local(opcode, line.int(operand)?);
+        }
+        0x84 => {
+            line.expect_len(operand + 2)?;
+            code.iinc(line.int(operand)?, line.int(operand + 1)?);
+        }
+        0x99..=0xa8 | 0xc6 | 0xc7 => {
+            line.expect_len(operand + 1)?;
+            let label = labels.get(code, line.word(operand)?, line);
+            code.branch(opcode, label);
+        }
+        0xaa => {
+            let low = line.int(operand)?;
+            let high = match line.tokens.len() - operand {
+                1 => None,
+                _ => Some(line.int(operand + 1)?),
+            };
+            line.expect_len(operand + 1 + high.is_some() as usize)?;
+            let (cases, default) = switch_cases(code, labels, line, lines, false)?;
+            let targets: Vec<_> = cases.into_iter().map(|x| x.1).collect();
+            match high {
+                Some(high) => code.tableswitch_range(low, high, default, &targets),
+                None => code.tableswitch(low, default, &targets),
+            };
+        }
+        0xab => {
+            line.expect_len(operand)?;
+            let (pairs, default) = switch_cases(code, labels, line, lines, true)?;
+            code.lookupswitch_in_order(default, &pairs);
+        }
+        0xb2..=0xb5 => {
+            line.expect_len(operand + 2)?;
+            let (class, name) = member(line, line.word(operand)?)?;
+            code.field(opcode, class, name, line.word(operand + 1)?);
+        }
+        0xb6..=0xb8 => {
+            line.expect_len(operand + 1)?;
+            let (class, name, desc) = method_spec(line, line.word(operand)?)?;
+            code.invoke(opcode, class, name, desc);
+        }
+        0xb9 => {
+            // Jasmin 写出参数个数；这里由描述符算出，写了也只检查格式。
+            if line.tokens.len() > operand + 1 {
+                line.expect_len(operand + 2)?;
+                line.int::<u8>(operand + 1)?;
+            }
+            let (class, name, desc) = method_spec(line, line.word(operand)?)?;
+            code.invoke(opcode, class, name, desc);
+        }
+        0xbb | 0xbd | 0xc0 | 0xc1 => {
+            line.expect_len(operand + 1)?;
+            code.class_op(opcode, line.word(operand)?);
+        }
+        0xbc => {
+            line.expect_len(operand + 1)?;
+            let word = line.word(operand)?;
+            code.newarray(array_type(word).ok_or_else(|| line.invalid(word))?);
+        }
+        0xc5 => {
+            line.expect_len(operand + 2)?;
+            code.multianewarray(line.word(operand)?, line.int(operand + 1)?);
+        }
+        // invokedynamic, wide, goto_w and jsr_w
+        _ => return Err(unknown()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+//! Class files produced without javac.  `ClassBuilder` assembles a class
+//! from instructions, resolving branch labels and computing `max_stack` and
+//! `max_locals`, and serializes it with `ClassFile::to_bytes`.
+
+pub mod asm_error;
+pub mod build_error;
+pub mod class_builder;
+pub mod code_builder;
+pub mod constant_pool;
+pub mod jasmin;
@@ -4,7 +4,7 @@ use dashmap::{DashMap, Entry};
 
 use crate::{
     class_loader::{
-        class_path::ClassPath, class_slot::{ClassLoadState, ClassSlot}, load_error::{LoadError, LoadResult}, ms_api::{MSAllocator, MSBox, MSRef},
+        class_path::{ClassPath, trace_class_load}, class_slot::{ClassLoadState, ClassSlot}, load_error::{LoadError, LoadResult}, ms_api::{MSAllocator, MSBox, MSRef},
     }, class_parser::class_file::ClassFile, gc_bindings::oop_handle::{KLASS_OOP_STORAGE_ID, OOPHandle}, oops::{
         array_klass::ArrayKlass,
         desc::FieldDesc,
@@ -84,7 +84,7 @@ impl BootstrapCLD {
                 match &*guard {
                     ClassLoadState::Loading { owner } => {
                         if owner.eq(&std::thread::current().id()) {
-                            return Err(LoadError::Circularity)
+                            return Err(LoadError::Circularity(name.into()))
                         }
                         slot.completed.wait(&mut guard);
                     }
@@ -195,15 +195,21 @@ impl BootstrapCLD {
     }
 
     fn find_normal_klass(sym: SymbolHandle) -> LoadResult<MSBox<Klass>> {
-        let bytes = match ClassPath::read_bs_class(sym.utf8()) {
+        let (bytes, source) = match ClassPath::boot().find_class(sym.utf8()) {
             Some(x) => x,
             None => return Err(LoadError::NotFound(sym.utf8().into())),
         };
 
-        let cf = ClassFile::from(&bytes)?;
-        let unlinked = UnlinkedNormalKlass::build(cf, None)?;
-        let boxed = NormalKlass::link(unlinked, None)?;
+        let name = sym.utf8();
+        let cf = ClassFile::from(&bytes).map_err(|e| LoadError::from(e).in_class(name))?;
+        if cf.declares_module() {
+            return Err(LoadError::ModuleInfo(name.into()));
+        }
+        let boxed = UnlinkedNormalKlass::build(cf, None)
+            .and_then(|unlinked| NormalKlass::link(unlinked, None))
+            .map_err(|e| LoadError::from(e).in_class(name))?;
 
+        trace_class_load(sym.utf8(), source.as_ref());
         Ok(boxed)
     }
 }
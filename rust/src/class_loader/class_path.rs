@@ -1,16 +1,163 @@
-use std::fs;
+use std::sync::{Arc, LazyLock};
 
-use crate::runtime::arguments::Arguments;
+use crate::{
+    class_loader::class_source::{ClassSource, DirectorySource, JarSource},
+    runtime::arguments::Arguments,
+};
 
-pub struct ClassPath;
+/// Separator between class path entries, as accepted by `java -cp`.
+pub const CLASS_PATH_SEPARATOR: char = ':';
+
+/// An ordered list of class sources.  Lookups return the first match, so
+/// earlier entries shadow later ones exactly like the reference launcher.
+#[derive(Debug, Default, Clone)]
+pub struct ClassPath {
+    entries: Vec<Arc<dyn ClassSource>>,
+}
+
+static BOOT_CLASS_PATH: LazyLock<ClassPath> = LazyLock::new(|| {
+    let args = Arguments::get();
+    ClassPath::parse(&args.bs_class_path).with_sources(&args.boot_sources)
+});
+
+/// A `.jar`/`.zip` element becomes a `JarSource`, anything else a directory.
+fn parse_entry(raw: &str) -> Arc<dyn ClassSource> {
+    let is_archive = std::path::Path::new(raw)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("jar") || ext.eq_ignore_ascii_case("zip"));
+
+    if is_archive {
+        Arc::new(JarSource::new(raw))
+    } else {
+        Arc::new(DirectorySource::new(raw))
+    }
+}
 
 impl ClassPath {
+    /// Split a `:`-separated class path.  Empty elements are ignored.
+    pub fn parse(spec: &str) -> Self {
+        let entries = spec
+            .split(CLASS_PATH_SEPARATOR)
+            .filter(|raw| !raw.is_empty())
+            .map(parse_entry)
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn from_sources(sources: Vec<Arc<dyn ClassSource>>) -> Self {
+        Self { entries: sources }
+    }
+
+    /// Append `sources` after the existing entries.
+    pub fn with_sources(mut self, sources: &[Arc<dyn ClassSource>]) -> Self {
+        self.entries.extend(sources.iter().cloned());
+        self
+    }
+
+    pub fn push(&mut self, source: Arc<dyn ClassSource>) {
+        self.entries.push(source);
+    }
+
+    pub fn entries(&self) -> &[Arc<dyn ClassSource>] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Read the bytes of a resource such as `META-INF/MANIFEST.MF`.
+    pub fn read_resource(&self, resource: &str) -> Option<Vec<u8>> {
+        self.entries.iter().find_map(|entry| entry.read_resource(resource))
+    }
+
+    /// Read a class by its binary name in internal form (`java/lang/Object`).
+    pub fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+        self.find_class(name).map(|(bytes, _)| bytes)
+    }
+
+    /// Like `read_class`, but also report which entry supplied the bytes.
+    pub fn find_class(&self, name: &str) -> Option<(Vec<u8>, &Arc<dyn ClassSource>)> {
+        self.entries
+            .iter()
+            .find_map(|entry| entry.read_class(name).map(|bytes| (bytes, entry)))
+    }
+
+    /// The bootstrap class path: `Arguments::bs_class_path` followed by
+    /// `Arguments::boot_sources`.
+    pub fn boot() -> &'static ClassPath {
+        &BOOT_CLASS_PATH
+    }
+
     pub fn read_bs_class(name: &str) -> Option<Vec<u8>> {
-        let path = format!("{}/{}.class", Arguments::get().bs_class_path, name);
-        
-        match fs::read(path) {
-            Ok(x) => Some(x),
-            Err(_) => None
-        }
+        Self::boot().read_class(name)
+    }
+}
+
+/// `-verbose:class` 输出，格式与 HotSpot 的旧版日志一致。
+pub fn trace_class_load(name: &str, source: &dyn ClassSource) {
+    if Arguments::get().verbose_class {
+        println!("[Loaded {} from {}]", name.replace('/', "."), source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::class_loader::class_source::MemorySource;
+
+    #[test]
+    fn parse_splits_entries_in_order_and_skips_empty_elements() {
+        let cp = ClassPath::parse("classes::lib/a.jar:lib/b.ZIP:");
+        let names: Vec<String> = cp.entries().iter().map(|x| x.to_string()).collect();
+
+        assert_eq!(names, ["classes", "lib/a.jar", "lib/b.ZIP"]);
+        assert!(format!("{:?}", cp.entries()[0]).starts_with("DirectorySource"));
+        assert!(format!("{:?}", cp.entries()[1]).starts_with("JarSource"));
+        assert!(format!("{:?}", cp.entries()[2]).starts_with("JarSource"));
+    }
+
+    #[test]
+    fn earlier_directories_shadow_later_ones() {
+        let root = std::env::temp_dir().join(format!("klover-cp-{}", std::process::id()));
+        let first = root.join("first");
+        let second = root.join("second");
+        fs::create_dir_all(first.join("p")).unwrap();
+        fs::create_dir_all(second.join("p")).unwrap();
+        fs::write(first.join("p/A.class"), b"first").unwrap();
+        fs::write(second.join("p/A.class"), b"second").unwrap();
+        fs::write(second.join("p/B.class"), b"only-second").unwrap();
+
+        let spec = format!("{}:{}", first.display(), second.display());
+        let cp = ClassPath::parse(&spec);
+
+        assert_eq!(cp.read_class("p/A").unwrap(), b"first");
+        assert_eq!(cp.read_class("p/B").unwrap(), b"only-second");
+        assert!(cp.read_class("p/C").is_none());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn memory_sources_take_part_in_ordered_lookup() {
+        let front = Arc::new(MemorySource::new("front"));
+        let back = Arc::new(MemorySource::new("back"));
+        front.insert_class("p/A", b"front".to_vec());
+        back.insert_class("p/A", b"back".to_vec());
+        back.insert_resource("p/data.txt", b"data".to_vec());
+
+        let cp = ClassPath::from_sources(vec![front.clone(), back]);
+        assert_eq!(cp.read_class("p/A").unwrap(), b"front");
+        assert_eq!(cp.read_resource("p/data.txt").unwrap(), b"data");
+
+        // 插入后立即可见。
+        assert!(cp.read_class("p/B").is_none());
+        front.insert_class("p/B", b"late".to_vec());
+        assert_eq!(cp.read_class("p/B").unwrap(), b"late");
+        assert_eq!(cp.entries()[0].to_string(), "memory:front");
     }
 }
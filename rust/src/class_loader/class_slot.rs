@@ -16,6 +16,12 @@ pub struct ClassSlot {
     pub completed: parking_lot::Condvar,
 }
 
+// SAFETY: the slot is shared between loading threads through the loader's
+// DashMap; its state is only touched under `state`'s mutex, and the klass
+// it publishes lives in metaspace for as long as its loader.
+unsafe impl Send for ClassSlot {}
+unsafe impl Sync for ClassSlot {}
+
 impl Default for ClassSlot {
     fn default() -> Self {
         Self {
@@ -0,0 +1,142 @@
+//! Where class path entries get their bytes from.
+//!
+//! `ClassPath` is an ordered list of `ClassSource`s.  Directories and jars
+//! named on the command line become `DirectorySource` / `JarSource`; an
+//! embedder can add its own sources (for example classes generated in
+//! memory) through `Arguments::boot_sources` and `Arguments::app_sources`.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use parking_lot::RwLock;
+
+use crate::class_loader::zip::zip_archive::ZipArchive;
+
+/// A provider of class files and other resources.
+///
+/// Implementations must be thread-safe: several loaders may search the same
+/// source concurrently.  `Display` names the source in diagnostics such as
+/// `-verbose:class`.
+pub trait ClassSource: fmt::Debug + fmt::Display + Send + Sync {
+    /// Read `resource` (a `/`-separated path relative to the source root).
+    /// `None` means this source does not have it and the search continues.
+    fn read_resource(&self, resource: &str) -> Option<Vec<u8>>;
+
+    /// Read a class by its binary name in internal form (`java/lang/Object`).
+    fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+        self.read_resource(&format!("{name}.class"))
+    }
+}
+
+/// A directory whose sub-directories mirror package names.
+#[derive(Debug)]
+pub struct DirectorySource {
+    root: PathBuf,
+}
+
+impl DirectorySource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl fmt::Display for DirectorySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.root.display())
+    }
+}
+
+impl ClassSource for DirectorySource {
+    fn read_resource(&self, resource: &str) -> Option<Vec<u8>> {
+        fs::read(self.root.join(resource)).ok()
+    }
+}
+
+/// A `.jar` or `.zip` archive.  The central directory is parsed on first
+/// lookup and kept for the lifetime of the source.
+#[derive(Debug)]
+pub struct JarSource {
+    path: PathBuf,
+    /// `None` once opening has failed; the source then behaves as empty.
+    archive: OnceLock<Option<ZipArchive>>,
+}
+
+impl JarSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            archive: OnceLock::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn archive(&self) -> Option<&ZipArchive> {
+        self.archive
+            .get_or_init(|| ZipArchive::open(&self.path).ok())
+            .as_ref()
+    }
+}
+
+impl fmt::Display for JarSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())
+    }
+}
+
+impl ClassSource for JarSource {
+    fn read_resource(&self, resource: &str) -> Option<Vec<u8>> {
+        // 损坏的成员与缺失同样处理，继续搜索后面的条目。
+        self.archive()?.read(resource).ok().flatten()
+    }
+}
+
+/// Resources held in memory, keyed by resource path.  Entries may be added
+/// after the source is on a class path; already-defined classes are not
+/// affected.
+#[derive(Debug)]
+pub struct MemorySource {
+    name: String,
+    resources: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+impl MemorySource {
+    /// `name` identifies the source in diagnostics.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            resources: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert_resource(&self, resource: impl Into<String>, bytes: Vec<u8>) {
+        self.resources.write().insert(resource.into(), bytes);
+    }
+
+    /// Add a class by its binary name in internal form.
+    pub fn insert_class(&self, name: &str, bytes: Vec<u8>) {
+        self.insert_resource(format!("{name}.class"), bytes);
+    }
+}
+
+impl fmt::Display for MemorySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory:{}", self.name)
+    }
+}
+
+impl ClassSource for MemorySource {
+    fn read_resource(&self, resource: &str) -> Option<Vec<u8>> {
+        self.resources.read().get(resource).cloned()
+    }
+}
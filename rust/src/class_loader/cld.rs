@@ -5,10 +5,11 @@ use dashmap::{DashMap, mapref::entry::Entry};
 use crate::{
     class_loader::{
         bootstrap_cld::BootstrapCLD,
+        class_path::{ClassPath, trace_class_load},
         class_slot::{ClassLoadState, ClassSlot},
         cld_map,
         load_error::{LoadError, LoadResult},
-        ms_api::{MSAllocator, MSRef},
+        ms_api::{MSAllocator, MSBox, MSRef},
     },
     class_parser::{class_file::ClassFile, cp_info::ConstantPoolInfo},
     gc_bindings::oop_handle::{CLD_MIRROR_STORAGE_ID, OOPHandle},
@@ -30,6 +31,9 @@ pub struct ClassLoaderData {
 
     pub ms_allocator: MSAllocator,
     klasses: DashMap<SymbolHandle, Arc<ClassSlot>>,
+
+    /// 本加载器自己搜索的 class path；`None` 表示只做委派（不自行定义类）。
+    class_path: Option<ClassPath>,
 }
 
 unsafe impl Send for ClassLoaderData {}
@@ -44,12 +48,22 @@ impl Drop for ClassLoaderData {
 impl ClassLoaderData {
     // ── 构造 ──────────────────────────────────────────────────────────
     pub fn new(debug_name: Option<String>) -> NonNull<Self> {
+        Self::create(debug_name, None)
+    }
+
+    /// 创建一个在委派给 bootstrap 失败后，从 `class_path` 自行定义类的 CLD。
+    pub fn with_class_path(debug_name: Option<String>, class_path: ClassPath) -> NonNull<Self> {
+        Self::create(debug_name, Some(class_path))
+    }
+
+    fn create(debug_name: Option<String>, class_path: Option<ClassPath>) -> NonNull<Self> {
         let cld = Box::new(Self {
             next: std::ptr::null_mut(),
             mirror: OOPHandle::new(CLD_MIRROR_STORAGE_ID),
             debug_name,
             ms_allocator: MSAllocator::new(),
             klasses: DashMap::new(),
+            class_path,
         });
 
         let ptr: NonNull<Self> = Box::leak(cld).into();
@@ -59,23 +73,26 @@ impl ClassLoaderData {
     }
 }
 
+/// The name of the class `cf` defines; a `module-info` class defines none.
+fn class_file_name(cf: &ClassFile) -> LoadResult<String> {
+    match &cf.constant_pool[cf.this_class as usize] {
+        ConstantPoolInfo::ClassInfo { name_index } => {
+            match &cf.constant_pool[*name_index as usize] {
+                ConstantPoolInfo::Utf8Info { utf8, .. } if cf.declares_module() => Err(LoadError::ModuleInfo(utf8.clone())),
+                ConstantPoolInfo::Utf8Info { utf8, .. } => Ok(utf8.clone()),
+                _ => Err(ResolveError::MismatchCPType.into()),
+            }
+        }
+
+        _ => Err(ResolveError::MismatchCPType.into()),
+    }
+}
+
 impl ClassLoaderData {
     pub fn define_class(&self, bytes: &[u8]) -> LoadResult<MSRef<Klass>> {
-        let cf = match ClassFile::from(bytes) {
-            Ok(x) => x,
-            Err(e) => return Err(LoadError::Parse(e)),
-        };
-
-        let name_utf8 = match &cf.constant_pool[cf.this_class as usize] {
-            ConstantPoolInfo::ClassInfo { name_index } => {
-                match &cf.constant_pool[*name_index as usize] {
-                    ConstantPoolInfo::Utf8Info { utf8 } => utf8.clone(),
-                    _ => return Err(LoadError::Resolve(ResolveError::MismatchCPType)),
-                }
-            }
+        let cf = ClassFile::from(bytes)?;
 
-            _ => return Err(LoadError::Resolve(ResolveError::MismatchCPType)),
-        };
+        let name_utf8 = class_file_name(&cf)?;
         // field desc
         let name = SymbolTable::intern(name_utf8.as_str());
 
@@ -94,10 +111,18 @@ impl ClassLoaderData {
             }
         };
 
-        let load_result = UnlinkedNormalKlass::build(cf, Some(self))
-            .map_err(LoadError::from)
-            .and_then(|unlinked| NormalKlass::link(unlinked, Some(self)).map_err(LoadError::from));
+        let load_result = self.build_and_link(cf, &name_utf8);
+        Self::publish(&slot, load_result)
+    }
 
+    fn build_and_link(&self, cf: ClassFile, name: &str) -> LoadResult<MSBox<Klass>> {
+        UnlinkedNormalKlass::build(cf, Some(self))
+            .and_then(|unlinked| NormalKlass::link(unlinked, Some(self)))
+            .map_err(|e| LoadError::from(e).in_class(name))
+    }
+
+    /// 把加载结果写入 slot 并唤醒等待者。
+    fn publish(slot: &ClassSlot, load_result: LoadResult<MSBox<Klass>>) -> LoadResult<MSRef<Klass>> {
         match load_result {
             Ok(klass) => {
                 let result = (&klass).into();
@@ -123,6 +148,26 @@ impl ClassLoaderData {
         }
     }
 
+    /// 等待其他线程完成 slot 上的加载。
+    fn wait_for(slot: &ClassSlot, name: &SymbolHandle) -> LoadResult<MSRef<Klass>> {
+        let current_thread = std::thread::current().id();
+        let mut state = slot.state.lock();
+
+        loop {
+            match &*state {
+                ClassLoadState::Loading { owner } => {
+                    if *owner == current_thread {
+                        return Err(LoadError::Circularity(name.utf8().into()));
+                    }
+                    slot.completed.wait(&mut state);
+                }
+
+                ClassLoadState::Loaded(klass) => return Ok(klass.into()),
+                ClassLoadState::Failed(error) => return Err(error.clone()),
+            }
+        }
+    }
+
     pub fn find_loaded_class(&self, name: &str) -> Option<MSRef<Klass>> {
         let sym = SymbolTable::intern(name);
 
@@ -140,32 +185,65 @@ impl ClassLoaderData {
     /// 加载指定名称的类。
     ///
     /// 按双亲委派模型：先委派给父加载器，最终落到 bootstrap。
-    /// 当前实现只是把所有请求委派给 `BootstrapCLD`（尚未支持用户自定义 ClassLoader
-    /// 的 `loadClass` 覆盖）。未来接入 native `ClassLoader.loadClass` 时重写。
+    /// 父加载器固定为 `BootstrapCLD`（尚未支持用户自定义 ClassLoader 的
+    /// `loadClass` 覆盖）；bootstrap 找不到且本 CLD 带有 class path 时，
+    /// 由本 CLD 自行定义。未来接入 native `ClassLoader.loadClass` 时重写。
     pub fn load_class(&self, name: &str) -> LoadResult<MSRef<Klass>> {
         let sym = SymbolTable::intern(name);
         let local_slot = self.klasses.get(&sym).map(|entry| entry.value().clone());
 
         if let Some(slot) = local_slot {
-            let current_thread = std::thread::current().id();
-            let mut state = slot.state.lock();
-
-            loop {
-                match &*state {
-                    ClassLoadState::Loading { owner } => {
-                        if *owner == current_thread {
-                            return Err(LoadError::Circularity);
-                        }
-                        slot.completed.wait(&mut state);
-                    }
+            return Self::wait_for(&slot, &sym);
+        }
 
-                    ClassLoadState::Loaded(klass) => return Ok(klass.into()),
-                    ClassLoadState::Failed(error) => return Err(error.clone()),
-                }
+        // TODO: 真正的双亲委派（调用 self.mirror 对应的 java/lang/ClassLoader.loadClass）。
+        match BootstrapCLD::find_class(name) {
+            Err(LoadError::NotFound(_)) if self.class_path.is_some() => self.find_class(sym),
+            result => result,
+        }
+    }
+
+    /// 父加载器找不到时，从本加载器的 class path 定义类。
+    ///
+    /// 与 `define_class` 不同，同名并发请求不会报 `Duplicated`：
+    /// 第一个线程负责读取和定义，其余线程等待同一个 slot。
+    fn find_class(&self, sym: SymbolHandle) -> LoadResult<MSRef<Klass>> {
+        let (slot, is_leader) = match self.klasses.entry(sym.clone()) {
+            Entry::Occupied(x) => (x.get().clone(), false),
+
+            Entry::Vacant(x) => {
+                let slot = Arc::new(ClassSlot::default());
+                x.insert(slot.clone());
+                (slot, true)
             }
+        };
+
+        if !is_leader {
+            return Self::wait_for(&slot, &sym);
         }
 
-        // TODO: 真正的双亲委派（调用 self.mirror 对应的 java/lang/ClassLoader.loadClass）。
-        BootstrapCLD::find_class(name)
+        let load_result = self.read_and_define(&sym);
+        Self::publish(&slot, load_result)
+    }
+
+    fn read_and_define(&self, sym: &SymbolHandle) -> LoadResult<MSBox<Klass>> {
+        let (bytes, source) = self
+            .class_path
+            .as_ref()
+            .and_then(|cp| cp.find_class(sym.utf8()))
+            .ok_or_else(|| LoadError::NotFound(sym.utf8().into()))?;
+
+        let cf = ClassFile::from(&bytes).map_err(|e| LoadError::from(e).in_class(sym.utf8()))?;
+        let found = class_file_name(&cf)?;
+        if found != sym.utf8() {
+            return Err(LoadError::WrongName {
+                expected: sym.utf8().into(),
+                found,
+            });
+        }
+
+        let klass = self.build_and_link(cf, sym.utf8())?;
+        trace_class_load(sym.utf8(), source.as_ref());
+        Ok(klass)
     }
 }
@@ -1,27 +1,94 @@
-use crate::{class_parser::parse_error::ParseError, oops::oops_errors::ResolveError};
+use std::{error::Error, fmt};
+
+use crate::{
+    class_parser::parse_error::ParseError,
+    engine::exec_error::JavaExceptionKind,
+    oops::oops_errors::ResolveError,
+};
 
 #[derive(Debug, Clone)]
 pub enum LoadError {
     NotFound(String),
-    Parse(ParseError),
-    Resolve(ResolveError),
+    /// `class` is `None` when the bytes were handed over without a name and
+    /// did not parse far enough to name themselves.
+    Parse { class: Option<String>, source: ParseError },
+    /// Creating or linking `class` failed.
+    Resolve { class: Option<String>, source: ResolveError },
     StillLoading(String),
     SuperNotNormal(String),
     Duplicated { cld_name: Option<String>, class_name: String },
     NoSuper { class_name: String },
-    Circularity,
+    /// The class file found for `expected` declares a different name.
+    WrongName { expected: String, found: String },
+    /// Loading the class requires loading the class itself first.
+    Circularity(String),
+    /// The class file declares a module rather than a class (JVMS 5.3.5).
+    ModuleInfo(String),
 }
 
 impl From<ParseError> for LoadError {
     fn from(value: ParseError) -> Self {
-        Self::Parse(value)
+        Self::Parse { class: None, source: value }
     }
 }
 
 impl From<ResolveError> for LoadError {
     fn from(value: ResolveError) -> Self {
-        Self::Resolve(value)
+        Self::Resolve { class: None, source: value }
     }
 }
 
 pub type LoadResult<T> = Result<T, LoadError>;
+
+impl LoadError {
+    /// JVMS 5.3.5: a missing class, or a module declaration, is a
+    /// `NoClassDefFoundError` and a malformed one a `ClassFormatError`;
+    /// `None` for the other failures.
+    pub fn java_exception(&self) -> Option<JavaExceptionKind> {
+        match self {
+            Self::NotFound(_) | Self::ModuleInfo(_) => Some(JavaExceptionKind::NoClassDefFoundError),
+            Self::Parse { .. } => Some(JavaExceptionKind::ClassFormatError),
+            _ => None,
+        }
+    }
+
+    /// Name the class being loaded, unless the error already does.
+    pub fn in_class(self, name: &str) -> Self {
+        match self {
+            Self::Parse { class: None, source } => Self::Parse { class: Some(name.into()), source },
+            Self::Resolve { class: None, source } => Self::Resolve { class: Some(name.into()), source },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "class {name} not found"),
+            Self::Parse { class: Some(name), .. } => write!(f, "invalid class file for {name}"),
+            Self::Parse { class: None, .. } => f.write_str("invalid class file"),
+            Self::Resolve { class: Some(name), .. } => write!(f, "cannot link class {name}"),
+            Self::Resolve { class: None, .. } => f.write_str("cannot link class"),
+            Self::StillLoading(name) => write!(f, "class {name} is still being loaded"),
+            Self::SuperNotNormal(name) => write!(f, "superclass of {name} is not a class"),
+            Self::Duplicated { cld_name, class_name } => {
+                write!(f, "class {class_name} is already defined in loader {}", cld_name.as_deref().unwrap_or("<unnamed>"))
+            }
+            Self::NoSuper { class_name } => write!(f, "class {class_name} has no superclass"),
+            Self::WrongName { expected, found } => write!(f, "class file for {expected} defines {found}"),
+            Self::Circularity(name) => write!(f, "class {name} is its own superclass or superinterface"),
+            Self::ModuleInfo(name) => write!(f, "{name} is not a class because access_flag ACC_MODULE is set"),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Parse { source, .. } => Some(source),
+            Self::Resolve { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! `META-INF/MANIFEST.MF` parsing (JAR File Specification, "Manifest Specification").
+//!
+//! A manifest is a main section followed by per-entry sections, separated by
+//! blank lines.  Each header is `Name: value`; a line starting with a single
+//! space continues the previous value.  Header names compare case-insensitively.
+
+use std::{collections::HashMap, error::Error, fmt};
+
+pub const MANIFEST_PATH: &str = "META-INF/MANIFEST.MF";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+    InvalidUtf8,
+    /// 1-based line number of a header without `": "`.
+    MissingSeparator { line: usize },
+    /// A continuation line with no header before it in the same section.
+    DanglingContinuation { line: usize },
+}
+
+pub type ManifestResult<T> = Result<T, ManifestError>;
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8 => f.write_str("manifest is not valid UTF-8"),
+            Self::MissingSeparator { line } => write!(f, "line {line}: header has no \": \" separator"),
+            Self::DanglingContinuation { line } => write!(f, "line {line}: continuation line without a header"),
+        }
+    }
+}
+
+impl Error for ManifestError {}
+
+/// Headers of one section, keyed by lower-cased name.
+#[derive(Debug, Default, Clone)]
+pub struct Attributes {
+    values: HashMap<String, String>,
+}
+
+impl Attributes {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(&name.to_ascii_lowercase()).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Manifest {
+    main: Attributes,
+    entries: HashMap<String, Attributes>,
+}
+
+impl Manifest {
+    pub fn parse(bytes: &[u8]) -> ManifestResult<Self> {
+        let text = std::str::from_utf8(bytes).map_err(|_| ManifestError::InvalidUtf8)?;
+
+        // 先把续行拼接成逻辑行，同时保留空行作为节分隔。
+        let mut sections: Vec<Vec<(usize, String)>> = vec![Vec::new()];
+        for (index, line) in split_lines(text).enumerate() {
+            let line_no = index + 1;
+
+            if line.is_empty() {
+                if !sections.last().unwrap().is_empty() {
+                    sections.push(Vec::new());
+                }
+                continue;
+            }
+
+            let section = sections.last_mut().unwrap();
+            if let Some(rest) = line.strip_prefix(' ') {
+                let (_, header) = section
+                    .last_mut()
+                    .ok_or(ManifestError::DanglingContinuation { line: line_no })?;
+                header.push_str(rest);
+            } else {
+                section.push((line_no, line.to_string()));
+            }
+        }
+
+        let mut manifest = Manifest::default();
+        for (index, section) in sections.into_iter().enumerate() {
+            if section.is_empty() {
+                continue;
+            }
+
+            let attrs = parse_section(section)?;
+            if index == 0 {
+                manifest.main = attrs;
+            } else if let Some(name) = attrs.get("Name") {
+                manifest.entries.insert(name.to_string(), attrs);
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn main_attributes(&self) -> &Attributes {
+        &self.main
+    }
+
+    /// Attributes of the per-entry section `Name: name`.
+    pub fn entry(&self, name: &str) -> Option<&Attributes> {
+        self.entries.get(name)
+    }
+
+    /// `Main-Class` converted to internal form (`com/example/Main`).
+    pub fn main_class(&self) -> Option<String> {
+        self.main
+            .get("Main-Class")
+            .map(|x| x.trim().replace('.', "/"))
+            .filter(|x| !x.is_empty())
+    }
+
+    /// The space-separated relative URLs listed in `Class-Path`.
+    pub fn class_path(&self) -> Vec<&str> {
+        self.main
+            .get("Class-Path")
+            .map(|x| x.split_ascii_whitespace().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Lines end with CRLF, LF or a lone CR.
+fn split_lines(text: &str) -> impl Iterator<Item = &str> {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    let text = text.strip_suffix('\r').unwrap_or(text);
+    text.split('\n')
+        .flat_map(|line| line.strip_suffix('\r').unwrap_or(line).split('\r'))
+}
+
+fn parse_section(section: Vec<(usize, String)>) -> ManifestResult<Attributes> {
+    let mut values = HashMap::with_capacity(section.len());
+
+    for (line, header) in section {
+        let (name, value) = header
+            .split_once(": ")
+            .or_else(|| header.strip_suffix(':').map(|name| (name, "")))
+            .ok_or(ManifestError::MissingSeparator { line })?;
+
+        values
+            .entry(name.to_ascii_lowercase())
+            .or_insert_with(|| value.to_string());
+    }
+
+    Ok(Attributes { values })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_main_section_with_continuation_lines() {
+        let manifest = Manifest::parse(
+            b"Manifest-Version: 1.0\r\n\
+              Main-Class: com.example.app.Ma\r\n in\r\n\
+              Class-Path: lib/first.jar lib/sec\r\n ond.jar  third.jar\r\n\
+              \r\n",
+        )
+        .unwrap();
+
+        assert_eq!(manifest.main_attributes().get("manifest-version"), Some("1.0"));
+        assert_eq!(manifest.main_class().as_deref(), Some("com/example/app/Main"));
+        assert_eq!(
+            manifest.class_path(),
+            ["lib/first.jar", "lib/second.jar", "third.jar"]
+        );
+    }
+
+    #[test]
+    fn parses_per_entry_sections() {
+        let manifest = Manifest::parse(
+            b"Manifest-Version: 1.0\n\nName: com/example/\nSealed: true\n\n\nName: a/B.class\nX-Kind: test\n",
+        )
+        .unwrap();
+
+        assert!(manifest.main_class().is_none());
+        assert_eq!(manifest.entry("com/example/").unwrap().get("SEALED"), Some("true"));
+        assert_eq!(manifest.entry("a/B.class").unwrap().get("X-Kind"), Some("test"));
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        assert_eq!(
+            Manifest::parse(b"Manifest-Version: 1.0\nbroken line\n").unwrap_err(),
+            ManifestError::MissingSeparator { line: 2 }
+        );
+        assert_eq!(
+            Manifest::parse(b" dangling\n").unwrap_err(),
+            ManifestError::DanglingContinuation { line: 1 }
+        );
+    }
+}
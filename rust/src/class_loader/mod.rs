@@ -1,7 +1,11 @@
 pub mod bootstrap_cld;
 pub mod class_path;
+pub mod class_source;
 pub mod class_slot;
 pub mod cld;
 pub mod cld_map;
 pub mod load_error;
+pub mod manifest;
 pub mod ms_api;
+pub mod system_cld;
+pub mod zip;
@@ -0,0 +1,88 @@
+use std::{ptr::NonNull, sync::LazyLock};
+
+use parking_lot::Mutex;
+
+use crate::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD,
+        class_path::ClassPath,
+        cld::ClassLoaderData,
+        load_error::{LoadError, LoadResult},
+        ms_api::MSRef,
+    },
+    engine::slot::Slot,
+    gc_bindings::oop_handle::NObjPtr,
+    oops::klass::Klass,
+    runtime::arguments::Arguments,
+};
+
+/// Java class backing the system loader object.
+const APP_CLASS_LOADER: &str = "java/lang/ClassLoader$AppClassLoader";
+
+/// 对 system CLD 指针的薄封装，手动实现 `Send`/`Sync` 以放入 static。
+/// CLD 创建后永不释放，且自身是 `Sync` 的。
+struct SystemCLDPtr(NonNull<ClassLoaderData>);
+
+unsafe impl Send for SystemCLDPtr {}
+unsafe impl Sync for SystemCLDPtr {}
+
+static SYSTEM_CLD: LazyLock<SystemCLDPtr> = LazyLock::new(|| {
+    let args = Arguments::get();
+    let class_path = ClassPath::parse(&args.class_path).with_sources(&args.app_sources);
+    SystemCLDPtr(ClassLoaderData::with_class_path(Some("app".into()), class_path))
+});
+
+/// Serializes the first materialization of the loader object.
+static MIRROR_LOCK: Mutex<()> = Mutex::new(());
+
+/// The system (application) class loader.
+///
+/// It is an ordinary `ClassLoaderData` that delegates to the bootstrap loader
+/// first and then defines classes from `Arguments::class_path` and
+/// `Arguments::app_sources`, so application classes never need to share a
+/// directory with the boot classes.
+pub struct SystemCLD;
+
+impl SystemCLD {
+    pub fn get() -> &'static ClassLoaderData {
+        unsafe { SYSTEM_CLD.0.as_ref() }
+    }
+
+    pub fn find_class(name: &str) -> LoadResult<MSRef<Klass>> {
+        Self::get().load_class(name)
+    }
+
+    /// The `java.lang.ClassLoader` instance representing this loader, created
+    /// on first use.  Its `native_cld_ptr` field points back at the CLD.
+    pub fn mirror() -> LoadResult<NObjPtr> {
+        let cld = Self::get();
+
+        let _guard = MIRROR_LOCK.lock();
+        let existing = cld.mirror.get();
+        if existing != 0 {
+            return Ok(existing);
+        }
+
+        let loader = BootstrapCLD::find_class(APP_CLASS_LOADER)?
+            .as_normal_ref()
+            .ok_or_else(|| LoadError::NotFound(APP_CLASS_LOADER.into()))?;
+        let base = loader
+            .super_klass_ref()
+            .ok_or_else(|| LoadError::NoSuper { class_name: APP_CLASS_LOADER.into() })?;
+        let cld_ptr_field = base
+            .find_declared_field("native_cld_ptr", "J")
+            .ok_or_else(|| LoadError::NotFound("java/lang/ClassLoader.native_cld_ptr".into()))?;
+
+        let obj = loader.allocate_instance();
+        let address = cld as *const ClassLoaderData as i64;
+        base.write_instance_field(
+            obj,
+            &cld_ptr_field,
+            &[Slot::long_high(address), Slot::long_low(address)],
+        )
+        .expect("native_cld_ptr is a declared long instance field");
+
+        cld.mirror.replace(obj);
+        Ok(obj)
+    }
+}
@@ -0,0 +1,360 @@
+//! Raw DEFLATE (RFC 1951) decoder.
+//!
+//! Only decompression is needed to read jars, so this is a straightforward
+//! bit-at-a-time canonical Huffman decoder rather than a table-driven one.
+
+use crate::class_loader::zip::zip_error::{ZipError, ZipResult};
+
+const MAX_BITS: usize = 15;
+const MAX_LIT_CODES: usize = 286;
+const MAX_DIST_CODES: usize = 30;
+const FIXED_LIT_CODES: usize = 288;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Order in which code-length code lengths are stored in a dynamic block.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buf: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            pos: 0,
+            buf: 0,
+            count: 0,
+        }
+    }
+
+    /// Read `n` (≤ 16) bits, least significant first.
+    fn bits(&mut self, n: u32) -> ZipResult<u32> {
+        while self.count < n {
+            let byte = *self.data.get(self.pos).ok_or(ZipError::Truncated)?;
+            self.pos += 1;
+            self.buf |= (byte as u32) << self.count;
+            self.count += 8;
+        }
+
+        let value = self.buf & ((1u32 << n) - 1);
+        self.buf >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Drop the rest of the current byte (stored blocks start byte-aligned).
+    fn align(&mut self) {
+        self.buf = 0;
+        self.count = 0;
+    }
+
+    fn bytes(&mut self, len: usize) -> ZipResult<&'a [u8]> {
+        let end = self.pos.checked_add(len).ok_or(ZipError::Truncated)?;
+        let bytes = self.data.get(self.pos..end).ok_or(ZipError::Truncated)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+}
+
+/// Canonical Huffman code: number of codes per length, and the symbols
+/// ordered by code.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> ZipResult<Self> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // 超额订阅的码表无法唯一解码；不完整的码表合法（例如只有一个距离码）。
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(ZipError::InvalidHuffmanTable);
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> ZipResult<u16> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+
+        for &count in &self.counts[1..] {
+            code |= reader.bits(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(ZipError::InvalidCode)
+    }
+}
+
+/// 预分配时假定的压缩比；更高的压缩比只是让 `out` 多增长几次。
+const INITIAL_RATIO: usize = 4;
+
+/// Decompress a raw deflate stream whose output is exactly `expected_len`
+/// bytes long.  Output beyond that is rejected instead of buffered.
+///
+/// `expected_len` comes from the archive and is not trusted for the
+/// allocation: the buffer starts at a few times the compressed size and
+/// grows as data arrives.
+pub fn inflate(input: &[u8], expected_len: usize) -> ZipResult<Vec<u8>> {
+    let mut reader = BitReader::new(input);
+    let mut out = Vec::with_capacity(expected_len.min(input.len().saturating_mul(INITIAL_RATIO)));
+
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => stored_block(&mut reader, &mut out, expected_len)?,
+            1 => {
+                let (lit, dist) = fixed_tables()?;
+                codes(&mut reader, &mut out, expected_len, &lit, &dist)?;
+            }
+            2 => {
+                let (lit, dist) = dynamic_tables(&mut reader)?;
+                codes(&mut reader, &mut out, expected_len, &lit, &dist)?;
+            }
+            _ => return Err(ZipError::InvalidBlockType),
+        }
+
+        if last {
+            break;
+        }
+    }
+
+    Ok(out)
+}
+
+fn check_room(out: &[u8], extra: usize, expected_len: usize) -> ZipResult<()> {
+    if out.len() + extra > expected_len {
+        return Err(ZipError::InflateOverflow { limit: expected_len });
+    }
+    Ok(())
+}
+
+fn stored_block(reader: &mut BitReader, out: &mut Vec<u8>, expected_len: usize) -> ZipResult<()> {
+    reader.align();
+    let header = reader.bytes(4)?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    let nlen = u16::from_le_bytes([header[2], header[3]]);
+    if len != !nlen {
+        return Err(ZipError::InvalidStoredLength);
+    }
+
+    check_room(out, len as usize, expected_len)?;
+    out.extend_from_slice(reader.bytes(len as usize)?);
+    Ok(())
+}
+
+fn fixed_tables() -> ZipResult<(Huffman, Huffman)> {
+    let mut lengths = [0u8; FIXED_LIT_CODES];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; MAX_DIST_CODES])?))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> ZipResult<(Huffman, Huffman)> {
+    let nlen = reader.bits(5)? as usize + 257;
+    let ndist = reader.bits(5)? as usize + 1;
+    let ncode = reader.bits(4)? as usize + 4;
+    if nlen > MAX_LIT_CODES || ndist > MAX_DIST_CODES {
+        return Err(ZipError::InvalidHuffmanTable);
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..ncode] {
+        code_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; nlen + ndist];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_length_code.decode(reader)?;
+        if symbol < 16 {
+            lengths[index] = symbol as u8;
+            index += 1;
+            continue;
+        }
+
+        let (value, repeat) = match symbol {
+            16 => {
+                let previous = *index
+                    .checked_sub(1)
+                    .and_then(|i| lengths.get(i))
+                    .ok_or(ZipError::InvalidHuffmanTable)?;
+                (previous, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+
+        if index + repeat > lengths.len() {
+            return Err(ZipError::InvalidHuffmanTable);
+        }
+        lengths[index..index + repeat].fill(value);
+        index += repeat;
+    }
+
+    // 没有块结束码的块永远无法终止。
+    if lengths[256] == 0 {
+        return Err(ZipError::InvalidHuffmanTable);
+    }
+
+    Ok((Huffman::new(&lengths[..nlen])?, Huffman::new(&lengths[nlen..])?))
+}
+
+fn codes(
+    reader: &mut BitReader,
+    out: &mut Vec<u8>,
+    expected_len: usize,
+    lit: &Huffman,
+    dist: &Huffman,
+) -> ZipResult<()> {
+    loop {
+        let symbol = lit.decode(reader)? as usize;
+        match symbol {
+            0..=255 => {
+                check_room(out, 1, expected_len)?;
+                out.push(symbol as u8);
+            }
+
+            256 => return Ok(()),
+
+            _ => {
+                let symbol = symbol - 257;
+                if symbol >= LENGTH_BASE.len() {
+                    return Err(ZipError::InvalidCode);
+                }
+                let len = LENGTH_BASE[symbol] as usize
+                    + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+
+                let symbol = dist.decode(reader)? as usize;
+                if symbol >= DIST_BASE.len() {
+                    return Err(ZipError::InvalidDistance);
+                }
+                let distance = DIST_BASE[symbol] as usize
+                    + reader.bits(DIST_EXTRA[symbol] as u32)? as usize;
+                if distance > out.len() {
+                    return Err(ZipError::InvalidDistance);
+                }
+
+                check_room(out, len, expected_len)?;
+                // 源区间可能与目标重叠（distance < len），只能逐字节复制。
+                let start = out.len() - distance;
+                for i in 0..len {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inflates_stored_block() {
+        let input = [0x01, 0x07, 0x00, 0xf8, 0xff, b's', b't', b'o', b'r', b'e', b'd', b'!'];
+        assert_eq!(inflate(&input, 7).unwrap(), b"stored!");
+    }
+
+    #[test]
+    fn inflates_fixed_huffman_block_with_back_references() {
+        let input = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0x01];
+        let expected = b"hello hello hello hello";
+        assert_eq!(inflate(&input, expected.len()).unwrap(), expected);
+    }
+
+    #[test]
+    fn inflates_dynamic_huffman_block() {
+        let input = [
+            0x2b, 0xc9, 0x48, 0x55, 0x28, 0x2c, 0xcd, 0x4c, 0xce, 0x56, 0x48, 0x2a, 0xca, 0x2f,
+            0xcf, 0x53, 0x48, 0xcb, 0xaf, 0x50, 0xc8, 0x2a, 0xcd, 0x2d, 0x28, 0x56, 0xc8, 0x2f,
+            0x4b, 0x2d, 0x52, 0x28, 0x01, 0x4a, 0xe7, 0x24, 0x56, 0x55, 0x2a, 0xa4, 0xe4, 0xa7,
+            0x5b, 0x83, 0x79, 0xb4, 0x51, 0xcc, 0xc0, 0xc8, 0xc4, 0xcc, 0xc2, 0xca, 0xc6, 0xce,
+            0xc1, 0xc9, 0xc5, 0xcd, 0xc3, 0xcb, 0xc7, 0x2f, 0x20, 0x28, 0x24, 0x2c, 0x22, 0x2a,
+            0x26, 0x2e, 0x21, 0x29, 0x25, 0x2d, 0x23, 0x2b, 0x27, 0xaf, 0xa0, 0xa8, 0xa4, 0xac,
+            0xa2, 0xaa, 0xa6, 0xae, 0xa1, 0xa9, 0xa5, 0xad, 0xa3, 0xab, 0xa7, 0x6f, 0x60, 0x68,
+            0x64, 0x6c, 0x62, 0x6a, 0x66, 0x6e, 0x61, 0x69, 0x65, 0x6d, 0x63, 0x6b, 0x67, 0x0f,
+            0x00,
+        ];
+        let mut expected = b"the quick brown fox jumps over the lazy dog; ".repeat(3);
+        expected.extend(0u8..64);
+
+        assert_eq!(inflate(&input, expected.len()).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_output_longer_than_declared() {
+        let input = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0x01];
+        assert!(matches!(inflate(&input, 10), Err(ZipError::InflateOverflow { limit: 10 })));
+    }
+
+    #[test]
+    fn declared_size_does_not_drive_the_allocation() {
+        let input = [0x01, 0x07, 0x00, 0xf8, 0xff, b's', b't', b'o', b'r', b'e', b'd', b'!'];
+        let out = inflate(&input, u32::MAX as usize).unwrap();
+
+        assert_eq!(out, b"stored!");
+        assert!(out.capacity() <= input.len() * INITIAL_RATIO);
+    }
+
+    #[test]
+    fn rejects_truncated_stream() {
+        let input = [0xcb, 0x48, 0xcd];
+        assert!(matches!(inflate(&input, 23), Err(ZipError::Truncated)));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod crc32;
+pub mod inflate;
+pub mod zip_archive;
+pub mod zip_error;
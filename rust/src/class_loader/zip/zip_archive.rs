@@ -0,0 +1,335 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::class_loader::zip::{
+    crc32::crc32,
+    inflate::inflate,
+    zip_error::{ZipError, ZipResult},
+};
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR_SIG: u32 = 0x0605_4b50;
+
+const LOCAL_HEADER_LEN: usize = 30;
+const CENTRAL_HEADER_LEN: usize = 46;
+const END_OF_CENTRAL_DIR_LEN: usize = 22;
+const MAX_COMMENT_LEN: usize = u16::MAX as usize;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+const FLAG_ENCRYPTED: u16 = 0x0001;
+
+/// Central-directory record of one member.
+#[derive(Debug, Clone)]
+struct ZipEntry {
+    flags: u16,
+    method: u16,
+    crc32: u32,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// A zip (or jar) file held in memory together with its central-directory
+/// index, so member lookups are a single hash probe.
+pub struct ZipArchive {
+    data: Vec<u8>,
+    entries: HashMap<String, ZipEntry>,
+}
+
+impl std::fmt::Debug for ZipArchive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZipArchive")
+            .field("len", &self.data.len())
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> ZipResult<u16> {
+    let bytes = data.get(offset..offset + 2).ok_or(ZipError::Truncated)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> ZipResult<u32> {
+    let bytes = data.get(offset..offset + 4).ok_or(ZipError::Truncated)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn expect_sig(data: &[u8], offset: usize, sig: u32) -> ZipResult<()> {
+    if u32_at(data, offset)? != sig {
+        return Err(ZipError::InvalidSignature { offset });
+    }
+    Ok(())
+}
+
+/// Zip64 archives mark overflowing 16/32-bit fields with all-ones.
+fn check_not_zip64_u32(value: u32) -> ZipResult<usize> {
+    if value == u32::MAX {
+        return Err(ZipError::UnsupportedZip64);
+    }
+    Ok(value as usize)
+}
+
+impl ZipArchive {
+    pub fn open(path: &Path) -> ZipResult<Self> {
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> ZipResult<Self> {
+        let eocd = Self::find_end_of_central_dir(&data)?;
+
+        let this_disk = u16_at(&data, eocd + 4)?;
+        let cd_disk = u16_at(&data, eocd + 6)?;
+        let disk_entries = u16_at(&data, eocd + 8)?;
+        let total_entries = u16_at(&data, eocd + 10)?;
+        if this_disk != 0 || cd_disk != 0 || disk_entries != total_entries {
+            return Err(ZipError::UnsupportedMultiDisk);
+        }
+        if total_entries == u16::MAX {
+            return Err(ZipError::UnsupportedZip64);
+        }
+
+        let cd_size = check_not_zip64_u32(u32_at(&data, eocd + 12)?)?;
+        let cd_offset = check_not_zip64_u32(u32_at(&data, eocd + 16)?)?;
+        if cd_offset.checked_add(cd_size).is_none_or(|end| end > eocd) {
+            return Err(ZipError::Truncated);
+        }
+
+        let mut entries = HashMap::with_capacity(total_entries as usize);
+        let mut offset = cd_offset;
+        for _ in 0..total_entries {
+            expect_sig(&data, offset, CENTRAL_HEADER_SIG)?;
+
+            let flags = u16_at(&data, offset + 8)?;
+            let method = u16_at(&data, offset + 10)?;
+            let crc32 = u32_at(&data, offset + 16)?;
+            let compressed_size = check_not_zip64_u32(u32_at(&data, offset + 20)?)?;
+            let uncompressed_size = check_not_zip64_u32(u32_at(&data, offset + 24)?)?;
+            let name_len = u16_at(&data, offset + 28)? as usize;
+            let extra_len = u16_at(&data, offset + 30)? as usize;
+            let comment_len = u16_at(&data, offset + 32)? as usize;
+            let local_header_offset = check_not_zip64_u32(u32_at(&data, offset + 42)?)?;
+
+            let name_start = offset + CENTRAL_HEADER_LEN;
+            let name = data
+                .get(name_start..name_start + name_len)
+                .ok_or(ZipError::Truncated)?;
+            // 名字按 UTF-8 处理；jar 工具总是设置 UTF-8 标志位。
+            let name = String::from_utf8_lossy(name).into_owned();
+
+            // 与 java.util.zip 一致：同名成员以第一个为准。
+            entries.entry(name).or_insert(ZipEntry {
+                flags,
+                method,
+                crc32,
+                compressed_size,
+                uncompressed_size,
+                local_header_offset,
+            });
+
+            offset = name_start + name_len + extra_len + comment_len;
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    /// The end-of-central-directory record sits at the very end, followed
+    /// only by an optional comment of at most 64 KiB.
+    fn find_end_of_central_dir(data: &[u8]) -> ZipResult<usize> {
+        if data.len() < END_OF_CENTRAL_DIR_LEN {
+            return Err(ZipError::NoEndOfCentralDirectory);
+        }
+
+        let last = data.len() - END_OF_CENTRAL_DIR_LEN;
+        let first = last.saturating_sub(MAX_COMMENT_LEN);
+        (first..=last)
+            .rev()
+            .find(|&offset| u32_at(data, offset).is_ok_and(|sig| sig == END_OF_CENTRAL_DIR_SIG))
+            .ok_or(ZipError::NoEndOfCentralDirectory)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Read and decompress member `name`.  `Ok(None)` means there is no such
+    /// member; a corrupt member is an error.
+    pub fn read(&self, name: &str) -> ZipResult<Option<Vec<u8>>> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+
+        if entry.flags & FLAG_ENCRYPTED != 0 {
+            return Err(ZipError::Encrypted(name.into()));
+        }
+
+        // 本地头里的 name/extra 长度可能与中央目录不同，必须重新读取。
+        let header = entry.local_header_offset;
+        expect_sig(&self.data, header, LOCAL_HEADER_SIG)?;
+        let name_len = u16_at(&self.data, header + 26)? as usize;
+        let extra_len = u16_at(&self.data, header + 28)? as usize;
+
+        let start = header + LOCAL_HEADER_LEN + name_len + extra_len;
+        let raw = start
+            .checked_add(entry.compressed_size)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or(ZipError::Truncated)?;
+
+        let bytes = match entry.method {
+            METHOD_STORED => raw.to_vec(),
+            METHOD_DEFLATED => inflate(raw, entry.uncompressed_size)?,
+            other => return Err(ZipError::UnsupportedCompression(other)),
+        };
+
+        if bytes.len() != entry.uncompressed_size {
+            return Err(ZipError::SizeMismatch {
+                name: name.into(),
+                expected: entry.uncompressed_size,
+                actual: bytes.len(),
+            });
+        }
+
+        let actual = crc32(&bytes);
+        if actual != entry.crc32 {
+            return Err(ZipError::CrcMismatch {
+                name: name.into(),
+                expected: entry.crc32,
+                actual,
+            });
+        }
+
+        Ok(Some(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Raw deflate of `b"hello hello hello hello"`.
+    const HELLO_DEFLATED: [u8; 10] = [0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0x01];
+    const HELLO: &[u8] = b"hello hello hello hello";
+
+    struct Member<'a> {
+        name: &'a str,
+        method: u16,
+        crc32: u32,
+        raw: &'a [u8],
+        uncompressed_size: usize,
+    }
+
+    fn stored<'a>(name: &'a str, data: &'a [u8]) -> Member<'a> {
+        Member {
+            name,
+            method: METHOD_STORED,
+            crc32: crc32(data),
+            raw: data,
+            uncompressed_size: data.len(),
+        }
+    }
+
+    /// Minimal zip writer; the local headers carry an extra field the central
+    /// directory does not, like archives produced by `jar`.
+    fn build_zip(members: &[Member]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut central = Vec::new();
+
+        for member in members {
+            let offset = out.len() as u32;
+            let local_extra = [0xfe, 0xca, 0x00, 0x00];
+
+            out.extend_from_slice(&LOCAL_HEADER_SIG.to_le_bytes());
+            out.extend_from_slice(&[20, 0, 0, 0]);
+            out.extend_from_slice(&member.method.to_le_bytes());
+            out.extend_from_slice(&[0; 4]);
+            out.extend_from_slice(&member.crc32.to_le_bytes());
+            out.extend_from_slice(&(member.raw.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(member.uncompressed_size as u32).to_le_bytes());
+            out.extend_from_slice(&(member.name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&(local_extra.len() as u16).to_le_bytes());
+            out.extend_from_slice(member.name.as_bytes());
+            out.extend_from_slice(&local_extra);
+            out.extend_from_slice(member.raw);
+
+            central.extend_from_slice(&CENTRAL_HEADER_SIG.to_le_bytes());
+            central.extend_from_slice(&[20, 0, 20, 0, 0, 0]);
+            central.extend_from_slice(&member.method.to_le_bytes());
+            central.extend_from_slice(&[0; 4]);
+            central.extend_from_slice(&member.crc32.to_le_bytes());
+            central.extend_from_slice(&(member.raw.len() as u32).to_le_bytes());
+            central.extend_from_slice(&(member.uncompressed_size as u32).to_le_bytes());
+            central.extend_from_slice(&(member.name.len() as u16).to_le_bytes());
+            central.extend_from_slice(&[0; 12]);
+            central.extend_from_slice(&offset.to_le_bytes());
+            central.extend_from_slice(member.name.as_bytes());
+        }
+
+        let cd_offset = out.len() as u32;
+        out.extend_from_slice(&central);
+        out.extend_from_slice(&END_OF_CENTRAL_DIR_SIG.to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+        out.extend_from_slice(&cd_offset.to_le_bytes());
+        out.extend_from_slice(&7u16.to_le_bytes());
+        out.extend_from_slice(b"comment");
+        out
+    }
+
+    #[test]
+    fn reads_stored_and_deflated_members() {
+        let zip = build_zip(&[
+            stored("p/", b""),
+            stored("p/Stored.txt", b"stored entry"),
+            Member {
+                name: "p/Deflated.txt",
+                method: METHOD_DEFLATED,
+                crc32: crc32(HELLO),
+                raw: &HELLO_DEFLATED,
+                uncompressed_size: HELLO.len(),
+            },
+        ]);
+        let archive = ZipArchive::from_bytes(zip).unwrap();
+
+        assert_eq!(archive.len(), 3);
+        assert!(archive.contains("p/"));
+        assert_eq!(archive.read("p/Stored.txt").unwrap().unwrap(), b"stored entry");
+        assert_eq!(archive.read("p/Deflated.txt").unwrap().unwrap(), HELLO);
+        assert!(archive.read("p/Missing.txt").unwrap().is_none());
+    }
+
+    #[test]
+    fn detects_crc_mismatch() {
+        let mut member = stored("A.class", b"payload");
+        member.crc32 ^= 1;
+        let archive = ZipArchive::from_bytes(build_zip(&[member])).unwrap();
+
+        assert!(matches!(
+            archive.read("A.class"),
+            Err(ZipError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_files_without_central_directory() {
+        assert!(matches!(
+            ZipArchive::from_bytes(b"not a zip file at all, just some bytes".to_vec()),
+            Err(ZipError::NoEndOfCentralDirectory)
+        ));
+    }
+}
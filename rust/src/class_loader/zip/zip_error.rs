@@ -0,0 +1,63 @@
+use std::{error::Error, fmt};
+
+#[derive(Debug, Clone)]
+pub enum ZipError {
+    Io(std::io::ErrorKind),
+    /// No end-of-central-directory record in the last 64 KiB of the file.
+    NoEndOfCentralDirectory,
+    Truncated,
+    InvalidSignature { offset: usize },
+    UnsupportedMultiDisk,
+    UnsupportedZip64,
+    UnsupportedCompression(u16),
+    Encrypted(String),
+
+    // ── deflate stream ──
+    InvalidBlockType,
+    InvalidStoredLength,
+    InvalidHuffmanTable,
+    InvalidCode,
+    InvalidDistance,
+    /// The stream inflates to more than the size recorded in the directory.
+    InflateOverflow { limit: usize },
+
+    SizeMismatch { name: String, expected: usize, actual: usize },
+    CrcMismatch { name: String, expected: u32, actual: u32 },
+}
+
+impl From<std::io::Error> for ZipError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value.kind())
+    }
+}
+
+pub type ZipResult<T> = Result<T, ZipError>;
+
+impl fmt::Display for ZipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "I/O error: {kind}"),
+            Self::NoEndOfCentralDirectory => f.write_str("no end of central directory record"),
+            Self::Truncated => f.write_str("archive is truncated"),
+            Self::InvalidSignature { offset } => write!(f, "invalid record signature at offset {offset}"),
+            Self::UnsupportedMultiDisk => f.write_str("multi-disk archives are not supported"),
+            Self::UnsupportedZip64 => f.write_str("ZIP64 archives are not supported"),
+            Self::UnsupportedCompression(method) => write!(f, "unsupported compression method {method}"),
+            Self::Encrypted(name) => write!(f, "entry {name} is encrypted"),
+            Self::InvalidBlockType => f.write_str("invalid deflate block type"),
+            Self::InvalidStoredLength => f.write_str("invalid stored block length"),
+            Self::InvalidHuffmanTable => f.write_str("invalid Huffman table"),
+            Self::InvalidCode => f.write_str("invalid deflate code"),
+            Self::InvalidDistance => f.write_str("invalid deflate distance"),
+            Self::InflateOverflow { limit } => write!(f, "entry inflates past its size of {limit} bytes"),
+            Self::SizeMismatch { name, expected, actual } => {
+                write!(f, "entry {name} is {actual} bytes, expected {expected}")
+            }
+            Self::CrcMismatch { name, expected, actual } => {
+                write!(f, "entry {name} has CRC {actual:#010x}, expected {expected:#010x}")
+            }
+        }
+    }
+}
+
+impl Error for ZipError {}
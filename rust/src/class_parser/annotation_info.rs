@@ -0,0 +1,337 @@
+//! `annotation`, `element_value` and `type_annotation` structures
+//! (JVMS 4.7.16 - 4.7.20).
+
+use crate::class_parser::{
+    attr_info::{check_cp_idx, is_utf8},
+    class_reader::ClassReader,
+    class_writer::ClassWriter,
+    cp_info::ConstantPoolInfo,
+    parse_error::{ParseError, ParseResult},
+    write_error::WriteResult,
+};
+
+#[derive(Debug, Clone)]
+pub struct AnnotationInfo {
+    /// Utf8 field descriptor of the annotation interface.
+    pub type_idx: u16,
+    pub element_value_pairs: Vec<ElementValuePairInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElementValuePairInfo {
+    pub element_name_idx: u16,
+    pub value: ElementValueInfo,
+}
+
+#[derive(Debug, Clone)]
+pub enum ElementValueInfo {
+    /// `tag` is one of `B C D F I J S Z s`.
+    Const { tag: u8, const_value_idx: u16 },
+
+    Enum { type_name_idx: u16, const_name_idx: u16 },
+
+    /// Utf8 return descriptor, e.g. `Ljava/lang/String;` or `V`.
+    Class { class_info_idx: u16 },
+
+    Annotation(AnnotationInfo),
+
+    Array(Vec<ElementValueInfo>),
+}
+
+/// `target_info` of a type annotation, selected by `target_type`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetInfo {
+    TypeParameter { type_parameter_idx: u8 },
+    Supertype { supertype_idx: u16 },
+    TypeParameterBound { type_parameter_idx: u8, bound_idx: u8 },
+    Empty,
+    FormalParameter { formal_parameter_idx: u8 },
+    Throws { throws_type_idx: u16 },
+    /// `(start_pc, length, index)` ranges of a local variable.
+    LocalVar(Vec<(u16, u16, u16)>),
+    Catch { exception_table_idx: u16 },
+    Offset { offset: u16 },
+    TypeArgument { offset: u16, type_argument_idx: u8 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypePathEntryInfo {
+    pub type_path_kind: u8,
+    pub type_argument_idx: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeAnnotationInfo {
+    pub target_type: u8,
+    pub target_info: TargetInfo,
+    pub target_path: Vec<TypePathEntryInfo>,
+    pub annotation: AnnotationInfo,
+}
+
+impl AnnotationInfo {
+    pub(super) fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        let type_idx = check_cp_idx(rd.read_u16()?, cp, is_utf8)?;
+
+        let count = rd.read_u16()?;
+        let mut element_value_pairs = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            element_value_pairs.push(ElementValuePairInfo {
+                element_name_idx: check_cp_idx(rd.read_u16()?, cp, is_utf8)?,
+                value: ElementValueInfo::read(rd, cp)?,
+            });
+        }
+
+        Ok(Self {
+            type_idx,
+            element_value_pairs,
+        })
+    }
+
+    /// `u2 num_annotations` followed by the annotations.
+    pub(super) fn read_list(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Vec<Self>> {
+        let count = rd.read_u16()?;
+        (0..count).map(|_| Self::read(rd, cp)).collect()
+    }
+
+    pub(super) fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_u16(self.type_idx);
+        wr.write_table("element_value_pairs", &self.element_value_pairs, |wr, x| {
+            wr.write_u16(x.element_name_idx);
+            x.value.write(wr)
+        })
+    }
+
+    pub(super) fn write_list(wr: &mut ClassWriter, list: &[Self]) -> WriteResult<()> {
+        wr.write_table("annotations", list, |wr, x| x.write(wr))
+    }
+}
+
+impl ElementValueInfo {
+    pub(super) fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        let tag = rd.read_u8()?;
+
+        let value = match tag {
+            b'B' | b'C' | b'I' | b'S' | b'Z' => Self::Const {
+                tag,
+                const_value_idx: check_cp_idx(rd.read_u16()?, cp, |x| {
+                    matches!(x, ConstantPoolInfo::IntegerInfo { .. })
+                })?,
+            },
+            b'D' => Self::Const {
+                tag,
+                const_value_idx: check_cp_idx(rd.read_u16()?, cp, |x| {
+                    matches!(x, ConstantPoolInfo::DoubleInfo { .. })
+                })?,
+            },
+            b'F' => Self::Const {
+                tag,
+                const_value_idx: check_cp_idx(rd.read_u16()?, cp, |x| {
+                    matches!(x, ConstantPoolInfo::FloatInfo { .. })
+                })?,
+            },
+            b'J' => Self::Const {
+                tag,
+                const_value_idx: check_cp_idx(rd.read_u16()?, cp, |x| {
+                    matches!(x, ConstantPoolInfo::LongInfo { .. })
+                })?,
+            },
+            b's' => Self::Const {
+                tag,
+                const_value_idx: check_cp_idx(rd.read_u16()?, cp, is_utf8)?,
+            },
+
+            b'e' => Self::Enum {
+                type_name_idx: check_cp_idx(rd.read_u16()?, cp, is_utf8)?,
+                const_name_idx: check_cp_idx(rd.read_u16()?, cp, is_utf8)?,
+            },
+
+            b'c' => Self::Class {
+                class_info_idx: check_cp_idx(rd.read_u16()?, cp, is_utf8)?,
+            },
+
+            b'@' => Self::Annotation(rd.descend(|rd| AnnotationInfo::read(rd, cp))?),
+
+            b'[' => {
+                let count = rd.read_u16()?;
+                let values = rd.descend(|rd| (0..count).map(|_| Self::read(rd, cp)).collect::<ParseResult<_>>())?;
+
+                Self::Array(values)
+            }
+
+            _ => return Err(ParseError::InvalidElementValueTag(tag)),
+        };
+
+        Ok(value)
+    }
+
+    pub(super) fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        match self {
+            Self::Const { tag, const_value_idx } => {
+                wr.write_u8(*tag);
+                wr.write_u16(*const_value_idx);
+            }
+            Self::Enum {
+                type_name_idx,
+                const_name_idx,
+            } => {
+                wr.write_u8(b'e');
+                wr.write_u16(*type_name_idx);
+                wr.write_u16(*const_name_idx);
+            }
+            Self::Class { class_info_idx } => {
+                wr.write_u8(b'c');
+                wr.write_u16(*class_info_idx);
+            }
+            Self::Annotation(annotation) => {
+                wr.write_u8(b'@');
+                annotation.write(wr)?;
+            }
+            Self::Array(values) => {
+                wr.write_u8(b'[');
+                wr.write_table("array element values", values, |wr, x| x.write(wr))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TargetInfo {
+    fn read(rd: &mut ClassReader, target_type: u8) -> ParseResult<Self> {
+        let target = match target_type {
+            0x00 | 0x01 => Self::TypeParameter {
+                type_parameter_idx: rd.read_u8()?,
+            },
+            0x10 => Self::Supertype {
+                supertype_idx: rd.read_u16()?,
+            },
+            0x11 | 0x12 => Self::TypeParameterBound {
+                type_parameter_idx: rd.read_u8()?,
+                bound_idx: rd.read_u8()?,
+            },
+            0x13..=0x15 => Self::Empty,
+            0x16 => Self::FormalParameter {
+                formal_parameter_idx: rd.read_u8()?,
+            },
+            0x17 => Self::Throws {
+                throws_type_idx: rd.read_u16()?,
+            },
+            0x40 | 0x41 => {
+                let count = rd.read_u16()?;
+                let mut table = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    table.push((rd.read_u16()?, rd.read_u16()?, rd.read_u16()?));
+                }
+
+                Self::LocalVar(table)
+            }
+            0x42 => Self::Catch {
+                exception_table_idx: rd.read_u16()?,
+            },
+            0x43..=0x46 => Self::Offset {
+                offset: rd.read_u16()?,
+            },
+            0x47..=0x4B => Self::TypeArgument {
+                offset: rd.read_u16()?,
+                type_argument_idx: rd.read_u8()?,
+            },
+
+            _ => return Err(ParseError::InvalidTargetType(target_type)),
+        };
+
+        Ok(target)
+    }
+
+    fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        match self {
+            Self::TypeParameter { type_parameter_idx } => wr.write_u8(*type_parameter_idx),
+            Self::Supertype { supertype_idx } => wr.write_u16(*supertype_idx),
+            Self::TypeParameterBound {
+                type_parameter_idx,
+                bound_idx,
+            } => {
+                wr.write_u8(*type_parameter_idx);
+                wr.write_u8(*bound_idx);
+            }
+            Self::Empty => {}
+            Self::FormalParameter { formal_parameter_idx } => wr.write_u8(*formal_parameter_idx),
+            Self::Throws { throws_type_idx } => wr.write_u16(*throws_type_idx),
+            Self::LocalVar(table) => {
+                wr.write_table("localvar_target", table, |wr, &(start_pc, length, index)| {
+                    wr.write_u16(start_pc);
+                    wr.write_u16(length);
+                    wr.write_u16(index);
+                    Ok(())
+                })?;
+            }
+            Self::Catch { exception_table_idx } => wr.write_u16(*exception_table_idx),
+            Self::Offset { offset } => wr.write_u16(*offset),
+            Self::TypeArgument {
+                offset,
+                type_argument_idx,
+            } => {
+                wr.write_u16(*offset);
+                wr.write_u8(*type_argument_idx);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// JVMS table 4.7.20-C: targets 0x40 and up annotate expressions and
+    /// only appear in the `Code` attribute.
+    pub fn is_code_target(target_type: u8) -> bool {
+        target_type >= 0x40
+    }
+}
+
+impl TypeAnnotationInfo {
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo], in_code: bool) -> ParseResult<Self> {
+        let target_type = rd.read_u8()?;
+        if TargetInfo::is_code_target(target_type) != in_code {
+            return Err(ParseError::InvalidTargetType(target_type));
+        }
+
+        let target_info = TargetInfo::read(rd, target_type)?;
+
+        let path_len = rd.read_u8()?;
+        let mut target_path = Vec::with_capacity(path_len as usize);
+        for _ in 0..path_len {
+            target_path.push(TypePathEntryInfo {
+                type_path_kind: rd.read_u8()?,
+                type_argument_idx: rd.read_u8()?,
+            });
+        }
+
+        Ok(Self {
+            target_type,
+            target_info,
+            target_path,
+            annotation: AnnotationInfo::read(rd, cp)?,
+        })
+    }
+
+    pub(super) fn read_list(
+        rd: &mut ClassReader,
+        cp: &[ConstantPoolInfo],
+        in_code: bool,
+    ) -> ParseResult<Vec<Self>> {
+        let count = rd.read_u16()?;
+        (0..count).map(|_| Self::read(rd, cp, in_code)).collect()
+    }
+
+    fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_u8(self.target_type);
+        self.target_info.write(wr)?;
+        wr.write_u8_len("type_path", self.target_path.len())?;
+        for entry in &self.target_path {
+            wr.write_u8(entry.type_path_kind);
+            wr.write_u8(entry.type_argument_idx);
+        }
+        self.annotation.write(wr)
+    }
+
+    pub(super) fn write_list(wr: &mut ClassWriter, list: &[Self]) -> WriteResult<()> {
+        wr.write_table("type annotations", list, |wr, x| x.write(wr))
+    }
+}
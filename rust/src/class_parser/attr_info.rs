@@ -1,8 +1,12 @@
 use crate::class_parser::{
-    class_file::read_attrs,
+    annotation_info::{AnnotationInfo, ElementValueInfo, TypeAnnotationInfo},
+    class_file::{read_attrs, write_attrs},
     class_reader::ClassReader,
+    class_writer::ClassWriter,
     cp_info::ConstantPoolInfo,
     parse_error::{ParseError, ParseResult},
+    stack_map_info::StackMapFrameInfo,
+    write_error::{WriteError, WriteResult},
 };
 
 pub struct ExceptionTableEntryInfo {
@@ -23,12 +27,223 @@ impl ExceptionTableEntryInfo {
     }
 }
 
+pub struct LineNumberTableEntryInfo {
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+impl LineNumberTableEntryInfo {
+    fn read(rd: &mut ClassReader) -> ParseResult<Self> {
+        Ok(Self {
+            start_pc: rd.read_u16()?,
+            line_number: rd.read_u16()?,
+        })
+    }
+}
+
+/// Shared by `LocalVariableTable` (`desc_idx` names a field descriptor) and
+/// `LocalVariableTypeTable` (it names a generic signature).
+pub struct LocalVariableTableEntryInfo {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name_idx: u16,
+    pub desc_idx: u16,
+    pub index: u16,
+}
+
+impl LocalVariableTableEntryInfo {
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        Ok(Self {
+            start_pc: rd.read_u16()?,
+            length: rd.read_u16()?,
+            name_idx: read_cp_idx(rd, cp, is_utf8)?,
+            desc_idx: read_cp_idx(rd, cp, is_utf8)?,
+            index: rd.read_u16()?,
+        })
+    }
+}
+
+pub struct InnerClassInfo {
+    pub inner_class_info_idx: u16,
+    /// 0 for top-level, local and anonymous classes.
+    pub outer_class_info_idx: u16,
+    /// 0 for anonymous classes.
+    pub inner_name_idx: u16,
+    pub inner_class_acc_flags: u16,
+}
+
+impl InnerClassInfo {
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        Ok(Self {
+            inner_class_info_idx: read_cp_idx(rd, cp, is_class)?,
+            outer_class_info_idx: read_optional_cp_idx(rd, cp, is_class)?,
+            inner_name_idx: read_optional_cp_idx(rd, cp, is_utf8)?,
+            inner_class_acc_flags: rd.read_u16()?,
+        })
+    }
+}
+
+pub struct MethodParameterInfo {
+    /// 0 for a parameter without a name.
+    pub name_idx: u16,
+    pub acc_flags: u16,
+}
+
+pub struct BootstrapMethodInfo {
+    pub bootstrap_method_ref: u16,
+    pub bootstrap_arguments: Vec<u16>,
+}
+
+impl BootstrapMethodInfo {
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        let bootstrap_method_ref = read_cp_idx(rd, cp, |_| true)?;
+
+        let argc = rd.read_u16()?;
+        let mut bootstrap_arguments = Vec::with_capacity(argc as usize);
+        for _ in 0..argc {
+            bootstrap_arguments.push(read_cp_idx(rd, cp, |_| true)?);
+        }
+
+        Ok(Self {
+            bootstrap_method_ref,
+            bootstrap_arguments,
+        })
+    }
+}
+
+pub struct ModuleRequiresInfo {
+    pub requires_idx: u16,
+    pub requires_flags: u16,
+    /// 0 when the version is unknown.
+    pub requires_version_idx: u16,
+}
+
+/// An `exports` or `opens` entry; `to_idxs` is empty for an unqualified one.
+pub struct ModuleExportsInfo {
+    pub package_idx: u16,
+    pub flags: u16,
+    pub to_idxs: Vec<u16>,
+}
+
+pub struct ModuleProvidesInfo {
+    pub provides_idx: u16,
+    pub with_idxs: Vec<u16>,
+}
+
+/// JVMS 4.7.25, found only in `module-info` classes.
+pub struct ModuleAttrInfo {
+    pub module_name_idx: u16,
+    pub module_flags: u16,
+    /// 0 when the module has no version.
+    pub module_version_idx: u16,
+    pub requires: Vec<ModuleRequiresInfo>,
+    pub exports: Vec<ModuleExportsInfo>,
+    pub opens: Vec<ModuleExportsInfo>,
+    /// Service interfaces, as `CONSTANT_Class` indices.
+    pub uses: Vec<u16>,
+    pub provides: Vec<ModuleProvidesInfo>,
+}
+
+/// `u2 count` followed by `count` indices whose entries satisfy `expect`.
+fn read_cp_idx_list(
+    rd: &mut ClassReader,
+    cp: &[ConstantPoolInfo],
+    expect: fn(&ConstantPoolInfo) -> bool,
+) -> ParseResult<Vec<u16>> {
+    let count = rd.read_u16()?;
+    (0..count).map(|_| read_cp_idx(rd, cp, expect)).collect()
+}
+
+impl ModuleExportsInfo {
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        Ok(Self {
+            package_idx: read_cp_idx(rd, cp, is_package)?,
+            flags: rd.read_u16()?,
+            to_idxs: read_cp_idx_list(rd, cp, is_module)?,
+        })
+    }
+
+    fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_u16(self.package_idx);
+        wr.write_u16(self.flags);
+        wr.write_idx_list("exports_to", &self.to_idxs)
+    }
+}
+
+impl ModuleAttrInfo {
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        let module_name_idx = read_cp_idx(rd, cp, is_module)?;
+        let module_flags = rd.read_u16()?;
+        let module_version_idx = read_optional_cp_idx(rd, cp, is_utf8)?;
+
+        let count = rd.read_u16()?;
+        let requires = (0..count)
+            .map(|_| {
+                Ok(ModuleRequiresInfo {
+                    requires_idx: read_cp_idx(rd, cp, is_module)?,
+                    requires_flags: rd.read_u16()?,
+                    requires_version_idx: read_optional_cp_idx(rd, cp, is_utf8)?,
+                })
+            })
+            .collect::<ParseResult<_>>()?;
+
+        let count = rd.read_u16()?;
+        let exports = (0..count).map(|_| ModuleExportsInfo::read(rd, cp)).collect::<ParseResult<_>>()?;
+        let count = rd.read_u16()?;
+        let opens = (0..count).map(|_| ModuleExportsInfo::read(rd, cp)).collect::<ParseResult<_>>()?;
+
+        let uses = read_cp_idx_list(rd, cp, is_class)?;
+
+        let count = rd.read_u16()?;
+        let provides = (0..count)
+            .map(|_| {
+                Ok(ModuleProvidesInfo {
+                    provides_idx: read_cp_idx(rd, cp, is_class)?,
+                    with_idxs: read_cp_idx_list(rd, cp, is_class)?,
+                })
+            })
+            .collect::<ParseResult<_>>()?;
+
+        Ok(Self {
+            module_name_idx,
+            module_flags,
+            module_version_idx,
+            requires,
+            exports,
+            opens,
+            uses,
+            provides,
+        })
+    }
+
+    fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_u16(self.module_name_idx);
+        wr.write_u16(self.module_flags);
+        wr.write_u16(self.module_version_idx);
+
+        wr.write_table("requires", &self.requires, |wr, x| {
+            wr.write_u16(x.requires_idx);
+            wr.write_u16(x.requires_flags);
+            wr.write_u16(x.requires_version_idx);
+            Ok(())
+        })?;
+        wr.write_table("exports", &self.exports, |wr, x| x.write(wr))?;
+        wr.write_table("opens", &self.opens, |wr, x| x.write(wr))?;
+        wr.write_idx_list("uses", &self.uses)?;
+        wr.write_table("provides", &self.provides, |wr, x| {
+            wr.write_u16(x.provides_idx);
+            wr.write_idx_list("provides_with", &x.with_idxs)
+        })
+    }
+}
+
 pub struct CodeAttrInfo {
     pub max_stack: u16,
     pub max_locals: u16,
     pub code: Vec<u8>,
     pub exception_table: Vec<ExceptionTableEntryInfo>,
     pub attrs: Vec<AttrInfo>,
+    pub raw_attrs: Vec<RawAttr>,
 }
 
 impl CodeAttrInfo {
@@ -36,8 +251,12 @@ impl CodeAttrInfo {
         let max_stack = rd.read_u16()?;
         let max_locals = rd.read_u16()?;
 
-        let code_len = rd.read_u32()?;
-        let code = rd.read(code_len as usize)?.to_vec();
+        // JVMS 4.7.3：code_length 必须大于 0 且小于 65536。
+        let code_len = rd.read_u32()? as usize;
+        if code_len == 0 || code_len > rd.limits.max_code_length.min(u16::MAX as usize) {
+            return Err(ParseError::InvalidCodeLength(code_len));
+        }
+        let code = rd.read(code_len)?.to_vec();
 
         let et_len = rd.read_u16()?;
         let mut exception_table = Vec::with_capacity(et_len as usize);
@@ -45,41 +264,226 @@ impl CodeAttrInfo {
             exception_table.push(ExceptionTableEntryInfo::read(rd)?);
         }
 
+        let (attrs, raw_attrs) = read_attrs(rd, cp, AttrLocation::Code)?;
+
         Ok(Self {
             max_stack,
             max_locals,
             code,
             exception_table,
-            attrs: read_attrs(rd, cp)?,
+            attrs,
+            raw_attrs,
         })
     }
+
+    fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_u16(self.max_stack);
+        wr.write_u16(self.max_locals);
+
+        let len = self.code.len();
+        wr.write_u32(u32::try_from(len).map_err(|_| WriteError::TooLong { what: "code", len })?);
+        wr.write(&self.code);
+
+        wr.write_table("exception_table", &self.exception_table, |wr, x| {
+            wr.write_u16(x.start_pc);
+            wr.write_u16(x.end_pc);
+            wr.write_u16(x.handler_pc);
+            wr.write_u16(x.catch_type);
+            Ok(())
+        })?;
+
+        write_attrs(wr, &self.attrs, &self.raw_attrs)
+    }
 }
 
 pub enum AttrInfo {
     ConstantValue { cp_idx: u16 },
 
     Code(CodeAttrInfo),
+
+    /// Inside `Code`; the type states the verifier checks branch targets
+    /// and exception handlers against.
+    StackMapTable(Vec<StackMapFrameInfo>),
+
+    /// Inside `Code`; a method may carry several, to be concatenated.
+    LineNumberTable(Vec<LineNumberTableEntryInfo>),
+
+    /// Inside `Code`.
+    LocalVariableTable(Vec<LocalVariableTableEntryInfo>),
+    /// Inside `Code`; generic signatures for the variables that need one.
+    LocalVariableTypeTable(Vec<LocalVariableTableEntryInfo>),
+
+    SourceFile { sourcefile_idx: u16 },
+
+    /// Checked exceptions a method declares, as `CONSTANT_Class` indices.
+    Exceptions { exception_idxs: Vec<u16> },
+
+    InnerClasses(Vec<InnerClassInfo>),
+
+    /// `method_idx` is 0 unless the class is enclosed by a method or
+    /// constructor; otherwise it names a `CONSTANT_NameAndType`.
+    EnclosingMethod { class_idx: u16, method_idx: u16 },
+
+    Signature { signature_idx: u16 },
+
+    Synthetic,
+
+    Deprecated,
+
+    MethodParameters(Vec<MethodParameterInfo>),
+
+    /// Default value of an annotation interface element.
+    AnnotationDefault(ElementValueInfo),
+
+    BootstrapMethods(Vec<BootstrapMethodInfo>),
+
+    RuntimeVisibleAnnotations(Vec<AnnotationInfo>),
+
+    /// One list per formal parameter, in declaration order.
+    RuntimeVisibleParameterAnnotations(Vec<Vec<AnnotationInfo>>),
+
+    RuntimeVisibleTypeAnnotations(Vec<TypeAnnotationInfo>),
+
+    /// The host of the nest the class belongs to, as a `CONSTANT_Class`
+    /// index (JVMS 4.7.28).
+    NestHost { host_class_idx: u16 },
+
+    /// Classes the host admits to its nest, as `CONSTANT_Class` indices.
+    NestMembers(Vec<u16>),
+
+    Module(ModuleAttrInfo),
+
+    /// Every package of the module, as `CONSTANT_Package` indices.
+    ModulePackages(Vec<u16>),
+
+    ModuleMainClass { main_class_idx: u16 },
 }
 
-impl AttrInfo {
-    pub fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Option<Self>> {
-        let name_idx = rd.read_u16()? as usize;
-        let len = rd.read_u32()? as usize;
-        let payload = rd.read(len)?;
+/// The structure an attribute table belongs to (JVMS 4.7, table 4.7-C).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrLocation {
+    ClassFile,
+    Field,
+    Method,
+    Code,
+}
+
+/// An attribute exactly as it appeared in the class file, kept for
+/// reflection APIs that hand the bytes to Java code.
+#[derive(Debug, Clone)]
+pub struct RawAttr {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Where a predefined attribute may appear and whether it may repeat there.
+/// `None` for attributes this VM does not know, which may appear anywhere.
+pub(super) fn placement(name: &str) -> Option<(&'static [AttrLocation], bool)> {
+    use AttrLocation::*;
+
+    let placement: (&'static [AttrLocation], bool) = match name {
+        "SourceFile" | "InnerClasses" | "EnclosingMethod" | "SourceDebugExtension"
+        | "BootstrapMethods" | "NestHost" | "NestMembers" | "Module" | "ModulePackages"
+        | "ModuleMainClass" => (&[ClassFile], false),
+
+        "ConstantValue" => (&[Field], false),
 
-        if name_idx == 0 || name_idx >= cp.len() {
-            return Err(ParseError::InvalidCPIndex);
+        "Code" | "Exceptions" | "RuntimeVisibleParameterAnnotations"
+        | "RuntimeInvisibleParameterAnnotations" | "AnnotationDefault" | "MethodParameters" => {
+            (&[Method], false)
         }
 
-        let mut pl_rd = ClassReader::new(payload);
+        "Synthetic" | "Deprecated" | "Signature" | "RuntimeVisibleAnnotations"
+        | "RuntimeInvisibleAnnotations" => (&[ClassFile, Field, Method], false),
 
-        let utf8_info = &cp[name_idx];
-        let name = match utf8_info {
-            ConstantPoolInfo::Utf8Info { utf8 } => utf8,
-            _ => return Err(ParseError::InvalidCPType),
-        };
+        "RuntimeVisibleTypeAnnotations" | "RuntimeInvisibleTypeAnnotations" => {
+            (&[ClassFile, Field, Method, Code], false)
+        }
+
+        "StackMapTable" => (&[Code], false),
+        "LineNumberTable" | "LocalVariableTable" | "LocalVariableTypeTable" => (&[Code], true),
+
+        _ => return None,
+    };
 
-        match name.as_str() {
+    Some(placement)
+}
+
+pub(super) fn is_utf8(info: &ConstantPoolInfo) -> bool {
+    matches!(info, ConstantPoolInfo::Utf8Info { .. })
+}
+
+fn is_class(info: &ConstantPoolInfo) -> bool {
+    matches!(info, ConstantPoolInfo::ClassInfo { .. })
+}
+
+fn is_module(info: &ConstantPoolInfo) -> bool {
+    matches!(info, ConstantPoolInfo::ModuleInfo { .. })
+}
+
+fn is_package(info: &ConstantPoolInfo) -> bool {
+    matches!(info, ConstantPoolInfo::PackageInfo { .. })
+}
+
+/// Check that `idx` names a usable entry satisfying `expect`.
+pub(super) fn check_cp_idx(idx: u16, cp: &[ConstantPoolInfo], expect: fn(&ConstantPoolInfo) -> bool) -> ParseResult<u16> {
+    match cp.get(idx as usize) {
+        _ if idx == 0 => Err(ParseError::InvalidCPIndex),
+        Some(ConstantPoolInfo::Unusable) | None => Err(ParseError::InvalidCPIndex),
+        Some(info) if expect(info) => Ok(idx),
+        Some(_) => Err(ParseError::InvalidCPType),
+    }
+}
+
+/// Read a non-zero `u2` constant-pool index whose entry satisfies `expect`.
+fn read_cp_idx(
+    rd: &mut ClassReader,
+    cp: &[ConstantPoolInfo],
+    expect: fn(&ConstantPoolInfo) -> bool,
+) -> ParseResult<u16> {
+    check_cp_idx(rd.read_u16()?, cp, expect)
+}
+
+/// Like `read_cp_idx`, but 0 ("absent") is allowed.
+fn read_optional_cp_idx(
+    rd: &mut ClassReader,
+    cp: &[ConstantPoolInfo],
+    expect: fn(&ConstantPoolInfo) -> bool,
+) -> ParseResult<u16> {
+    match rd.read_u16()? {
+        0 => Ok(0),
+        idx => check_cp_idx(idx, cp, expect),
+    }
+}
+
+/// `u2 count` followed by `count` fixed-size entries; `len` must match.
+fn read_table<T>(
+    rd: &mut ClassReader,
+    len: usize,
+    entry_len: usize,
+    mut read: impl FnMut(&mut ClassReader) -> ParseResult<T>,
+) -> ParseResult<Vec<T>> {
+    let count = rd.read_u16()? as usize;
+    if len != 2 + count * entry_len {
+        return Err(ParseError::InvalidAttrLen(len));
+    }
+
+    (0..count).map(|_| read(rd)).collect()
+}
+
+impl AttrInfo {
+    /// Decode the payload `pl_rd` reads of the attribute called `name` found
+    /// at `location`.  `None` for attributes the VM does not interpret; their
+    /// bytes stay in `RawAttr`.
+    pub(super) fn parse(
+        name: &str,
+        pl_rd: &mut ClassReader,
+        cp: &[ConstantPoolInfo],
+        location: AttrLocation,
+    ) -> ParseResult<Option<Self>> {
+        let len = pl_rd.remaining();
+
+        let attr = match name {
             "ConstantValue" => {
                 if len != 2 {
                     return Err(ParseError::InvalidAttrLen(len));
@@ -90,12 +494,333 @@ impl AttrInfo {
                     return Err(ParseError::InvalidCPIndex);
                 }
 
-                Ok(Some(Self::ConstantValue { cp_idx }))
+                Self::ConstantValue { cp_idx }
+            }
+
+            "Code" => Self::Code(CodeAttrInfo::read(pl_rd, cp)?),
+
+            "StackMapTable" => Self::StackMapTable(StackMapFrameInfo::read_table(pl_rd, cp)?),
+
+            "LineNumberTable" => Self::LineNumberTable(read_table(pl_rd, len, 4, |rd| {
+                LineNumberTableEntryInfo::read(rd)
+            })?),
+
+            "LocalVariableTable" => Self::LocalVariableTable(read_table(pl_rd, len, 10, |rd| {
+                LocalVariableTableEntryInfo::read(rd, cp)
+            })?),
+
+            "LocalVariableTypeTable" => Self::LocalVariableTypeTable(read_table(pl_rd, len, 10, |rd| {
+                LocalVariableTableEntryInfo::read(rd, cp)
+            })?),
+
+            "SourceFile" => {
+                if len != 2 {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                Self::SourceFile {
+                    sourcefile_idx: read_cp_idx(pl_rd, cp, is_utf8)?,
+                }
+            }
+
+            "Exceptions" => Self::Exceptions {
+                exception_idxs: read_table(pl_rd, len, 2, |rd| read_cp_idx(rd, cp, is_class))?,
+            },
+
+            "InnerClasses" => Self::InnerClasses(read_table(pl_rd, len, 8, |rd| {
+                InnerClassInfo::read(rd, cp)
+            })?),
+
+            "EnclosingMethod" => {
+                if len != 4 {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                Self::EnclosingMethod {
+                    class_idx: read_cp_idx(pl_rd, cp, is_class)?,
+                    method_idx: read_optional_cp_idx(pl_rd, cp, |x| {
+                        matches!(x, ConstantPoolInfo::NameAndTypeInfo { .. })
+                    })?,
+                }
+            }
+
+            "Signature" => {
+                if len != 2 {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                Self::Signature {
+                    signature_idx: read_cp_idx(pl_rd, cp, is_utf8)?,
+                }
+            }
+
+            "Synthetic" | "Deprecated" => {
+                if len != 0 {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                if name == "Synthetic" { Self::Synthetic } else { Self::Deprecated }
             }
 
-            "Code" => Ok(Some(Self::Code(CodeAttrInfo::read(&mut pl_rd, cp)?))),
+            "MethodParameters" => {
+                // 参数个数是 u1，不能复用 read_table。
+                let count = pl_rd.read_u8()? as usize;
+                if len != 1 + count * 4 {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                let mut parameters = Vec::with_capacity(count);
+                for _ in 0..count {
+                    parameters.push(MethodParameterInfo {
+                        name_idx: read_optional_cp_idx(pl_rd, cp, is_utf8)?,
+                        acc_flags: pl_rd.read_u16()?,
+                    });
+                }
 
-            _ => Ok(None),
+                Self::MethodParameters(parameters)
+            }
+
+            "AnnotationDefault" => Self::AnnotationDefault(ElementValueInfo::read(pl_rd, cp)?),
+
+            "RuntimeVisibleAnnotations" => {
+                Self::RuntimeVisibleAnnotations(AnnotationInfo::read_list(pl_rd, cp)?)
+            }
+
+            "RuntimeVisibleParameterAnnotations" => {
+                let count = pl_rd.read_u8()?;
+                let parameters = (0..count)
+                    .map(|_| AnnotationInfo::read_list(pl_rd, cp))
+                    .collect::<ParseResult<_>>()?;
+
+                Self::RuntimeVisibleParameterAnnotations(parameters)
+            }
+
+            "RuntimeVisibleTypeAnnotations" => Self::RuntimeVisibleTypeAnnotations(
+                TypeAnnotationInfo::read_list(pl_rd, cp, location == AttrLocation::Code)?,
+            ),
+
+            "BootstrapMethods" => {
+                let count = pl_rd.read_u16()?;
+                let mut methods = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    methods.push(BootstrapMethodInfo::read(pl_rd, cp)?);
+                }
+
+                // 每项长度可变，只能在读完后核对。
+                if !pl_rd.is_empty() {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                Self::BootstrapMethods(methods)
+            }
+
+            "NestHost" => {
+                if len != 2 {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                Self::NestHost {
+                    host_class_idx: read_cp_idx(pl_rd, cp, is_class)?,
+                }
+            }
+
+            "NestMembers" => Self::NestMembers(read_table(pl_rd, len, 2, |rd| read_cp_idx(rd, cp, is_class))?),
+
+            "Module" => Self::Module(ModuleAttrInfo::read(pl_rd, cp)?),
+
+            "ModulePackages" => {
+                Self::ModulePackages(read_table(pl_rd, len, 2, |rd| read_cp_idx(rd, cp, is_package))?)
+            }
+
+            "ModuleMainClass" => {
+                if len != 2 {
+                    return Err(ParseError::InvalidAttrLen(len));
+                }
+
+                Self::ModuleMainClass {
+                    main_class_idx: read_cp_idx(pl_rd, cp, is_class)?,
+                }
+            }
+
+            _ => return Ok(None),
+        };
+
+        // 注解等变长属性只能在解码后核对 attribute_length。
+        if matches!(
+            attr,
+            Self::Code(_)
+                | Self::Module(_)
+                | Self::StackMapTable(_)
+                | Self::AnnotationDefault(_)
+                | Self::RuntimeVisibleAnnotations(_)
+                | Self::RuntimeVisibleParameterAnnotations(_)
+                | Self::RuntimeVisibleTypeAnnotations(_)
+        ) && !pl_rd.is_empty()
+        {
+            return Err(ParseError::InvalidAttrLen(len));
         }
+
+        Ok(Some(attr))
+    }
+
+    /// The attribute name this variant is decoded from.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ConstantValue { .. } => "ConstantValue",
+            Self::Code(_) => "Code",
+            Self::StackMapTable(_) => "StackMapTable",
+            Self::LineNumberTable(_) => "LineNumberTable",
+            Self::LocalVariableTable(_) => "LocalVariableTable",
+            Self::LocalVariableTypeTable(_) => "LocalVariableTypeTable",
+            Self::SourceFile { .. } => "SourceFile",
+            Self::Exceptions { .. } => "Exceptions",
+            Self::InnerClasses(_) => "InnerClasses",
+            Self::EnclosingMethod { .. } => "EnclosingMethod",
+            Self::Signature { .. } => "Signature",
+            Self::Synthetic => "Synthetic",
+            Self::Deprecated => "Deprecated",
+            Self::MethodParameters(_) => "MethodParameters",
+            Self::AnnotationDefault(_) => "AnnotationDefault",
+            Self::BootstrapMethods(_) => "BootstrapMethods",
+            Self::RuntimeVisibleAnnotations(_) => "RuntimeVisibleAnnotations",
+            Self::RuntimeVisibleParameterAnnotations(_) => "RuntimeVisibleParameterAnnotations",
+            Self::RuntimeVisibleTypeAnnotations(_) => "RuntimeVisibleTypeAnnotations",
+            Self::NestHost { .. } => "NestHost",
+            Self::NestMembers(_) => "NestMembers",
+            Self::Module(_) => "Module",
+            Self::ModulePackages(_) => "ModulePackages",
+            Self::ModuleMainClass { .. } => "ModuleMainClass",
+        }
+    }
+
+    /// Does `parse` decode attributes called `name`?
+    pub(super) fn decodes(name: &str) -> bool {
+        matches!(
+            name,
+            "ConstantValue"
+                | "Code"
+                | "StackMapTable"
+                | "LineNumberTable"
+                | "LocalVariableTable"
+                | "LocalVariableTypeTable"
+                | "SourceFile"
+                | "Exceptions"
+                | "InnerClasses"
+                | "EnclosingMethod"
+                | "Signature"
+                | "Synthetic"
+                | "Deprecated"
+                | "MethodParameters"
+                | "AnnotationDefault"
+                | "BootstrapMethods"
+                | "RuntimeVisibleAnnotations"
+                | "RuntimeVisibleParameterAnnotations"
+                | "RuntimeVisibleTypeAnnotations"
+                | "NestHost"
+                | "NestMembers"
+                | "Module"
+                | "ModulePackages"
+                | "ModuleMainClass"
+        )
+    }
+
+    /// Write the whole attribute, name and length included.
+    pub(super) fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_attr(self.name(), |wr| self.write_payload(wr))
+    }
+
+    fn write_payload(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        match self {
+            Self::ConstantValue { cp_idx } => wr.write_u16(*cp_idx),
+            Self::Code(code) => code.write(wr)?,
+            Self::StackMapTable(frames) => StackMapFrameInfo::write_table(wr, frames)?,
+            Self::LineNumberTable(table) => wr.write_table("LineNumberTable", table, |wr, x| {
+                wr.write_u16(x.start_pc);
+                wr.write_u16(x.line_number);
+                Ok(())
+            })?,
+            Self::LocalVariableTable(table) | Self::LocalVariableTypeTable(table) => {
+                wr.write_table(self.name(), table, |wr, x| {
+                    wr.write_u16(x.start_pc);
+                    wr.write_u16(x.length);
+                    wr.write_u16(x.name_idx);
+                    wr.write_u16(x.desc_idx);
+                    wr.write_u16(x.index);
+                    Ok(())
+                })?
+            }
+            Self::SourceFile { sourcefile_idx } => wr.write_u16(*sourcefile_idx),
+            Self::Exceptions { exception_idxs } => wr.write_idx_list("Exceptions", exception_idxs)?,
+            Self::InnerClasses(classes) => wr.write_table("InnerClasses", classes, |wr, x| {
+                wr.write_u16(x.inner_class_info_idx);
+                wr.write_u16(x.outer_class_info_idx);
+                wr.write_u16(x.inner_name_idx);
+                wr.write_u16(x.inner_class_acc_flags);
+                Ok(())
+            })?,
+            Self::EnclosingMethod { class_idx, method_idx } => {
+                wr.write_u16(*class_idx);
+                wr.write_u16(*method_idx);
+            }
+            Self::Signature { signature_idx } => wr.write_u16(*signature_idx),
+            Self::Synthetic | Self::Deprecated => {}
+            Self::MethodParameters(parameters) => {
+                wr.write_u8_len("MethodParameters", parameters.len())?;
+                for x in parameters {
+                    wr.write_u16(x.name_idx);
+                    wr.write_u16(x.acc_flags);
+                }
+            }
+            Self::AnnotationDefault(value) => value.write(wr)?,
+            Self::BootstrapMethods(methods) => wr.write_table("BootstrapMethods", methods, |wr, x| {
+                wr.write_u16(x.bootstrap_method_ref);
+                wr.write_idx_list("bootstrap_arguments", &x.bootstrap_arguments)
+            })?,
+            Self::RuntimeVisibleAnnotations(annotations) => AnnotationInfo::write_list(wr, annotations)?,
+            Self::RuntimeVisibleParameterAnnotations(parameters) => {
+                wr.write_u8_len("RuntimeVisibleParameterAnnotations", parameters.len())?;
+                for x in parameters {
+                    AnnotationInfo::write_list(wr, x)?;
+                }
+            }
+            Self::RuntimeVisibleTypeAnnotations(annotations) => TypeAnnotationInfo::write_list(wr, annotations)?,
+            Self::NestHost { host_class_idx } => wr.write_u16(*host_class_idx),
+            Self::NestMembers(members) => wr.write_idx_list("NestMembers", members)?,
+            Self::Module(module) => module.write(wr)?,
+            Self::ModulePackages(packages) => wr.write_idx_list("ModulePackages", packages)?,
+            Self::ModuleMainClass { main_class_idx } => wr.write_u16(*main_class_idx),
+        }
+
+        Ok(())
+    }
+}
+
+/// Accessors shared by the structures that carry an attribute table.
+pub trait HasAttrs {
+    fn attrs(&self) -> &[AttrInfo];
+
+    fn raw_attrs(&self) -> &[RawAttr];
+
+    /// Undecoded payload of the attribute called `name`.
+    fn raw_attr(&self, name: &str) -> Option<&[u8]> {
+        self.raw_attrs()
+            .iter()
+            .find(|x| x.name == name)
+            .map(|x| x.bytes.as_slice())
+    }
+
+    fn signature_idx(&self) -> Option<u16> {
+        self.attrs().iter().find_map(|x| match x {
+            AttrInfo::Signature { signature_idx } => Some(*signature_idx),
+            _ => None,
+        })
+    }
+
+    fn is_synthetic(&self) -> bool {
+        self.attrs().iter().any(|x| matches!(x, AttrInfo::Synthetic))
+    }
+
+    fn is_deprecated(&self) -> bool {
+        self.attrs().iter().any(|x| matches!(x, AttrInfo::Deprecated))
     }
 }
@@ -1,11 +1,18 @@
-use crate::class_parser::attr_info::AttrInfo;
+use crate::class_parser::attr_info::{
+    placement, AttrInfo, AttrLocation, BootstrapMethodInfo, HasAttrs, InnerClassInfo, RawAttr,
+};
 use crate::class_parser::{
     class_reader::ClassReader,
+    class_writer::{self, ClassWriter},
     cp_info::ConstantPoolInfo,
     field_info::FieldInfo,
+    format_check,
+    limits::ClassFileLimits,
     method_info::MethodInfo,
     parse_error::{ParseError, ParseResult},
+    write_error::WriteResult,
 };
+use crate::oops::acc_flags::AccFlags;
 
 const VALID_MAGIC: u32 = 0xCAFEBABE;
 
@@ -26,23 +33,60 @@ pub struct ClassFile {
     pub methods: Vec<MethodInfo>,
 
     pub attrs: Vec<AttrInfo>,
+    pub raw_attrs: Vec<RawAttr>,
 }
 
+/// Java 11.
+const MAX_MAJOR: u16 = 55;
+
 fn is_version_valid(minor: u16, major: u16) -> bool {
-    major >= 45 && major <= 52 && minor == 0
+    (45..=MAX_MAJOR).contains(&major) && minor == 0
+}
+
+/// First class-file version that may use `CONSTANT_MethodHandle`,
+/// `CONSTANT_MethodType` and `CONSTANT_InvokeDynamic` (JVMS 4.4, table 4.4-B).
+const INVOKE_DYNAMIC_MAJOR: u16 = 51;
+
+/// First class-file version that may use `CONSTANT_Module` and
+/// `CONSTANT_Package`.
+pub(super) const MODULE_MAJOR: u16 = 53;
+
+/// First class-file version that may use `CONSTANT_Dynamic`.
+const DYNAMIC_CONSTANT_MAJOR: u16 = 55;
+
+/// The tag of `entry` if class files of version `major` may not use it.
+fn too_recent_tag(entry: &ConstantPoolInfo, major: u16) -> Option<u8> {
+    let (tag, since) = match entry {
+        ConstantPoolInfo::MethodHandleInfo { .. } => (15, INVOKE_DYNAMIC_MAJOR),
+        ConstantPoolInfo::MethodTypeInfo { .. } => (16, INVOKE_DYNAMIC_MAJOR),
+        ConstantPoolInfo::DynamicInfo { .. } => (17, DYNAMIC_CONSTANT_MAJOR),
+        ConstantPoolInfo::InvokeDynamicInfo { .. } => (18, INVOKE_DYNAMIC_MAJOR),
+        ConstantPoolInfo::ModuleInfo { .. } => (19, MODULE_MAJOR),
+        ConstantPoolInfo::PackageInfo { .. } => (20, MODULE_MAJOR),
+        _ => return None,
+    };
+
+    (major < since).then_some(tag)
 }
 
-fn read_cp(rd: &mut ClassReader) -> ParseResult<Vec<ConstantPoolInfo>> {
+fn read_cp(rd: &mut ClassReader, major: u16) -> ParseResult<Vec<ConstantPoolInfo>> {
     // -- constant pool --
     // JVM 4.4.5: LongInfo / DoubleInfo occupy two slots (n, n+1).
     // Slot n+1 is unusable.  Slot 0 is also unusable (JVM CP is 1-based),
     // so we push a placeholder to keep cp[idx] == CP[idx].
     let cp_count = rd.read_u16()?;
+    let entries = (cp_count as usize).saturating_sub(1);
+    if entries > rd.limits.max_constant_pool_entries {
+        return Err(ParseError::TooManyConstants(entries));
+    }
     let mut cp = Vec::with_capacity(cp_count as usize);
     cp.push(ConstantPoolInfo::Unusable); // slot 0 placeholder
     let mut slot: u16 = 1;
     while slot < cp_count {
         let entry = ConstantPoolInfo::read(rd)?;
+        if let Some(tag) = too_recent_tag(&entry, major) {
+            return Err(ParseError::UnsupportedCPTag(tag));
+        }
         let wide = matches!(
             entry,
             ConstantPoolInfo::LongInfo { .. } | ConstantPoolInfo::DoubleInfo { .. }
@@ -68,16 +112,75 @@ fn read_interfaces(rd: &mut ClassReader) -> ParseResult<Vec<u16>> {
     Ok(interfaces)
 }
 
-pub(super) fn read_attrs(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Vec<AttrInfo>> {
+/// Read an `attributes` table that belongs to `location`.  Known attributes
+/// are decoded; every attribute is also kept verbatim.
+pub(super) fn read_attrs(
+    rd: &mut ClassReader,
+    cp: &[ConstantPoolInfo],
+    location: AttrLocation,
+) -> ParseResult<(Vec<AttrInfo>, Vec<RawAttr>)> {
     let attrs_count = rd.read_u16()?;
     let mut attrs = Vec::with_capacity(attrs_count as _);
+    let mut raw_attrs: Vec<RawAttr> = Vec::with_capacity(attrs_count as _);
+
     for _ in 0..attrs_count {
-        if let Some(x) = AttrInfo::read(rd, cp)? {
+        let name_idx = rd.read_u16()?;
+        let name = match cp.get(name_idx as usize) {
+            Some(ConstantPoolInfo::Utf8Info { utf8, .. }) => utf8.clone(),
+            Some(ConstantPoolInfo::Unusable) | None => return Err(ParseError::InvalidCPIndex),
+            Some(_) => return Err(ParseError::InvalidCPType),
+        };
+
+        let len = rd.read_u32()? as usize;
+        let payload = rd.read(len)?;
+        let mut payload_rd = rd.nested(payload)?;
+
+        // JVMS 4.7：预定义属性只能出现在规定的位置，且大多不能重复。
+        if let Some((locations, repeatable)) = placement(&name) {
+            if !locations.contains(&location) {
+                return Err(ParseError::MisplacedAttr { name, location });
+            }
+            if !repeatable && raw_attrs.iter().any(|x| x.name == name) {
+                return Err(ParseError::DuplicatedAttr(name));
+            }
+        }
+
+        if let Some(x) = AttrInfo::parse(&name, &mut payload_rd, cp, location)? {
             attrs.push(x);
         }
+
+        raw_attrs.push(RawAttr {
+            name,
+            bytes: payload.to_vec(),
+        });
+    }
+
+    Ok((attrs, raw_attrs))
+}
+
+/// Write an `attributes` table.  `raw_attrs` gives the order: attributes the
+/// VM decodes are written from `attrs`, so edits to them take effect, and
+/// the others are copied verbatim.  Decoded attributes without a raw
+/// counterpart, as a builder produces, come last.
+pub(super) fn write_attrs(wr: &mut ClassWriter, attrs: &[AttrInfo], raw_attrs: &[RawAttr]) -> WriteResult<()> {
+    let mut decoded = attrs.iter().peekable();
+    let mut table = Vec::with_capacity(attrs.len().max(raw_attrs.len()));
+    for raw in raw_attrs {
+        if !AttrInfo::decodes(&raw.name) {
+            table.push(Err(raw));
+        } else if let Some(attr) = decoded.next_if(|x| x.name() == raw.name) {
+            table.push(Ok(attr));
+        }
     }
+    table.extend(decoded.map(Ok));
 
-    Ok(attrs)
+    wr.write_table("attributes", &table, |wr, x| match x {
+        Ok(attr) => attr.write(wr),
+        Err(raw) => wr.write_attr(&raw.name, |wr| {
+            wr.write(&raw.bytes);
+            Ok(())
+        }),
+    })
 }
 
 fn read_fields(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Vec<FieldInfo>> {
@@ -102,8 +205,13 @@ fn read_methods(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Ve
 }
 
 impl ClassFile {
+    /// Parse `stream` within the limits of the running VM.
     pub fn from(stream: &[u8]) -> ParseResult<Self> {
-        let mut rd = ClassReader::new(stream);
+        Self::parse(stream, ClassFileLimits::current())
+    }
+
+    pub fn parse(stream: &[u8], limits: ClassFileLimits) -> ParseResult<Self> {
+        let mut rd = ClassReader::new(stream, limits);
 
         // -- header --
         let magic = rd.read_u32()?;
@@ -117,7 +225,7 @@ impl ClassFile {
             return Err(ParseError::InvalidVersion { minor, major });
         }
 
-        let cp = read_cp(&mut rd)?;
+        let cp = read_cp(&mut rd, major)?;
 
         // -- access flags --
         let acc_flags = rd.read_u16()?;
@@ -129,9 +237,32 @@ impl ClassFile {
         let fields = read_fields(&mut rd, &cp)?;
         let methods = read_methods(&mut rd, &cp)?;
         
-        let attrs = read_attrs(&mut rd, &cp)?;
+        let (attrs, raw_attrs) = read_attrs(&mut rd, &cp, AttrLocation::ClassFile)?;
+
+        // JVMS 4.7.23：每个 Dynamic 和 InvokeDynamic 都要指向 BootstrapMethods 中的一项。
+        let bootstrap_methods = attrs
+            .iter()
+            .find_map(|x| match x {
+                AttrInfo::BootstrapMethods(methods) => Some(methods.len()),
+                _ => None,
+            })
+            .unwrap_or(0);
+        for entry in &cp {
+            if let ConstantPoolInfo::DynamicInfo {
+                bootstrap_method_attr_index,
+                ..
+            }
+            | ConstantPoolInfo::InvokeDynamicInfo {
+                bootstrap_method_attr_index,
+                ..
+            } = entry
+                && *bootstrap_method_attr_index as usize >= bootstrap_methods
+            {
+                return Err(ParseError::InvalidBootstrapMethodIndex(*bootstrap_method_attr_index));
+            }
+        }
 
-        Ok(Self {
+        let cf = Self {
             minor_version: minor,
             major_version: major,
             this_class,
@@ -142,6 +273,86 @@ impl ClassFile {
             fields,
             methods,
             attrs,
+            raw_attrs,
+        };
+        format_check::check(&cf)?;
+
+        Ok(cf)
+    }
+}
+
+impl ClassFile {
+    /// Serialize the class file; parsing the result gives back an equal
+    /// class file.  Unchanged class files come out byte for byte as they
+    /// were read, except for `StackMapTable` frames, which are written in
+    /// their shortest form.
+    pub fn to_bytes(&self) -> WriteResult<Vec<u8>> {
+        let utf8_idxs = class_writer::utf8_idxs(&self.constant_pool);
+        let mut wr = ClassWriter::new(&utf8_idxs);
+
+        wr.write_u32(VALID_MAGIC);
+        wr.write_u16(self.minor_version);
+        wr.write_u16(self.major_version);
+
+        wr.write_u16_len("constant_pool", self.constant_pool.len())?;
+        for entry in self.constant_pool.iter().skip(1) {
+            entry.write(&mut wr)?;
+        }
+
+        wr.write_u16(self.acc_flags);
+        wr.write_u16(self.this_class);
+        wr.write_u16(self.super_index);
+        wr.write_idx_list("interfaces", &self.interfaces)?;
+        wr.write_table("fields", &self.fields, |wr, x| x.write(wr))?;
+        wr.write_table("methods", &self.methods, |wr, x| x.write(wr))?;
+        write_attrs(&mut wr, &self.attrs, &self.raw_attrs)?;
+
+        Ok(wr.into_bytes())
+    }
+
+    pub fn inner_classes(&self) -> &[InnerClassInfo] {
+        self.attrs
+            .iter()
+            .find_map(|x| match x {
+                AttrInfo::InnerClasses(classes) => Some(classes.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// `(class_idx, method_idx)` of a local or anonymous class.
+    pub fn enclosing_method(&self) -> Option<(u16, u16)> {
+        self.attrs.iter().find_map(|x| match x {
+            AttrInfo::EnclosingMethod {
+                class_idx,
+                method_idx,
+            } => Some((*class_idx, *method_idx)),
+            _ => None,
         })
     }
+
+    /// Is this a `module-info` class, declaring a module rather than a class?
+    pub fn declares_module(&self) -> bool {
+        self.acc_flags & AccFlags::ACC_MODULE.bits() != 0
+    }
+
+    pub fn bootstrap_methods(&self) -> &[BootstrapMethodInfo] {
+        self.attrs
+            .iter()
+            .find_map(|x| match x {
+                AttrInfo::BootstrapMethods(methods) => Some(methods.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl HasAttrs for ClassFile {
+    fn attrs(&self) -> &[AttrInfo] {
+        &self.attrs
+    }
+
+    fn raw_attrs(&self) -> &[RawAttr] {
+        &self.raw_attrs
+    }
 }
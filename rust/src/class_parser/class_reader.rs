@@ -1,20 +1,57 @@
-use super::parse_error::{ParseError, ParseResult};
+use super::{
+    limits::ClassFileLimits,
+    parse_error::{ParseError, ParseResult},
+};
 
 pub struct ClassReader<'a> {
     stream: &'a [u8],
     pos: usize,
+    pub limits: ClassFileLimits,
+    /// Attributes and element values enclosing the current position.
+    depth: usize,
 }
 
 impl<'a> ClassReader<'a> {
-    pub fn new(stream: &'a [u8]) -> Self {
+    pub fn new(stream: &'a [u8], limits: ClassFileLimits) -> Self {
         Self {
             stream: stream,
             pos: 0,
+            limits,
+            depth: 0,
         }
     }
+
+    /// A reader over `payload`, an attribute nested one level below the
+    /// current position.
+    pub fn nested<'b>(&self, payload: &'b [u8]) -> ParseResult<ClassReader<'b>> {
+        let depth = self.depth + 1;
+        if depth > self.limits.max_attribute_nesting {
+            return Err(ParseError::NestingTooDeep(depth));
+        }
+
+        Ok(ClassReader {
+            stream: payload,
+            pos: 0,
+            limits: self.limits,
+            depth,
+        })
+    }
+
+    /// Run `read` one nesting level deeper, e.g. for an element value
+    /// inside another.
+    pub fn descend<T>(&mut self, read: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.depth >= self.limits.max_attribute_nesting {
+            return Err(ParseError::NestingTooDeep(self.depth + 1));
+        }
+
+        self.depth += 1;
+        let res = read(self);
+        self.depth -= 1;
+        res
+    }
 }
 
-impl ClassReader<'_> {
+impl<'a> ClassReader<'a> {
     pub fn read_u8(&mut self) -> ParseResult<u8> {
         let x = self.stream.get(self.pos).copied().ok_or(ParseError::EOF)?;
         
@@ -81,8 +118,9 @@ impl ClassReader<'_> {
         ]))
     }
 
-    pub fn read(&mut self, len: usize) -> ParseResult<&[u8]> {
-        let res = match self.stream.get(self.pos..self.pos + len) {
+    pub fn read(&mut self, len: usize) -> ParseResult<&'a [u8]> {
+        let end = self.pos.checked_add(len).ok_or(ParseError::EOF)?;
+        let res = match self.stream.get(self.pos..end) {
             Some(x) => x,
             None => return Err(ParseError::EOF),
         };
@@ -95,4 +133,9 @@ impl ClassReader<'_> {
     pub fn is_empty(&self) -> bool {
         self.stream.len() == self.pos
     }
+
+    /// Bytes not read yet.
+    pub fn remaining(&self) -> usize {
+        self.stream.len() - self.pos
+    }
 }
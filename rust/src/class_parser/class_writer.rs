@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use super::{
+    cp_info::ConstantPoolInfo,
+    write_error::{WriteError, WriteResult},
+};
+
+/// The counterpart of `ClassReader`: appends big-endian class-file items to
+/// a buffer.
+pub struct ClassWriter<'a> {
+    out: Vec<u8>,
+    /// First `CONSTANT_Utf8` index of each text, to name attributes.
+    utf8_idxs: &'a HashMap<&'a str, u16>,
+}
+
+/// Index the `CONSTANT_Utf8` entries of `cp` by text; the first entry wins.
+pub fn utf8_idxs(cp: &[ConstantPoolInfo]) -> HashMap<&str, u16> {
+    let mut idxs = HashMap::new();
+    for (idx, entry) in cp.iter().enumerate() {
+        if let ConstantPoolInfo::Utf8Info { utf8, wtf8: None } = entry {
+            idxs.entry(utf8.as_str()).or_insert(idx as u16);
+        }
+    }
+
+    idxs
+}
+
+impl<'a> ClassWriter<'a> {
+    pub fn new(utf8_idxs: &'a HashMap<&'a str, u16>) -> Self {
+        Self {
+            out: Vec::new(),
+            utf8_idxs,
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.out
+    }
+
+    /// The `CONSTANT_Utf8` index naming the attribute `name`.
+    pub fn attr_name_idx(&self, name: &str) -> WriteResult<u16> {
+        self.utf8_idxs
+            .get(name)
+            .copied()
+            .ok_or_else(|| WriteError::MissingAttrName(name.to_owned()))
+    }
+
+    /// Write the attribute `name` whose payload `write` produces, preceded by
+    /// its `u4 attribute_length`.
+    pub fn write_attr(
+        &mut self,
+        name: &str,
+        write: impl FnOnce(&mut Self) -> WriteResult<()>,
+    ) -> WriteResult<()> {
+        self.write_u16(self.attr_name_idx(name)?);
+
+        let len_pos = self.out.len();
+        self.write_u32(0);
+        write(self)?;
+
+        let len = self.out.len() - len_pos - 4;
+        let len = u32::try_from(len).map_err(|_| WriteError::TooLong { what: "attribute", len })?;
+        self.out[len_pos..len_pos + 4].copy_from_slice(&len.to_be_bytes());
+
+        Ok(())
+    }
+}
+
+impl ClassWriter<'_> {
+    pub fn write_u8(&mut self, x: u8) {
+        self.out.push(x);
+    }
+
+    pub fn write_u16(&mut self, x: u16) {
+        self.out.extend(x.to_be_bytes());
+    }
+
+    pub fn write_u32(&mut self, x: u32) {
+        self.out.extend(x.to_be_bytes());
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        self.out.extend_from_slice(bytes);
+    }
+
+    /// `len` as the `u1` count of `what`.
+    pub fn write_u8_len(&mut self, what: &'static str, len: usize) -> WriteResult<()> {
+        let count = u8::try_from(len).map_err(|_| WriteError::TooLong { what, len })?;
+        self.write_u8(count);
+        Ok(())
+    }
+
+    /// `len` as the `u2` count of `what`.
+    pub fn write_u16_len(&mut self, what: &'static str, len: usize) -> WriteResult<()> {
+        let count = u16::try_from(len).map_err(|_| WriteError::TooLong { what, len })?;
+        self.write_u16(count);
+        Ok(())
+    }
+
+    /// `u2 count` followed by `items`, each written by `write`.
+    pub fn write_table<T>(
+        &mut self,
+        what: &'static str,
+        items: &[T],
+        mut write: impl FnMut(&mut Self, &T) -> WriteResult<()>,
+    ) -> WriteResult<()> {
+        self.write_u16_len(what, items.len())?;
+        items.iter().try_for_each(|x| write(self, x))
+    }
+
+    /// `u2 count` followed by the `u2` indices `idxs`.
+    pub fn write_idx_list(&mut self, what: &'static str, idxs: &[u16]) -> WriteResult<()> {
+        self.write_table(what, idxs, |wr, x| {
+            wr.write_u16(*x);
+            Ok(())
+        })
+    }
+}
@@ -1,6 +1,9 @@
 use super::{
     class_reader::ClassReader,
+    class_writer::ClassWriter,
+    modified_utf8,
     parse_error::{ParseError, ParseResult},
+    write_error::WriteResult,
 };
 
 #[derive(Debug)]
@@ -49,9 +52,42 @@ pub enum ConstantPoolInfo {
         desc_index: u16, // Utf8Info
     },
 
-    // best-effort UTF-8
+    MethodHandleInfo {
+        reference_kind: u8,    // 1..=9, JVMS 5.4.3.5
+        reference_index: u16,  // FieldrefInfo / MethodrefInfo / InterfaceMethodrefInfo
+    },
+
+    MethodTypeInfo {
+        descriptor_index: u16, // Utf8Info
+    },
+
+    /// JVMS 4.4.10 `CONSTANT_Dynamic`; the descriptor is a field descriptor.
+    DynamicInfo {
+        bootstrap_method_attr_index: u16, // into the BootstrapMethods attribute
+        name_and_type_index: u16,         // NameAndTypeInfo
+    },
+
+    InvokeDynamicInfo {
+        bootstrap_method_attr_index: u16, // into the BootstrapMethods attribute
+        name_and_type_index: u16,         // NameAndTypeInfo
+    },
+
+    /// Only in `module-info` classes.
+    ModuleInfo {
+        name_index: u16, // Utf8Info
+    },
+
+    /// Only in `module-info` classes; the name is in internal form.
+    PackageInfo {
+        name_index: u16, // Utf8Info
+    },
+
+    // JVMS 4.4.7 modified UTF-8
     Utf8Info {
         utf8: String,
+        /// The exact WTF-8 text when it holds unpaired surrogates; `utf8`
+        /// then shows each of them as U+FFFD.
+        wtf8: Option<Box<[u8]>>,
     },
 
     /// JVM spec 4.4.5: Long and Double occupy two consecutive slots.
@@ -101,20 +137,154 @@ impl ConstantPoolInfo {
             1 => {
                 let len = rd.read_u16()? as usize;
                 let raw = rd.read(len)?;
-                let utf8 = match String::from_utf8(Vec::from(raw)) {
-                    Ok(x) => x,
-                    Err(_) => return Err(ParseError::InvalidUtf8(Vec::from(raw))),
+                // JVMS 4.4.7：不能出现 0 字节和 4 字节形式，代理对分成两个 3 字节序列。
+                let Some(wtf8) = modified_utf8::decode(raw) else {
+                    return Err(ParseError::InvalidUtf8(Vec::from(raw)));
                 };
 
-                Self::Utf8Info { utf8 }
+                match String::from_utf8(wtf8) {
+                    Ok(utf8) => Self::Utf8Info { utf8, wtf8: None },
+                    Err(err) => {
+                        let wtf8 = err.into_bytes();
+                        Self::Utf8Info {
+                            utf8: modified_utf8::to_lossy_string(&wtf8),
+                            wtf8: Some(wtf8.into()),
+                        }
+                    }
+                }
             },
 
-            // Ignore for now.
-            15 | 16 | 18 => return Err(ParseError::UnsupportedCPTag(tag)),
+            15 => Self::MethodHandleInfo {
+                reference_kind: rd.read_u8()?,
+                reference_index: rd.read_u16()?,
+            },
+            16 => Self::MethodTypeInfo {
+                descriptor_index: rd.read_u16()?,
+            },
+            17 => Self::DynamicInfo {
+                bootstrap_method_attr_index: rd.read_u16()?,
+                name_and_type_index: rd.read_u16()?,
+            },
+            18 => Self::InvokeDynamicInfo {
+                bootstrap_method_attr_index: rd.read_u16()?,
+                name_and_type_index: rd.read_u16()?,
+            },
+            19 => Self::ModuleInfo {
+                name_index: rd.read_u16()?,
+            },
+            20 => Self::PackageInfo {
+                name_index: rd.read_u16()?,
+            },
 
             _ => return Err(ParseError::InvalidCPTag(tag)),
         };
 
         Ok(res)
     }
+
+    /// Write the entry, tag first.  `Unusable` writes nothing: it only pads
+    /// the slot after a `long` or `double`.
+    pub(super) fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        match self {
+            Self::ClassInfo { name_index } => {
+                wr.write_u8(7);
+                wr.write_u16(*name_index);
+            }
+            Self::FieldrefInfo {
+                class_index,
+                name_and_type_index,
+            } => {
+                wr.write_u8(9);
+                wr.write_u16(*class_index);
+                wr.write_u16(*name_and_type_index);
+            }
+            Self::MethodrefInfo {
+                class_index,
+                name_and_type_index,
+            } => {
+                wr.write_u8(10);
+                wr.write_u16(*class_index);
+                wr.write_u16(*name_and_type_index);
+            }
+            Self::InterfaceMethodrefInfo {
+                class_index,
+                name_and_type_index,
+            } => {
+                wr.write_u8(11);
+                wr.write_u16(*class_index);
+                wr.write_u16(*name_and_type_index);
+            }
+            Self::StringInfo { string_index } => {
+                wr.write_u8(8);
+                wr.write_u16(*string_index);
+            }
+            Self::IntegerInfo { value } => {
+                wr.write_u8(3);
+                wr.write(&value.to_be_bytes());
+            }
+            Self::FloatInfo { value } => {
+                wr.write_u8(4);
+                wr.write(&value.to_be_bytes());
+            }
+            Self::LongInfo { value } => {
+                wr.write_u8(5);
+                wr.write(&value.to_be_bytes());
+            }
+            Self::DoubleInfo { value } => {
+                wr.write_u8(6);
+                wr.write(&value.to_be_bytes());
+            }
+            Self::NameAndTypeInfo { name_index, desc_index } => {
+                wr.write_u8(12);
+                wr.write_u16(*name_index);
+                wr.write_u16(*desc_index);
+            }
+            Self::Utf8Info { utf8, wtf8 } => {
+                let text = wtf8.as_deref().unwrap_or(utf8.as_bytes());
+                let encoded = modified_utf8::encode(text);
+                wr.write_u8(1);
+                wr.write_u16_len("CONSTANT_Utf8", encoded.len())?;
+                wr.write(&encoded);
+            }
+            Self::MethodHandleInfo {
+                reference_kind,
+                reference_index,
+            } => {
+                wr.write_u8(15);
+                wr.write_u8(*reference_kind);
+                wr.write_u16(*reference_index);
+            }
+            Self::MethodTypeInfo { descriptor_index } => {
+                wr.write_u8(16);
+                wr.write_u16(*descriptor_index);
+            }
+            Self::DynamicInfo {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                wr.write_u8(17);
+                wr.write_u16(*bootstrap_method_attr_index);
+                wr.write_u16(*name_and_type_index);
+            }
+            Self::InvokeDynamicInfo {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                wr.write_u8(18);
+                wr.write_u16(*bootstrap_method_attr_index);
+                wr.write_u16(*name_and_type_index);
+            }
+            Self::ModuleInfo { name_index } => {
+                wr.write_u8(19);
+                wr.write_u16(*name_index);
+            }
+            Self::PackageInfo { name_index } => {
+                wr.write_u8(20);
+                wr.write_u16(*name_index);
+            }
+            Self::Unusable => {}
+        }
+
+        Ok(())
+    }
 }
@@ -1,6 +1,10 @@
-use crate::class_parser::{attr_info::AttrInfo, class_file::read_attrs, cp_info::ConstantPoolInfo};
+use crate::class_parser::{
+    attr_info::{AttrInfo, AttrLocation, HasAttrs, RawAttr},
+    class_file::{read_attrs, write_attrs},
+    cp_info::ConstantPoolInfo,
+};
 
-use super::{class_reader::ClassReader, parse_error::ParseResult};
+use super::{class_reader::ClassReader, class_writer::ClassWriter, parse_error::ParseResult, write_error::WriteResult};
 
 pub struct FieldInfo {
     pub acc_flags: u16,
@@ -8,6 +12,7 @@ pub struct FieldInfo {
     pub desc_idx: u16,
     
     pub attrs: Vec<AttrInfo>,
+    pub raw_attrs: Vec<RawAttr>,
 }
     
 impl FieldInfo {
@@ -18,13 +23,31 @@ impl FieldInfo {
         let name_idx = rd.read_u16()?;
         let desc_idx = rd.read_u16()?;
 
-        let attrs = read_attrs(rd, cp)?;
+        let (attrs, raw_attrs) = read_attrs(rd, cp, AttrLocation::Field)?;
 
         Ok(Self {
             acc_flags,
             name_idx,
             desc_idx,
-            attrs
+            attrs,
+            raw_attrs,
         })
     }
+
+    pub(super) fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_u16(self.acc_flags);
+        wr.write_u16(self.name_idx);
+        wr.write_u16(self.desc_idx);
+        write_attrs(wr, &self.attrs, &self.raw_attrs)
+    }
+}
+
+impl HasAttrs for FieldInfo {
+    fn attrs(&self) -> &[AttrInfo] {
+        &self.attrs
+    }
+
+    fn raw_attrs(&self) -> &[RawAttr] {
+        &self.raw_attrs
+    }
 }
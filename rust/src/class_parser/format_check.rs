@@ -0,0 +1,459 @@
+//! JVMS 4.8 format checking: the constraints on a class file that do not
+//! need the code to be looked at or other classes to be loaded.
+
+use std::collections::HashSet;
+
+use crate::{
+    class_parser::{
+        attr_info::AttrInfo,
+        class_file::{ClassFile, MODULE_MAJOR},
+        cp_info::ConstantPoolInfo,
+        parse_error::{ParseError, ParseResult},
+    },
+    oops::acc_flags::AccFlags,
+};
+
+const OBJECT_CLASS: &str = "java/lang/Object";
+
+/// JVMS 4.3.2: an array type has at most 255 dimensions.
+const MAX_ARRAY_DIMENSIONS: usize = 255;
+
+/// JVMS 4.3.3: parameters, `this` included, take at most 255 slots.
+const MAX_PARAMETER_SLOTS: usize = 255;
+
+/// JVMS 4.6: interface methods may be `private` or have bodies from 52 on.
+const INTERFACE_METHOD_BODIES_MAJOR: u16 = 52;
+
+/// JVMS 2.9.2: `<clinit>` must be `static` from 51 on.
+const STATIC_CLINIT_MAJOR: u16 = 51;
+
+pub(super) fn check(cf: &ClassFile) -> ParseResult<()> {
+    let cp = &cf.constant_pool;
+    let is_module = cf.acc_flags & AccFlags::ACC_MODULE.bits() != 0;
+    for entry in cp {
+        check_cp_entry(cp, entry, is_module)?;
+    }
+
+    if is_module {
+        return check_module_info(cf);
+    }
+
+    check_class_flags(cf.acc_flags, cf.major_version)?;
+    check_this_and_super(cf)?;
+    check_interfaces(cf)?;
+
+    let is_interface = cf.acc_flags & AccFlags::ACC_INTERFACE.bits() != 0;
+    let mut seen = HashSet::new();
+    for field in &cf.fields {
+        let name = utf8_at(cp, field.name_idx)?;
+        let desc = utf8_at(cp, field.desc_idx)?;
+        if !is_unqualified_name(name, false) {
+            return Err(ParseError::InvalidName(name.into()));
+        }
+        if !is_field_descriptor(desc) {
+            return Err(ParseError::InvalidDescriptor(desc.into()));
+        }
+        check_field_flags(field.acc_flags, name, is_interface)?;
+        if !seen.insert((name, desc)) {
+            return Err(ParseError::DuplicatedField { name: name.into(), desc: desc.into() });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for method in &cf.methods {
+        let name = utf8_at(cp, method.name_idx)?;
+        let desc = utf8_at(cp, method.desc_idx)?;
+        let is_static = method.acc_flags & AccFlags::ACC_STATIC.bits() != 0;
+        check_method_name(name, desc, is_interface)?;
+        match method_descriptor_slots(desc) {
+            Some(slots) if slots + !is_static as usize <= MAX_PARAMETER_SLOTS => {}
+            _ => return Err(ParseError::InvalidDescriptor(desc.into())),
+        }
+        check_method_flags(method.acc_flags, name, is_interface, cf.major_version)?;
+
+        // JVMS 4.7.3：native 和 abstract 方法没有 Code，其余方法必须有。
+        let has_code = method.attrs.iter().any(|x| matches!(x, AttrInfo::Code(_)));
+        let bodiless = method.acc_flags & (AccFlags::ACC_NATIVE | AccFlags::ACC_ABSTRACT).bits() != 0;
+        if has_code && bodiless {
+            return Err(ParseError::UnexpectedCode(name.into()));
+        }
+        if !has_code && !bodiless {
+            return Err(ParseError::MissingCode(name.into()));
+        }
+
+        if !seen.insert((name, desc)) {
+            return Err(ParseError::DuplicatedMethod { name: name.into(), desc: desc.into() });
+        }
+    }
+
+    Ok(())
+}
+
+fn utf8_at(cp: &[ConstantPoolInfo], index: u16) -> ParseResult<&str> {
+    match cp.get(index as usize) {
+        Some(ConstantPoolInfo::Utf8Info { utf8, .. }) => Ok(utf8),
+        Some(ConstantPoolInfo::Unusable) | None => Err(ParseError::InvalidCPIndex),
+        Some(_) => Err(ParseError::InvalidCPType),
+    }
+}
+
+fn class_name_at(cp: &[ConstantPoolInfo], index: u16) -> ParseResult<&str> {
+    match cp.get(index as usize) {
+        Some(ConstantPoolInfo::ClassInfo { name_index }) => utf8_at(cp, *name_index),
+        Some(ConstantPoolInfo::Unusable) | None => Err(ParseError::InvalidCPIndex),
+        Some(_) => Err(ParseError::InvalidCPType),
+    }
+}
+
+fn name_and_type_at(cp: &[ConstantPoolInfo], index: u16) -> ParseResult<(&str, &str)> {
+    match cp.get(index as usize) {
+        Some(ConstantPoolInfo::NameAndTypeInfo { name_index, desc_index }) => {
+            Ok((utf8_at(cp, *name_index)?, utf8_at(cp, *desc_index)?))
+        }
+        Some(ConstantPoolInfo::Unusable) | None => Err(ParseError::InvalidCPIndex),
+        Some(_) => Err(ParseError::InvalidCPType),
+    }
+}
+
+/// JVMS 4.4: the entries an entry refers to have the right types, and the
+/// names and descriptors they hold are well formed.  Module and package
+/// entries belong to `module-info` classes only.
+fn check_cp_entry(cp: &[ConstantPoolInfo], entry: &ConstantPoolInfo, is_module: bool) -> ParseResult<()> {
+    match entry {
+        ConstantPoolInfo::ClassInfo { name_index } => {
+            let name = utf8_at(cp, *name_index)?;
+            let valid = match name.starts_with('[') {
+                true => is_field_descriptor(name),
+                false => is_binary_name(name),
+            };
+            if !valid {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+        }
+        ConstantPoolInfo::FieldrefInfo { class_index, name_and_type_index } => {
+            class_name_at(cp, *class_index)?;
+            let (name, desc) = name_and_type_at(cp, *name_and_type_index)?;
+            if !is_unqualified_name(name, false) {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+            if !is_field_descriptor(desc) {
+                return Err(ParseError::InvalidDescriptor(desc.into()));
+            }
+        }
+        ConstantPoolInfo::MethodrefInfo { class_index, name_and_type_index }
+        | ConstantPoolInfo::InterfaceMethodrefInfo { class_index, name_and_type_index } => {
+            class_name_at(cp, *class_index)?;
+            let (name, desc) = name_and_type_at(cp, *name_and_type_index)?;
+            // JVMS 4.4.2：以 '<' 开头的只能是返回 void 的 <init>。
+            let valid = match name {
+                "<init>" => desc.ends_with(")V"),
+                _ => is_unqualified_name(name, true),
+            };
+            if !valid {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+            if method_descriptor_slots(desc).is_none() {
+                return Err(ParseError::InvalidDescriptor(desc.into()));
+            }
+        }
+        ConstantPoolInfo::StringInfo { string_index } => {
+            utf8_at(cp, *string_index)?;
+        }
+        ConstantPoolInfo::NameAndTypeInfo { name_index, desc_index } => {
+            let name = utf8_at(cp, *name_index)?;
+            let desc = utf8_at(cp, *desc_index)?;
+            if !is_unqualified_name(name, false) {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+            if !is_field_descriptor(desc) && method_descriptor_slots(desc).is_none() {
+                return Err(ParseError::InvalidDescriptor(desc.into()));
+            }
+        }
+        ConstantPoolInfo::MethodHandleInfo { reference_index, .. } => match cp.get(*reference_index as usize) {
+            // 引用种类与成员是否匹配在解析时检查（JVMS 5.4.3.5）。
+            Some(
+                ConstantPoolInfo::FieldrefInfo { .. }
+                | ConstantPoolInfo::MethodrefInfo { .. }
+                | ConstantPoolInfo::InterfaceMethodrefInfo { .. },
+            ) => {}
+            Some(ConstantPoolInfo::Unusable) | None => return Err(ParseError::InvalidCPIndex),
+            Some(_) => return Err(ParseError::InvalidCPType),
+        },
+        ConstantPoolInfo::MethodTypeInfo { descriptor_index } => {
+            let desc = utf8_at(cp, *descriptor_index)?;
+            if method_descriptor_slots(desc).is_none() {
+                return Err(ParseError::InvalidDescriptor(desc.into()));
+            }
+        }
+        ConstantPoolInfo::InvokeDynamicInfo { name_and_type_index, .. } => {
+            let (name, desc) = name_and_type_at(cp, *name_and_type_index)?;
+            if !is_unqualified_name(name, true) {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+            if method_descriptor_slots(desc).is_none() {
+                return Err(ParseError::InvalidDescriptor(desc.into()));
+            }
+        }
+        ConstantPoolInfo::DynamicInfo { name_and_type_index, .. } => {
+            let (name, desc) = name_and_type_at(cp, *name_and_type_index)?;
+            if !is_unqualified_name(name, false) {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+            if !is_field_descriptor(desc) {
+                return Err(ParseError::InvalidDescriptor(desc.into()));
+            }
+        }
+        ConstantPoolInfo::ModuleInfo { .. } if !is_module => return Err(ParseError::InvalidCPTag(19)),
+        ConstantPoolInfo::PackageInfo { .. } if !is_module => return Err(ParseError::InvalidCPTag(20)),
+        ConstantPoolInfo::ModuleInfo { name_index } => {
+            let name = utf8_at(cp, *name_index)?;
+            if !is_module_name(name) {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+        }
+        ConstantPoolInfo::PackageInfo { name_index } => {
+            let name = utf8_at(cp, *name_index)?;
+            if !is_binary_name(name) {
+                return Err(ParseError::InvalidName(name.into()));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// JVMS 4.1: a class file declaring a module has nothing but its name and
+/// the module attributes.
+fn check_module_info(cf: &ClassFile) -> ParseResult<()> {
+    let violation = if cf.major_version < MODULE_MAJOR {
+        Some("ACC_MODULE before version 53")
+    } else if cf.acc_flags != AccFlags::ACC_MODULE.bits() {
+        Some("flags other than ACC_MODULE")
+    } else if !matches!(class_name_at(&cf.constant_pool, cf.this_class), Ok("module-info")) {
+        Some("this_class is not module-info")
+    } else if cf.super_index != 0 {
+        Some("a superclass")
+    } else if !cf.interfaces.is_empty() || !cf.fields.is_empty() || !cf.methods.is_empty() {
+        Some("interfaces, fields or methods")
+    } else if !cf.attrs.iter().any(|x| matches!(x, AttrInfo::Module(_))) {
+        Some("no Module attribute")
+    } else {
+        None
+    };
+
+    match violation {
+        Some(violation) => Err(ParseError::InvalidModuleInfo(violation)),
+        None => Ok(()),
+    }
+}
+
+/// `this_class` names a class, and `super_class` its superclass: none only
+/// for `java/lang/Object`, and `java/lang/Object` for an interface.
+fn check_this_and_super(cf: &ClassFile) -> ParseResult<()> {
+    let cp = &cf.constant_pool;
+    let this_class = match class_name_at(cp, cf.this_class) {
+        Ok(name) if !name.starts_with('[') => name,
+        _ => return Err(ParseError::InvalidThisClass(cf.this_class)),
+    };
+
+    let is_interface = cf.acc_flags & AccFlags::ACC_INTERFACE.bits() != 0;
+    let valid_super = match cf.super_index {
+        0 => this_class == OBJECT_CLASS,
+        index => match class_name_at(cp, index) {
+            Ok(name) if is_interface => name == OBJECT_CLASS,
+            Ok(name) => !name.starts_with('[') && name != this_class,
+            Err(_) => false,
+        },
+    };
+    if !valid_super {
+        return Err(ParseError::InvalidSuperClass(cf.super_index));
+    }
+
+    Ok(())
+}
+
+fn check_interfaces(cf: &ClassFile) -> ParseResult<()> {
+    let mut seen = HashSet::new();
+    for &index in &cf.interfaces {
+        let name = class_name_at(&cf.constant_pool, index)?;
+        if name.starts_with('[') {
+            return Err(ParseError::InvalidName(name.into()));
+        }
+        if !seen.insert(name) {
+            return Err(ParseError::DuplicatedInterface(name.into()));
+        }
+    }
+
+    Ok(())
+}
+
+/// JVMS 4.1, table 4.1-B.
+fn check_class_flags(bits: u16, major: u16) -> ParseResult<()> {
+    let flags = AccFlags::from_bits_retain(bits);
+    let valid = if flags.contains(AccFlags::ACC_INTERFACE) {
+        // 版本 50 之前的接口可以不带 ACC_ABSTRACT（HotSpot 会补上）。
+        let abstract_ok = flags.contains(AccFlags::ACC_ABSTRACT) || major < 50;
+        let forbidden = AccFlags::ACC_FINAL | AccFlags::ACC_SUPER | AccFlags::ACC_ENUM;
+        abstract_ok && !flags.intersects(forbidden)
+    } else {
+        !flags.contains(AccFlags::ACC_ANNOTATION) && !flags.contains(AccFlags::ACC_FINAL | AccFlags::ACC_ABSTRACT)
+    };
+
+    match valid {
+        true => Ok(()),
+        false => Err(ParseError::InvalidClassFlags(bits)),
+    }
+}
+
+/// At most one of `public`, `private` and `protected`.
+fn has_one_access(bits: u16) -> bool {
+    let access = AccFlags::ACC_PUBLIC | AccFlags::ACC_PRIVATE | AccFlags::ACC_PROTECTED;
+    (bits & access.bits()).count_ones() <= 1
+}
+
+/// JVMS 4.5, table 4.5-A.
+fn check_field_flags(bits: u16, name: &str, is_interface: bool) -> ParseResult<()> {
+    let flags = AccFlags::from_bits_retain(bits);
+    let valid = if is_interface {
+        let required = AccFlags::ACC_PUBLIC | AccFlags::ACC_STATIC | AccFlags::ACC_FINAL;
+        let forbidden = AccFlags::ACC_PRIVATE
+            | AccFlags::ACC_PROTECTED
+            | AccFlags::ACC_VOLATILE
+            | AccFlags::ACC_TRANSIENT
+            | AccFlags::ACC_ENUM;
+        flags.contains(required) && !flags.intersects(forbidden)
+    } else {
+        has_one_access(bits) && !flags.contains(AccFlags::ACC_FINAL | AccFlags::ACC_VOLATILE)
+    };
+
+    match valid {
+        true => Ok(()),
+        false => Err(ParseError::InvalidFieldFlags { name: name.into(), flags: bits }),
+    }
+}
+
+/// JVMS 4.6, table 4.6-A.
+fn check_method_flags(bits: u16, name: &str, is_interface: bool, major: u16) -> ParseResult<()> {
+    let flags = AccFlags::from_bits_retain(bits);
+    let abstract_forbidden = AccFlags::ACC_PRIVATE
+        | AccFlags::ACC_STATIC
+        | AccFlags::ACC_FINAL
+        | AccFlags::ACC_SYNCHRONIZED
+        | AccFlags::ACC_NATIVE
+        | AccFlags::ACC_STRICT;
+    let valid = if name == "<clinit>" {
+        // 其余标志被忽略。
+        major < STATIC_CLINIT_MAJOR || flags.contains(AccFlags::ACC_STATIC)
+    } else if !has_one_access(bits) || flags.contains(AccFlags::ACC_ABSTRACT) && flags.intersects(abstract_forbidden) {
+        false
+    } else if name == "<init>" {
+        let forbidden = AccFlags::ACC_STATIC
+            | AccFlags::ACC_FINAL
+            | AccFlags::ACC_SYNCHRONIZED
+            | AccFlags::ACC_BRIDGE
+            | AccFlags::ACC_NATIVE
+            | AccFlags::ACC_ABSTRACT;
+        !flags.intersects(forbidden)
+    } else if is_interface {
+        let forbidden = AccFlags::ACC_PROTECTED | AccFlags::ACC_FINAL | AccFlags::ACC_SYNCHRONIZED | AccFlags::ACC_NATIVE;
+        let required = match major < INTERFACE_METHOD_BODIES_MAJOR {
+            true => flags.contains(AccFlags::ACC_PUBLIC | AccFlags::ACC_ABSTRACT),
+            false => flags.intersects(AccFlags::ACC_PUBLIC | AccFlags::ACC_PRIVATE),
+        };
+        required && !flags.intersects(forbidden)
+    } else {
+        true
+    };
+
+    match valid {
+        true => Ok(()),
+        false => Err(ParseError::InvalidMethodFlags { name: name.into(), flags: bits }),
+    }
+}
+
+/// JVMS 4.2.2 and 2.9: a method is an initializer or has an unqualified
+/// method name.
+fn check_method_name(name: &str, desc: &str, is_interface: bool) -> ParseResult<()> {
+    match name {
+        "<init>" if is_interface => Err(ParseError::InvalidName(name.into())),
+        "<init>" if !desc.ends_with(")V") => Err(ParseError::InvalidDescriptor(desc.into())),
+        "<clinit>" if desc != "()V" => Err(ParseError::InvalidDescriptor(desc.into())),
+        "<init>" | "<clinit>" => Ok(()),
+        _ if is_unqualified_name(name, true) => Ok(()),
+        _ => Err(ParseError::InvalidName(name.into())),
+    }
+}
+
+/// JVMS 4.2.2: a non-empty name without `.`, `;`, `[` or `/`; method names
+/// may not contain `<` or `>` either.
+pub fn is_unqualified_name(name: &str, method: bool) -> bool {
+    let forbidden: &[char] = if method { &['.', ';', '[', '/', '<', '>'] } else { &['.', ';', '[', '/'] };
+    !name.is_empty() && !name.contains(forbidden)
+}
+
+/// JVMS 4.2.3: a non-empty name in which `\`, `:` and `@` only appear
+/// escaped by `\`.
+fn is_module_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if !matches!(chars.next(), Some('\\' | ':' | '@')) => return false,
+            ':' | '@' => return false,
+            _ => {}
+        }
+    }
+
+    !name.is_empty()
+}
+
+/// JVMS 4.2.1: a binary class or interface name in internal form.
+pub fn is_binary_name(name: &str) -> bool {
+    name.split('/').all(|x| is_unqualified_name(x, false))
+}
+
+/// Length of the field type at the start of `desc`, if there is one.
+fn field_type_len(desc: &str) -> Option<usize> {
+    let dimensions = desc.bytes().take_while(|&x| x == b'[').count();
+    if dimensions > MAX_ARRAY_DIMENSIONS {
+        return None;
+    }
+
+    let element = &desc[dimensions..];
+    let element_len = match element.as_bytes().first()? {
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' => 1,
+        b'L' => {
+            let end = element.find(';')?;
+            if !is_binary_name(&element[1..end]) {
+                return None;
+            }
+            end + 1
+        }
+        _ => return None,
+    };
+
+    Some(dimensions + element_len)
+}
+
+/// JVMS 4.3.2.
+pub fn is_field_descriptor(desc: &str) -> bool {
+    field_type_len(desc) == Some(desc.len())
+}
+
+/// JVMS 4.3.3: the slots the parameters of method descriptor `desc` take,
+/// or `None` if `desc` is malformed.
+pub fn method_descriptor_slots(desc: &str) -> Option<usize> {
+    let mut rest = desc.strip_prefix('(')?;
+    let mut slots = 0;
+    while !rest.starts_with(')') {
+        let len = field_type_len(rest)?;
+        slots += if len == 1 && matches!(rest.as_bytes()[0], b'J' | b'D') { 2 } else { 1 };
+        rest = &rest[len..];
+    }
+
+    let ret = &rest[1..];
+    match ret == "V" || is_field_descriptor(ret) {
+        true => Some(slots),
+        false => None,
+    }
+}
@@ -0,0 +1,38 @@
+//! Bounds on what the VM accepts from a class file.  They can only tighten
+//! what JVMS allows; they keep hostile class files from exhausting memory or
+//! the native stack while they are parsed and linked.
+
+use crate::runtime::arguments::Arguments;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClassFileLimits {
+    /// Constant-pool entries, not counting the unused entry 0.
+    pub max_constant_pool_entries: usize,
+    /// Bytes in the `code` array of a `Code` attribute; JVMS 4.7.3 allows
+    /// at most 65535.
+    pub max_code_length: usize,
+    /// How deep attributes may nest in attributes, and annotation element
+    /// values in element values.
+    pub max_attribute_nesting: usize,
+    /// Superclasses and superinterfaces above a class, counted along the
+    /// longest path.  Linking checks it before loading further supertypes.
+    pub max_class_hierarchy_depth: usize,
+}
+
+impl Default for ClassFileLimits {
+    fn default() -> Self {
+        Self {
+            max_constant_pool_entries: u16::MAX as usize - 1,
+            max_code_length: u16::MAX as usize,
+            max_attribute_nesting: 64,
+            max_class_hierarchy_depth: 128,
+        }
+    }
+}
+
+impl ClassFileLimits {
+    /// The limits of the running VM, or the defaults before it starts.
+    pub fn current() -> Self {
+        Arguments::try_get().map_or_else(Self::default, |x| x.class_file_limits)
+    }
+}
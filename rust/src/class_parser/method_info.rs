@@ -1,13 +1,14 @@
-use crate::class_parser::{class_file::read_attrs, cp_info::ConstantPoolInfo};
-use crate::class_parser::attr_info::AttrInfo;
+use crate::class_parser::{class_file::{read_attrs, write_attrs}, cp_info::ConstantPoolInfo};
+use crate::class_parser::attr_info::{AttrInfo, AttrLocation, HasAttrs, MethodParameterInfo, RawAttr};
 
-use super::{class_reader::ClassReader, parse_error::ParseResult};
+use super::{class_reader::ClassReader, class_writer::ClassWriter, parse_error::ParseResult, write_error::WriteResult};
 
 pub struct MethodInfo {
     pub acc_flags: u16,
     pub name_idx: u16,
     pub desc_idx: u16,
     pub attrs: Vec<AttrInfo>,
+    pub raw_attrs: Vec<RawAttr>,
 }
 
 impl MethodInfo {
@@ -18,13 +19,59 @@ impl MethodInfo {
         let name_idx= rd.read_u16()?;
         let desc_idx = rd.read_u16()?;
 
-        let attrs = read_attrs(rd, cp)?;
+        let (attrs, raw_attrs) = read_attrs(rd, cp, AttrLocation::Method)?;
 
         Ok(Self {
             acc_flags,
             name_idx,
             desc_idx,
             attrs,
+            raw_attrs,
         })
     }
+
+    pub(super) fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        wr.write_u16(self.acc_flags);
+        wr.write_u16(self.name_idx);
+        wr.write_u16(self.desc_idx);
+        write_attrs(wr, &self.attrs, &self.raw_attrs)
+    }
+}
+
+impl MethodInfo {
+    /// `CONSTANT_Class` indices from the `Exceptions` attribute.
+    pub fn exceptions(&self) -> &[u16] {
+        self.attrs
+            .iter()
+            .find_map(|x| match x {
+                AttrInfo::Exceptions { exception_idxs } => Some(exception_idxs.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn method_parameters(&self) -> &[MethodParameterInfo] {
+        self.attrs
+            .iter()
+            .find_map(|x| match x {
+                AttrInfo::MethodParameters(parameters) => Some(parameters.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Undecoded `element_value` of an annotation interface element.
+    pub fn annotation_default(&self) -> Option<&[u8]> {
+        self.raw_attr("AnnotationDefault")
+    }
+}
+
+impl HasAttrs for MethodInfo {
+    fn attrs(&self) -> &[AttrInfo] {
+        &self.attrs
+    }
+
+    fn raw_attrs(&self) -> &[RawAttr] {
+        &self.raw_attrs
+    }
 }
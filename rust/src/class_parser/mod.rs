@@ -1,7 +1,14 @@
+pub mod annotation_info;
 pub mod attr_info;
 pub mod class_file;
 mod class_reader;
+mod class_writer;
 pub mod cp_info;
 pub mod field_info;
+pub mod format_check;
+pub mod limits;
 pub mod method_info;
+pub mod modified_utf8;
 pub mod parse_error;
+pub mod stack_map_info;
+pub mod write_error;
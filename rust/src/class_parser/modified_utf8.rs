@@ -0,0 +1,155 @@
+//! Java text encodings.  Class files use modified UTF-8 (JVMS 4.4.7): NUL is
+//! `C0 80`, and characters outside the BMP are surrogate pairs, each half
+//! encoded on its own in three bytes.  The VM keeps text as WTF-8 instead:
+//! UTF-8 that may also hold unpaired surrogates, as Java strings can.  Valid
+//! Rust strings are valid WTF-8.
+
+/// Continuation bits of `byte`, if it is a continuation byte.
+fn continuation(byte: Option<&u8>) -> Option<u32> {
+    byte.filter(|&&x| x & 0xc0 == 0x80).map(|&x| (x & 0x3f) as u32)
+}
+
+fn is_high_surrogate(unit: u32) -> bool {
+    (0xd800..0xdc00).contains(&unit)
+}
+
+fn is_low_surrogate(unit: u32) -> bool {
+    (0xdc00..0xe000).contains(&unit)
+}
+
+/// The UTF-16 code unit at the start of `bytes` and its length, in modified
+/// UTF-8.  Overlong forms other than `C0 80` are rejected.
+fn decode_unit(bytes: &[u8]) -> Option<(u32, usize)> {
+    let first = *bytes.first()?;
+    match first {
+        0x01..=0x7f => Some((first as u32, 1)),
+        0xc0..=0xdf => {
+            let unit = ((first as u32 & 0x1f) << 6) | continuation(bytes.get(1))?;
+            (unit == 0 || unit >= 0x80).then_some((unit, 2))
+        }
+        0xe0..=0xef => {
+            let unit = ((first as u32 & 0x0f) << 12) | (continuation(bytes.get(1))? << 6) | continuation(bytes.get(2))?;
+            (unit >= 0x800).then_some((unit, 3))
+        }
+        // 0 字节和 4 字节形式在 class 文件中都不合法。
+        _ => None,
+    }
+}
+
+fn push_utf8(out: &mut Vec<u8>, code_point: u32) {
+    match code_point {
+        0..0x80 => out.push(code_point as u8),
+        0x80..0x800 => out.extend([0xc0 | (code_point >> 6) as u8, 0x80 | (code_point & 0x3f) as u8]),
+        0x800..0x10000 => out.extend([
+            0xe0 | (code_point >> 12) as u8,
+            0x80 | ((code_point >> 6) & 0x3f) as u8,
+            0x80 | (code_point & 0x3f) as u8,
+        ]),
+        _ => out.extend([
+            0xf0 | (code_point >> 18) as u8,
+            0x80 | ((code_point >> 12) & 0x3f) as u8,
+            0x80 | ((code_point >> 6) & 0x3f) as u8,
+            0x80 | (code_point & 0x3f) as u8,
+        ]),
+    }
+}
+
+/// Decode modified UTF-8 to WTF-8, or `None` if `bytes` is malformed.
+/// Surrogate pairs become one supplementary character; unpaired surrogates
+/// are kept.
+pub fn decode(bytes: &[u8]) -> Option<Vec<u8>> {
+    // 绝大多数常量是 ASCII，原样返回即可。
+    if bytes.iter().all(|&x| (0x01..0x80).contains(&x)) {
+        return Some(bytes.to_vec());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let (unit, len) = decode_unit(&bytes[pos..])?;
+        pos += len;
+
+        if is_high_surrogate(unit)
+            && let Some((low, low_len)) = decode_unit(&bytes[pos..]).filter(|(x, _)| is_low_surrogate(*x))
+        {
+            pos += low_len;
+            push_utf8(&mut out, 0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00));
+        } else {
+            push_utf8(&mut out, unit);
+        }
+    }
+
+    Some(out)
+}
+
+/// Encode WTF-8 as modified UTF-8.
+pub fn encode(wtf8: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(wtf8.len());
+    for unit in to_utf16(wtf8) {
+        match unit {
+            0 => out.extend([0xc0, 0x80]),
+            unit => push_utf8(&mut out, unit as u32),
+        }
+    }
+
+    out
+}
+
+/// The UTF-16 code units of WTF-8 text.
+pub fn to_utf16(wtf8: &[u8]) -> Vec<u16> {
+    let mut units = Vec::with_capacity(wtf8.len());
+    let mut pos = 0;
+    while pos < wtf8.len() {
+        let first = wtf8[pos] as u32;
+        let (code_point, len) = match first {
+            0x00..=0x7f => (first, 1),
+            0xc0..=0xdf => (((first & 0x1f) << 6) | continuation(wtf8.get(pos + 1)).unwrap_or(0), 2),
+            0xe0..=0xef => {
+                let rest = (continuation(wtf8.get(pos + 1)).unwrap_or(0) << 6) | continuation(wtf8.get(pos + 2)).unwrap_or(0);
+                (((first & 0x0f) << 12) | rest, 3)
+            }
+            _ => {
+                let rest = (continuation(wtf8.get(pos + 1)).unwrap_or(0) << 12)
+                    | (continuation(wtf8.get(pos + 2)).unwrap_or(0) << 6)
+                    | continuation(wtf8.get(pos + 3)).unwrap_or(0);
+                (((first & 0x07) << 18) | rest, 4)
+            }
+        };
+        pos += len;
+
+        match code_point {
+            0x10000.. => {
+                let offset = code_point - 0x10000;
+                units.extend([0xd800 | (offset >> 10) as u16, 0xdc00 | (offset & 0x3ff) as u16]);
+            }
+            _ => units.push(code_point as u16),
+        }
+    }
+
+    units
+}
+
+/// WTF-8 for UTF-16 code units: pairs are joined, unpaired surrogates kept.
+pub fn from_utf16(units: &[u16]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(units.len());
+    let mut iter = units.iter().map(|&x| x as u32).peekable();
+    while let Some(unit) = iter.next() {
+        match iter.peek() {
+            Some(&low) if is_high_surrogate(unit) && is_low_surrogate(low) => {
+                iter.next();
+                push_utf8(&mut out, 0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00));
+            }
+            _ => push_utf8(&mut out, unit),
+        }
+    }
+
+    out
+}
+
+/// WTF-8 text as a Rust string, with U+FFFD for each unpaired surrogate.
+pub fn to_lossy_string(wtf8: &[u8]) -> String {
+    match std::str::from_utf8(wtf8) {
+        Ok(text) => text.to_owned(),
+        Err(_) => String::from_utf16_lossy(&to_utf16(wtf8)),
+    }
+}
@@ -1,3 +1,7 @@
+use std::{error::Error, fmt};
+
+use crate::class_parser::attr_info::AttrLocation;
+
 #[derive(Debug, Clone)]
 pub enum ParseError {
     EOF,
@@ -9,6 +13,93 @@ pub enum ParseError {
     InvalidCPIndex,
     UnsupportedCPTag(u8),
     InvalidAttrLen(usize),
+    DuplicatedAttr(String),
+    MisplacedAttr { name: String, location: AttrLocation },
+    InvalidElementValueTag(u8),
+    /// An `InvokeDynamic` entry without a matching `BootstrapMethods` entry.
+    InvalidBootstrapMethodIndex(u16),
+    /// Unknown `target_type`, or one not allowed where the attribute appears.
+    InvalidTargetType(u8),
+    /// A `StackMapTable` frame type in the reserved range 128-246.
+    InvalidStackMapFrameType(u8),
+    InvalidVerificationTypeTag(u8),
+    /// A class, field or method name that breaks JVMS 4.2.
+    InvalidName(String),
+    /// A field or method descriptor that breaks JVMS 4.3.
+    InvalidDescriptor(String),
+    InvalidClassFlags(u16),
+    InvalidFieldFlags { name: String, flags: u16 },
+    InvalidMethodFlags { name: String, flags: u16 },
+    /// `this_class` is not a class entry naming a class or interface.
+    InvalidThisClass(u16),
+    /// `super_class` is missing, or names an array, the class itself or,
+    /// for an interface, anything but `java/lang/Object`.
+    InvalidSuperClass(u16),
+    DuplicatedInterface(String),
+    DuplicatedField { name: String, desc: String },
+    DuplicatedMethod { name: String, desc: String },
+    /// A method that is neither `native` nor `abstract` without `Code`.
+    MissingCode(String),
+    /// A `native` or `abstract` method with `Code`.
+    UnexpectedCode(String),
+    /// More constant-pool entries than `ClassFileLimits` allows.
+    TooManyConstants(usize),
+    /// An empty `code` array, or one longer than `ClassFileLimits` allows.
+    InvalidCodeLength(usize),
+    /// Attributes or element values nested deeper than `ClassFileLimits`
+    /// allows.
+    NestingTooDeep(usize),
+    /// A `module-info` class that breaks JVMS 4.1; says what it has or lacks.
+    InvalidModuleInfo(&'static str),
 }
 
 pub type ParseResult<T> = Result<T, ParseError>;
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EOF => f.write_str("unexpected end of class file"),
+            Self::InvalidMagic(magic) => write!(f, "bad magic number {magic:#010x}"),
+            Self::InvalidVersion { minor, major } => write!(f, "unsupported class file version {major}.{minor}"),
+            Self::InvalidCPTag(tag) => write!(f, "invalid constant pool tag {tag}"),
+            Self::InvalidUtf8(bytes) => write!(f, "invalid modified UTF-8 {bytes:02x?}"),
+            Self::InvalidCPType => f.write_str("constant pool entry has the wrong type"),
+            Self::InvalidCPIndex => f.write_str("invalid constant pool index"),
+            Self::UnsupportedCPTag(tag) => write!(f, "unsupported constant pool tag {tag}"),
+            Self::InvalidAttrLen(len) => write!(f, "attribute length {len} does not match its contents"),
+            Self::DuplicatedAttr(name) => write!(f, "duplicated {name} attribute"),
+            Self::MisplacedAttr { name, location } => {
+                let location = match location {
+                    AttrLocation::ClassFile => "a class",
+                    AttrLocation::Field => "a field",
+                    AttrLocation::Method => "a method",
+                    AttrLocation::Code => "a Code attribute",
+                };
+                write!(f, "{name} attribute not allowed on {location}")
+            }
+            Self::InvalidElementValueTag(tag) => write!(f, "invalid annotation element value tag {:?}", *tag as char),
+            Self::InvalidBootstrapMethodIndex(index) => write!(f, "no bootstrap method {index}"),
+            Self::InvalidTargetType(target) => write!(f, "invalid type annotation target type {target:#04x}"),
+            Self::InvalidStackMapFrameType(frame_type) => write!(f, "reserved stack map frame type {frame_type}"),
+            Self::InvalidVerificationTypeTag(tag) => write!(f, "invalid verification type tag {tag}"),
+            Self::InvalidName(name) => write!(f, "illegal name {name:?}"),
+            Self::InvalidDescriptor(desc) => write!(f, "illegal descriptor {desc:?}"),
+            Self::InvalidClassFlags(flags) => write!(f, "illegal class modifiers {flags:#06x}"),
+            Self::InvalidFieldFlags { name, flags } => write!(f, "illegal modifiers {flags:#06x} on field {name}"),
+            Self::InvalidMethodFlags { name, flags } => write!(f, "illegal modifiers {flags:#06x} on method {name}"),
+            Self::InvalidThisClass(index) => write!(f, "invalid this_class {index}"),
+            Self::InvalidSuperClass(index) => write!(f, "invalid super_class {index}"),
+            Self::DuplicatedInterface(name) => write!(f, "duplicated interface {name}"),
+            Self::DuplicatedField { name, desc } => write!(f, "duplicated field {name}:{desc}"),
+            Self::DuplicatedMethod { name, desc } => write!(f, "duplicated method {name}{desc}"),
+            Self::MissingCode(name) => write!(f, "method {name} has no Code attribute"),
+            Self::UnexpectedCode(name) => write!(f, "native or abstract method {name} has a Code attribute"),
+            Self::TooManyConstants(count) => write!(f, "{count} constant pool entries exceed the limit"),
+            Self::InvalidCodeLength(len) => write!(f, "invalid code length {len}"),
+            Self::NestingTooDeep(depth) => write!(f, "attributes nested {depth} deep exceed the limit"),
+            Self::InvalidModuleInfo(violation) => write!(f, "invalid module-info class: {violation}"),
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,202 @@
+//! `StackMapTable` entries (JVMS 4.7.4), kept in their compressed form: the
+//! verifier expands each frame relative to the one before it.
+
+use crate::class_parser::{
+    attr_info::check_cp_idx,
+    class_reader::ClassReader,
+    class_writer::ClassWriter,
+    cp_info::ConstantPoolInfo,
+    parse_error::{ParseError, ParseResult},
+    write_error::WriteResult,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationTypeInfo {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    /// `CONSTANT_Class` index of the class or array type.
+    Object { cpool_idx: u16 },
+    /// Offset of the `new` instruction that created the object.
+    Uninitialized { offset: u16 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackMapFrameInfo {
+    /// `same_frame` and `same_frame_extended`.
+    Same { offset_delta: u16 },
+    /// `same_locals_1_stack_item_frame` and its extended form.
+    SameLocals1StackItem { offset_delta: u16, stack: VerificationTypeInfo },
+    /// `chop_frame`: the last `k` locals are absent, 1 <= k <= 3.
+    Chop { offset_delta: u16, k: u8 },
+    /// `append_frame`: 1 to 3 more locals.
+    Append { offset_delta: u16, locals: Vec<VerificationTypeInfo> },
+    Full {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+        stack: Vec<VerificationTypeInfo>,
+    },
+}
+
+impl VerificationTypeInfo {
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        let info = match rd.read_u8()? {
+            0 => Self::Top,
+            1 => Self::Integer,
+            2 => Self::Float,
+            3 => Self::Double,
+            4 => Self::Long,
+            5 => Self::Null,
+            6 => Self::UninitializedThis,
+            7 => Self::Object {
+                cpool_idx: check_cp_idx(rd.read_u16()?, cp, |x| matches!(x, ConstantPoolInfo::ClassInfo { .. }))?,
+            },
+            8 => Self::Uninitialized { offset: rd.read_u16()? },
+            tag => return Err(ParseError::InvalidVerificationTypeTag(tag)),
+        };
+
+        Ok(info)
+    }
+
+    fn read_list(rd: &mut ClassReader, cp: &[ConstantPoolInfo], count: usize) -> ParseResult<Vec<Self>> {
+        (0..count).map(|_| Self::read(rd, cp)).collect()
+    }
+
+    fn write(&self, wr: &mut ClassWriter) {
+        match self {
+            Self::Top => wr.write_u8(0),
+            Self::Integer => wr.write_u8(1),
+            Self::Float => wr.write_u8(2),
+            Self::Double => wr.write_u8(3),
+            Self::Long => wr.write_u8(4),
+            Self::Null => wr.write_u8(5),
+            Self::UninitializedThis => wr.write_u8(6),
+            Self::Object { cpool_idx } => {
+                wr.write_u8(7);
+                wr.write_u16(*cpool_idx);
+            }
+            Self::Uninitialized { offset } => {
+                wr.write_u8(8);
+                wr.write_u16(*offset);
+            }
+        }
+    }
+
+    fn write_list(wr: &mut ClassWriter, list: &[Self]) {
+        list.iter().for_each(|x| x.write(wr));
+    }
+}
+
+impl StackMapFrameInfo {
+    /// `u2 number_of_entries` followed by the frames.
+    pub(super) fn read_table(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Vec<Self>> {
+        let count = rd.read_u16()?;
+        (0..count).map(|_| Self::read(rd, cp)).collect()
+    }
+
+    fn read(rd: &mut ClassReader, cp: &[ConstantPoolInfo]) -> ParseResult<Self> {
+        let frame_type = rd.read_u8()?;
+
+        let frame = match frame_type {
+            0..=63 => Self::Same {
+                offset_delta: frame_type as u16,
+            },
+            64..=127 => Self::SameLocals1StackItem {
+                offset_delta: frame_type as u16 - 64,
+                stack: VerificationTypeInfo::read(rd, cp)?,
+            },
+            247 => Self::SameLocals1StackItem {
+                offset_delta: rd.read_u16()?,
+                stack: VerificationTypeInfo::read(rd, cp)?,
+            },
+            248..=250 => Self::Chop {
+                offset_delta: rd.read_u16()?,
+                k: 251 - frame_type,
+            },
+            251 => Self::Same {
+                offset_delta: rd.read_u16()?,
+            },
+            252..=254 => {
+                let offset_delta = rd.read_u16()?;
+                let locals = VerificationTypeInfo::read_list(rd, cp, frame_type as usize - 251)?;
+
+                Self::Append { offset_delta, locals }
+            }
+            255 => {
+                let offset_delta = rd.read_u16()?;
+                let count = rd.read_u16()? as usize;
+                let locals = VerificationTypeInfo::read_list(rd, cp, count)?;
+                let count = rd.read_u16()? as usize;
+                let stack = VerificationTypeInfo::read_list(rd, cp, count)?;
+
+                Self::Full { offset_delta, locals, stack }
+            }
+            // 128-246 为保留值。
+            _ => return Err(ParseError::InvalidStackMapFrameType(frame_type)),
+        };
+
+        Ok(frame)
+    }
+
+    /// `u2 number_of_entries` followed by the frames.
+    pub(super) fn write_table(wr: &mut ClassWriter, frames: &[Self]) -> WriteResult<()> {
+        wr.write_table("StackMapTable", frames, |wr, x| x.write(wr))
+    }
+
+    /// Write the frame in its shortest form.
+    fn write(&self, wr: &mut ClassWriter) -> WriteResult<()> {
+        match self {
+            Self::Same { offset_delta } if *offset_delta < 64 => wr.write_u8(*offset_delta as u8),
+            Self::Same { offset_delta } => {
+                wr.write_u8(251);
+                wr.write_u16(*offset_delta);
+            }
+            Self::SameLocals1StackItem { offset_delta, stack } => {
+                if *offset_delta < 64 {
+                    wr.write_u8(64 + *offset_delta as u8);
+                } else {
+                    wr.write_u8(247);
+                    wr.write_u16(*offset_delta);
+                }
+                stack.write(wr);
+            }
+            Self::Chop { offset_delta, k } => {
+                wr.write_u8(251 - k);
+                wr.write_u16(*offset_delta);
+            }
+            Self::Append { offset_delta, locals } => {
+                wr.write_u8(251 + locals.len() as u8);
+                wr.write_u16(*offset_delta);
+                VerificationTypeInfo::write_list(wr, locals);
+            }
+            Self::Full {
+                offset_delta,
+                locals,
+                stack,
+            } => {
+                wr.write_u8(255);
+                wr.write_u16(*offset_delta);
+                wr.write_u16_len("full_frame locals", locals.len())?;
+                VerificationTypeInfo::write_list(wr, locals);
+                wr.write_u16_len("full_frame stack", stack.len())?;
+                VerificationTypeInfo::write_list(wr, stack);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn offset_delta(&self) -> u16 {
+        match self {
+            Self::Same { offset_delta }
+            | Self::SameLocals1StackItem { offset_delta, .. }
+            | Self::Chop { offset_delta, .. }
+            | Self::Append { offset_delta, .. }
+            | Self::Full { offset_delta, .. } => *offset_delta,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use std::{error::Error, fmt};
+
+/// Why a `ClassFile` cannot be turned back into bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteError {
+    /// No `CONSTANT_Utf8` entry holds the name of an attribute to write.
+    MissingAttrName(String),
+    /// A table, string or attribute longer than its length field can count;
+    /// says which.
+    TooLong { what: &'static str, len: usize },
+}
+
+pub type WriteResult<T> = Result<T, WriteError>;
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingAttrName(name) => write!(f, "no constant pool entry names the {name} attribute"),
+            Self::TooLong { what, len } => write!(f, "{what} of length {len} does not fit in a class file"),
+        }
+    }
+}
+
+impl Error for WriteError {}
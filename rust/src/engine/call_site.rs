@@ -0,0 +1,367 @@
+use crate::{
+    class_loader::{bootstrap_cld::BootstrapCLD, ms_api::MSRef},
+    engine::{
+        call::Invocation,
+        exec_error::{ExecError, ExecResult, JavaExceptionKind},
+        outcome::RetValue,
+        slot::Slot,
+    },
+    gc_bindings::{oop_codec::klass_of, oop_handle::NObjPtr},
+    oops::{
+        acc_flags::AccFlags,
+        array_klass::ArrayKlass,
+        cp_entry::{CallSiteKey, DynamicCPEntry, InvokeDynamicCPEntry, Loadable, RefKind},
+        desc::{FieldDesc, FieldElemType, ReturnDesc},
+        java_box::JavaBox,
+        java_class::JavaClass,
+        java_invoke::{
+            DirectMember, DirectMethodHandle, JavaCallSite, JavaLookup, JavaMethodHandle,
+            JavaMethodType,
+        },
+        java_string::JavaString,
+        klass::Klass,
+        normal_klass::NormalKlass,
+        symbol_table::SymbolHandle,
+    },
+};
+
+/// Leading bootstrap-method parameters: `Lookup`, the name and the
+/// `MethodType` of a call site or the `Class` of a constant.
+const LEADING_BOOTSTRAP_ARGS: usize = 3;
+
+/// What a bootstrap method is run for (JVMS 5.4.3.6).
+#[derive(Debug, Clone)]
+pub(crate) enum LinkTarget {
+    CallSite(MSRef<InvokeDynamicCPEntry>, CallSiteKey),
+    Constant(MSRef<DynamicCPEntry>),
+}
+
+impl LinkTarget {
+    fn bootstrap_method_attr_index(&self) -> u16 {
+        match self {
+            Self::CallSite(x, _) => x.bootstrap_method_attr_index,
+            Self::Constant(x) => x.bootstrap_method_attr_index,
+        }
+    }
+
+    fn name(&self) -> &SymbolHandle {
+        match self {
+            Self::CallSite(x, _) => &x.name,
+            Self::Constant(x) => &x.name,
+        }
+    }
+
+    /// Make `kind` the final outcome of linking.
+    pub(crate) fn fail(&self, kind: JavaExceptionKind) {
+        let _ = match self {
+            Self::CallSite(x, site) => x.link(*site, Err(kind)).map(|_| ()),
+            Self::Constant(x) => x.link(Err(kind)).map(|_| ()),
+        };
+    }
+}
+
+/// A VM control frame below a running bootstrap method.  When the bootstrap
+/// method returns, the dispatcher links the target with its result: a call
+/// site is then invoked on behalf of the suspended `invokedynamic`, while a
+/// constant lets whatever needed it try again.
+#[derive(Debug)]
+pub(crate) struct CallSiteLinkFrame {
+    holder: MSRef<NormalKlass>,
+    target: LinkTarget,
+}
+
+impl CallSiteLinkFrame {
+    pub(crate) fn new(holder: MSRef<NormalKlass>, target: LinkTarget) -> Self {
+        Self { holder, target }
+    }
+
+    /// The class whose constant pool holds the target.
+    pub(crate) fn holder(&self) -> &MSRef<NormalKlass> {
+        &self.holder
+    }
+
+    pub(crate) fn target(&self) -> &LinkTarget {
+        &self.target
+    }
+
+    /// Is this frame resolving `constant`?
+    pub(crate) fn is_linking(&self, constant: &DynamicCPEntry) -> bool {
+        matches!(&self.target, LinkTarget::Constant(x) if std::ptr::eq(&**x, constant))
+    }
+}
+
+/// The next step towards running a bootstrap method.
+#[derive(Debug)]
+pub(crate) enum BootstrapStep {
+    Invoke(Invocation),
+    /// A static argument is a dynamic constant that has to be resolved first.
+    Resolve(MSRef<DynamicCPEntry>),
+}
+
+/// Call-site specifier resolution (JVMS 5.4.3.6).  Every failure surfaces as
+/// `BootstrapMethodError`.
+#[derive(Debug)]
+pub struct CallSiteLinkage;
+
+type LinkResult<T> = Result<T, JavaExceptionKind>;
+
+fn link_error<E>(_: E) -> JavaExceptionKind {
+    JavaExceptionKind::BootstrapMethodError
+}
+
+impl CallSiteLinkage {
+    /// Resolve the bootstrap method of `target` and materialize its
+    /// arguments: a lookup on `holder`, the target's name and type, then the
+    /// static arguments converted to the bootstrap method's parameters.  A
+    /// variable-arity bootstrap method gets the trailing ones in an array.
+    pub(crate) fn bootstrap_invocation(holder: &NormalKlass, target: &LinkTarget) -> LinkResult<BootstrapStep> {
+        let bootstrap = holder
+            .bootstrap_method(target.bootstrap_method_attr_index() as usize)
+            .ok_or(JavaExceptionKind::BootstrapMethodError)?;
+
+        // 未解析的动态常量参数要先解析，完成后再重新进入这里。
+        for argument in &bootstrap.arguments {
+            if let Loadable::Dynamic(constant) = argument
+                && constant.value().is_none()
+            {
+                return Ok(BootstrapStep::Resolve(constant.clone()));
+            }
+        }
+
+        let handle = DirectMethodHandle::resolve(&bootstrap.method_handle, holder).map_err(link_error)?;
+        // Only static bootstrap methods are run; they are all javac emits.
+        let (RefKind::InvokeStatic, DirectMember::Method(method_ref)) = (handle.kind, handle.member) else {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        };
+
+        let method = method_ref.method();
+        let params = &method.desc.params_desc;
+        // 可变参数的引导方法把多出的静态参数收集进最后的数组参数。
+        let varargs =
+            method.acc_flags.contains(AccFlags::ACC_VARARGS) && params.last().is_some_and(|x| x.dimensions > 0);
+        let fixed = params.len() - usize::from(varargs);
+        let arity_matches = match varargs {
+            true => fixed <= LEADING_BOOTSTRAP_ARGS + bootstrap.arguments.len(),
+            false => fixed == LEADING_BOOTSTRAP_ARGS + bootstrap.arguments.len(),
+        };
+        if method.acc_flags.contains(AccFlags::ACC_NATIVE)
+            || matches!(method.desc.ret_desc, ReturnDesc::Void)
+            || fixed < LEADING_BOOTSTRAP_ARGS
+            || !arity_matches
+            || !params[..LEADING_BOOTSTRAP_ARGS].iter().all(FieldDesc::is_ref_type)
+        {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        }
+
+        // 返回值不做拆箱：基本类型常量的引导方法必须正好返回该类型。
+        if let (LinkTarget::Constant(constant), ReturnDesc::Type(ret)) = (target, &method.desc.ret_desc)
+            && (constant.desc.is_ref_type() != ret.is_ref_type()
+                || !constant.desc.is_ref_type() && constant.desc.raw != ret.raw)
+        {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        }
+
+        let type_arg = match target {
+            LinkTarget::CallSite(call_site, _) => JavaMethodType::create(call_site.desc.raw.utf8()).map_err(link_error)?,
+            LinkTarget::Constant(constant) => Self::type_mirror(holder, &constant.desc)?,
+        };
+        let mut args = vec![
+            Slot::reference(JavaLookup::create(holder).map_err(link_error)?),
+            Slot::reference(JavaString::from_symbol(target.name()).map_err(link_error)?),
+            Slot::reference(type_arg),
+        ];
+        let (fixed_args, trailing) = bootstrap.arguments.split_at(fixed - LEADING_BOOTSTRAP_ARGS);
+        for (argument, param) in fixed_args.iter().zip(&params[LEADING_BOOTSTRAP_ARGS..fixed]) {
+            args.extend(Self::static_argument(holder, argument, param)?);
+        }
+        if varargs {
+            args.push(Slot::reference(Self::collect(holder, trailing, &params[fixed])?));
+        }
+
+        Ok(BootstrapStep::Invoke(Invocation { target: method_ref, args }))
+    }
+
+    /// The `Class` passed for a constant's type.  Only classes and
+    /// interfaces have mirrors yet, so primitive and array types pass null.
+    fn type_mirror(holder: &NormalKlass, desc: &FieldDesc) -> LinkResult<NObjPtr> {
+        if desc.dimensions != 0 || !desc.is_ref_type() {
+            return Ok(0);
+        }
+
+        let klass = Self::reference_class(holder, desc)?;
+        JavaClass::mirror(&klass).map_err(link_error)
+    }
+
+    /// The class or array class `name`, as `holder` sees it.
+    fn load(holder: &NormalKlass, name: &str) -> LinkResult<MSRef<Klass>> {
+        match holder.cld() {
+            Some(cld) => cld.load_class(name),
+            None => BootstrapCLD::find_class(name),
+        }
+        .map_err(link_error)
+    }
+
+    /// The class a reference-typed constant's descriptor names, as `holder`
+    /// sees it.
+    fn reference_class(holder: &NormalKlass, desc: &FieldDesc) -> LinkResult<MSRef<NormalKlass>> {
+        let FieldElemType::Class { name, .. } = &desc.elem else {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        };
+
+        Self::load(holder, name.utf8())?
+            .as_normal_ref()
+            .ok_or(JavaExceptionKind::BootstrapMethodError)
+    }
+
+    /// Is `obj` null or an instance of the reference type `desc`?
+    fn is_instance(holder: &NormalKlass, obj: NObjPtr, desc: &FieldDesc) -> LinkResult<bool> {
+        if obj == 0 {
+            return Ok(true);
+        }
+
+        let klass = klass_of(obj).ok_or(JavaExceptionKind::BootstrapMethodError)?;
+        let instance = match klass.as_normal_ref() {
+            Some(klass) if desc.dimensions == 0 => klass.is_subtype_of(&*Self::reference_class(holder, desc)?),
+            // 数组只接受 Object 或描述符完全相同的类型。
+            _ => desc.raw.utf8() == "Ljava/lang/Object;" || klass.name().equals(&desc.raw),
+        };
+
+        Ok(instance)
+    }
+
+    /// The array a variable-arity bootstrap method receives for the static
+    /// arguments past its fixed parameters.  As with `invokeWithArguments`,
+    /// a lone argument that already has the array type is passed as it is.
+    fn collect(holder: &NormalKlass, arguments: &[Loadable], array: &FieldDesc) -> LinkResult<NObjPtr> {
+        if let [argument @ Loadable::Dynamic(entry)] = arguments
+            && entry.desc.raw == array.raw
+        {
+            return Self::reference_argument(holder, argument);
+        }
+
+        let component = FieldDesc::from(&array.raw.utf8()[1..]).map_err(link_error)?;
+        if !component.is_ref_type() {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        }
+
+        let array_class = Self::load(holder, array.raw.utf8())?;
+        let array_klass = array_class.as_array().ok_or(JavaExceptionKind::BootstrapMethodError)?;
+        let collected = array_klass.allocate(&array_class, arguments.len());
+        for (index, argument) in arguments.iter().enumerate() {
+            let obj = Self::reference_argument(holder, argument)?;
+            if !Self::is_instance(holder, obj, &component)? {
+                return Err(JavaExceptionKind::BootstrapMethodError);
+            }
+            ArrayKlass::write_ref(collected, index, obj).map_err(link_error)?;
+        }
+
+        Ok(collected)
+    }
+
+    /// The object a static argument becomes when its parameter is a
+    /// reference: numeric constants are boxed, as `Integer`, `Long`, `Float`
+    /// or `Double`.
+    fn reference_argument(holder: &NormalKlass, argument: &Loadable) -> LinkResult<NObjPtr> {
+        let boxed = |value| JavaBox::create(value).map_err(link_error)?.ok_or(JavaExceptionKind::BootstrapMethodError);
+
+        match argument {
+            Loadable::Integer(x) => boxed(RetValue::Int(*x)),
+            Loadable::Float(x) => boxed(RetValue::Float(*x)),
+            Loadable::Long(x) => boxed(RetValue::Long(*x)),
+            Loadable::Double(x) => boxed(RetValue::Double(*x)),
+            Loadable::Class(entry) => {
+                let klass = entry.resolve(holder).map_err(link_error)?;
+                let klass = klass.as_normal().ok_or(JavaExceptionKind::BootstrapMethodError)?;
+                JavaClass::mirror(klass).map_err(link_error)
+            }
+            Loadable::StringLoadable(entry) => JavaString::from_symbol(entry.raw()).map_err(link_error),
+            Loadable::MethodType(desc) => JavaMethodType::create(desc.raw.utf8()).map_err(link_error),
+            Loadable::MethodHandle(entry) => JavaMethodHandle::constant(entry, holder).map_err(link_error),
+            Loadable::Dynamic(entry) => match entry.value() {
+                Some(Ok(RetValue::Ref(obj))) => Ok(obj),
+                Some(Ok(value)) => boxed(value),
+                _ => Err(JavaExceptionKind::BootstrapMethodError),
+            },
+        }
+    }
+
+    /// A reference parameter takes any static argument of its type, boxing
+    /// numeric constants.  A primitive parameter takes a numeric constant of
+    /// exactly its type; nothing is unboxed.
+    fn static_argument(holder: &NormalKlass, argument: &Loadable, param: &FieldDesc) -> LinkResult<Vec<Slot>> {
+        if param.is_ref_type() {
+            let obj = Self::reference_argument(holder, argument)?;
+            return match Self::is_instance(holder, obj, param)? {
+                true => Ok(vec![Slot::reference(obj)]),
+                false => Err(JavaExceptionKind::BootstrapMethodError),
+            };
+        }
+
+        let slots = match (argument, &param.elem) {
+            (
+                Loadable::Integer(x),
+                FieldElemType::Int
+                | FieldElemType::Short
+                | FieldElemType::Char
+                | FieldElemType::Byte
+                | FieldElemType::Boolean,
+            ) => vec![Slot::int(*x)],
+            (Loadable::Float(x), FieldElemType::Float) => vec![Slot::float(*x)],
+            (Loadable::Long(x), FieldElemType::Long) => vec![Slot::long_high(*x), Slot::long_low(*x)],
+            (Loadable::Double(x), FieldElemType::Double) => vec![Slot::double_high(*x), Slot::double_low(*x)],
+            // 动态常量的类型必须和参数完全一致，基本类型同样不做转换。
+            (Loadable::Dynamic(entry), _) if entry.desc.raw == param.raw => match entry.value() {
+                Some(Ok(RetValue::Int(x))) => vec![Slot::int(x)],
+                Some(Ok(RetValue::Float(x))) => vec![Slot::float(x)],
+                Some(Ok(RetValue::Long(x))) => vec![Slot::long_high(x), Slot::long_low(x)],
+                Some(Ok(RetValue::Double(x))) => vec![Slot::double_high(x), Slot::double_low(x)],
+                _ => return Err(JavaExceptionKind::BootstrapMethodError),
+            },
+            _ => return Err(JavaExceptionKind::BootstrapMethodError),
+        };
+
+        Ok(slots)
+    }
+
+    /// Check what the bootstrap method returned: a `CallSite` whose target has
+    /// exactly the type of the `invokedynamic` instruction.
+    pub fn check_call_site(call_site: &InvokeDynamicCPEntry, value: RetValue) -> LinkResult<NObjPtr> {
+        let RetValue::Ref(obj) = value else {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        };
+        if obj == 0 || !JavaCallSite::is_call_site(obj) {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        }
+
+        let target = JavaCallSite::target(obj).map_err(link_error)?;
+        if target == 0 || !JavaMethodHandle::is_method_handle(target) {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        }
+
+        let target_type = JavaMethodHandle::type_descriptor(target).map_err(link_error)?;
+        if target_type != call_site.desc.raw.utf8() {
+            return Err(JavaExceptionKind::BootstrapMethodError);
+        }
+
+        Ok(obj)
+    }
+
+    /// Check what the bootstrap method returned for a reference-typed
+    /// constant: null or an instance of the constant's type.
+    pub(crate) fn check_constant(holder: &NormalKlass, constant: &DynamicCPEntry, value: RetValue) -> LinkResult<RetValue> {
+        let RetValue::Ref(obj) = value else {
+            return Ok(value);
+        };
+
+        match Self::is_instance(holder, obj, &constant.desc)? {
+            true => Ok(value),
+            false => Err(JavaExceptionKind::BootstrapMethodError),
+        }
+    }
+
+    /// The direct method handle a linked call site currently targets.
+    pub fn target_handle(call_site: NObjPtr) -> ExecResult<DirectMethodHandle> {
+        let target = JavaCallSite::target(call_site)?;
+        JavaMethodHandle::direct(target)?.ok_or(ExecError::UnsupportedMethodHandle(None))
+    }
+}
@@ -23,6 +23,15 @@ pub(crate) enum Continuation {
     },
     GetStatic(ResolvedFieldRef),
     PutStatic(ResolvedFieldRef),
+    New(MSRef<NormalKlass>),
+    /// Run the constructor of a `REF_newInvokeSpecial` handle on a new object.
+    Construct {
+        target: ResolvedMethod,
+        arg_slots: usize,
+    },
+    /// Enter the bootstrap method of a call site being linked; the
+    /// CallSiteLinkFrame below it receives the result.
+    InvokeBootstrap(Invocation),
     /// Resume the ClassInitFrame that requested a prerequisite initialization.
     ResumeInitializer,
 }
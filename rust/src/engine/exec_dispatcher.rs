@@ -1,33 +1,57 @@
 use crate::{
-    class_loader::ms_api::MSRef,
+    class_loader::{bootstrap_cld::BootstrapCLD, ms_api::MSRef},
     engine::{
         call::Invocation,
+        call_site::{BootstrapStep, CallSiteLinkFrame, CallSiteLinkage, LinkTarget},
         class_init::{ClassInitFrame, ClassInitPhase, ClassInitialization, Continuation},
         exec_error::{ExecError, ExecResult, JavaExceptionKind},
         interpreter::{interpreter::Interpreter, interpreter_frame::InterpreterFrame},
+        method_handle::{ConstructFrame, HandleInvocation},
+        native::registry::NativeRegistry,
         outcome::{PendingException, RetValue, RunOutcome, StepOutcome, ThreadExit},
         resolved_method::ResolvedMethod,
+        slot::Slot,
+        stack_trace::{StackTrace, ThrowableReport},
     },
+    gc_bindings::{oop_codec::klass_of, oop_handle::NObjPtr},
     oops::{
         acc_flags::AccFlags,
-        cp_entry::ResolvedFieldRef,
+        cp_entry::{CallSiteKey, DynamicCPEntry, InvokeDynamicCPEntry, RefKind, ResolvedFieldRef},
+        desc::MethodDesc,
+        java_invoke::{DirectMember, DirectMethodHandle, JavaMethodHandle},
+        java_throwable::{JavaThrowable, THROWABLE_CLASS, is_error},
         normal_klass::{ClassInitAction, NormalKlass},
     },
     runtime::{java_stack::JavaFrame, java_thread::JavaThread},
 };
 
+/// Called once a thread has been unwound by an uncaught exception.
+pub type UncaughtExceptionHandler = fn(&JavaThread, &ThrowableReport);
+
+/// The default handler: HotSpot's `Exception in thread "..."` report on stderr.
+pub fn print_uncaught_exception(thread: &JavaThread, report: &ThrowableReport) {
+    eprint!("Exception in thread \"{}\" {report}", thread.name());
+}
+
 #[derive(Debug)]
 pub struct ExecDispatcher {
     interpreter: Interpreter,
+    uncaught_handler: UncaughtExceptionHandler,
 }
 
 impl ExecDispatcher {
     pub fn new() -> Self {
         Self {
             interpreter: Interpreter,
+            uncaught_handler: print_uncaught_exception,
         }
     }
 
+    /// Replace the default stderr report, e.g. to silence or capture it.
+    pub fn set_uncaught_exception_handler(&mut self, handler: UncaughtExceptionHandler) {
+        self.uncaught_handler = handler;
+    }
+
     pub fn enter_root(
         &mut self,
         thread: &mut JavaThread,
@@ -44,7 +68,7 @@ impl ExecDispatcher {
             let holder = invocation.target.holder_ref();
             self.request_class_initialization(thread, holder, Continuation::EnterRoot(invocation))
         } else {
-            self.commit_root(thread, invocation)
+            self.commit_invocation(thread, invocation)
         }
     }
 
@@ -62,7 +86,9 @@ impl ExecDispatcher {
         )
     }
 
-    fn commit_static_call(
+    /// Enter `target` with the top `arg_slots` of the caller's operand stack.
+    /// Also used by instance calls, whose target needs no initialization.
+    fn commit_call(
         &mut self,
         thread: &mut JavaThread,
         target: ResolvedMethod,
@@ -70,16 +96,57 @@ impl ExecDispatcher {
     ) -> ExecResult<()> {
         // Arguments remain on the suspended caller until initialization has
         // succeeded and this commit path is reached.
+        if !Self::ensure_verified(thread, target.holder()) {
+            return Ok(());
+        }
+
         let args = thread
             .stack()
             .current_interpreter()
             .map_err(ExecError::Stack)?
             .peek_top_slots(arg_slots)?;
 
+        if target.method().acc_flags.contains(AccFlags::ACC_NATIVE) {
+            return Self::commit_native_call(thread, target, args);
+        }
+
         let frame = Self::build_interpreter_frame(Invocation { target, args })?;
         thread.stack_mut().push_interpreter_call(frame, arg_slots)
     }
 
+    /// Natives run to completion on the caller's frame: no interpreter frame
+    /// is pushed, the arguments are popped and the result pushed in place.
+    fn commit_native_call(
+        thread: &mut JavaThread,
+        target: ResolvedMethod,
+        args: Vec<Slot>,
+    ) -> ExecResult<()> {
+        let Some(native) = NativeRegistry::lookup(target.holder(), target.method()) else {
+            thread.pending_exception = Some(PendingException::JVMGen(
+                JavaExceptionKind::UnsatisfiedLinkError,
+            ));
+            return Ok(());
+        };
+
+        let value = native(thread, &args)?;
+
+        thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?
+            .drop_top_slots(args.len())?;
+
+        if thread.pending_exception.is_some() {
+            return Ok(());
+        }
+
+        thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?
+            .push_return_value(value)
+    }
+
     fn request_get_static(
         &mut self,
         thread: &mut JavaThread,
@@ -140,7 +207,329 @@ impl ExecDispatcher {
             .drop_top_slots(slot_count)
     }
 
-    fn commit_root(&mut self, thread: &mut JavaThread, invocation: Invocation) -> ExecResult<()> {
+    fn request_new(&mut self, thread: &mut JavaThread, klass: MSRef<NormalKlass>) -> ExecResult<()> {
+        self.request_class_initialization(thread, klass.clone(), Continuation::New(klass))
+    }
+
+    fn commit_new(&mut self, thread: &mut JavaThread, klass: MSRef<NormalKlass>) -> ExecResult<()> {
+        let obj = klass.allocate_instance();
+        thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?
+            .push(Slot::reference(obj))
+    }
+
+    /// JVMS 6.5 `invokedynamic`.  The first execution of a call site runs its
+    /// bootstrap method as Java code above a CallSiteLinkFrame; the call itself
+    /// resumes in `complete_call_site_link`.  Later executions reuse the
+    /// linked CallSite, or rethrow the error linking failed with.
+    fn request_invoke_dynamic(
+        &mut self,
+        thread: &mut JavaThread,
+        holder: MSRef<NormalKlass>,
+        call_site: MSRef<InvokeDynamicCPEntry>,
+        site: CallSiteKey,
+    ) -> ExecResult<()> {
+        match call_site.call_site(site) {
+            Some(Ok(linked)) => self.invoke_call_site(thread, &call_site, linked),
+            Some(Err(kind)) => Self::raise(thread, kind),
+            None => self.request_link(thread, holder, LinkTarget::CallSite(call_site, site)),
+        }
+    }
+
+    /// `ldc` of a dynamically-computed constant (JVMS 5.4.3.6).  It is
+    /// resolved like a call site, and the value pushed once it is known.
+    fn request_load_dynamic(
+        &mut self,
+        thread: &mut JavaThread,
+        holder: MSRef<NormalKlass>,
+        constant: MSRef<DynamicCPEntry>,
+    ) -> ExecResult<()> {
+        match constant.value() {
+            Some(Ok(value)) => thread
+                .stack_mut()
+                .current_interpreter_mut()
+                .map_err(ExecError::Stack)?
+                .push_return_value(value),
+            Some(Err(kind)) => Self::raise(thread, kind),
+            None => self.request_link(thread, holder, LinkTarget::Constant(constant)),
+        }
+    }
+
+    /// Push a CallSiteLinkFrame for `target` and start on its bootstrap
+    /// method.
+    fn request_link(&mut self, thread: &mut JavaThread, holder: MSRef<NormalKlass>, target: LinkTarget) -> ExecResult<()> {
+        // 常量的引导参数直接或间接依赖它自己时永远无法解析。HotSpot 会一直
+        // 递归到栈溢出，这里直接失败。
+        if let LinkTarget::Constant(constant) = &target
+            && thread.stack().is_linking(constant)
+        {
+            return Self::raise(thread, JavaExceptionKind::BootstrapMethodError);
+        }
+
+        thread
+            .stack_mut()
+            .push_call_site_link(CallSiteLinkFrame::new(holder, target))
+            .map_err(ExecError::Stack)?;
+        self.run_bootstrap(thread)
+    }
+
+    /// Invoke the bootstrap method of the CallSiteLinkFrame on top of the
+    /// stack, or first resolve a dynamic constant among its arguments; this
+    /// is tried again once that constant is linked.
+    fn run_bootstrap(&mut self, thread: &mut JavaThread) -> ExecResult<()> {
+        let frame = thread.stack().current_call_site_link().map_err(ExecError::Stack)?;
+        let (holder, target) = (frame.holder().clone(), frame.target().clone());
+
+        match CallSiteLinkage::bootstrap_invocation(&holder, &target) {
+            Ok(BootstrapStep::Invoke(invocation)) => {
+                let bootstrap_holder = invocation.target.holder_ref();
+                self.request_class_initialization(
+                    thread,
+                    bootstrap_holder,
+                    Continuation::InvokeBootstrap(invocation),
+                )
+            }
+            Ok(BootstrapStep::Resolve(constant)) => self.request_link(thread, holder, LinkTarget::Constant(constant)),
+            Err(kind) => {
+                thread.stack_mut().pop();
+                target.fail(kind);
+                Self::raise(thread, kind)
+            }
+        }
+    }
+
+    /// The bootstrap method returned `value` to the CallSiteLinkFrame on top
+    /// of the stack.  Link the target, then continue the suspended call, push
+    /// the constant, or retry the link frame that needed it.
+    fn complete_call_site_link(&mut self, thread: &mut JavaThread, value: RetValue) -> ExecResult<()> {
+        let frame = thread.stack_mut().pop().ok_or(ExecError::NoCurrentFrame)?;
+        let JavaFrame::CallSiteLink(frame) = frame else {
+            return Err(ExecError::InvalidCallSiteLinkFrameState);
+        };
+
+        match frame.target() {
+            LinkTarget::CallSite(call_site, site) => {
+                let result = CallSiteLinkage::check_call_site(call_site, value);
+                match call_site.link(*site, result) {
+                    Ok(linked) => self.invoke_call_site(thread, call_site, linked),
+                    Err(kind) => Self::raise(thread, kind),
+                }
+            }
+            LinkTarget::Constant(constant) => {
+                let result = CallSiteLinkage::check_constant(frame.holder(), constant, value);
+                match constant.link(result) {
+                    Ok(_) if thread.stack().current_is_call_site_link() => self.run_bootstrap(thread),
+                    Ok(value) => thread
+                        .stack_mut()
+                        .current_interpreter_mut()
+                        .map_err(ExecError::Stack)?
+                        .push_return_value(value),
+                    Err(kind) => Self::raise(thread, kind),
+                }
+            }
+        }
+    }
+
+    /// Invoke the current target of a linked call site.  Its arguments are
+    /// still on the caller's operand stack, typed by the call site descriptor.
+    fn invoke_call_site(
+        &mut self,
+        thread: &mut JavaThread,
+        call_site: &InvokeDynamicCPEntry,
+        linked: NObjPtr,
+    ) -> ExecResult<()> {
+        let target = CallSiteLinkage::target_handle(linked)?;
+        let arg_slots = call_site.desc.parameter_slot_count();
+        self.invoke_direct_handle(thread, target, arg_slots)
+    }
+
+    /// `invokeExact`/`invoke`: check the handle on the caller's operand stack
+    /// against the call site, remove it from below the arguments and invoke
+    /// the member it refers to.
+    fn request_handle_invocation(
+        &mut self,
+        thread: &mut JavaThread,
+        call_type: MethodDesc,
+        exact: bool,
+    ) -> ExecResult<()> {
+        let arg_slots = call_type.parameter_slot_count();
+        let mut args = thread
+            .stack()
+            .current_interpreter()
+            .map_err(ExecError::Stack)?
+            .peek_top_slots(arg_slots + 1)?;
+        let handle = args.remove(0).as_ref()?;
+
+        if let Some(kind) = HandleInvocation::check(handle, &call_type, exact, &args)? {
+            return Self::raise(thread, kind);
+        }
+        let direct = JavaMethodHandle::direct(handle)?.ok_or(ExecError::UnsupportedMethodHandle(None))?;
+
+        thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?
+            .remove_below_top_slots(arg_slots)?;
+
+        self.invoke_direct_handle(thread, direct, arg_slots)
+    }
+
+    /// Invoke or access the member of `handle` with the top `arg_slots` of the
+    /// caller's operand stack, as the bytecode its reference kind names would.
+    fn invoke_direct_handle(
+        &mut self,
+        thread: &mut JavaThread,
+        handle: DirectMethodHandle,
+        arg_slots: usize,
+    ) -> ExecResult<()> {
+        let has_receiver = !matches!(
+            handle.kind,
+            RefKind::GetStatic | RefKind::PutStatic | RefKind::InvokeStatic | RefKind::NewInvokeSpecial
+        );
+        let receiver = if has_receiver {
+            let receiver = thread
+                .stack()
+                .current_interpreter()
+                .map_err(ExecError::Stack)?
+                .peek_top_slots(arg_slots)?[0]
+                .as_ref()?;
+            match ResolvedMethod::receiver_class(receiver) {
+                None => return Self::raise(thread, JavaExceptionKind::NullPointerException),
+                Some(class) if !class.is_subtype_of(handle.holder()) => {
+                    return Self::raise(thread, JavaExceptionKind::ClassCastException);
+                }
+                class => class,
+            }
+        } else {
+            None
+        };
+
+        match (handle.kind, handle.member) {
+            (RefKind::GetStatic, DirectMember::Field(field)) => self.request_get_static(thread, field),
+            (RefKind::PutStatic, DirectMember::Field(field)) => self.request_put_static(thread, field),
+            (RefKind::GetField, DirectMember::Field(field)) => Self::commit_get_field(thread, field),
+            (RefKind::PutField, DirectMember::Field(field)) => Self::commit_put_field(thread, field),
+            (RefKind::InvokeStatic, DirectMember::Method(target)) => {
+                self.request_static_call(thread, target, arg_slots)
+            }
+            (RefKind::InvokeSpecial, DirectMember::Method(target)) => {
+                self.commit_call(thread, target, arg_slots)
+            }
+            (RefKind::InvokeVirtual | RefKind::InvokeInterface, DirectMember::Method(method)) => {
+                let selected = receiver
+                    .ok_or(JavaExceptionKind::NullPointerException)
+                    .and_then(|class| method.select(&class));
+                match selected {
+                    Ok(target) => self.commit_call(thread, target, arg_slots),
+                    Err(kind) => Self::raise(thread, kind),
+                }
+            }
+            (RefKind::NewInvokeSpecial, DirectMember::Method(target)) => {
+                let holder = target.holder_ref();
+                self.request_class_initialization(
+                    thread,
+                    holder,
+                    Continuation::Construct { target, arg_slots },
+                )
+            }
+            (kind, _) => Err(ExecError::UnsupportedMethodHandle(Some(kind))),
+        }
+    }
+
+    /// `REF_getField`: replace the receiver on top of the stack with the
+    /// field's value.
+    fn commit_get_field(thread: &mut JavaThread, resolved: ResolvedFieldRef) -> ExecResult<()> {
+        let frame = thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?;
+        let receiver = frame.pop()?.as_ref()?;
+        let slots = resolved.holder.read_instance_field(receiver, &resolved.field)?;
+        frame.push_slots(&slots)
+    }
+
+    /// `REF_putField`: store the value on top of the stack into the receiver
+    /// below it.
+    fn commit_put_field(thread: &mut JavaThread, resolved: ResolvedFieldRef) -> ExecResult<()> {
+        let slot_count = resolved.field.desc.slot_count() + 1;
+        let frame = thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?;
+        let slots = frame.peek_top_slots(slot_count)?;
+
+        resolved
+            .holder
+            .write_instance_field(slots[0].as_ref()?, &resolved.field, &slots[1..])?;
+        frame.drop_top_slots(slot_count)
+    }
+
+    /// `REF_newInvokeSpecial`: allocate the object and run the constructor
+    /// above a ConstructFrame, which returns the object to the caller.
+    fn commit_construct(
+        &mut self,
+        thread: &mut JavaThread,
+        target: ResolvedMethod,
+        arg_slots: usize,
+    ) -> ExecResult<()> {
+        let object = target.holder().allocate_instance();
+
+        let frame = thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?;
+        let mut args = vec![Slot::reference(object)];
+        args.extend(frame.peek_top_slots(arg_slots)?);
+        frame.drop_top_slots(arg_slots)?;
+
+        thread
+            .stack_mut()
+            .push_construct(ConstructFrame::new(object))
+            .map_err(ExecError::Stack)?;
+        self.commit_invocation(thread, Invocation { target, args })
+    }
+
+    /// The constructor run by `commit_construct` returned into its frame.
+    fn complete_construct(thread: &mut JavaThread) -> ExecResult<()> {
+        let frame = thread.stack_mut().pop().ok_or(ExecError::NoCurrentFrame)?;
+        let JavaFrame::Construct(frame) = frame else {
+            return Err(ExecError::InvalidConstructFrameState);
+        };
+
+        thread
+            .stack_mut()
+            .current_interpreter_mut()
+            .map_err(ExecError::Stack)?
+            .push(Slot::reference(frame.object()))
+    }
+
+    /// Raise a VM-generated exception on `thread`.
+    fn raise(thread: &mut JavaThread, kind: JavaExceptionKind) -> ExecResult<()> {
+        thread.pending_exception = Some(PendingException::JVMGen(kind));
+        Ok(())
+    }
+
+    /// JVMS 5.4.1: verify `klass` before its code first runs.  On failure
+    /// the error is raised on `thread` and `false` returned.
+    fn ensure_verified(thread: &mut JavaThread, klass: &NormalKlass) -> bool {
+        match klass.verify() {
+            Ok(()) => true,
+            Err(error) => {
+                thread.pending_exception = Some(PendingException::JVMGen(error.java_exception()));
+                false
+            }
+        }
+    }
+
+    /// A materialized invocation has no caller operands to drop: a root call,
+    /// or a bootstrap method entered from a CallSiteLinkFrame.
+    fn commit_invocation(&mut self, thread: &mut JavaThread, invocation: Invocation) -> ExecResult<()> {
+        if !Self::ensure_verified(thread, invocation.target.holder()) {
+            return Ok(());
+        }
+
         let frame = Self::build_interpreter_frame(invocation)?;
         thread
             .stack_mut()
@@ -154,6 +543,11 @@ impl ExecDispatcher {
         klass: MSRef<NormalKlass>,
         continuation: Continuation,
     ) -> ExecResult<()> {
+        // JVMS 5.5：初始化前类必须已链接，校验是链接的一步。
+        if !Self::ensure_verified(thread, &klass) {
+            return Ok(());
+        }
+
         match ClassInitialization::begin(&klass, thread.id())? {
             ClassInitAction::AlreadyInitialized | ClassInitAction::RecursiveRequest => {
                 self.apply_continuation(thread, continuation)
@@ -183,12 +577,18 @@ impl ExecDispatcher {
         continuation: Continuation,
     ) -> ExecResult<()> {
         match continuation {
-            Continuation::EnterRoot(invocation) => self.commit_root(thread, invocation),
+            Continuation::EnterRoot(invocation) | Continuation::InvokeBootstrap(invocation) => {
+                self.commit_invocation(thread, invocation)
+            }
             Continuation::InvokeStatic { target, arg_slots } => {
-                self.commit_static_call(thread, target, arg_slots)
+                self.commit_call(thread, target, arg_slots)
             }
             Continuation::GetStatic(resolved) => self.commit_get_static(thread, resolved),
             Continuation::PutStatic(resolved) => self.commit_put_static(thread, resolved),
+            Continuation::New(klass) => self.commit_new(thread, klass),
+            Continuation::Construct { target, arg_slots } => {
+                self.commit_construct(thread, target, arg_slots)
+            }
             Continuation::ResumeInitializer => {
                 let initializer = thread
                     .stack_mut()
@@ -320,6 +720,19 @@ impl ExecDispatcher {
             return Ok(Some(RunOutcome::Terminated(ThreadExit::Returned(value))));
         }
 
+        if thread.stack().current_is_call_site_link() {
+            self.complete_call_site_link(thread, value)?;
+            return Ok(None);
+        }
+
+        if thread.stack().current_is_construct() {
+            if !matches!(value, RetValue::Void) {
+                return Err(ExecError::InvalidConstructFrameState);
+            }
+            Self::complete_construct(thread)?;
+            return Ok(None);
+        }
+
         if thread.stack().current_is_class_init() {
             if !matches!(value, RetValue::Void) {
                 return Err(ExecError::InvalidClassInitializerReturn);
@@ -344,13 +757,62 @@ impl ExecDispatcher {
         Ok(None)
     }
 
-    fn terminate_with_exception(
+    /// Unwind to the nearest handler for `exception`.  `None` when one was
+    /// found and execution resumes there; otherwise the thread ends.
+    fn dispatch_exception(
         &mut self,
         thread: &mut JavaThread,
-        exception: PendingException,
-    ) -> ExecResult<RunOutcome> {
+        mut exception: PendingException,
+    ) -> ExecResult<Option<RunOutcome>> {
+        // The trace of a VM-raised exception is the stack at the throw point,
+        // so take the snapshot before any frame is popped.
+        let mut trace = match exception {
+            PendingException::JVMGen(_) => StackTrace::capture(thread.stack()),
+            PendingException::JavaObj(_) => StackTrace::default(),
+        };
+        let mut klass = Self::exception_klass(&exception);
+
         let mut cleanup_error = None;
-        while let Some(frame) = thread.stack_mut().pop() {
+        loop {
+            if cleanup_error.is_none()
+                && let Some(klass) = &klass
+                && let Ok(frame) = thread.stack_mut().current_interpreter_mut()
+                && let Some(handler_pc) = frame.find_handler(klass)
+            {
+                let obj = Self::materialize(&exception, &trace)?;
+                frame.enter_handler(handler_pc, obj)?;
+                return Ok(None);
+            }
+
+            let Some(frame) = thread.stack_mut().pop() else {
+                break;
+            };
+
+            // The bootstrap method completed abruptly, so linking failed for
+            // good.  JVMS 6.5 invokedynamic: anything but an Error reaches
+            // the caller as a BootstrapMethodError caused by it.
+            if let JavaFrame::CallSiteLink(frame) = &frame {
+                if klass.as_deref().is_some_and(is_error) {
+                    // Later attempts see the same error where the VM can
+                    // name it.
+                    let kind = match exception {
+                        PendingException::JVMGen(kind) => kind,
+                        PendingException::JavaObj(_) => JavaExceptionKind::BootstrapMethodError,
+                    };
+                    frame.target().fail(kind);
+                } else {
+                    let cause = Self::materialize(&exception, &trace)?;
+                    trace = StackTrace::capture(thread.stack());
+                    exception = PendingException::JVMGen(JavaExceptionKind::BootstrapMethodError);
+                    let wrapper = Self::materialize(&exception, &trace)?;
+                    JavaThrowable::set_cause(wrapper, Some(cause))?;
+
+                    exception = PendingException::JavaObj(wrapper);
+                    klass = Self::exception_klass(&exception);
+                    frame.target().fail(JavaExceptionKind::BootstrapMethodError);
+                }
+            }
+
             if let JavaFrame::ClassInit(frame) = frame {
                 let phase = frame.phase();
                 let (klass, _) = frame.into_parts();
@@ -382,10 +844,60 @@ impl ExecDispatcher {
             return Err(error);
         }
 
+        let report = Self::uncaught_report(&exception, trace);
         thread.terminate();
-        Ok(RunOutcome::Terminated(ThreadExit::UncaughtException(
+        (self.uncaught_handler)(thread, &report);
+        Ok(Some(RunOutcome::Terminated(ThreadExit::UncaughtException(
             exception,
-        )))
+        ))))
+    }
+
+    /// The class handlers are matched against.  A VM-raised exception whose
+    /// class cannot be loaded is caught by nothing.
+    fn exception_klass(exception: &PendingException) -> Option<MSRef<NormalKlass>> {
+        match exception {
+            PendingException::JavaObj(obj) => klass_of(*obj)?.as_normal_ref(),
+            PendingException::JVMGen(kind) => BootstrapCLD::find_class(kind.class_name()).ok()?.as_normal_ref(),
+        }
+    }
+
+    /// The object a handler receives.  A VM-raised exception only becomes
+    /// one once something catches it.
+    fn materialize(exception: &PendingException, trace: &StackTrace) -> ExecResult<NObjPtr> {
+        match exception {
+            PendingException::JavaObj(obj) => Ok(*obj),
+            PendingException::JVMGen(kind) => {
+                let obj = JavaThrowable::create(kind.class_name(), kind.message())?;
+                JavaThrowable::set_stack_trace(obj, trace)?;
+                Ok(obj)
+            }
+        }
+    }
+
+    fn uncaught_report(exception: &PendingException, trace: StackTrace) -> ThrowableReport {
+        match exception {
+            PendingException::JVMGen(kind) => ThrowableReport::for_kind(*kind, trace),
+            // A Java object carries the trace recorded when it was created.
+            PendingException::JavaObj(obj) => {
+                JavaThrowable::report(*obj).unwrap_or_else(|_| ThrowableReport {
+                    class_name: klass_of(*obj)
+                        .map(|klass| klass.name().utf8().to_string())
+                        .unwrap_or_else(|| THROWABLE_CLASS.into()),
+                    message: None,
+                    stack_trace: StackTrace::default(),
+                    cause: None,
+                })
+            }
+        }
+    }
+
+    /// `System.exit` ends the thread without running any more Java code.
+    /// Classes whose initialization was in progress are released rather than
+    /// marked erroneous, since nothing went wrong inside their `<clinit>`.
+    fn terminate_with_exit(&mut self, thread: &mut JavaThread, status: i32) -> RunOutcome {
+        self.abort_after_engine_error(thread);
+        thread.terminate();
+        RunOutcome::Terminated(ThreadExit::SystemExit(status))
     }
 
     /// An ExecError is a VM failure rather than a Java exception. The current
@@ -399,6 +911,22 @@ impl ExecDispatcher {
         }
     }
 
+    /// Name the instruction an engine error was raised by, for embedders'
+    /// logs.
+    fn at_current_instruction(thread: &JavaThread, error: ExecError) -> ExecError {
+        let Ok(frame) = thread.stack().current_interpreter() else {
+            return error;
+        };
+        let method = frame.target().method();
+
+        ExecError::At {
+            class: frame.target().holder().name().utf8().into(),
+            method: format!("{}{}", method.name.utf8(), method.desc.raw.utf8()),
+            bci: frame.last_pc(),
+            source: Box::new(error),
+        }
+    }
+
     /// Shared interpreter-frame construction after arguments are materialized.
     fn build_interpreter_frame(invocation: Invocation) -> ExecResult<InterpreterFrame> {
         InterpreterFrame::new(invocation.target, &invocation.args)
@@ -412,7 +940,24 @@ impl ExecDispatcher {
             return Ok(None);
         }
 
-        match self.interpreter.execute_one(thread)? {
+        // A CallSiteLinkFrame is only ever current while a bootstrap method
+        // returns into it, and a ConstructFrame while a constructor does.
+        if thread.stack().current_is_call_site_link() {
+            return Err(ExecError::InvalidCallSiteLinkFrameState);
+        }
+        if thread.stack().current_is_construct() {
+            return Err(ExecError::InvalidConstructFrameState);
+        }
+
+        let outcome = match self.interpreter.execute_one(thread) {
+            Ok(outcome) => outcome,
+            Err(error) => match error.java_exception() {
+                Some(kind) => StepOutcome::Throw(PendingException::JVMGen(kind)),
+                None => return Err(Self::at_current_instruction(thread, error)),
+            },
+        };
+
+        match outcome {
             StepOutcome::Continue => {}
 
             StepOutcome::Branch(target) => {
@@ -435,12 +980,32 @@ impl ExecDispatcher {
                 self.request_static_call(thread, target, arg_slots)?;
             }
 
+            StepOutcome::InvokeInstance { target, arg_slots } => {
+                self.commit_call(thread, target, arg_slots)?;
+            }
+
+            StepOutcome::InvokeHandle { call_type, exact } => {
+                self.request_handle_invocation(thread, call_type, exact)?;
+            }
+
+            StepOutcome::InvokeDynamic { holder, call_site, site } => {
+                self.request_invoke_dynamic(thread, holder, call_site, site)?;
+            }
+
+            StepOutcome::LoadDynamic { holder, constant } => {
+                self.request_load_dynamic(thread, holder, constant)?;
+            }
+
+            StepOutcome::New(klass) => {
+                self.request_new(thread, klass)?;
+            }
+
             StepOutcome::Return(value) => {
                 return self.complete_interpreter_return(thread, value);
             }
 
             StepOutcome::Throw(exception) => {
-                return self.terminate_with_exception(thread, exception).map(Some);
+                return self.dispatch_exception(thread, exception);
             }
         }
 
@@ -453,8 +1018,19 @@ impl ExecDispatcher {
         budget: usize,
     ) -> ExecResult<RunOutcome> {
         for _ in 0..budget {
+            if let Some(status) = thread.exit_request.take() {
+                return Ok(self.terminate_with_exit(thread, status));
+            }
+
             if let Some(exception) = thread.pending_exception.take() {
-                return self.terminate_with_exception(thread, exception);
+                match self.dispatch_exception(thread, exception) {
+                    Ok(Some(outcome)) => return Ok(outcome),
+                    Ok(None) => continue,
+                    Err(error) => {
+                        self.abort_after_engine_error(thread);
+                        return Err(error);
+                    }
+                }
             }
 
             match self.run_one(thread) {
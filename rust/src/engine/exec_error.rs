@@ -1,6 +1,12 @@
+use std::{error::Error, fmt};
+
 use crate::{
+    class_loader::load_error::LoadError,
     engine::slot::SlotKind,
-    oops::oops_errors::{ClassInitError, ResolveError},
+    oops::{
+        cp_entry::RefKind,
+        oops_errors::{ClassInitError, ResolveError},
+    },
     runtime::runtime_error::StackError,
 };
 
@@ -16,6 +22,12 @@ impl From<ResolveError> for ExecError {
     }
 }
 
+impl From<LoadError> for ExecError {
+    fn from(value: LoadError) -> Self {
+        Self::Load(value)
+    }
+}
+
 impl From<ClassInitError> for ExecError {
     fn from(value: ClassInitError) -> Self {
         Self::ClassInitialization(value)
@@ -26,15 +38,36 @@ impl From<ClassInitError> for ExecError {
 pub enum ExecError {
     Stack(StackError),
     Resolve(ResolveError),
+    Load(LoadError),
     ClassInitialization(ClassInitError),
+    /// `source` was raised executing the instruction at `bci` of `method`
+    /// (`name(desc)`) in `class`.
+    At {
+        class: String,
+        method: String,
+        bci: usize,
+        source: Box<ExecError>,
+    },
     InvalidClassInitializerReturn,
     InvalidClassInitializationFrameState,
+    InvalidCallSiteLinkFrameState,
+    InvalidConstructFrameState,
     IncompatibleStaticCall,
+    IncompatibleInstanceCall,
     IncompatibleStaticFieldAccess,
+    IncompatibleInstanceFieldAccess,
+    /// The `getfield`/`putfield` receiver is not an instance of the field's
+    /// class, which the verifier would have rejected.
+    ReceiverLacksField,
+    NullObjectReference,
+    ArrayIndexOutOfBounds(usize),
     InvalidStaticFieldStorage,
     InvalidFieldValue,
     InvalidConstantValue,
     UnsupportedStringConstantValue,
+    /// A call-site target the engine cannot invoke yet; `None` if it is not
+    /// a direct method handle.
+    UnsupportedMethodHandle(Option<RefKind>),
 
     NoCurrentFrame,
     MethodHasNoCode,
@@ -88,8 +121,134 @@ pub enum ExecError {
 
 pub type ExecResult<T> = Result<T, ExecError>;
 
+impl ExecError {
+    /// The exception the running program sees in place of this error, for
+    /// errors the JVMS specifies as linkage failures.
+    pub fn java_exception(&self) -> Option<JavaExceptionKind> {
+        match self {
+            Self::Resolve(error) => error.java_exception(),
+            Self::Load(error) => error.java_exception(),
+            Self::At { source, .. } => source.java_exception(),
+            Self::IncompatibleStaticCall
+            | Self::IncompatibleInstanceCall
+            | Self::IncompatibleStaticFieldAccess
+            | Self::IncompatibleInstanceFieldAccess => Some(JavaExceptionKind::IncompatibleClassChangeError),
+            Self::ReceiverLacksField => Some(JavaExceptionKind::VerifyError),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stack(error) => error.fmt(f),
+            Self::Resolve(error) => error.fmt(f),
+            Self::Load(error) => error.fmt(f),
+            Self::ClassInitialization(error) => error.fmt(f),
+            Self::At { class, method, bci, .. } => write!(f, "in {class}.{method} at bci {bci}"),
+            Self::InvalidClassInitializerReturn => f.write_str("<clinit> returned a value"),
+            Self::InvalidClassInitializationFrameState => f.write_str("class initialization frame in an unexpected state"),
+            Self::InvalidCallSiteLinkFrameState => f.write_str("call site link frame in an unexpected state"),
+            Self::InvalidConstructFrameState => f.write_str("construct frame in an unexpected state"),
+            Self::IncompatibleStaticCall => f.write_str("static call of an instance method"),
+            Self::IncompatibleInstanceCall => f.write_str("instance call of a static method"),
+            Self::IncompatibleStaticFieldAccess => f.write_str("static access of an instance field"),
+            Self::IncompatibleInstanceFieldAccess => f.write_str("instance access of a static field"),
+            Self::ReceiverLacksField => f.write_str("field access on an object without the field"),
+            Self::NullObjectReference => f.write_str("null object reference"),
+            Self::ArrayIndexOutOfBounds(index) => write!(f, "array index {index} out of bounds"),
+            Self::InvalidStaticFieldStorage => f.write_str("invalid static field storage"),
+            Self::InvalidFieldValue => f.write_str("value does not fit the field"),
+            Self::InvalidConstantValue => f.write_str("ConstantValue does not match the field type"),
+            Self::UnsupportedStringConstantValue => f.write_str("String ConstantValue is not supported"),
+            Self::UnsupportedMethodHandle(Some(kind)) => write!(f, "unsupported method handle kind {kind:?}"),
+            Self::UnsupportedMethodHandle(None) => f.write_str("call site target is not a direct method handle"),
+            Self::NoCurrentFrame => f.write_str("no current frame"),
+            Self::MethodHasNoCode => f.write_str("method has no Code attribute"),
+            Self::UnexpectedEndOfCode { bci } => write!(f, "code ends inside the instruction at bci {bci}"),
+            Self::UnsupportedOpcode { opcode, bci } => write!(f, "unsupported opcode {opcode:#04x} at bci {bci}"),
+            Self::InvalidBranchTarget { from, offset } => write!(f, "branch from bci {from} by {offset} leaves the code"),
+            Self::InvalidLocalIndex(index) => write!(f, "invalid local variable index {index}"),
+            Self::InvalidConstantPoolIndex(index) => write!(f, "invalid constant pool index {index}"),
+            Self::InvalidLdcConstant { index } => write!(f, "constant {index} cannot be loaded by ldc"),
+            Self::UnsupportedLdcConstant { index } => write!(f, "ldc of constant {index} is not supported"),
+            Self::OperandStackOverflow => f.write_str("operand stack overflow"),
+            Self::OperandStackUnderflow => f.write_str("operand stack underflow"),
+            Self::InvalidOperandStackShape => f.write_str("operand stack holds the wrong kind of values"),
+            Self::InvalidStackOperation { opcode } => write!(f, "opcode {opcode:#04x} cannot apply to the operand stack"),
+            Self::SlotTypeMismatch { expected, actual } => write!(f, "expected a {expected:?} slot, found {actual:?}"),
+            Self::TooManyArguments { args, max_locals } => write!(f, "{args} argument slots exceed max_locals {max_locals}"),
+            Self::InvalidProgramCounter { target, code_len } => write!(f, "pc {target} outside code of length {code_len}"),
+        }
+    }
+}
+
+impl Error for ExecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            // The wrappers above display their inner error directly.
+            Self::Stack(error) => error.source(),
+            Self::Resolve(error) => error.source(),
+            Self::Load(error) => error.source(),
+            Self::ClassInitialization(error) => error.source(),
+            Self::At { source, .. } => Some(&**source),
+            _ => None,
+        }
+    }
+}
+
+/// Exceptions raised by the VM itself, before a Java object exists for them.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum JavaExceptionKind {
     ArithmeticException,
     NoClassDefFoundError,
+    UnsatisfiedLinkError,
+    NullPointerException,
+    IllegalArgumentException,
+    InstantiationError,
+    BootstrapMethodError,
+    AbstractMethodError,
+    IncompatibleClassChangeError,
+    WrongMethodTypeException,
+    LambdaConversionException,
+    ClassCastException,
+    IllegalAccessError,
+    NoSuchFieldError,
+    NoSuchMethodError,
+    VerifyError,
+    ClassFormatError,
+}
+
+impl JavaExceptionKind {
+    /// Binary name of the exception class in internal form.
+    pub fn class_name(self) -> &'static str {
+        match self {
+            Self::ArithmeticException => "java/lang/ArithmeticException",
+            Self::NoClassDefFoundError => "java/lang/NoClassDefFoundError",
+            Self::UnsatisfiedLinkError => "java/lang/UnsatisfiedLinkError",
+            Self::NullPointerException => "java/lang/NullPointerException",
+            Self::IllegalArgumentException => "java/lang/IllegalArgumentException",
+            Self::InstantiationError => "java/lang/InstantiationError",
+            Self::BootstrapMethodError => "java/lang/BootstrapMethodError",
+            Self::AbstractMethodError => "java/lang/AbstractMethodError",
+            Self::IncompatibleClassChangeError => "java/lang/IncompatibleClassChangeError",
+            Self::WrongMethodTypeException => "java/lang/invoke/WrongMethodTypeException",
+            Self::LambdaConversionException => "java/lang/invoke/LambdaConversionException",
+            Self::ClassCastException => "java/lang/ClassCastException",
+            Self::IllegalAccessError => "java/lang/IllegalAccessError",
+            Self::NoSuchFieldError => "java/lang/NoSuchFieldError",
+            Self::NoSuchMethodError => "java/lang/NoSuchMethodError",
+            Self::VerifyError => "java/lang/VerifyError",
+            Self::ClassFormatError => "java/lang/ClassFormatError",
+        }
+    }
+
+    /// Detail message HotSpot attaches when it raises this exception itself.
+    pub fn message(self) -> Option<&'static str> {
+        match self {
+            Self::ArithmeticException => Some("/ by zero"),
+            _ => None,
+        }
+    }
 }
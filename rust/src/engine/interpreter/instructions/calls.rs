@@ -1,11 +1,16 @@
 use crate::{
     engine::{
-        exec_error::{ExecError, ExecResult},
+        exec_error::{ExecError, ExecResult, JavaExceptionKind},
         interpreter::interpreter_frame::InterpreterFrame,
-        outcome::StepOutcome,
+        outcome::{PendingException, StepOutcome},
         resolved_method::ResolvedMethod,
     },
-    oops::acc_flags::AccFlags,
+    oops::{
+        acc_flags::AccFlags,
+        cp_entry::{CPEntry, CallSiteKey, ResolvedMethodRef},
+        desc::MethodDesc,
+        normal_klass::NormalKlass,
+    },
 };
 
 /// Resolve the method reference and hand the actual frame transition to the
@@ -14,16 +19,186 @@ use crate::{
 /// target frame.
 pub fn invokestatic(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
     let index = frame.read_u16()? as usize;
-    let resolved = frame.resolve_method_ref(index)?;
+    let target = if names_interface_method(frame, index) {
+        ResolvedMethod::from(frame.resolve_interface_method_ref(index)?)
+    } else {
+        ResolvedMethod::from(frame.resolve_method_ref(index)?)
+    };
 
-    if !resolved.method.acc_flags.contains(AccFlags::ACC_STATIC) {
+    if !target.method().acc_flags.contains(AccFlags::ACC_STATIC) {
         return Err(ExecError::IncompatibleStaticCall);
     }
 
-    let arg_slots = resolved.method.desc.parameter_slot_count();
+    let arg_slots = target.method().desc.parameter_slot_count();
+
+    Ok(StepOutcome::InvokeStatic { target, arg_slots })
+}
+
+/// Since Java 8 `invokestatic` and `invokespecial` may name an interface
+/// method through a `CONSTANT_InterfaceMethodref`.
+fn names_interface_method(frame: &InterpreterFrame, index: usize) -> bool {
+    matches!(frame.constant_pool_entry(index), Some(CPEntry::InterfaceMethodRef(_)))
+}
+
+/// JVMS 6.5 `invokespecial`: a superclass method named from a class with
+/// `ACC_SUPER` is looked up again from the direct superclass of the current
+/// class.  Constructors and private methods are invoked as resolved.
+fn select_special(current: &NormalKlass, resolved: ResolvedMethodRef) -> ResolvedMethod {
+    let is_super_call = resolved.method.name.utf8() != "<init>"
+        && current.acc_flags().contains(AccFlags::ACC_SUPER)
+        && !std::ptr::eq(current, &*resolved.holder)
+        && current.is_subclass_of(&resolved.holder);
+
+    let mut next = current.super_klass_ref().filter(|_| is_super_call);
+    while let Some(klass) = next {
+        if let Some(method) = klass.find_declared_method_symbol(&resolved.method.name, &resolved.method.desc.raw) {
+            return ResolvedMethod::new(klass, method);
+        }
+        next = klass.super_klass_ref();
+    }
+
+    ResolvedMethod::from(resolved)
+}
+
+pub fn invokespecial(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let index = frame.read_u16()? as usize;
+    if names_interface_method(frame, index) {
+        return invokespecial_interface(frame, index);
+    }
+    let resolved = frame.resolve_method_ref(index)?;
 
-    Ok(StepOutcome::InvokeStatic {
-        target: ResolvedMethod::from(resolved),
+    if resolved.method.acc_flags.contains(AccFlags::ACC_STATIC) {
+        return Err(ExecError::IncompatibleInstanceCall);
+    }
+
+    let arg_slots = resolved.method.desc.parameter_slot_count() + 1;
+    let receiver = frame.peek_top_slots(arg_slots)?[0].as_ref()?;
+    if receiver == 0 {
+        return throw(JavaExceptionKind::NullPointerException);
+    }
+    if resolved.method.name.utf8() != "<init>"
+        && !frame.allows_protected_receiver(&resolved.holder, &resolved.method.acc_flags, receiver)
+    {
+        return throw(JavaExceptionKind::IllegalAccessError);
+    }
+
+    Ok(StepOutcome::InvokeInstance {
+        target: select_special(frame.target().holder(), resolved),
         arg_slots,
     })
 }
+
+/// `Iface.super.m()` and calls of an interface's own methods: selection
+/// starts at the interface the reference names.
+fn invokespecial_interface(frame: &mut InterpreterFrame, index: usize) -> ExecResult<StepOutcome> {
+    let resolved = frame.resolve_interface_method_ref(index)?;
+
+    if resolved.method.acc_flags.contains(AccFlags::ACC_STATIC) {
+        return Err(ExecError::IncompatibleInstanceCall);
+    }
+
+    let arg_slots = resolved.method.desc.parameter_slot_count() + 1;
+    if frame.peek_top_slots(arg_slots)?[0].as_ref()? == 0 {
+        return throw(JavaExceptionKind::NullPointerException);
+    }
+
+    let interface = resolved.interface.clone();
+    match ResolvedMethod::from(resolved).select_in_interface(&interface) {
+        Ok(target) => Ok(StepOutcome::InvokeInstance { target, arg_slots }),
+        Err(kind) => throw(kind),
+    }
+}
+
+fn throw(kind: JavaExceptionKind) -> ExecResult<StepOutcome> {
+    Ok(StepOutcome::Throw(PendingException::JVMGen(kind)))
+}
+
+/// Select the method an instance call on the receiver below the top
+/// `arg_slots` invokes.  `interface` is the interface the receiver must
+/// implement for `invokeinterface`.
+fn select_virtual(
+    frame: &InterpreterFrame,
+    resolved: ResolvedMethod,
+    arg_slots: usize,
+    interface: Option<&NormalKlass>,
+) -> ExecResult<StepOutcome> {
+    let receiver = frame.peek_top_slots(arg_slots)?[0].as_ref()?;
+    let Some(class) = ResolvedMethod::receiver_class(receiver) else {
+        return throw(JavaExceptionKind::NullPointerException);
+    };
+
+    if interface.is_some_and(|x| !class.is_subtype_of(x)) {
+        return throw(JavaExceptionKind::IncompatibleClassChangeError);
+    }
+    if !frame.allows_protected_receiver(resolved.holder(), &resolved.method().acc_flags, receiver) {
+        return throw(JavaExceptionKind::IllegalAccessError);
+    }
+
+    match resolved.select(&class) {
+        Ok(target) => Ok(StepOutcome::InvokeInstance { target, arg_slots }),
+        Err(kind) => throw(kind),
+    }
+}
+
+/// The descriptor a `CONSTANT_Methodref` names, which for a signature
+/// polymorphic method differs from the declared one.
+fn call_site_type(frame: &InterpreterFrame, index: usize) -> ExecResult<MethodDesc> {
+    match frame.constant_pool_entry(index) {
+        Some(CPEntry::MethodRef(entry)) => Ok(MethodDesc::from(entry.desc().utf8())?),
+        _ => Err(ExecError::InvalidConstantPoolIndex(index)),
+    }
+}
+
+pub fn invokevirtual(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let index = frame.read_u16()? as usize;
+    let resolved = frame.resolve_method_ref(index)?;
+
+    if resolved.method.is_signature_polymorphic(&resolved.holder) {
+        let call_type = call_site_type(frame, index)?;
+        if frame.peek_top_slots(call_type.parameter_slot_count() + 1)?[0].as_ref()? == 0 {
+            return throw(JavaExceptionKind::NullPointerException);
+        }
+
+        return Ok(StepOutcome::InvokeHandle {
+            call_type,
+            exact: resolved.method.name.utf8() == "invokeExact",
+        });
+    }
+
+    if resolved.method.acc_flags.contains(AccFlags::ACC_STATIC) {
+        return Err(ExecError::IncompatibleInstanceCall);
+    }
+
+    let arg_slots = resolved.method.desc.parameter_slot_count() + 1;
+    select_virtual(frame, ResolvedMethod::from(resolved), arg_slots, None)
+}
+
+pub fn invokeinterface(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let index = frame.read_u16()? as usize;
+    // JVMS 6.5: the redundant argument count, then a zero byte.
+    frame.read_u16()?;
+    let resolved = frame.resolve_interface_method_ref(index)?;
+
+    // 从版本 55 起可以用 invokeinterface 调用私有接口方法，选中的就是它本身。
+    if resolved.method.acc_flags.contains(AccFlags::ACC_STATIC) {
+        return Err(ExecError::IncompatibleInstanceCall);
+    }
+
+    let arg_slots = resolved.method.desc.parameter_slot_count() + 1;
+    let interface = resolved.interface.clone();
+    select_virtual(frame, ResolvedMethod::from(resolved), arg_slots, Some(&interface))
+}
+
+/// Linking and invoking the call site both need the thread, so they are left
+/// to the dispatcher.
+pub fn invokedynamic(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let index = frame.read_u16()? as usize;
+    // JVMS 6.5: two bytes that are always zero follow the index.
+    frame.read_u16()?;
+
+    let holder = frame.target().holder_ref();
+    let call_site = holder.invoke_dynamic_entry(index)?;
+    let site = CallSiteKey::new(frame.target().method(), frame.last_pc());
+
+    Ok(StepOutcome::InvokeDynamic { holder, call_site, site })
+}
@@ -5,7 +5,11 @@ use crate::{
         outcome::StepOutcome,
         slot::Slot,
     },
-    oops::cp_entry::CPEntry,
+    gc_bindings::oop_handle::NObjPtr,
+    oops::{
+        cp_entry::CPEntry,
+        java_invoke::{JavaMethodHandle, JavaMethodType},
+    },
 };
 
 enum NumericConstant {
@@ -94,17 +98,64 @@ fn push_numeric_constant(
     Ok(StepOutcome::Continue)
 }
 
+/// JVMS 5.4.3.5: a method type loads as its interned `MethodType`, a method
+/// handle as the `MethodHandle` its constant resolves to.
+fn read_method_constant(f: &InterpreterFrame, index: usize) -> ExecResult<Option<NObjPtr>> {
+    match f.constant_pool_entry(index) {
+        Some(CPEntry::MethodType(desc)) => {
+            let method_type = JavaMethodType::create(desc.raw.utf8())?;
+            Ok(Some(method_type))
+        }
+        Some(CPEntry::MethodHandle(entry)) => Ok(Some(JavaMethodHandle::constant(entry, f.target().holder())?)),
+        _ => Ok(None),
+    }
+}
+
+/// A dynamically-computed constant is left to the dispatcher, which may have
+/// to run its bootstrap method first; `wide` says whether the instruction
+/// is `ldc2_w`, which must load a `long` or `double`.
+fn load_dynamic_constant(f: &InterpreterFrame, index: usize, wide: bool) -> ExecResult<Option<StepOutcome>> {
+    let Some(CPEntry::Dynamic(entry)) = f.constant_pool_entry(index) else {
+        return Ok(None);
+    };
+    if (entry.desc.slot_count() == 2) != wide {
+        return Err(ExecError::InvalidLdcConstant { index });
+    }
+
+    let holder = f.target().holder_ref();
+    let constant = holder.dynamic_entry(index)?;
+    Ok(Some(StepOutcome::LoadDynamic { holder, constant }))
+}
+
+fn push_single_constant(f: &mut InterpreterFrame, index: usize) -> ExecResult<StepOutcome> {
+    if let Some(outcome) = load_dynamic_constant(f, index, false)? {
+        return Ok(outcome);
+    }
+
+    match read_method_constant(f, index)? {
+        Some(obj) => {
+            f.push(Slot::reference(obj))?;
+            Ok(StepOutcome::Continue)
+        }
+        None => push_numeric_constant(f, index, false),
+    }
+}
+
 pub fn ldc(f: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
     let index = f.read_u8()? as usize;
-    push_numeric_constant(f, index, false)
+    push_single_constant(f, index)
 }
 
 pub fn ldc_w(f: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
     let index = f.read_u16()? as usize;
-    push_numeric_constant(f, index, false)
+    push_single_constant(f, index)
 }
 
 pub fn ldc2_w(f: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
     let index = f.read_u16()? as usize;
+    if let Some(outcome) = load_dynamic_constant(f, index, true)? {
+        return Ok(outcome);
+    }
+
     push_numeric_constant(f, index, true)
 }
@@ -1,12 +1,23 @@
 use crate::{
     engine::{
-        exec_error::{ExecError, ExecResult},
+        exec_error::{ExecError, ExecResult, JavaExceptionKind},
         interpreter::interpreter_frame::InterpreterFrame,
-        outcome::StepOutcome,
+        outcome::{PendingException, StepOutcome},
+        slot::Slot,
+    },
+    gc_bindings::{oop_codec::klass_of, oop_handle::NObjPtr},
+    oops::{
+        acc_flags::AccFlags, array_klass::ArrayKlass, cp_entry::ResolvedFieldRef,
+        oops_errors::ResolveError,
     },
-    oops::{acc_flags::AccFlags, cp_entry::ResolvedFieldRef},
 };
 
+fn null_pointer() -> StepOutcome {
+    StepOutcome::Throw(PendingException::JVMGen(
+        JavaExceptionKind::NullPointerException,
+    ))
+}
+
 fn resolve_static_field(frame: &mut InterpreterFrame) -> ExecResult<ResolvedFieldRef> {
     let index = frame.read_u16()? as usize;
     let resolved = frame.resolve_field_ref(index)?;
@@ -25,3 +36,102 @@ pub fn getstatic(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
 pub fn putstatic(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
     Ok(StepOutcome::PutStatic(resolve_static_field(frame)?))
 }
+
+fn resolve_instance_field(frame: &mut InterpreterFrame) -> ExecResult<ResolvedFieldRef> {
+    let index = frame.read_u16()? as usize;
+    let resolved = frame.resolve_field_ref(index)?;
+
+    if resolved.field.acc_flags.contains(AccFlags::ACC_STATIC) {
+        return Err(ExecError::IncompatibleInstanceFieldAccess);
+    }
+
+    Ok(resolved)
+}
+
+/// Without a verifier, make sure `obj` really has the field before touching
+/// its storage.  `None` when the access may go ahead.
+fn check_receiver(frame: &InterpreterFrame, obj: NObjPtr, resolved: &ResolvedFieldRef) -> ExecResult<Option<StepOutcome>> {
+    if obj == 0 {
+        return Ok(Some(null_pointer()));
+    }
+
+    let receiver = klass_of(obj).and_then(|klass| klass.as_normal_ref());
+    match receiver {
+        Some(klass) if klass.is_subclass_of(&resolved.holder) => {}
+        _ => return Err(ExecError::ReceiverLacksField),
+    }
+
+    if !frame.allows_protected_receiver(&resolved.holder, &resolved.field.acc_flags, obj) {
+        return Ok(Some(StepOutcome::Throw(PendingException::JVMGen(
+            JavaExceptionKind::IllegalAccessError,
+        ))));
+    }
+
+    Ok(None)
+}
+
+pub fn getfield(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let resolved = resolve_instance_field(frame)?;
+    let obj = frame.pop()?.as_ref()?;
+    if let Some(outcome) = check_receiver(frame, obj, &resolved)? {
+        return Ok(outcome);
+    }
+
+    let slots = resolved.holder.read_instance_field(obj, &resolved.field)?;
+    frame.push_slots(&slots)?;
+    Ok(StepOutcome::Continue)
+}
+
+pub fn putfield(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let resolved = resolve_instance_field(frame)?;
+    let slot_count = resolved.field.desc.slot_count();
+    let slots = frame.peek_top_slots(slot_count)?;
+    frame.drop_top_slots(slot_count)?;
+
+    let obj = frame.pop()?.as_ref()?;
+    if let Some(outcome) = check_receiver(frame, obj, &resolved)? {
+        return Ok(outcome);
+    }
+
+    resolved.holder.write_instance_field(obj, &resolved.field, &slots)?;
+    Ok(StepOutcome::Continue)
+}
+
+/// `new` is an active use of the class, so the allocation itself is left to
+/// the dispatcher once the class is initialized.
+pub fn new(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let index = frame.read_u16()? as usize;
+    let klass = frame
+        .resolve_class(index)?
+        .as_normal_ref()
+        .ok_or(ResolveError::NotANormal)?;
+
+    if klass.is_interface() || klass.is_abstract() {
+        return Ok(StepOutcome::Throw(PendingException::JVMGen(
+            JavaExceptionKind::InstantiationError,
+        )));
+    }
+
+    Ok(StepOutcome::New(klass))
+}
+
+pub fn arraylength(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let array = frame.pop()?.as_ref()?;
+    if array == 0 {
+        return Ok(null_pointer());
+    }
+
+    frame.push(Slot::int(ArrayKlass::length(array)? as i32))?;
+    Ok(StepOutcome::Continue)
+}
+
+/// The verifier guarantees a `Throwable` on the stack; only `null` needs
+/// checking here.
+pub fn athrow(frame: &mut InterpreterFrame) -> ExecResult<StepOutcome> {
+    let exception = frame.pop()?.as_ref()?;
+    if exception == 0 {
+        return Ok(null_pointer());
+    }
+
+    Ok(StepOutcome::Throw(PendingException::JavaObj(exception)))
+}
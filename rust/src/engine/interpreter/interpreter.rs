@@ -169,10 +169,23 @@ impl Interpreter {
             0xa4 => if_icmple(frame),
             0xa7 => goto(frame),
 
-            // Static fields and method invocation.
+            // Fields, method invocation and object creation.
             0xb2 => getstatic(frame),
             0xb3 => putstatic(frame),
+            0xb4 => getfield(frame),
+            0xb5 => putfield(frame),
+            0xb6 => invokevirtual(frame),
+            0xb7 => invokespecial(frame),
             0xb8 => invokestatic(frame),
+            0xb9 => invokeinterface(frame),
+            0xba => invokedynamic(frame),
+            0xbb => new(frame),
+
+            // Arrays.
+            0xbe => arraylength(frame),
+
+            // Exceptions.
+            0xbf => athrow(frame),
 
             // Method returns.
             0xac => ireturn(frame),
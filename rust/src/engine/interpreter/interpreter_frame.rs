@@ -6,13 +6,36 @@ use crate::{
         resolved_method::ResolvedMethod,
         slot::Slot,
     },
+    class_loader::ms_api::MSRef,
+    gc_bindings::{oop_codec::klass_of, oop_handle::NObjPtr},
     oops::{
-        attr::Code,
-        cp_entry::{CPEntry, ResolvedFieldRef, ResolvedMethodRef},
+        acc_flags::AccFlags,
+        attr::{Code, LocalVariable},
+        klass::Klass,
+        normal_klass::NormalKlass,
+        cp_entry::{CPEntry, ResolvedFieldRef, ResolvedInterfaceMethodRef, ResolvedMethodRef},
         oops_errors::ResolveResult,
     },
 };
 
+/// A named local of a frame, for debuggers and crash dumps.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameLocal<'a> {
+    pub variable: &'a LocalVariable,
+    /// One slot, or two for `long`/`double` (high half first).
+    pub slots: &'a [Slot],
+}
+
+impl FrameLocal<'_> {
+    pub fn name(&self) -> &str {
+        self.variable.name.utf8()
+    }
+
+    pub fn descriptor(&self) -> &str {
+        self.variable.descriptor.utf8()
+    }
+}
+
 #[derive(Debug)]
 pub struct InterpreterFrame {
     target: ResolvedMethod,
@@ -57,6 +80,10 @@ impl InterpreterFrame {
 }
 
 impl InterpreterFrame {
+    pub fn target(&self) -> &ResolvedMethod {
+        &self.target
+    }
+
     pub fn code(&self) -> &Code {
         self.target.method().code.as_ref().unwrap()
     }
@@ -73,9 +100,27 @@ impl InterpreterFrame {
         self.target.holder().resolve_method_ref(index)
     }
 
+    pub fn resolve_interface_method_ref(&self, index: usize) -> ResolveResult<ResolvedInterfaceMethodRef> {
+        self.target.holder().resolve_interface_method_ref(index)
+    }
+
     pub fn resolve_field_ref(&self, index: usize) -> ResolveResult<ResolvedFieldRef> {
         self.target.holder().resolve_field_ref(index)
     }
+
+    pub fn resolve_class(&self, index: usize) -> ResolveResult<MSRef<Klass>> {
+        self.target.holder().resolve_class(index)
+    }
+
+    /// The protected-receiver rule for an instance member of `holder` used on
+    /// `receiver`.  Arrays only inherit `Object`'s members, which the rule
+    /// does not restrict for them.
+    pub fn allows_protected_receiver(&self, holder: &NormalKlass, flags: &AccFlags, receiver: NObjPtr) -> bool {
+        match klass_of(receiver).and_then(|klass| klass.as_normal_ref()) {
+            Some(class) => self.target.holder().allows_protected_receiver(holder, flags, &class),
+            None => true,
+        }
+    }
 }
 
 impl InterpreterFrame {
@@ -94,6 +139,19 @@ impl InterpreterFrame {
             .ok_or(ExecError::InvalidLocalIndex(index))
     }
 
+    /// Locals in scope at the current instruction, paired with their
+    /// `LocalVariableTable` names.  Empty when the class has no debug info.
+    pub fn named_locals(&self) -> Vec<FrameLocal<'_>> {
+        self.code()
+            .live_locals(self.last_pc)
+            .filter_map(|variable| {
+                let start = variable.index as usize;
+                let slots = self.locals.get(start..start + variable.slot_count())?;
+                Some(FrameLocal { variable, slots })
+            })
+            .collect()
+    }
+
     pub fn set_local(&mut self, index: usize, value: Slot) -> ExecResult<()> {
         let local = self
             .locals
@@ -176,6 +234,37 @@ impl InterpreterFrame {
         Ok(())
     }
 
+    /// The handler for an exception of class `exception` thrown by the
+    /// instruction at `last_pc`: the first entry of the exception table that
+    /// covers it and catches `exception` or one of its superclasses.  A
+    /// catch type that cannot be resolved catches nothing.
+    pub fn find_handler(&self, exception: &NormalKlass) -> Option<usize> {
+        let holder = self.target.holder();
+
+        self.code()
+            .exception_table
+            .iter()
+            .find(|entry| {
+                let covered = (entry.start_pc() as usize..entry.end_pc() as usize).contains(&self.last_pc);
+                covered
+                    && entry.catch_type().is_none_or(|catch_type| {
+                        catch_type
+                            .resolve(holder)
+                            .ok()
+                            .and_then(|klass| klass.as_normal_ref())
+                            .is_some_and(|klass| exception.is_subclass_of(&klass))
+                    })
+            })
+            .map(|entry| entry.handler_pc() as usize)
+    }
+
+    /// JVMS 2.10: the handler starts with the exception as the only operand.
+    pub fn enter_handler(&mut self, handler_pc: usize, exception: NObjPtr) -> ExecResult<()> {
+        self.opstack.clear();
+        self.opstack.push_slot(Slot::reference(exception))?;
+        self.set_pc(handler_pc)
+    }
+
     pub fn fetch_opcode(&mut self) -> ExecResult<u8> {
         self.last_pc = self.pc;
         self.read_u8()
@@ -233,6 +322,10 @@ impl InterpreterFrame {
         self.opstack.drop_top_slots(arg_slots)
     }
 
+    pub fn remove_below_top_slots(&mut self, arg_slots: usize) -> ExecResult<Slot> {
+        self.opstack.remove_below_top_slots(arg_slots)
+    }
+
     pub fn push_slots(&mut self, slots: &[Slot]) -> ExecResult<()> {
         match slots {
             [slot] => self.opstack.push_slot(*slot),
@@ -85,6 +85,10 @@ impl OperandStack {
         Ok(())
     }
 
+    pub(crate) fn clear(&mut self) {
+        self.slots.clear();
+    }
+
     pub(crate) fn pop_slot(&mut self) -> ExecResult<Slot> {
         let value = self.top_values(1)?[0];
         let StackValue::Category1(slot) = value else {
@@ -208,4 +212,12 @@ impl OperandStack {
         self.slots.truncate(start);
         Ok(())
     }
+
+    /// Remove the category-1 value just below the top `slot_count` slots,
+    /// keeping those in place.
+    pub(crate) fn remove_below_top_slots(&mut self, slot_count: usize) -> ExecResult<Slot> {
+        let start = self.top_slot_start(slot_count + 1)?;
+        StackValue::Category1(self.slots[start]).validate()?;
+        Ok(self.slots.remove(start))
+    }
 }
@@ -0,0 +1,299 @@
+//! Classes spun for `LambdaMetafactory.metafactory` call sites.
+//!
+//! As in the JDK's `InnerClassLambdaMetafactory`, each linked call site gets
+//! a synthetic class `Host$$Lambda$N` implementing the functional interface.
+//! Its constructor stores the captured arguments in fields; its interface
+//! method loads them, appends its own arguments and invokes the
+//! implementation method the way its reference kind says.
+
+use std::{
+    ptr::NonNull,
+    sync::{
+        LazyLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use crate::{
+    assembler::{class_builder::ClassBuilder, code_builder::CodeBuilder},
+    class_loader::{
+        cld::ClassLoaderData,
+        load_error::{LoadError, LoadResult},
+        ms_api::MSRef,
+    },
+    engine::resolved_method::ResolvedMethod,
+    gc_bindings::oop_handle::NObjPtr,
+    oops::{
+        acc_flags::AccFlags,
+        cp_entry::RefKind,
+        desc::{FieldDesc, FieldElemType, MethodDesc, ReturnDesc},
+        java_invoke::{DirectMember, DirectMethodHandle, JavaMethodHandle},
+        normal_klass::NormalKlass,
+    },
+};
+
+/// Loader of the classes spun for hosts of the bootstrap loader.  It defines
+/// nothing else and delegates every lookup to the bootstrap loader.
+struct LambdaCLDPtr(NonNull<ClassLoaderData>);
+
+unsafe impl Send for LambdaCLDPtr {}
+unsafe impl Sync for LambdaCLDPtr {}
+
+static BOOT_LAMBDA_CLD: LazyLock<LambdaCLDPtr> =
+    LazyLock::new(|| LambdaCLDPtr(ClassLoaderData::new(Some("lambda".into()))));
+
+static NEXT_LAMBDA_ID: AtomicUsize = AtomicUsize::new(0);
+
+const OBJECT_CLASS: &str = "java/lang/Object";
+
+/// `<x>load` of a value of type `desc` from local variable `local`.
+fn load(code: &mut CodeBuilder, desc: &FieldDesc, local: usize) {
+    let opcode = match desc.elem {
+        _ if desc.is_ref_type() => 0x19,
+        FieldElemType::Long => 0x16,
+        FieldElemType::Float => 0x17,
+        FieldElemType::Double => 0x18,
+        _ => 0x15,
+    };
+    code.local(opcode, local as u16);
+}
+
+fn return_value(code: &mut CodeBuilder, ret: &ReturnDesc) {
+    let opcode = match ret {
+        ReturnDesc::Void => 0xb1,
+        ReturnDesc::Type(desc) => match desc.elem {
+            _ if desc.is_ref_type() => 0xb0,
+            FieldElemType::Long => 0xad,
+            FieldElemType::Float => 0xae,
+            FieldElemType::Double => 0xaf,
+            _ => 0xac,
+        },
+    };
+    code.op(opcode);
+}
+
+/// The implementation method, with the parameters its call site supplies:
+/// the receiver first for instance methods.
+struct Implementation<'a> {
+    kind: RefKind,
+    method: &'a ResolvedMethod,
+    params: Vec<FieldDesc>,
+}
+
+impl<'a> Implementation<'a> {
+    fn new(handle: &'a DirectMethodHandle) -> Option<Self> {
+        let DirectMember::Method(method) = &handle.member else {
+            return None;
+        };
+
+        let mut params = Vec::new();
+        if matches!(
+            handle.kind,
+            RefKind::InvokeVirtual | RefKind::InvokeInterface | RefKind::InvokeSpecial
+        ) {
+            params.push(FieldDesc::from(&format!("L{};", method.holder().name().utf8())).ok()?);
+        }
+        params.extend(method.method().desc.params_desc.iter().cloned());
+
+        Some(Self {
+            kind: handle.kind,
+            method,
+            params,
+        })
+    }
+
+    /// What the call leaves on the stack; a constructor yields the new object.
+    fn returns(&self) -> Option<FieldDesc> {
+        if self.kind == RefKind::NewInvokeSpecial {
+            return FieldDesc::from(&format!("L{};", self.method.holder().name().utf8())).ok();
+        }
+
+        match &self.method.method().desc.ret_desc {
+            ReturnDesc::Void => None,
+            ReturnDesc::Type(desc) => Some(desc.clone()),
+        }
+    }
+}
+
+/// Only identical types, or two reference types, are compatible: arguments
+/// are passed as they are, without casts, boxing or widening.
+fn compatible(supplied: &FieldDesc, expected: &FieldDesc) -> bool {
+    (supplied.is_ref_type() && expected.is_ref_type()) || supplied.raw.equals(&expected.raw)
+}
+
+pub struct LambdaMetafactory;
+
+impl LambdaMetafactory {
+    /// Can the interface method `sam_type`, with the captured arguments of
+    /// `invoked_type`, call `implementation`?  Violations are what
+    /// `LambdaConversionException` reports.
+    pub fn is_supported(invoked_type: &MethodDesc, sam_type: &MethodDesc, implementation: &DirectMethodHandle) -> bool {
+        let Some(implementation) = Implementation::new(implementation) else {
+            return false;
+        };
+        let ReturnDesc::Type(interface) = &invoked_type.ret_desc else {
+            return false;
+        };
+
+        let supplied = invoked_type.params_desc.len() + sam_type.params_desc.len();
+        let arguments_match = supplied == implementation.params.len()
+            && (invoked_type.params_desc.iter())
+                .chain(&sam_type.params_desc)
+                .zip(&implementation.params)
+                .all(|(x, y)| compatible(x, y));
+
+        let return_matches = match (&sam_type.ret_desc, implementation.returns()) {
+            (ReturnDesc::Void, _) => true,
+            (ReturnDesc::Type(expected), Some(actual)) => compatible(&actual, expected),
+            (ReturnDesc::Type(_), None) => false,
+        };
+
+        interface.dimensions == 0
+            && matches!(interface.elem, FieldElemType::Class { .. })
+            && arguments_match
+            && return_matches
+    }
+
+    /// Define the class implementing `sam_name` for a call site in `host`.
+    /// The call site must pass `is_supported`.
+    pub fn spin_inner_class(
+        host: &MSRef<NormalKlass>,
+        sam_name: &str,
+        invoked_type: &MethodDesc,
+        sam_type: &MethodDesc,
+        implementation: &DirectMethodHandle,
+    ) -> LoadResult<MSRef<NormalKlass>> {
+        let implementation = Implementation::new(implementation).expect("checked by is_supported");
+        let ReturnDesc::Type(interface) = &invoked_type.ret_desc else {
+            unreachable!("checked by is_supported");
+        };
+        let FieldElemType::Class { name: interface, .. } = &interface.elem else {
+            unreachable!("checked by is_supported");
+        };
+
+        let id = NEXT_LAMBDA_ID.fetch_add(1, Ordering::Relaxed);
+        let name = format!("{}$$Lambda${id}", host.name().utf8());
+        let captured = &invoked_type.params_desc;
+
+        let mut class = ClassBuilder::new(&name);
+        class
+            .version(52)
+            .flags((AccFlags::ACC_FINAL | AccFlags::ACC_SUPER | AccFlags::ACC_SYNTHETIC).bits())
+            .implement(interface.utf8());
+        for (i, desc) in captured.iter().enumerate() {
+            let flags = (AccFlags::ACC_PRIVATE | AccFlags::ACC_FINAL).bits();
+            class.field(flags, &format!("arg${}", i + 1), desc.raw.utf8());
+        }
+
+        Self::constructor(&mut class, &name, captured);
+        Self::interface_method(&mut class, &name, sam_name, captured, sam_type, &implementation);
+        let bytes = class.to_bytes().expect("spun classes are well-formed");
+
+        let cld = match host.cld() {
+            Some(cld) => cld,
+            None => unsafe { BOOT_LAMBDA_CLD.0.as_ref() },
+        };
+        let klass = cld
+            .define_class(&bytes)?
+            .as_normal_ref()
+            .ok_or(LoadError::NotFound(name))?;
+        klass.set_host(host.clone());
+
+        Ok(klass)
+    }
+
+    /// A `newInvokeSpecial` handle of type `invoked_type` for the constructor
+    /// of a class `spin_inner_class` defined.
+    pub fn constructor_handle(klass: &MSRef<NormalKlass>, invoked_type: &MethodDesc) -> LoadResult<NObjPtr> {
+        let captured = invoked_type
+            .params_desc
+            .iter()
+            .map(|x| x.raw.utf8())
+            .collect::<String>();
+        let constructor = klass
+            .find_declared_method("<init>", &format!("({captured})V"))
+            .ok_or_else(|| LoadError::NotFound(format!("{}.<init>", klass.name().utf8())))?;
+
+        let handle = DirectMethodHandle {
+            kind: RefKind::NewInvokeSpecial,
+            member: DirectMember::Method(ResolvedMethod::new(klass.clone(), constructor)),
+        };
+        JavaMethodHandle::create(&handle, invoked_type.raw.utf8())
+    }
+
+    /// `<init>`: store each captured argument in its field.
+    fn constructor(class: &mut ClassBuilder, name: &str, captured: &[FieldDesc]) {
+        let desc = format!("({})V", captured.iter().map(|x| x.raw.utf8()).collect::<String>());
+        let mut code = class.method(AccFlags::ACC_PRIVATE.bits(), "<init>", &desc);
+        code.op(0x2a).invoke(0xb7, OBJECT_CLASS, "<init>", "()V"); // aload_0
+
+        let mut local = 1;
+        for (i, desc) in captured.iter().enumerate() {
+            code.op(0x2a);
+            load(&mut code, desc, local);
+            code.field(0xb5, name, &format!("arg${}", i + 1), desc.raw.utf8());
+            local += desc.slot_count();
+        }
+        return_value(&mut code, &ReturnDesc::Void);
+
+        code.finish().expect("spun constructors are well-formed");
+    }
+
+    /// The interface method: captured arguments, then its own, passed on to
+    /// the implementation.
+    fn interface_method(
+        class: &mut ClassBuilder,
+        name: &str,
+        sam_name: &str,
+        captured: &[FieldDesc],
+        sam_type: &MethodDesc,
+        implementation: &Implementation,
+    ) {
+        let holder = implementation.method.holder();
+        let method = implementation.method.method();
+        let mut code = class.method(AccFlags::ACC_PUBLIC.bits(), sam_name, sam_type.raw.utf8());
+
+        if implementation.kind == RefKind::NewInvokeSpecial {
+            code.class_op(0xbb, holder.name().utf8()).op(0x59); // dup
+        }
+
+        for (i, desc) in captured.iter().enumerate() {
+            code.op(0x2a); // aload_0
+            code.field(0xb4, name, &format!("arg${}", i + 1), desc.raw.utf8());
+        }
+
+        let mut local = 1;
+        for desc in &sam_type.params_desc {
+            load(&mut code, desc, local);
+            local += desc.slot_count();
+        }
+
+        let opcode = match implementation.kind {
+            RefKind::InvokeStatic => 0xb8,
+            RefKind::InvokeVirtual => 0xb6,
+            RefKind::InvokeInterface => 0xb9,
+            _ => 0xb7,
+        };
+        let (holder_name, method_name, desc) = (holder.name().utf8(), method.name.utf8(), method.desc.raw.utf8());
+        if holder.is_interface() {
+            code.invoke_interface(opcode, holder_name, method_name, desc);
+        } else {
+            code.invoke(opcode, holder_name, method_name, desc);
+        }
+
+        if matches!(sam_type.ret_desc, ReturnDesc::Void) {
+            match implementation.returns().map(|x| x.slot_count()) {
+                Some(1) => {
+                    code.op(0x57); // pop
+                }
+                Some(2) => {
+                    code.op(0x58); // pop2
+                }
+                _ => {}
+            }
+        }
+        return_value(&mut code, &sam_type.ret_desc);
+
+        code.finish().expect("spun interface methods are well-formed");
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    class_loader::{bootstrap_cld::BootstrapCLD, ms_api::MSRef},
+    engine::{
+        exec_error::{ExecError, ExecResult, JavaExceptionKind},
+        resolved_method::ResolvedMethod,
+        slot::Slot,
+    },
+    gc_bindings::oop_handle::{KLASS_OOP_STORAGE_ID, NObjPtr, OOPHandle},
+    oops::{
+        desc::{FieldElemType, MethodDesc},
+        java_invoke::{JavaMethodHandle, JavaMethodType},
+        normal_klass::NormalKlass,
+    },
+};
+
+/// A VM control frame below the constructor a `REF_newInvokeSpecial` handle
+/// runs.  The constructor returns `void`; the frame then hands the new object
+/// to the caller as the result of the handle invocation.
+#[derive(Debug)]
+pub(crate) struct ConstructFrame {
+    object: OOPHandle,
+}
+
+impl ConstructFrame {
+    pub(crate) fn new(object: NObjPtr) -> Self {
+        let root = OOPHandle::new(KLASS_OOP_STORAGE_ID);
+        root.replace(object);
+        Self { object: root }
+    }
+
+    pub(crate) fn object(&self) -> NObjPtr {
+        self.object.get()
+    }
+}
+
+/// Type checks of `invokeExact` and `invoke` (JVMS 2.9.3).
+#[derive(Debug)]
+pub struct HandleInvocation;
+
+impl HandleInvocation {
+    /// Check the arguments `args`, typed by `call_type`, against `handle`, and
+    /// return the exception the invocation throws instead, if any.  Reference
+    /// arguments that `invoke` passes to a more specific parameter are cast at
+    /// run time.
+    pub fn check(
+        handle: NObjPtr,
+        call_type: &MethodDesc,
+        exact: bool,
+        args: &[Slot],
+    ) -> ExecResult<Option<JavaExceptionKind>> {
+        let handle_type = MethodDesc::from(&JavaMethodHandle::type_descriptor(handle)?)?;
+        if !JavaMethodType::accepts(&handle_type, call_type, exact) {
+            return Ok(Some(JavaExceptionKind::WrongMethodTypeException));
+        }
+
+        let mut slot = 0;
+        for (param, arg) in handle_type.params_desc.iter().zip(&call_type.params_desc) {
+            let value = args[slot];
+            slot += param.slot_count();
+
+            if param.raw.equals(&arg.raw) || !param.is_ref_type() {
+                continue;
+            }
+            let object = value.as_ref()?;
+            if object == 0 {
+                continue;
+            }
+
+            let FieldElemType::Class { name, .. } = &param.elem else {
+                return Ok(Some(JavaExceptionKind::WrongMethodTypeException));
+            };
+            let conforms = param.dimensions == 0
+                && Self::load_class(handle, name.utf8())?
+                    .zip(ResolvedMethod::receiver_class(object))
+                    .is_some_and(|(expected, actual)| actual.is_subtype_of(&expected));
+            if !conforms {
+                return Ok(Some(JavaExceptionKind::ClassCastException));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// `name` as seen from the class that declares the handle's member.
+    fn load_class(handle: NObjPtr, name: &str) -> ExecResult<Option<MSRef<NormalKlass>>> {
+        let Some(direct) = JavaMethodHandle::direct(handle)? else {
+            return Err(ExecError::UnsupportedMethodHandle(None));
+        };
+        let loaded = match direct.holder().cld() {
+            Some(cld) => cld.load_class(name),
+            None => BootstrapCLD::find_class(name),
+        };
+
+        Ok(loaded.ok().and_then(|x| x.as_normal_ref()))
+    }
+}
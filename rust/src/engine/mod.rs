@@ -1,8 +1,13 @@
 pub mod call;
+pub mod call_site;
 pub mod class_init;
 pub mod exec_dispatcher;
 pub mod exec_error;
 pub mod interpreter;
+pub mod lambda_metafactory;
+pub mod method_handle;
+pub mod native;
 pub mod outcome;
 pub mod resolved_method;
 pub mod slot;
+pub mod stack_trace;
@@ -0,0 +1,75 @@
+use crate::{
+    class_loader::{bootstrap_cld::BootstrapCLD, load_error::LoadResult},
+    engine::{exec_error::ExecResult, outcome::RetValue, slot::Slot},
+    gc_bindings::oop_handle::NObjPtr,
+    oops::{array_klass::ArrayKlass, java_class::JavaClass, java_string::JavaString, oops_errors::{MemberName, ResolveError}},
+    runtime::java_thread::JavaThread,
+};
+
+const CONSTANT_POOL_CLASS: &str = "sun/reflect/ConstantPool";
+
+/// Copy `bytes` into a new `byte[]`; `null` when there are none.
+fn byte_array(bytes: Option<&[u8]>) -> LoadResult<NObjPtr> {
+    let Some(bytes) = bytes else {
+        return Ok(0);
+    };
+
+    let klass = BootstrapCLD::find_class("[B")?;
+    let array_klass = klass.as_array().expect("[B is an array class");
+    let array = array_klass.allocate(&klass, bytes.len());
+    ArrayKlass::write_bytes(array, bytes).expect("fresh byte[] fits its contents");
+
+    Ok(array)
+}
+
+/// `public native String getName()`
+pub(super) fn get_name(_: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let klass = JavaClass::klass_of(args[0].as_ref()?)?;
+    let name = JavaString::create(&klass.name().utf8().replace('/', "."))
+?;
+
+    Ok(RetValue::Ref(name))
+}
+
+fn raw_attribute(args: &[Slot], name: &str) -> ExecResult<RetValue> {
+    let klass = JavaClass::klass_of(args[0].as_ref()?)?;
+    let array = byte_array(klass.annotations().raw(name))?;
+
+    Ok(RetValue::Ref(array))
+}
+
+/// `native byte[] getRawAnnotations()`
+pub(super) fn get_raw_annotations(_: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    raw_attribute(args, "RuntimeVisibleAnnotations")
+}
+
+/// `native byte[] getRawTypeAnnotations()`
+pub(super) fn get_raw_type_annotations(_: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    raw_attribute(args, "RuntimeVisibleTypeAnnotations")
+}
+
+/// `native ConstantPool getConstantPool()`
+///
+/// As in HotSpot, the pool object refers to its class through the
+/// `constantPoolOop` field, here holding the `Class` mirror itself.
+pub(super) fn get_constant_pool(_: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let mirror = args[0].as_ref()?;
+
+    let klass = BootstrapCLD::find_class(CONSTANT_POOL_CLASS)?
+        .as_normal_ref()
+        .ok_or(ResolveError::NotANormal)?;
+    let oop_field = klass
+        .find_declared_field("constantPoolOop", "Ljava/lang/Object;")
+        .ok_or_else(|| {
+            ResolveError::FieldNotFound(MemberName {
+                class: CONSTANT_POOL_CLASS.into(),
+                name: "constantPoolOop".into(),
+                desc: "Ljava/lang/Object;".into(),
+            })
+        })?;
+
+    let pool = klass.allocate_instance();
+    klass.write_instance_field(pool, &oop_field, &[Slot::reference(mirror)])?;
+
+    Ok(RetValue::Ref(pool))
+}
@@ -0,0 +1,11 @@
+use crate::{
+    class_loader::system_cld::SystemCLD,
+    engine::{exec_error::ExecResult, outcome::RetValue, slot::Slot},
+    runtime::java_thread::JavaThread,
+};
+
+/// `static native ClassLoader getSystemClassLoader0()`
+pub(super) fn get_system_class_loader0(_: &mut JavaThread, _: &[Slot]) -> ExecResult<RetValue> {
+    let mirror = SystemCLD::mirror()?;
+    Ok(RetValue::Ref(mirror))
+}
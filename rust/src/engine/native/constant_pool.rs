@@ -0,0 +1,95 @@
+use crate::{
+    engine::{
+        exec_error::{ExecResult, JavaExceptionKind},
+        outcome::{PendingException, RetValue},
+        slot::Slot,
+    },
+    oops::{cp_entry::CPEntry, java_class::JavaClass, java_string::JavaString},
+    runtime::java_thread::JavaThread,
+};
+
+/// Look up entry `args[2]` of the pool of class `args[1]` (the
+/// `constantPoolOop`) and convert it with `read`.  A bad index or a
+/// mismatching tag raises `IllegalArgumentException`.
+fn read_entry<T>(
+    thread: &mut JavaThread,
+    args: &[Slot],
+    read: impl FnOnce(&CPEntry) -> Option<T>,
+) -> ExecResult<Option<T>> {
+    let klass = JavaClass::klass_of(args[1].as_ref()?)?;
+    let index = args[2].as_int()?;
+
+    let value = usize::try_from(index)
+        .ok()
+        .and_then(|x| klass.constant_pool_entry(x))
+        .and_then(read);
+
+    if value.is_none() {
+        thread.pending_exception = Some(PendingException::JVMGen(
+            JavaExceptionKind::IllegalArgumentException,
+        ));
+    }
+
+    Ok(value)
+}
+
+/// `private native int getSize0(Object constantPoolOop)`
+pub(super) fn get_size0(_: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let klass = JavaClass::klass_of(args[1].as_ref()?)?;
+    Ok(RetValue::Int(klass.constant_pool_len() as i32))
+}
+
+/// `private native int getIntAt0(Object constantPoolOop, int index)`
+pub(super) fn get_int_at0(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let value = read_entry(thread, args, |x| match x {
+        CPEntry::Integer(x) => Some(*x),
+        _ => None,
+    })?;
+
+    Ok(RetValue::Int(value.unwrap_or_default()))
+}
+
+/// `private native long getLongAt0(Object constantPoolOop, int index)`
+pub(super) fn get_long_at0(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let value = read_entry(thread, args, |x| match x {
+        CPEntry::Long(x) => Some(*x),
+        _ => None,
+    })?;
+
+    Ok(RetValue::Long(value.unwrap_or_default()))
+}
+
+/// `private native float getFloatAt0(Object constantPoolOop, int index)`
+pub(super) fn get_float_at0(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let value = read_entry(thread, args, |x| match x {
+        CPEntry::Float(x) => Some(*x),
+        _ => None,
+    })?;
+
+    Ok(RetValue::Float(value.unwrap_or_default()))
+}
+
+/// `private native double getDoubleAt0(Object constantPoolOop, int index)`
+pub(super) fn get_double_at0(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let value = read_entry(thread, args, |x| match x {
+        CPEntry::Double(x) => Some(*x),
+        _ => None,
+    })?;
+
+    Ok(RetValue::Double(value.unwrap_or_default()))
+}
+
+/// `private native String getUTF8At0(Object constantPoolOop, int index)`
+pub(super) fn get_utf8_at0(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let value = read_entry(thread, args, |x| match x {
+        CPEntry::Utf8(x) => Some(x.clone()),
+        _ => None,
+    })?;
+
+    let string = match value {
+        Some(x) => JavaString::from_symbol(&x)?,
+        None => 0,
+    };
+
+    Ok(RetValue::Ref(string))
+}
@@ -0,0 +1,43 @@
+use crate::{
+    engine::{
+        exec_error::{ExecResult, JavaExceptionKind},
+        lambda_metafactory::LambdaMetafactory,
+        outcome::{PendingException, RetValue},
+        slot::Slot,
+    },
+    oops::{
+        desc::MethodDesc,
+        java_invoke::{JavaLookup, JavaMethodHandle, JavaMethodType},
+        java_string::JavaString,
+    },
+    runtime::java_thread::JavaThread,
+};
+
+/// `private static native MethodHandle spinInnerClass(Lookup caller,
+/// String invokedName, MethodType invokedType, MethodType samMethodType,
+/// MethodHandle implMethod)`
+///
+/// Returns a constructor handle of the spun class, typed `invokedType`.
+/// Conversions the spinner cannot express raise `LambdaConversionException`.
+pub(super) fn spin_inner_class(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let host = JavaLookup::lookup_class(args[0].as_ref()?)?;
+    let sam_name = JavaString::to_rust_string(args[1].as_ref()?)?;
+    let invoked_type = MethodDesc::from(&JavaMethodType::descriptor(args[2].as_ref()?)?)?;
+    let sam_type = MethodDesc::from(&JavaMethodType::descriptor(args[3].as_ref()?)?)?;
+
+    let implementation = JavaMethodHandle::direct(args[4].as_ref()?)?
+        .filter(|x| LambdaMetafactory::is_supported(&invoked_type, &sam_type, x));
+    let Some(implementation) = implementation else {
+        thread.pending_exception = Some(PendingException::JVMGen(
+            JavaExceptionKind::LambdaConversionException,
+        ));
+        return Ok(RetValue::Ref(0));
+    };
+
+    let klass = LambdaMetafactory::spin_inner_class(&host, &sam_name, &invoked_type, &sam_type, &implementation)
+?;
+    let handle = LambdaMetafactory::constructor_handle(&klass, &invoked_type)
+?;
+
+    Ok(RetValue::Ref(handle))
+}
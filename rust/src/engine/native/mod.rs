@@ -0,0 +1,7 @@
+mod class;
+mod class_loader;
+mod constant_pool;
+mod invoke;
+pub mod registry;
+mod system;
+mod throwable;
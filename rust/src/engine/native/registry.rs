@@ -0,0 +1,133 @@
+use crate::{
+    engine::{exec_error::ExecResult, native::{class, class_loader, constant_pool, invoke, system, throwable}, outcome::RetValue, slot::Slot},
+    oops::{method::Method, normal_klass::NormalKlass},
+    runtime::java_thread::JavaThread,
+};
+
+/// A VM implementation of a Java `native` method.
+///
+/// `args` holds the materialized argument slots (receiver first for instance
+/// methods).  A native that throws stores the exception in
+/// `JavaThread::pending_exception`; its return value is then ignored.
+pub type NativeMethod = fn(&mut JavaThread, &[Slot]) -> ExecResult<RetValue>;
+
+struct NativeEntry {
+    class: &'static str,
+    name: &'static str,
+    desc: &'static str,
+    method: NativeMethod,
+}
+
+static NATIVES: &[NativeEntry] = &[
+    NativeEntry {
+        class: "java/lang/Class",
+        name: "getName",
+        desc: "()Ljava/lang/String;",
+        method: class::get_name,
+    },
+    NativeEntry {
+        class: "java/lang/Class",
+        name: "getRawAnnotations",
+        desc: "()[B",
+        method: class::get_raw_annotations,
+    },
+    NativeEntry {
+        class: "java/lang/Class",
+        name: "getRawTypeAnnotations",
+        desc: "()[B",
+        method: class::get_raw_type_annotations,
+    },
+    NativeEntry {
+        class: "java/lang/Class",
+        name: "getConstantPool",
+        desc: "()Lsun/reflect/ConstantPool;",
+        method: class::get_constant_pool,
+    },
+    NativeEntry {
+        class: "sun/reflect/ConstantPool",
+        name: "getSize0",
+        desc: "(Ljava/lang/Object;)I",
+        method: constant_pool::get_size0,
+    },
+    NativeEntry {
+        class: "sun/reflect/ConstantPool",
+        name: "getIntAt0",
+        desc: "(Ljava/lang/Object;I)I",
+        method: constant_pool::get_int_at0,
+    },
+    NativeEntry {
+        class: "sun/reflect/ConstantPool",
+        name: "getLongAt0",
+        desc: "(Ljava/lang/Object;I)J",
+        method: constant_pool::get_long_at0,
+    },
+    NativeEntry {
+        class: "sun/reflect/ConstantPool",
+        name: "getFloatAt0",
+        desc: "(Ljava/lang/Object;I)F",
+        method: constant_pool::get_float_at0,
+    },
+    NativeEntry {
+        class: "sun/reflect/ConstantPool",
+        name: "getDoubleAt0",
+        desc: "(Ljava/lang/Object;I)D",
+        method: constant_pool::get_double_at0,
+    },
+    NativeEntry {
+        class: "sun/reflect/ConstantPool",
+        name: "getUTF8At0",
+        desc: "(Ljava/lang/Object;I)Ljava/lang/String;",
+        method: constant_pool::get_utf8_at0,
+    },
+    NativeEntry {
+        class: "java/lang/ClassLoader",
+        name: "getSystemClassLoader0",
+        desc: "()Ljava/lang/ClassLoader;",
+        method: class_loader::get_system_class_loader0,
+    },
+    NativeEntry {
+        class: "java/lang/invoke/LambdaMetafactory",
+        name: "spinInnerClass",
+        desc: "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodHandle;)Ljava/lang/invoke/MethodHandle;",
+        method: invoke::spin_inner_class,
+    },
+    NativeEntry {
+        class: "java/lang/System",
+        name: "exit0",
+        desc: "(I)V",
+        method: system::exit0,
+    },
+    NativeEntry {
+        class: "java/lang/System",
+        name: "getProperty0",
+        desc: "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;",
+        method: system::get_property0,
+    },
+    NativeEntry {
+        class: "java/lang/Throwable",
+        name: "fillInStackTrace",
+        desc: "(I)Ljava/lang/Throwable;",
+        method: throwable::fill_in_stack_trace,
+    },
+    NativeEntry {
+        class: "java/lang/Throwable",
+        name: "printStackTrace",
+        desc: "()V",
+        method: throwable::print_stack_trace,
+    },
+];
+
+pub struct NativeRegistry;
+
+impl NativeRegistry {
+    pub fn lookup(holder: &NormalKlass, method: &Method) -> Option<NativeMethod> {
+        NATIVES
+            .iter()
+            .find(|entry| {
+                entry.class == holder.name().utf8()
+                    && entry.name == method.name.utf8()
+                    && entry.desc == method.desc.raw.utf8()
+            })
+            .map(|entry| entry.method)
+    }
+}
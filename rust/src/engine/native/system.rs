@@ -0,0 +1,32 @@
+use crate::{
+    engine::{
+        exec_error::{ExecResult, JavaExceptionKind},
+        outcome::{PendingException, RetValue},
+        slot::Slot,
+    },
+    oops::java_string::JavaString,
+    runtime::{arguments::Arguments, java_thread::JavaThread},
+};
+
+/// `static native void exit0(int status)`
+pub(super) fn exit0(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    thread.exit_request = Some(args[0].as_int()?);
+    Ok(RetValue::Void)
+}
+
+/// `private static native String getProperty0(String key, String def)`
+///
+/// 只读 `-D` 给出的属性；没有设置的键返回 `def`。
+pub(super) fn get_property0(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let key = args[0].as_ref()?;
+    if key == 0 {
+        thread.pending_exception = Some(PendingException::JVMGen(JavaExceptionKind::NullPointerException));
+        return Ok(RetValue::Ref(0));
+    }
+
+    let value = match Arguments::get().system_property(&JavaString::to_rust_string(key)?) {
+        Some(value) => JavaString::create(value)?,
+        None => args[1].as_ref()?,
+    };
+    Ok(RetValue::Ref(value))
+}
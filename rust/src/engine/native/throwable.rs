@@ -0,0 +1,42 @@
+use crate::{
+    engine::{exec_error::ExecResult, outcome::RetValue, slot::Slot, stack_trace::StackTrace},
+    oops::{java_throwable::{JavaThrowable, is_throwable}},
+    runtime::java_thread::JavaThread,
+};
+
+/// Interpreter frames that belong to creating the throwable rather than to
+/// the code that raised it: `fillInStackTrace` itself and the constructors
+/// of the throwable's class and its superclasses.
+fn construction_frames(thread: &JavaThread) -> usize {
+    let mut frames = thread.stack().interpreter_frames().peekable();
+    let mut skip = 0;
+
+    while frames.next_if(|x| x.target().method().name.utf8() == "fillInStackTrace").is_some() {
+        skip += 1;
+    }
+    while frames
+        .next_if(|x| x.target().method().name.utf8() == "<init>" && is_throwable(x.target().holder()))
+        .is_some()
+    {
+        skip += 1;
+    }
+
+    skip
+}
+
+/// `private native Throwable fillInStackTrace(int dummy)`
+pub(super) fn fill_in_stack_trace(thread: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let this = args[0].as_ref()?;
+    let trace = StackTrace::capture_from(thread.stack(), construction_frames(thread));
+
+    JavaThrowable::set_stack_trace(this, &trace)?;
+    Ok(RetValue::Ref(this))
+}
+
+/// `public native void printStackTrace()`
+pub(super) fn print_stack_trace(_: &mut JavaThread, args: &[Slot]) -> ExecResult<RetValue> {
+    let report = JavaThrowable::report(args[0].as_ref()?)?;
+
+    eprint!("{report}");
+    Ok(RetValue::Void)
+}
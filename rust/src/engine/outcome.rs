@@ -1,10 +1,15 @@
 use crate::{
+    class_loader::ms_api::MSRef,
     engine::{exec_error::JavaExceptionKind, resolved_method::ResolvedMethod},
     gc_bindings::oop_handle::NObjPtr,
-    oops::cp_entry::ResolvedFieldRef,
+    oops::{
+        cp_entry::{CallSiteKey, DynamicCPEntry, InvokeDynamicCPEntry, ResolvedFieldRef},
+        desc::MethodDesc,
+        normal_klass::NormalKlass,
+    },
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum RetValue {
     Void,
     Int(i32),
@@ -33,6 +38,34 @@ pub enum StepOutcome {
         target: ResolvedMethod,
         arg_slots: usize,
     },
+    /// `invokespecial`, `invokevirtual` or `invokeinterface` with the target
+    /// already selected; `arg_slots` includes the receiver.
+    InvokeInstance {
+        target: ResolvedMethod,
+        arg_slots: usize,
+    },
+    /// `invokeExact` or `invoke` on a method handle.  The handle lies below
+    /// the arguments, which are typed by `call_type`.
+    InvokeHandle {
+        call_type: MethodDesc,
+        exact: bool,
+    },
+    /// The dispatcher runs the bootstrap method if the instruction `site` is
+    /// not linked yet, then invokes its target with the arguments on the
+    /// caller's stack.
+    InvokeDynamic {
+        holder: MSRef<NormalKlass>,
+        call_site: MSRef<InvokeDynamicCPEntry>,
+        site: CallSiteKey,
+    },
+    /// `ldc`, `ldc_w` or `ldc2_w` of a dynamically-computed constant; the
+    /// dispatcher runs its bootstrap method unless it is resolved already.
+    LoadDynamic {
+        holder: MSRef<NormalKlass>,
+        constant: MSRef<DynamicCPEntry>,
+    },
+    /// Allocation waits for the class to be initialized.
+    New(MSRef<NormalKlass>),
     Return(RetValue),
     Throw(PendingException),
 }
@@ -41,6 +74,8 @@ pub enum StepOutcome {
 pub enum ThreadExit {
     Returned(RetValue),
     UncaughtException(PendingException),
+    /// `System.exit(status)` was called on this thread.
+    SystemExit(i32),
 }
 
 #[derive(Debug)]
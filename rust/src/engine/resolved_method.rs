@@ -1,8 +1,18 @@
 use crate::{
-    class_loader::ms_api::MSRef,
-    oops::{cp_entry::ResolvedMethodRef, method::Method, normal_klass::NormalKlass},
+    class_loader::{bootstrap_cld::BootstrapCLD, ms_api::MSRef},
+    engine::exec_error::JavaExceptionKind,
+    gc_bindings::{oop_codec::klass_of, oop_handle::NObjPtr},
+    oops::{
+        acc_flags::AccFlags,
+        cp_entry::{ResolvedInterfaceMethodRef, ResolvedMethodRef},
+        method::Method,
+        normal_klass::NormalKlass,
+        symbol_table::SymbolHandle,
+    },
 };
 
+const OBJECT_CLASS: &str = "java/lang/Object";
+
 #[derive(Debug, Clone)]
 pub struct ResolvedMethod {
     holder: MSRef<NormalKlass>,
@@ -15,6 +25,12 @@ impl From<ResolvedMethodRef> for ResolvedMethod {
     }
 }
 
+impl From<ResolvedInterfaceMethodRef> for ResolvedMethod {
+    fn from(value: ResolvedInterfaceMethodRef) -> Self {
+        Self::new(value.holder, value.method)
+    }
+}
+
 impl ResolvedMethod {
     pub fn new(holder: MSRef<NormalKlass>, method: MSRef<Method>) -> Self {
         Self { holder, method }
@@ -31,4 +47,74 @@ impl ResolvedMethod {
     pub fn method(&self) -> &Method {
         &self.method
     }
+
+    /// The class an instance call on `receiver` selects from; arrays have the
+    /// methods of `Object`.  `None` for `null`.
+    pub fn receiver_class(receiver: NObjPtr) -> Option<MSRef<NormalKlass>> {
+        let klass = klass_of(receiver)?;
+        klass
+            .as_normal_ref()
+            .or_else(|| BootstrapCLD::find_class(OBJECT_CLASS).ok()?.as_normal_ref())
+    }
+
+    /// JVMS 5.4.6 method selection for a receiver of class `receiver`: a
+    /// private method is invoked as resolved; otherwise the first overriding
+    /// declaration up the superclass chain, then the one non-abstract
+    /// maximally-specific superinterface method.  The error is what the
+    /// invocation throws when there is no such method, or more than one.
+    pub fn select(&self, receiver: &MSRef<NormalKlass>) -> Result<ResolvedMethod, JavaExceptionKind> {
+        if self.method.acc_flags.contains(AccFlags::ACC_PRIVATE) {
+            return Ok(self.clone());
+        }
+
+        let name = &self.method.name;
+        let desc = &self.method.desc.raw;
+
+        let mut current = Some(receiver.clone());
+        while let Some(klass) = current {
+            // 私有方法不参与覆盖。
+            if let Some(method) = klass.find_declared_method_symbol(name, desc)
+                && !method.acc_flags.intersects(AccFlags::ACC_STATIC | AccFlags::ACC_PRIVATE)
+            {
+                if method.acc_flags.contains(AccFlags::ACC_ABSTRACT) {
+                    return Err(JavaExceptionKind::AbstractMethodError);
+                }
+                return Ok(Self::new(klass, method));
+            }
+            current = klass.super_klass_ref();
+        }
+
+        Self::select_default(receiver, name, desc)
+    }
+
+    /// `invokespecial` of a method the reference finds through `interface`:
+    /// its own declaration or one of `Object`, as resolved; otherwise the
+    /// one non-abstract maximally-specific superinterface method.
+    pub fn select_in_interface(&self, interface: &NormalKlass) -> Result<ResolvedMethod, JavaExceptionKind> {
+        if std::ptr::eq(self.holder(), interface) || !self.holder.is_interface() {
+            if self.method.acc_flags.contains(AccFlags::ACC_ABSTRACT) {
+                return Err(JavaExceptionKind::AbstractMethodError);
+            }
+            return Ok(self.clone());
+        }
+
+        Self::select_default(interface, &self.method.name, &self.method.desc.raw)
+    }
+
+    fn select_default(
+        klass: &NormalKlass,
+        name: &SymbolHandle,
+        desc: &SymbolHandle,
+    ) -> Result<ResolvedMethod, JavaExceptionKind> {
+        let mut concrete = klass
+            .maximally_specific_methods(name, desc)
+            .into_iter()
+            .filter(|(_, method)| !method.acc_flags.contains(AccFlags::ACC_ABSTRACT));
+
+        match (concrete.next(), concrete.next()) {
+            (Some((holder, method)), None) => Ok(Self::new(holder, method)),
+            (None, _) => Err(JavaExceptionKind::AbstractMethodError),
+            _ => Err(JavaExceptionKind::IncompatibleClassChangeError),
+        }
+    }
 }
@@ -0,0 +1,225 @@
+//! Java stack traces: capture from a `JavaStack` and HotSpot-style printing.
+
+use std::fmt;
+
+use crate::{
+    engine::{exec_error::JavaExceptionKind, interpreter::interpreter_frame::InterpreterFrame},
+    runtime::java_stack::JavaStack,
+};
+
+/// One Java frame of a stack trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Declaring class in internal form (`java/lang/Object`).
+    pub class_name: String,
+    pub method_name: String,
+    /// Index of the instruction executing in this frame: the throwing
+    /// instruction for the top frame, the pending invoke for callers.
+    pub bci: usize,
+    /// From the class's `SourceFile` attribute.
+    pub file_name: Option<String>,
+    /// From the method's `LineNumberTable`, looked up by `bci`.
+    pub line_number: Option<u32>,
+}
+
+impl StackFrame {
+    pub fn of(frame: &InterpreterFrame) -> Self {
+        let target = frame.target();
+        let bci = frame.last_pc();
+
+        Self {
+            class_name: target.holder().name().utf8().to_string(),
+            method_name: target.method().name.utf8().to_string(),
+            bci,
+            file_name: target.holder().source_file().map(|x| x.utf8().to_string()),
+            line_number: frame.code().line_number(bci).map(u32::from),
+        }
+    }
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.class_name.replace('/', "."), self.method_name)?;
+
+        match (&self.file_name, self.line_number) {
+            (Some(file), Some(line)) => write!(f, "({file}:{line})"),
+            (Some(file), None) => write!(f, "({file})"),
+            (None, _) => write!(f, "(Unknown Source)"),
+        }
+    }
+}
+
+/// Frames ordered from the innermost (most recent call) outwards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StackTrace {
+    pub frames: Vec<StackFrame>,
+}
+
+impl StackTrace {
+    /// Snapshot the interpreter frames of `stack`.  Class-initialization
+    /// control frames have no bytecode and are skipped.
+    pub fn capture(stack: &JavaStack) -> Self {
+        Self::capture_from(stack, 0)
+    }
+
+    /// Like `capture`, but leave out the `skip` innermost interpreter frames.
+    pub fn capture_from(stack: &JavaStack, skip: usize) -> Self {
+        let frames = stack
+            .interpreter_frames()
+            .skip(skip)
+            .map(StackFrame::of)
+            .collect();
+
+        Self { frames }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+/// Everything needed to print a throwable and its causes without touching
+/// the Java heap again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThrowableReport {
+    /// Exception class in internal form.
+    pub class_name: String,
+    pub message: Option<String>,
+    pub stack_trace: StackTrace,
+    pub cause: Option<Box<ThrowableReport>>,
+}
+
+impl ThrowableReport {
+    /// Report for an exception the VM raised before any Java object exists.
+    pub fn for_kind(kind: JavaExceptionKind, stack_trace: StackTrace) -> Self {
+        Self {
+            class_name: kind.class_name().into(),
+            message: kind.message().map(Into::into),
+            stack_trace,
+            cause: None,
+        }
+    }
+
+    /// `Throwable.toString()`: the binary class name and the message, if any.
+    pub fn summary(&self) -> String {
+        let class_name = self.class_name.replace('/', ".");
+        match &self.message {
+            Some(message) => format!("{class_name}: {message}"),
+            None => class_name,
+        }
+    }
+
+    fn write_frames(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        enclosing: Option<&StackTrace>,
+    ) -> fmt::Result {
+        let frames = &self.stack_trace.frames;
+
+        // 与 Throwable.printEnclosedTrace 相同：省略与外层异常共同的尾部帧。
+        let in_common = enclosing.map_or(0, |outer| {
+            frames
+                .iter()
+                .rev()
+                .zip(outer.frames.iter().rev())
+                .take_while(|(a, b)| a == b)
+                .count()
+        });
+
+        for frame in &frames[..frames.len() - in_common] {
+            writeln!(f, "\tat {frame}")?;
+        }
+        if in_common != 0 {
+            writeln!(f, "\t... {in_common} more")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The `printStackTrace` text, one line per frame and a `Caused by:` section
+/// per cause.
+impl fmt::Display for ThrowableReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.summary())?;
+        self.write_frames(f, None)?;
+
+        let mut enclosing = self;
+        while let Some(cause) = enclosing.cause.as_deref() {
+            writeln!(f, "Caused by: {}", cause.summary())?;
+            cause.write_frames(f, Some(&enclosing.stack_trace))?;
+            enclosing = cause;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(method: &str) -> StackFrame {
+        StackFrame {
+            class_name: "p/Main".into(),
+            method_name: method.into(),
+            bci: 0,
+            file_name: None,
+            line_number: None,
+        }
+    }
+
+    fn trace(methods: &[&str]) -> StackTrace {
+        StackTrace {
+            frames: methods.iter().map(|x| frame(x)).collect(),
+        }
+    }
+
+    #[test]
+    fn prints_frames_and_elides_frames_shared_with_the_enclosing_trace() {
+        let report = ThrowableReport {
+            class_name: "java/lang/RuntimeException".into(),
+            message: Some("wrapped".into()),
+            stack_trace: trace(&["wrap", "main"]),
+            cause: Some(Box::new(ThrowableReport {
+                class_name: "java/lang/ArithmeticException".into(),
+                message: Some("/ by zero".into()),
+                stack_trace: trace(&["divide", "compute", "wrap", "main"]),
+                cause: None,
+            })),
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "java.lang.RuntimeException: wrapped\n\
+             \tat p.Main.wrap(Unknown Source)\n\
+             \tat p.Main.main(Unknown Source)\n\
+             Caused by: java.lang.ArithmeticException: / by zero\n\
+             \tat p.Main.divide(Unknown Source)\n\
+             \tat p.Main.compute(Unknown Source)\n\
+             \t... 2 more\n"
+        );
+    }
+
+    #[test]
+    fn frames_show_as_much_source_position_as_is_known() {
+        let mut frame = frame("run");
+        assert_eq!(frame.to_string(), "p.Main.run(Unknown Source)");
+
+        frame.file_name = Some("Main.java".into());
+        assert_eq!(frame.to_string(), "p.Main.run(Main.java)");
+
+        frame.line_number = Some(42);
+        assert_eq!(frame.to_string(), "p.Main.run(Main.java:42)");
+    }
+
+    #[test]
+    fn summary_omits_a_missing_message() {
+        let report = ThrowableReport::for_kind(JavaExceptionKind::NullPointerException, trace(&[]));
+        assert_eq!(report.to_string(), "java.lang.NullPointerException\n");
+    }
+}
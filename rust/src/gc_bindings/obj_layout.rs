@@ -6,6 +6,9 @@
 //! 布局含义见 `normal_klass.rs` 的 `Fields::build` 注释和
 //! `core/obj_model/obj_layout.h` 的 GC 扫描算法文档。
 
+/// 对象头（markword）字节数。根类（`java.lang.Object`）的字段区从这里开始。
+pub const OBJ_HEADER_BYTES: usize = 8;
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct ObjLayout {
@@ -22,3 +25,14 @@ pub struct ObjLayout {
     /// GC 遍历：本层 oop 区起点 = `super_layout.byte_size`（首层是 8，跳过 markword）。
     pub ptrs_count: usize,
 }
+
+impl ObjLayout {
+    /// 本类部分相对对象起点的偏移：父类的累计大小，根类则跳过 markword。
+    pub fn layer_start(&self) -> usize {
+        if self.super_layout.is_null() {
+            OBJ_HEADER_BYTES
+        } else {
+            unsafe { (*self.super_layout).byte_size }
+        }
+    }
+}
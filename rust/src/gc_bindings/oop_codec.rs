@@ -84,3 +84,16 @@ pub unsafe fn klass_from_markword(raw: u64) -> MSRef<Klass> {
     // SAFETY: 调用方保证 markword 来自合法分配的对象。
     unsafe { MSRef::decode(cp).expect("Klass ptr not set") }
 }
+
+/// 读取对象的 klass；`narrow == 0` 返回 `None`。
+///
+/// `narrow` 必须是由 `encode_oop` 得到的、指向已分配对象的引用。
+pub fn klass_of(narrow: u32) -> Option<MSRef<Klass>> {
+    let obj = decode_oop(narrow);
+    if obj.is_null() {
+        return None;
+    }
+
+    // SAFETY: 非空引用指向 gcheap_alloc 分配的对象，其 markword 已写好。
+    Some(unsafe { klass_from_markword((*obj).markword) })
+}
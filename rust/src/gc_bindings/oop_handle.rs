@@ -2,6 +2,8 @@
 
 use std::ptr::NonNull;
 
+use crate::gc_bindings::oop_codec::{decode_oop, encode_oop};
+
 #[repr(C)]
 pub struct ObjDesc {
     pub markword: u64,
@@ -54,6 +56,18 @@ impl OOPHandle {
         let slot = NonNull::new(raw).expect("alloc_oop_slot returned NULL");
         Self { slot, storage_id }
     }
+
+    /// The referenced object as a narrow pointer; `0` while the slot is NULL.
+    pub fn get(&self) -> NObjPtr {
+        encode_oop(unsafe { *self.slot.as_ptr() })
+    }
+
+    /// Store `value` into the slot and return the previous referent.
+    pub fn replace(&self, value: NObjPtr) -> NObjPtr {
+        let previous = self.get();
+        unsafe { *self.slot.as_ptr() = decode_oop(value) };
+        previous
+    }
 }
 
 impl Drop for OOPHandle {
@@ -1,6 +1,8 @@
+pub mod assembler;
 pub mod class_loader;
 pub mod class_parser;
 pub mod gc_bindings;
 pub mod engine;
 pub mod oops;
 pub mod runtime;
+pub mod verifier;
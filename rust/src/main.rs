@@ -0,0 +1,94 @@
+//! `klover` — a `java`-style launcher.
+
+use std::{error::Error, process::ExitCode};
+
+use klover::{
+    class_loader::load_error::LoadError,
+    engine::outcome::ThreadExit,
+    runtime::{
+        command_line::{CommandLine, CommandLineError, LaunchTarget, USAGE},
+        launcher::{JarLaunch, Launcher},
+        runtime_error::LaunchError,
+        vm::vm_init,
+    },
+};
+
+fn main() -> ExitCode {
+    let CommandLine {
+        mut arguments,
+        target,
+        program_args,
+    } = match CommandLine::parse(std::env::args().skip(1)) {
+        Ok(x) => x,
+        Err(CommandLineError::HelpRequested) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("Error: {}", describe_command_line_error(&err));
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let main_class = match target {
+        LaunchTarget::MainClass(x) => x,
+        LaunchTarget::Jar(jar) => match JarLaunch::from_jar(&jar) {
+            // 与 java 一致：-jar 时忽略 -cp。
+            Ok(launch) => {
+                arguments.class_path = launch.class_path;
+                launch.main_class
+            }
+            Err(err) => {
+                eprintln!("Error: Invalid or corrupt jarfile {}: {err}", jar.display());
+                print_causes(&err);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    vm_init(arguments);
+
+    match Launcher::run_main(&main_class, &program_args) {
+        Ok(ThreadExit::Returned(_)) => ExitCode::SUCCESS,
+        Ok(ThreadExit::SystemExit(status)) => ExitCode::from(status as u8),
+        // 报告已由 dispatcher 的默认 uncaught handler 打印。
+        Ok(ThreadExit::UncaughtException(_)) => ExitCode::FAILURE,
+        Err(LaunchError::Load(LoadError::NotFound(_))) => {
+            eprintln!("Error: Could not find or load main class {}", main_class.replace('/', "."));
+            ExitCode::FAILURE
+        }
+        Err(LaunchError::MainNotFound(_)) => {
+            eprintln!(
+                "Error: Main method not found in class {}, please define the main method as:\n   public static void main(String[] args)",
+                main_class.replace('/', ".")
+            );
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            print_causes(&err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The `source` chain below an error already printed.
+fn print_causes(err: &dyn Error) {
+    let mut next = err.source();
+    while let Some(cause) = next {
+        eprintln!("Caused by: {cause}");
+        next = cause.source();
+    }
+}
+
+fn describe_command_line_error(err: &CommandLineError) -> String {
+    match err {
+        CommandLineError::HelpRequested => "help requested".into(),
+        CommandLineError::NoMainClass => "no main class or -jar file given".into(),
+        CommandLineError::MissingValue(option) => format!("{option} requires an argument"),
+        CommandLineError::InvalidSize(option) => format!("invalid size in {option}"),
+        CommandLineError::InvalidValue(option) => format!("invalid number in {option}"),
+        CommandLineError::UnrecognizedOption(option) => format!("unrecognized option: {option}"),
+    }
+}
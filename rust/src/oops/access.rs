@@ -0,0 +1,123 @@
+//! JVMS 5.4.4 access control.
+//!
+//! A class spun on behalf of a host (see `NormalKlass::host`) may access
+//! whatever its host may, as well as its own members.  Private members are
+//! shared within a nest (JVMS 5.4.4, Java 11); an invalid nest host makes
+//! such a check fail with an error rather than deny access.
+
+use crate::{
+    class_loader::{bootstrap_cld::BootstrapCLD, ms_api::MSRef},
+    oops::{
+        acc_flags::AccFlags, array_klass::ArrayKlass, desc::FieldElemType, klass::Klass,
+        normal_klass::NormalKlass, oops_errors::ResolveResult,
+    },
+};
+
+/// Package part of a binary name in internal form; empty for the unnamed
+/// package.
+fn package_name(name: &str) -> &str {
+    name.rfind('/').map_or("", |end| &name[..end])
+}
+
+impl NormalKlass {
+    /// The same runtime package: the same package name and the same defining
+    /// loader (JVMS 5.3).
+    pub fn is_same_runtime_package(&self, other: &NormalKlass) -> bool {
+        let loader = |klass: &NormalKlass| klass.cld().map(|x| x as *const _);
+
+        loader(self) == loader(other)
+            && package_name(self.name().utf8()) == package_name(other.name().utf8())
+    }
+
+    /// Can this class refer to `klass`?  An array class is accessible when
+    /// its element class is.
+    pub fn can_access_class(&self, klass: &Klass) -> bool {
+        let target = match klass {
+            Klass::Normal(x) => x,
+            Klass::Primitive(_) => return true,
+            Klass::Array(x) => {
+                return self
+                    .element_class(x)
+                    .is_none_or(|element| self.can_access_class(&element));
+            }
+        };
+
+        self.grants_class(target) || self.host().is_some_and(|host| host.grants_class(target))
+    }
+
+    /// Can this class use a field or method with `flags` declared in
+    /// `holder`, referenced through the class `referenced`?  Fails when a
+    /// private member needs a nest host that cannot be validated.
+    pub fn can_access_member(
+        &self,
+        holder: &NormalKlass,
+        flags: &AccFlags,
+        referenced: &NormalKlass,
+    ) -> ResolveResult<bool> {
+        if std::ptr::eq(self, holder) || self.grants_member(holder, flags, referenced)? {
+            return Ok(true);
+        }
+
+        match self.host() {
+            Some(host) => host.grants_member(holder, flags, referenced),
+            None => Ok(false),
+        }
+    }
+
+    /// JVMS 4.10.1.8: an instance member that is protected, and accessible
+    /// only because its holder is a superclass in another runtime package,
+    /// may only be used on instances of this class or its subclasses.
+    pub fn allows_protected_receiver(&self, holder: &NormalKlass, flags: &AccFlags, receiver: &NormalKlass) -> bool {
+        let context = self.host().unwrap_or(self);
+
+        !flags.contains(AccFlags::ACC_PROTECTED)
+            || flags.contains(AccFlags::ACC_STATIC)
+            || !context.is_subclass_of(holder)
+            || context.is_same_runtime_package(holder)
+            || receiver.is_subclass_of(context)
+    }
+
+    pub fn is_nestmate_of(&self, other: &NormalKlass) -> ResolveResult<bool> {
+        if std::ptr::eq(self, other) {
+            return Ok(true);
+        }
+
+        Ok(std::ptr::eq(self.nest_host()?, other.nest_host()?))
+    }
+
+    fn grants_class(&self, klass: &NormalKlass) -> bool {
+        klass.acc_flags().contains(AccFlags::ACC_PUBLIC) || self.is_same_runtime_package(klass)
+    }
+
+    fn grants_member(&self, holder: &NormalKlass, flags: &AccFlags, referenced: &NormalKlass) -> ResolveResult<bool> {
+        if flags.contains(AccFlags::ACC_PUBLIC) {
+            return Ok(true);
+        }
+        if flags.contains(AccFlags::ACC_PRIVATE) {
+            return self.is_nestmate_of(holder);
+        }
+        if self.is_same_runtime_package(holder) {
+            return Ok(true);
+        }
+
+        Ok(flags.contains(AccFlags::ACC_PROTECTED)
+            && self.is_subclass_of(holder)
+            && (flags.contains(AccFlags::ACC_STATIC)
+                || referenced.is_subclass_of(self)
+                || self.is_subclass_of(referenced)))
+    }
+
+    fn element_class(&self, array: &ArrayKlass) -> Option<MSRef<Klass>> {
+        let FieldElemType::Class { name, resolved } = &array.desc.elem else {
+            return None;
+        };
+        if let Some(klass) = resolved.get() {
+            return Some(klass.clone());
+        }
+
+        match self.cld() {
+            Some(cld) => cld.load_class(name.utf8()).ok(),
+            None => BootstrapCLD::find_class(name.utf8()).ok(),
+        }
+    }
+}
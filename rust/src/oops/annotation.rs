@@ -0,0 +1,241 @@
+//! Runtime-visible annotations of classes, fields and methods, resolved
+//! against the constant pool into a tree a reflection layer can walk.
+
+use std::cell::OnceCell;
+
+use crate::{
+    class_parser::{
+        annotation_info::{AnnotationInfo, ElementValueInfo, TargetInfo, TypeAnnotationInfo, TypePathEntryInfo},
+        attr_info::{AttrInfo, RawAttr},
+    },
+    oops::{
+        cp_entry::{CPEntry, get_utf8},
+        normal_klass::cp_slice_get,
+        oops_errors::{ResolveError, ResolveResult},
+        symbol_table::SymbolHandle,
+    },
+};
+
+/// Attributes whose bytes are handed to Java as-is by `Class.getRawAnnotations`
+/// and its field and method counterparts.
+const RAW_ANNOTATION_ATTRS: &[&str] = &[
+    "RuntimeVisibleAnnotations",
+    "RuntimeVisibleParameterAnnotations",
+    "RuntimeVisibleTypeAnnotations",
+    "AnnotationDefault",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementValue {
+    Byte(i8),
+    Char(u16),
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Long(i64),
+    Short(i16),
+    Boolean(bool),
+    String(SymbolHandle),
+
+    Enum {
+        /// Field descriptor of the enum class.
+        type_name: SymbolHandle,
+        const_name: SymbolHandle,
+    },
+
+    /// Return descriptor of the class literal, e.g. `Ljava/lang/String;`.
+    Class(SymbolHandle),
+
+    Annotation(Annotation),
+
+    Array(Vec<ElementValue>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// Field descriptor of the annotation interface, e.g. `Ljava/lang/Deprecated;`.
+    pub type_name: SymbolHandle,
+    /// Explicitly given elements in class-file order; defaults are not filled in.
+    pub elements: Vec<(SymbolHandle, ElementValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeAnnotation {
+    pub target_type: u8,
+    pub target: TargetInfo,
+    pub type_path: Vec<TypePathEntryInfo>,
+    pub annotation: Annotation,
+}
+
+/// Everything annotation-related one class, field or method carries.
+#[derive(Debug, Default)]
+pub struct Annotations {
+    pub declared: Vec<Annotation>,
+    /// Empty unless the method has `RuntimeVisibleParameterAnnotations`.
+    pub parameters: Vec<Vec<Annotation>>,
+    /// Includes the ones in a method's `Code` attribute.
+    pub type_annotations: Vec<TypeAnnotation>,
+    /// `AnnotationDefault` of an annotation interface element.
+    pub default_value: Option<ElementValue>,
+
+    raw: Vec<RawAttr>,
+}
+
+impl ElementValue {
+    fn build(info: &ElementValueInfo, cp: &[OnceCell<CPEntry>]) -> ResolveResult<Self> {
+        let value = match info {
+            ElementValueInfo::Const { tag, const_value_idx } => {
+                let entry = cp_slice_get(cp, *const_value_idx as usize);
+                match (tag, entry) {
+                    (b's', _) => Self::String(get_utf8(cp, *const_value_idx as usize)?),
+                    (b'B', Some(CPEntry::Integer(x))) => Self::Byte(*x as i8),
+                    (b'C', Some(CPEntry::Integer(x))) => Self::Char(*x as u16),
+                    (b'S', Some(CPEntry::Integer(x))) => Self::Short(*x as i16),
+                    (b'Z', Some(CPEntry::Integer(x))) => Self::Boolean(*x != 0),
+                    (b'I', Some(CPEntry::Integer(x))) => Self::Int(*x),
+                    (b'J', Some(CPEntry::Long(x))) => Self::Long(*x),
+                    (b'F', Some(CPEntry::Float(x))) => Self::Float(*x),
+                    (b'D', Some(CPEntry::Double(x))) => Self::Double(*x),
+                    _ => return Err(ResolveError::MismatchCPType),
+                }
+            }
+
+            ElementValueInfo::Enum {
+                type_name_idx,
+                const_name_idx,
+            } => Self::Enum {
+                type_name: get_utf8(cp, *type_name_idx as usize)?,
+                const_name: get_utf8(cp, *const_name_idx as usize)?,
+            },
+
+            ElementValueInfo::Class { class_info_idx } => {
+                Self::Class(get_utf8(cp, *class_info_idx as usize)?)
+            }
+
+            ElementValueInfo::Annotation(info) => Self::Annotation(Annotation::build(info, cp)?),
+
+            ElementValueInfo::Array(values) => Self::Array(
+                values
+                    .iter()
+                    .map(|x| Self::build(x, cp))
+                    .collect::<ResolveResult<_>>()?,
+            ),
+        };
+
+        Ok(value)
+    }
+}
+
+impl Annotation {
+    fn build(info: &AnnotationInfo, cp: &[OnceCell<CPEntry>]) -> ResolveResult<Self> {
+        let mut elements = Vec::with_capacity(info.element_value_pairs.len());
+        for pair in &info.element_value_pairs {
+            elements.push((
+                get_utf8(cp, pair.element_name_idx as usize)?,
+                ElementValue::build(&pair.value, cp)?,
+            ));
+        }
+
+        Ok(Self {
+            type_name: get_utf8(cp, info.type_idx as usize)?,
+            elements,
+        })
+    }
+
+    fn build_list(infos: &[AnnotationInfo], cp: &[OnceCell<CPEntry>]) -> ResolveResult<Vec<Self>> {
+        infos.iter().map(|x| Self::build(x, cp)).collect()
+    }
+
+    /// Value of the element called `name`, if it was given explicitly.
+    pub fn element(&self, name: &str) -> Option<&ElementValue> {
+        self.elements
+            .iter()
+            .find(|(x, _)| x.utf8() == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl TypeAnnotation {
+    fn build(info: &TypeAnnotationInfo, cp: &[OnceCell<CPEntry>]) -> ResolveResult<Self> {
+        Ok(Self {
+            target_type: info.target_type,
+            target: info.target_info.clone(),
+            type_path: info.target_path.clone(),
+            annotation: Annotation::build(&info.annotation, cp)?,
+        })
+    }
+}
+
+impl Annotations {
+    pub(super) fn build(
+        attrs: &[AttrInfo],
+        raw_attrs: &[RawAttr],
+        cp: &[OnceCell<CPEntry>],
+    ) -> ResolveResult<Self> {
+        let mut annotations = Self {
+            raw: raw_attrs
+                .iter()
+                .filter(|x| RAW_ANNOTATION_ATTRS.contains(&x.name.as_str()))
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+
+        for attr in attrs {
+            match attr {
+                AttrInfo::RuntimeVisibleAnnotations(infos) => {
+                    annotations.declared = Annotation::build_list(infos, cp)?;
+                }
+                AttrInfo::RuntimeVisibleParameterAnnotations(parameters) => {
+                    annotations.parameters = parameters
+                        .iter()
+                        .map(|x| Annotation::build_list(x, cp))
+                        .collect::<ResolveResult<_>>()?;
+                }
+                AttrInfo::AnnotationDefault(info) => {
+                    annotations.default_value = Some(ElementValue::build(info, cp)?);
+                }
+                _ => annotations.add_type_annotations(attr, cp)?,
+            }
+        }
+
+        Ok(annotations)
+    }
+
+    /// Pick up `RuntimeVisibleTypeAnnotations` from `attr`, which may also be
+    /// an attribute of a method's `Code`.
+    pub(super) fn add_type_annotations(
+        &mut self,
+        attr: &AttrInfo,
+        cp: &[OnceCell<CPEntry>],
+    ) -> ResolveResult<()> {
+        if let AttrInfo::RuntimeVisibleTypeAnnotations(infos) = attr {
+            for info in infos {
+                self.type_annotations.push(TypeAnnotation::build(info, cp)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.declared.is_empty()
+            && self.parameters.iter().all(Vec::is_empty)
+            && self.type_annotations.is_empty()
+            && self.default_value.is_none()
+    }
+
+    /// The declared annotation of type `type_name` (a field descriptor).
+    pub fn find(&self, type_name: &str) -> Option<&Annotation> {
+        self.declared.iter().find(|x| x.type_name.utf8() == type_name)
+    }
+
+    /// Class-file bytes of the annotation attribute called `name`, as the
+    /// reflection natives return them.
+    pub fn raw(&self, name: &str) -> Option<&[u8]> {
+        self.raw
+            .iter()
+            .find(|x| x.name == name)
+            .map(|x| x.bytes.as_slice())
+    }
+}
@@ -1,4 +1,12 @@
-use crate::{gc_bindings::oop_handle::{NObjPtr, OOPHandle}, oops::{desc::{FieldDesc, FieldElemType}, symbol_table::SymbolHandle}};
+use crate::{
+    engine::exec_error::{ExecError, ExecResult},
+    gc_bindings::{
+        gc_bindings::alloc_object,
+        oop_codec::{decode_oop, encode_oop},
+        oop_handle::{NObjPtr, OOPHandle},
+    },
+    oops::{desc::{FieldDesc, FieldElemType}, klass::Klass, symbol_table::SymbolHandle},
+};
 
 /// 数组元素的固定布局：
 ///   markword(8) + length(4) + padding(4) + elements(...)
@@ -51,3 +59,97 @@ impl ArrayKlass {
             )
     }
 }
+
+impl ArrayKlass {
+    /// Allocate a zeroed array of `length` elements.
+    ///
+    /// `klass` must be the `Klass::Array` wrapping `self`; its address is
+    /// what the object header encodes.
+    pub fn allocate(&self, klass: &Klass, length: usize) -> NObjPtr {
+        let data_bytes = self.element_size() * length;
+        let byte_size = (ARRAY_DATA_OFFSET + data_bytes).next_multiple_of(8);
+
+        let obj = alloc_object(klass, byte_size);
+        unsafe {
+            (obj as *mut u8)
+                .add(ARRAY_LENGTH_OFFSET)
+                .cast::<i32>()
+                .write(length as i32);
+        }
+
+        encode_oop(obj)
+    }
+
+    pub fn length(array: NObjPtr) -> ExecResult<usize> {
+        let obj = decode_oop(array);
+        if obj.is_null() {
+            return Err(ExecError::NullObjectReference);
+        }
+
+        let length = unsafe { (obj as *const u8).add(ARRAY_LENGTH_OFFSET).cast::<i32>().read() };
+        Ok(length as usize)
+    }
+
+    /// Raw storage of element `index`; the caller knows the element size.
+    fn element_ptr(array: NObjPtr, index: usize, element_size: usize) -> ExecResult<*mut u8> {
+        if index >= Self::length(array)? {
+            return Err(ExecError::ArrayIndexOutOfBounds(index));
+        }
+
+        let obj = decode_oop(array) as *mut u8;
+        Ok(unsafe { obj.add(ARRAY_DATA_OFFSET + index * element_size) })
+    }
+
+    pub fn read_ref(array: NObjPtr, index: usize) -> ExecResult<NObjPtr> {
+        let ptr = Self::element_ptr(array, index, size_of::<NObjPtr>())?;
+        Ok(unsafe { ptr.cast::<NObjPtr>().read_unaligned() })
+    }
+
+    pub fn write_ref(array: NObjPtr, index: usize, value: NObjPtr) -> ExecResult<()> {
+        let ptr = Self::element_ptr(array, index, size_of::<NObjPtr>())?;
+        unsafe { ptr.cast::<NObjPtr>().write_unaligned(value) };
+        Ok(())
+    }
+
+    /// Copy the contents of a `byte[]`.
+    pub fn read_bytes(array: NObjPtr) -> ExecResult<Vec<u8>> {
+        let length = Self::length(array)?;
+        let obj = decode_oop(array) as *const u8;
+
+        Ok(unsafe { std::slice::from_raw_parts(obj.add(ARRAY_DATA_OFFSET), length) }.to_vec())
+    }
+
+    /// Fill a `byte[]` from the start with `bytes`.
+    pub fn write_bytes(array: NObjPtr, bytes: &[u8]) -> ExecResult<()> {
+        if bytes.len() > Self::length(array)? {
+            return Err(ExecError::ArrayIndexOutOfBounds(bytes.len()));
+        }
+
+        let obj = decode_oop(array) as *mut u8;
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), obj.add(ARRAY_DATA_OFFSET), bytes.len()) };
+        Ok(())
+    }
+
+    /// Copy the contents of a `char[]`.
+    pub fn read_chars(array: NObjPtr) -> ExecResult<Vec<u16>> {
+        let length = Self::length(array)?;
+        let obj = decode_oop(array) as *const u8;
+
+        let chars = unsafe { obj.add(ARRAY_DATA_OFFSET).cast::<u16>() };
+        Ok((0..length).map(|i| unsafe { chars.add(i).read_unaligned() }).collect())
+    }
+
+    /// Fill a `char[]` from the start with `chars`.
+    pub fn write_chars(array: NObjPtr, chars: &[u16]) -> ExecResult<()> {
+        if chars.len() > Self::length(array)? {
+            return Err(ExecError::ArrayIndexOutOfBounds(chars.len()));
+        }
+
+        let obj = decode_oop(array) as *mut u8;
+        let dest = unsafe { obj.add(ARRAY_DATA_OFFSET).cast::<u16>() };
+        for (i, &c) in chars.iter().enumerate() {
+            unsafe { dest.add(i).write_unaligned(c) };
+        }
+        Ok(())
+    }
+}
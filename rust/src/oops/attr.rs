@@ -1,8 +1,8 @@
 use std::cell::OnceCell;
 
 use crate::{
-    class_loader::ms_api::{MSAllocator, MSBox, MSRef}, class_parser::attr_info::{CodeAttrInfo, ExceptionTableEntryInfo}, oops::{
-        cp_entry::{CPEntry, ClassCPEntry, StringCPEntry}, normal_klass::cp_slice_get, oops_errors::{ResolveError, ResolveResult},
+    class_loader::ms_api::{MSAllocator, MSBox, MSRef}, class_parser::{attr_info::{AttrInfo, BootstrapMethodInfo, CodeAttrInfo, ExceptionTableEntryInfo}, stack_map_info::StackMapFrameInfo}, oops::{
+        cp_entry::{CPEntry, ClassCPEntry, Loadable, MethodHandleCPEntry, StringCPEntry, get_utf8}, normal_klass::cp_slice_get, oops_errors::{ResolveError, ResolveResult}, symbol_table::SymbolHandle,
     },
 };
 
@@ -56,12 +56,101 @@ impl ExceptionTableEntry {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineNumberTableEntry {
+    /// 该行对应代码的起始 bci。
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+/// 一条 `LocalVariableTable` 记录：局部变量 `index` 在
+/// `[start_pc, start_pc + length)` 范围内有值。
+#[derive(Debug, Clone)]
+pub struct LocalVariable {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name: SymbolHandle,
+    /// Field descriptor, e.g. `Ljava/util/List;`.
+    pub descriptor: SymbolHandle,
+    /// Generic signature from the matching `LocalVariableTypeTable` entry.
+    pub signature: Option<SymbolHandle>,
+    pub index: u16,
+}
+
+impl LocalVariable {
+    /// Whether the variable holds a value at `pc`.
+    pub fn is_live_at(&self, pc: usize) -> bool {
+        let start = self.start_pc as usize;
+        (start..start + self.length as usize).contains(&pc)
+    }
+
+    /// `long` and `double` take two local slots.
+    pub fn slot_count(&self) -> usize {
+        match self.descriptor.utf8().as_bytes().first() {
+            Some(b'J' | b'D') => 2,
+            _ => 1,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Code {
     pub max_stack: usize,
     pub max_locals: usize,
     pub bytecodes: MSBox<[u8]>,
     pub exception_table: MSBox<[ExceptionTableEntry]>,
+    /// 所有 `LineNumberTable` 属性合并后按 `start_pc` 排序；无调试信息时为空。
+    pub line_number_table: MSBox<[LineNumberTableEntry]>,
+    /// 合并后的 `LocalVariableTable`；未用 `-g` 编译时为空。
+    pub local_variable_table: MSBox<[LocalVariable]>,
+    /// `StackMapTable` 的原始帧，由校验器逐帧展开；没有该属性时为空。
+    pub stack_map_table: Box<[StackMapFrameInfo]>,
+}
+
+fn build_local_variables(
+    info: &CodeAttrInfo,
+    cp: &[OnceCell<CPEntry>],
+) -> ResolveResult<Vec<LocalVariable>> {
+    let tables = |want_types: bool| {
+        info.attrs
+            .iter()
+            .filter_map(move |attr| match attr {
+                AttrInfo::LocalVariableTable(x) if !want_types => Some(x),
+                AttrInfo::LocalVariableTypeTable(x) if want_types => Some(x),
+                _ => None,
+            })
+            .flatten()
+    };
+
+    let mut variables = Vec::new();
+    for entry in tables(false) {
+        let variable = LocalVariable {
+            start_pc: entry.start_pc,
+            length: entry.length,
+            name: get_utf8(cp, entry.name_idx as usize)?,
+            descriptor: get_utf8(cp, entry.desc_idx as usize)?,
+            signature: None,
+            index: entry.index,
+        };
+
+        let end = entry.start_pc as usize + entry.length as usize;
+        if end > info.code.len() || entry.index as usize + variable.slot_count() > info.max_locals as usize {
+            return Err(ResolveError::InvalidLocalVariableTable);
+        }
+        variables.push(variable);
+    }
+
+    // JVMS 4.7.14：类型表条目与 (start_pc, length, index) 相同的变量表条目对应。
+    for entry in tables(true) {
+        let variable = variables
+            .iter_mut()
+            .find(|x| (x.start_pc, x.length, x.index) == (entry.start_pc, entry.length, entry.index))
+            .ok_or(ResolveError::InvalidLocalVariableTable)?;
+        variable.signature = Some(get_utf8(cp, entry.desc_idx as usize)?);
+    }
+
+    variables.sort_by_key(|x| (x.index, x.start_pc));
+    Ok(variables)
 }
 
 impl Code {
@@ -90,13 +179,76 @@ impl Code {
             MSBox::from_raw(uninit.assume_init_mut())
         };
 
+        let mut lines: Vec<LineNumberTableEntry> = info
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                AttrInfo::LineNumberTable(table) => Some(table),
+                _ => None,
+            })
+            .flatten()
+            .map(|x| LineNumberTableEntry {
+                start_pc: x.start_pc,
+                line_number: x.line_number,
+            })
+            .collect();
+        if lines.iter().any(|x| x.start_pc as usize >= info.code.len()) {
+            return Err(ResolveError::InvalidLineNumberTable);
+        }
+        lines.sort_by_key(|x| x.start_pc);
+
+        let line_number_table = unsafe {
+            let uninit = msa.calloc(lines.len());
+            let slice = uninit.write_copy_of_slice(&lines);
+
+            MSBox::from_raw(slice)
+        };
+
+        let local_variable_table = unsafe {
+            let variables = build_local_variables(info, cp)?;
+            let uninit = msa.calloc(variables.len());
+
+            for (i, v) in variables.into_iter().enumerate() {
+                uninit[i].write(v);
+            }
+
+            MSBox::from_raw(uninit.assume_init_mut())
+        };
+
+        let stack_map_table = info
+            .attrs
+            .iter()
+            .find_map(|attr| match attr {
+                AttrInfo::StackMapTable(frames) => Some(frames.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+            .into();
+
         Ok(Self {
             max_stack: info.max_stack as usize,
             max_locals: info.max_locals as usize,
             bytecodes: code,
             exception_table: et,
+            line_number_table,
+            local_variable_table,
+            stack_map_table,
         })
     }
+
+    /// Source line of the instruction at `bci`: the entry with the greatest
+    /// `start_pc` not after it.  `None` without a `LineNumberTable`.
+    pub fn line_number(&self, bci: usize) -> Option<u16> {
+        let table = &self.line_number_table[..];
+        let after = table.partition_point(|x| x.start_pc as usize <= bci);
+
+        after.checked_sub(1).map(|i| table[i].line_number)
+    }
+
+    /// Variables in scope at `pc`, ordered by local index.
+    pub fn live_locals(&self, pc: usize) -> impl Iterator<Item = &LocalVariable> {
+        self.local_variable_table.iter().filter(move |x| x.is_live_at(pc))
+    }
 }
 
 #[derive(Debug)]
@@ -110,7 +262,7 @@ pub enum ConstantValue {
 
 impl ConstantValue {
     pub fn build(cp_idx: usize, cp: &[OnceCell<CPEntry>]) -> ResolveResult<Self> {
-        match cp[cp_idx].get() {
+        match cp.get(cp_idx).and_then(OnceCell::get) {
             Some(CPEntry::Integer(value)) => Ok(Self::Integer(*value)),
             Some(CPEntry::Float(value)) => Ok(Self::Float(*value)),
             Some(CPEntry::Long(value)) => Ok(Self::Long(*value)),
@@ -121,3 +273,31 @@ impl ConstantValue {
         }
     }
 }
+
+/// An entry of the `BootstrapMethods` attribute (JVMS 4.7.23).
+#[derive(Debug)]
+pub struct BootstrapMethod {
+    pub method_handle: MSRef<MethodHandleCPEntry>,
+    /// Static arguments, in the order they are passed after the name and type.
+    pub arguments: Box<[Loadable]>,
+}
+
+impl BootstrapMethod {
+    pub fn build(info: &BootstrapMethodInfo, cp: &[OnceCell<CPEntry>]) -> ResolveResult<Self> {
+        let method_handle = match cp_slice_get(cp, info.bootstrap_method_ref as usize) {
+            Some(CPEntry::MethodHandle(entry)) => unsafe { MSRef::from_raw(entry.into()) },
+            _ => return Err(ResolveError::MismatchCPType),
+        };
+
+        let arguments = info
+            .bootstrap_arguments
+            .iter()
+            .map(|x| Loadable::build(*x as usize, cp))
+            .collect::<ResolveResult<_>>()?;
+
+        Ok(Self {
+            method_handle,
+            arguments,
+        })
+    }
+}
@@ -1,16 +1,20 @@
 use std::{cell::OnceCell, ptr::NonNull, sync::OnceLock};
 
+use dashmap::DashMap;
+
 use crate::{
-    class_loader::{bootstrap_cld::BootstrapCLD, cld::ClassLoaderData, ms_api::MSRef},
+    class_loader::{bootstrap_cld::BootstrapCLD, cld::ClassLoaderData, load_error::LoadError, ms_api::MSRef},
     class_parser::cp_info::ConstantPoolInfo,
-    gc_bindings::oop_handle::{KLASS_OOP_STORAGE_ID, OOPHandle},
+    engine::{exec_error::JavaExceptionKind, outcome::RetValue},
+    gc_bindings::oop_handle::{KLASS_OOP_STORAGE_ID, NObjPtr, OOPHandle},
     oops::{
-        desc::MethodDesc,
+        acc_flags::AccFlags,
+        desc::{FieldDesc, MethodDesc},
         field::Field,
         klass::Klass,
         method::Method,
         normal_klass::NormalKlass,
-        oops_errors::{ResolveError, ResolveResult},
+        oops_errors::{MemberName, ResolveError, ResolveResult},
         symbol_table::{SymbolHandle, SymbolTable},
     },
 };
@@ -22,6 +26,28 @@ pub struct SymbolicMemberRef {
     desc: SymbolHandle,
 }
 
+impl SymbolicMemberRef {
+    fn member_name(&self) -> MemberName {
+        MemberName {
+            class: self.class.name().utf8().into(),
+            name: self.name.utf8().into(),
+            desc: self.desc.utf8().into(),
+        }
+    }
+}
+
+fn illegal_access(referrer: &NormalKlass, holder: &NormalKlass, name: &SymbolHandle, desc: &SymbolHandle) -> ResolveError {
+    let target = MemberName {
+        class: holder.name().utf8().into(),
+        name: name.utf8().into(),
+        desc: desc.utf8().into(),
+    };
+    ResolveError::IllegalAccess {
+        referrer: referrer.name().utf8().into(),
+        target: target.to_string(),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ResolvedFieldRef {
     pub holder: MSRef<NormalKlass>,
@@ -36,29 +62,60 @@ pub struct ResolvedMethodRef {
 
 #[derive(Clone, Debug)]
 pub struct ResolvedInterfaceMethodRef {
+    /// The interface the reference names; `invokespecial` selects from it.
+    pub interface: MSRef<NormalKlass>,
     pub holder: MSRef<NormalKlass>,
     pub method: MSRef<Method>,
 }
 
+/// JVMS 5.4.3.3 step 3, also step 5 of 5.4.3.4: the one non-abstract
+/// maximally-specific superinterface method, or else any of them.
+fn lookup_superinterfaces(
+    klass: &NormalKlass,
+    name: &SymbolHandle,
+    desc: &SymbolHandle,
+) -> Option<(MSRef<NormalKlass>, MSRef<Method>)> {
+    let candidates = klass.maximally_specific_methods(name, desc);
+    let mut concrete = candidates
+        .iter()
+        .filter(|(_, method)| !method.acc_flags.contains(AccFlags::ACC_ABSTRACT));
+
+    match (concrete.next(), concrete.next()) {
+        (Some(only), None) => Some(only.clone()),
+        _ => candidates.into_iter().next(),
+    }
+}
+
 #[derive(Debug)]
 pub struct CPRefEntry<R> {
     symbolic: SymbolicMemberRef,
     resolved: OnceLock<ResolveResult<R>>,
 }
 
+/// Entry `idx` of the pool being built; the index comes from the class
+/// file, so it may be out of range.
+fn cell_at(cp: &[OnceCell<CPEntry>], idx: usize) -> ResolveResult<&OnceCell<CPEntry>> {
+    cp.get(idx).ok_or(ResolveError::InvalidCPIndex)
+}
+
+fn info_at(parsed_cp: &[ConstantPoolInfo], idx: usize) -> ResolveResult<&ConstantPoolInfo> {
+    parsed_cp.get(idx).ok_or(ResolveError::InvalidCPIndex)
+}
+
 fn resolve_name_and_type(
     idx: usize,
     cp: &[OnceCell<CPEntry>],
     parsed_cp: &[ConstantPoolInfo],
 ) -> ResolveResult<(SymbolHandle, SymbolHandle)> {
-    match cp[idx].get() {
+    let cell = cell_at(cp, idx)?;
+    match cell.get() {
         Some(x) => match x {
             CPEntry::NameAndType { name, desc } => Ok((name.clone(), desc.clone())),
 
             _ => Err(ResolveError::MismatchCPType),
         },
 
-        None => match &parsed_cp[idx] {
+        None => match info_at(parsed_cp, idx)? {
             ConstantPoolInfo::NameAndTypeInfo {
                 name_index,
                 desc_index,
@@ -66,12 +123,10 @@ fn resolve_name_and_type(
                 let name = resolve_symbol(*name_index as usize, cp, parsed_cp)?;
                 let desc = resolve_symbol(*desc_index as usize, cp, parsed_cp)?;
 
-                cp[idx]
-                    .set(CPEntry::NameAndType {
-                        name: name.clone(),
-                        desc: desc.clone(),
-                    })
-                    .unwrap();
+                cell.get_or_init(|| CPEntry::NameAndType {
+                    name: name.clone(),
+                    desc: desc.clone(),
+                });
 
                 Ok((name, desc))
             }
@@ -82,6 +137,19 @@ fn resolve_name_and_type(
 }
 
 impl<R> CPRefEntry<R> {
+    /// Binary name of the class or interface named by `class_index`.
+    pub fn class_name(&self) -> &SymbolHandle {
+        self.symbolic.class.name()
+    }
+
+    pub fn name(&self) -> &SymbolHandle {
+        &self.symbolic.name
+    }
+
+    pub fn desc(&self) -> &SymbolHandle {
+        &self.symbolic.desc
+    }
+
     fn build(
         info: &ConstantPoolInfo,
         cp: &[OnceCell<CPEntry>],
@@ -136,7 +204,7 @@ impl<R> CPRefEntry<R> {
                 })
             }
 
-            _ => unreachable!(),
+            _ => Err(ResolveError::MismatchCPType),
         }
     }
 }
@@ -149,17 +217,22 @@ impl CPRefEntry<ResolvedFieldRef> {
     }
 
     fn resolve_slow_path(&self, referrer: &NormalKlass) -> ResolveResult<ResolvedFieldRef> {
-        let target = self.symbolic.class.get(referrer.cld())?;
+        let target = self.symbolic.class.resolve(referrer)?;
         let target = target.as_normal_ref().ok_or(ResolveError::NotANormal)?;
         let mut visited = Vec::new();
 
-        Self::lookup_field(
-            target,
+        let resolved = Self::lookup_field(
+            target.clone(),
             &self.symbolic.name,
             &self.symbolic.desc,
             &mut visited,
         )
-        .ok_or(ResolveError::FieldNotFound)
+        .ok_or_else(|| ResolveError::FieldNotFound(self.symbolic.member_name()))?;
+
+        if !referrer.can_access_member(&resolved.holder, &resolved.field.acc_flags, &target)? {
+            return Err(illegal_access(referrer, &resolved.holder, &self.symbolic.name, &self.symbolic.desc));
+        }
+        Ok(resolved)
     }
 
     /// JVMS 5.4.3.2 field lookup order: the current type, its direct
@@ -203,12 +276,20 @@ impl CPRefEntry<ResolvedMethodRef> {
     }
 
     fn resolve_slow_path(&self, referrer: &NormalKlass) -> ResolveResult<ResolvedMethodRef> {
-        let target = self.symbolic.class.get(referrer.cld())?;
+        let target = self.symbolic.class.resolve(referrer)?;
+        let class = target.as_normal_ref().ok_or(ResolveError::NotANormal)?;
+        let resolved = self.lookup_method(class.clone())?;
 
-        let mut current = target.as_normal_ref().ok_or(ResolveError::NotANormal)?;
+        if !referrer.can_access_member(&resolved.holder, &resolved.method.acc_flags, &class)? {
+            return Err(illegal_access(referrer, &resolved.holder, &self.symbolic.name, &self.symbolic.desc));
+        }
+        Ok(resolved)
+    }
 
-        if current.is_interface() {
-            return Err(ResolveError::WrongRefType);
+    /// JVMS 5.4.3.3 method lookup in the class `class`.
+    fn lookup_method(&self, class: MSRef<NormalKlass>) -> ResolveResult<ResolvedMethodRef> {
+        if class.is_interface() {
+            return Err(ResolveError::WrongRefType(class.name().utf8().into()));
         }
 
         if self.symbolic.name.utf8() == "<clinit>" {
@@ -216,17 +297,27 @@ impl CPRefEntry<ResolvedMethodRef> {
         }
 
         if self.symbolic.name.utf8() == "<init>" {
-            let method = current
+            let method = class
                 .find_declared_method_symbol(&self.symbolic.name, &self.symbolic.desc)
-                .ok_or(ResolveError::MethodNotFound)?;
+                .ok_or_else(|| ResolveError::MethodNotFound(self.symbolic.member_name()))?;
 
             return Ok(ResolvedMethodRef {
-                holder: current,
+                holder: class,
                 method,
             });
         }
 
-        loop {
+        let mut next = Some(class.clone());
+        while let Some(current) = next {
+            // JVMS 5.4.3.3: a signature polymorphic method matches whatever
+            // descriptor the call site uses.
+            if let Some(method) = current.signature_polymorphic_method(&self.symbolic.name) {
+                return Ok(ResolvedMethodRef {
+                    holder: current,
+                    method,
+                });
+            }
+
             if let Some(method) =
                 current.find_declared_method_symbol(&self.symbolic.name, &self.symbolic.desc)
             {
@@ -236,56 +327,142 @@ impl CPRefEntry<ResolvedMethodRef> {
                 });
             }
 
-            current = current
-                .super_klass_ref()
-                .ok_or(ResolveError::MethodNotFound)?;
+            next = current.super_klass_ref();
         }
+
+        let (holder, method) = lookup_superinterfaces(&class, &self.symbolic.name, &self.symbolic.desc)
+            .ok_or_else(|| ResolveError::MethodNotFound(self.symbolic.member_name()))?;
+        Ok(ResolvedMethodRef { holder, method })
     }
 }
 
-impl CPRefEntry<ResolvedInterfaceMethodRef> {}
+impl CPRefEntry<ResolvedInterfaceMethodRef> {
+    pub(super) fn resolve(&self, referrer: &NormalKlass) -> ResolveResult<ResolvedInterfaceMethodRef> {
+        self.resolved
+            .get_or_init(|| self.resolve_slow_path(referrer))
+            .clone()
+    }
+
+    fn resolve_slow_path(&self, referrer: &NormalKlass) -> ResolveResult<ResolvedInterfaceMethodRef> {
+        let target = self.symbolic.class.resolve(referrer)?;
+        let interface = target.as_normal_ref().ok_or(ResolveError::NotANormal)?;
+        let resolved = self.lookup_interface_method(interface)?;
+
+        if !referrer.can_access_member(&resolved.holder, &resolved.method.acc_flags, &resolved.interface)? {
+            return Err(illegal_access(referrer, &resolved.holder, &self.symbolic.name, &self.symbolic.desc));
+        }
+        Ok(resolved)
+    }
+
+    /// JVMS 5.4.3.4: the interface itself, then the public instance methods
+    /// of `Object`, then its superinterfaces.
+    fn lookup_interface_method(&self, interface: MSRef<NormalKlass>) -> ResolveResult<ResolvedInterfaceMethodRef> {
+        if !interface.is_interface() {
+            return Err(ResolveError::WrongRefType(interface.name().utf8().into()));
+        }
+
+        let name = &self.symbolic.name;
+        let desc = &self.symbolic.desc;
+        if name.utf8() == "<init>" || name.utf8() == "<clinit>" {
+            return Err(ResolveError::IllegalMethodName(name.utf8().into()));
+        }
+
+        if let Some(method) = interface.find_declared_method_symbol(name, desc) {
+            return Ok(ResolvedInterfaceMethodRef {
+                holder: interface.clone(),
+                interface,
+                method,
+            });
+        }
+
+        if let Some(object) = interface.super_klass_ref()
+            && let Some(method) = object.find_declared_method_symbol(name, desc)
+            && method.acc_flags.contains(AccFlags::ACC_PUBLIC)
+            && !method.acc_flags.contains(AccFlags::ACC_STATIC)
+        {
+            return Ok(ResolvedInterfaceMethodRef {
+                interface,
+                holder: object,
+                method,
+            });
+        }
+
+        let (holder, method) = lookup_superinterfaces(&interface, name, desc)
+            .ok_or_else(|| ResolveError::MethodNotFound(self.symbolic.member_name()))?;
+        Ok(ResolvedInterfaceMethodRef {
+            interface,
+            holder,
+            method,
+        })
+    }
+}
 
 #[derive(Debug)]
 pub struct ClassCPEntry {
     name: SymbolHandle,
-    resolved: OnceLock<MSRef<Klass>>,
+    /// JVMS 5.4.3: once resolution has failed, every later attempt fails
+    /// with the same error.
+    resolved: OnceLock<ResolveResult<MSRef<Klass>>>,
 }
 
 impl ClassCPEntry {
+    pub fn name(&self) -> &SymbolHandle {
+        &self.name
+    }
+
+    /// The resolved class, if resolution has already happened.
+    pub fn resolved(&self) -> Option<MSRef<Klass>> {
+        self.resolved.get()?.as_ref().ok().cloned()
+    }
+
     pub fn set(&self, klass: MSRef<Klass>) {
-        if let Err(candidate) = self.resolved.set(klass) {
+        if let Err(candidate) = self.resolved.set(Ok(klass)) {
             let existing = self
                 .resolved
                 .get()
                 .expect("ClassCPEntry initialized concurrently but value is missing");
 
             assert!(
-                existing.equals(&candidate),
+                matches!((existing, &candidate), (Ok(x), Ok(y)) if x.equals(y)),
                 "ClassCPEntry resolved to different Klass instances"
             );
         }
     }
 
+    /// Resolve the class on behalf of `referrer`, the class whose constant
+    /// pool holds this entry, checking that `referrer` may access it.
+    pub fn resolve(&self, referrer: &NormalKlass) -> ResolveResult<MSRef<Klass>> {
+        let klass = self.get(referrer.cld())?;
+
+        if !referrer.can_access_class(&klass) {
+            return Err(ResolveError::IllegalAccess {
+                referrer: referrer.name().utf8().into(),
+                target: klass.name().utf8().into(),
+            });
+        }
+        Ok(klass)
+    }
+
     pub fn get(&self, cld: Option<&ClassLoaderData>) -> ResolveResult<MSRef<Klass>> {
         if let Some(x) = self.resolved.get() {
-            return Ok(x.clone());
+            return x.clone();
         }
 
         let loaded = match cld {
             Some(x) => x.load_class(self.name.utf8()),
             None => BootstrapCLD::find_class(self.name.utf8()),
         }
-        .map_err(|_| ResolveError::ClassNotFound)?;
-
-        if self.resolved.set(loaded.clone()).is_ok() {
-            return Ok(loaded);
-        }
+        .map_err(|error| match error {
+            LoadError::NotFound(name) => ResolveError::ClassNotFound(name),
+            other => ResolveError::ClassLoad(Box::new(other)),
+        });
 
-        Ok(self
-            .resolved
+        // Whichever outcome was published first wins a race.
+        let _ = self.resolved.set(loaded);
+        self.resolved
             .get()
             .expect("resolved class missing after race")
-            .clone())
+            .clone()
     }
 }
 
@@ -296,11 +473,307 @@ pub struct StringCPEntry {
 }
 
 impl StringCPEntry {
+    /// The string's contents as found in the class file.
+    pub fn raw(&self) -> &SymbolHandle {
+        &self.raw
+    }
+
     pub fn get(&self) -> &OOPHandle {
         unimplemented!()
     }
 }
 
+/// `reference_kind` of a `CONSTANT_MethodHandle` (JVMS 5.4.3.5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefKind {
+    GetField = 1,
+    GetStatic = 2,
+    PutField = 3,
+    PutStatic = 4,
+    InvokeVirtual = 5,
+    InvokeStatic = 6,
+    InvokeSpecial = 7,
+    NewInvokeSpecial = 8,
+    InvokeInterface = 9,
+}
+
+impl RefKind {
+    pub fn from_u8(kind: u8) -> ResolveResult<Self> {
+        Ok(match kind {
+            1 => Self::GetField,
+            2 => Self::GetStatic,
+            3 => Self::PutField,
+            4 => Self::PutStatic,
+            5 => Self::InvokeVirtual,
+            6 => Self::InvokeStatic,
+            7 => Self::InvokeSpecial,
+            8 => Self::NewInvokeSpecial,
+            9 => Self::InvokeInterface,
+            _ => return Err(ResolveError::UnknownRefKind(kind)),
+        })
+    }
+
+    pub fn is_field_access(self) -> bool {
+        (self as u8) <= Self::PutStatic as u8
+    }
+}
+
+/// The member a method handle refers to; the entry itself lives in its own
+/// constant-pool slot.
+#[derive(Debug)]
+pub enum MethodHandleRef {
+    Field(MSRef<CPRefEntry<ResolvedFieldRef>>),
+    Method(MSRef<CPRefEntry<ResolvedMethodRef>>),
+    InterfaceMethod(MSRef<CPRefEntry<ResolvedInterfaceMethodRef>>),
+}
+
+#[derive(Debug)]
+pub struct MethodHandleCPEntry {
+    pub kind: RefKind,
+    pub reference: MethodHandleRef,
+
+    /// The `MethodHandle` object, once the constant has been resolved.
+    handle: OnceLock<OOPHandle>,
+}
+
+impl MethodHandleCPEntry {
+    pub fn class_name(&self) -> &SymbolHandle {
+        match &self.reference {
+            MethodHandleRef::Field(x) => x.class_name(),
+            MethodHandleRef::Method(x) => x.class_name(),
+            MethodHandleRef::InterfaceMethod(x) => x.class_name(),
+        }
+    }
+
+    pub fn name(&self) -> &SymbolHandle {
+        match &self.reference {
+            MethodHandleRef::Field(x) => x.name(),
+            MethodHandleRef::Method(x) => x.name(),
+            MethodHandleRef::InterfaceMethod(x) => x.name(),
+        }
+    }
+
+    pub fn desc(&self) -> &SymbolHandle {
+        match &self.reference {
+            MethodHandleRef::Field(x) => x.desc(),
+            MethodHandleRef::Method(x) => x.desc(),
+            MethodHandleRef::InterfaceMethod(x) => x.desc(),
+        }
+    }
+
+    /// The method type of the handle, per JVMS 5.4.3.5: field getters and
+    /// setters take and return the field, instance methods take the receiver
+    /// first and constructors return the new object.
+    pub fn type_descriptor(&self) -> String {
+        let class_name = self.class_name().utf8();
+        let class = if class_name.starts_with('[') {
+            class_name.to_string()
+        } else {
+            format!("L{class_name};")
+        };
+        let desc = self.desc().utf8();
+
+        match self.kind {
+            RefKind::GetField => format!("({class}){desc}"),
+            RefKind::GetStatic => format!("(){desc}"),
+            RefKind::PutField => format!("({class}{desc})V"),
+            RefKind::PutStatic => format!("({desc})V"),
+            RefKind::InvokeStatic => desc.to_string(),
+            RefKind::InvokeVirtual | RefKind::InvokeSpecial | RefKind::InvokeInterface => {
+                format!("({class}{}", &desc[1..])
+            }
+            RefKind::NewInvokeSpecial => {
+                let params_end = desc.find(')').map_or(desc.len(), |x| x + 1);
+                format!("{}{class}", &desc[..params_end])
+            }
+        }
+    }
+
+    /// The `MethodHandle` this constant resolved to, if it has been.
+    pub fn handle(&self) -> Option<NObjPtr> {
+        self.handle.get().map(OOPHandle::get)
+    }
+
+    /// Record the resolved handle.  Like any symbolic reference, the constant
+    /// resolves to the same object every time, so a racing thread's object
+    /// is discarded in favor of the first.
+    pub(crate) fn set_handle(&self, handle: NObjPtr) -> NObjPtr {
+        self.handle.get_or_init(|| {
+            let root = OOPHandle::new(KLASS_OOP_STORAGE_ID);
+            root.replace(handle);
+            root
+        });
+
+        self.handle().expect("handle set above")
+    }
+
+    /// JVMS 4.4.8: which member kinds each `reference_kind` may name, and the
+    /// restrictions on `<init>`/`<clinit>`.
+    fn build(
+        kind: u8,
+        reference_index: usize,
+        cp: &[OnceCell<CPEntry>],
+        parsed_cp: &[ConstantPoolInfo],
+    ) -> ResolveResult<Self> {
+        let kind = RefKind::from_u8(kind)?;
+
+        // 只有成员引用才能递归构建，避免 MethodHandle 互相引用时无限递归。
+        match info_at(parsed_cp, reference_index)? {
+            ConstantPoolInfo::FieldrefInfo { .. }
+            | ConstantPoolInfo::MethodrefInfo { .. }
+            | ConstantPoolInfo::InterfaceMethodrefInfo { .. } => {}
+            _ => return Err(ResolveError::MismatchCPType),
+        }
+        // 被引用的条目可能排在后面，先把它建好再取地址。
+        let cell = cell_at(cp, reference_index)?;
+        if cell.get().is_none() {
+            CPEntry::from(reference_index, cp, parsed_cp)?;
+        }
+
+        use RefKind::*;
+        let reference = match (kind, cell.get()) {
+            (GetField | GetStatic | PutField | PutStatic, Some(CPEntry::FieldRef(x))) => unsafe {
+                MethodHandleRef::Field(MSRef::from_raw(NonNull::from(x)))
+            },
+            (
+                InvokeVirtual | InvokeStatic | InvokeSpecial | NewInvokeSpecial,
+                Some(CPEntry::MethodRef(x)),
+            ) => unsafe { MethodHandleRef::Method(MSRef::from_raw(NonNull::from(x))) },
+            (InvokeStatic | InvokeSpecial | InvokeInterface, Some(CPEntry::InterfaceMethodRef(x))) => unsafe {
+                MethodHandleRef::InterfaceMethod(MSRef::from_raw(NonNull::from(x)))
+            },
+            _ => return Err(ResolveError::MismatchCPType),
+        };
+
+        let entry = Self {
+            kind,
+            reference,
+            handle: OnceLock::new(),
+        };
+
+        let name = entry.name().utf8();
+        match kind {
+            NewInvokeSpecial if name != "<init>" => {
+                return Err(ResolveError::IllegalMethodName(name.into()));
+            }
+            InvokeVirtual | InvokeStatic | InvokeSpecial | InvokeInterface
+                if name == "<init>" || name == "<clinit>" =>
+            {
+                return Err(ResolveError::IllegalMethodName(name.into()));
+            }
+            _ => {}
+        }
+
+        Ok(entry)
+    }
+}
+
+/// One `invokedynamic` instruction: the method holding it and its bci.
+/// JVMS 6.5 links each instruction on its own, even when several share a
+/// `CONSTANT_InvokeDynamic` entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallSiteKey {
+    /// Address of the `Method`, which lives as long as its class.
+    method: usize,
+    bci: usize,
+}
+
+impl CallSiteKey {
+    pub fn new(method: &Method, bci: usize) -> Self {
+        Self {
+            method: method as *const Method as usize,
+            bci,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvokeDynamicCPEntry {
+    /// Index into the class's `BootstrapMethods` attribute.
+    pub bootstrap_method_attr_index: u16,
+    pub name: SymbolHandle,
+    pub desc: MethodDesc,
+
+    /// For each instruction using this entry, the `CallSite` the bootstrap
+    /// method returned or the error linking failed with.  JVMS 6.5
+    /// `invokedynamic`: either outcome is final.
+    call_sites: DashMap<CallSiteKey, Result<OOPHandle, JavaExceptionKind>>,
+}
+
+impl InvokeDynamicCPEntry {
+    /// The `CallSite` object linked for `site`, if linkage has been attempted.
+    pub fn call_site(&self, site: CallSiteKey) -> Option<Result<NObjPtr, JavaExceptionKind>> {
+        self.call_sites
+            .get(&site)
+            .map(|x| x.as_ref().map(OOPHandle::get).map_err(|kind| *kind))
+    }
+
+    /// Record the outcome of running the bootstrap method for `site`.  When
+    /// several threads link the same instruction, the first one to finish
+    /// wins and the others observe its result.
+    pub(crate) fn link(
+        &self,
+        site: CallSiteKey,
+        result: Result<NObjPtr, JavaExceptionKind>,
+    ) -> Result<NObjPtr, JavaExceptionKind> {
+        let linked = self.call_sites.entry(site).or_insert_with(|| {
+            result.map(|call_site| {
+                let handle = OOPHandle::new(KLASS_OOP_STORAGE_ID);
+                handle.replace(call_site);
+                handle
+            })
+        });
+
+        linked.as_ref().map(OOPHandle::get).map_err(|kind| *kind)
+    }
+}
+
+#[derive(Debug)]
+enum DynamicValue {
+    Primitive(RetValue),
+    Ref(OOPHandle),
+}
+
+#[derive(Debug)]
+pub struct DynamicCPEntry {
+    /// Index into the class's `BootstrapMethods` attribute.
+    pub bootstrap_method_attr_index: u16,
+    pub name: SymbolHandle,
+    pub desc: FieldDesc,
+
+    /// The value the bootstrap method computed, or the error resolution
+    /// failed with.  JVMS 5.4.3: either outcome is final.
+    value: OnceLock<Result<DynamicValue, JavaExceptionKind>>,
+}
+
+impl DynamicCPEntry {
+    /// The constant's value, if resolution has been attempted.
+    pub fn value(&self) -> Option<Result<RetValue, JavaExceptionKind>> {
+        self.value.get().map(|x| match x {
+            Ok(DynamicValue::Primitive(value)) => Ok(*value),
+            Ok(DynamicValue::Ref(handle)) => Ok(RetValue::Ref(handle.get())),
+            Err(kind) => Err(*kind),
+        })
+    }
+
+    /// Record the outcome of running the bootstrap method; as with call
+    /// sites, the first thread to finish wins.
+    pub(crate) fn link(&self, result: Result<RetValue, JavaExceptionKind>) -> Result<RetValue, JavaExceptionKind> {
+        self.value.get_or_init(|| {
+            result.map(|value| match value {
+                RetValue::Ref(obj) => {
+                    let handle = OOPHandle::new(KLASS_OOP_STORAGE_ID);
+                    handle.replace(obj);
+                    DynamicValue::Ref(handle)
+                }
+                value => DynamicValue::Primitive(value),
+            })
+        });
+
+        self.value().expect("constant linked above")
+    }
+}
+
 #[derive(Debug)]
 pub enum CPEntry {
     Class(ClassCPEntry),
@@ -328,8 +801,18 @@ pub enum CPEntry {
 
     Utf8(SymbolHandle),
 
-    // Ignore for now.
-    InvokeDynamic {},
+    MethodHandle(MethodHandleCPEntry),
+
+    MethodType(MethodDesc),
+
+    Dynamic(DynamicCPEntry),
+
+    InvokeDynamic(InvokeDynamicCPEntry),
+
+    /// Module and package names, in `module-info` classes only.
+    Module(SymbolHandle),
+
+    Package(SymbolHandle),
 }
 
 fn resolve_class_symbol(
@@ -337,22 +820,23 @@ fn resolve_class_symbol(
     cp: &[OnceCell<CPEntry>],
     parsed_cp: &[ConstantPoolInfo],
 ) -> ResolveResult<SymbolHandle> {
-    match cp[idx].get() {
+    let cell = cell_at(cp, idx)?;
+    match cell.get() {
         Some(x) => match x {
             CPEntry::Class(entry) => Ok(entry.name.clone()),
             _ => Err(ResolveError::MismatchCPType),
         },
 
-        None => match &parsed_cp[idx] {
+        None => match info_at(parsed_cp, idx)? {
             ConstantPoolInfo::ClassInfo { name_index } => {
                 let name = resolve_symbol(*name_index as usize, cp, parsed_cp)?;
 
-                cp[idx]
-                    .set(CPEntry::Class(ClassCPEntry {
+                cell.get_or_init(|| {
+                    CPEntry::Class(ClassCPEntry {
                         name: name.clone(),
                         resolved: OnceLock::new(),
-                    }))
-                    .unwrap();
+                    })
+                });
 
                 Ok(name)
             }
@@ -369,7 +853,7 @@ fn resolve_class_entry(
 ) -> ResolveResult<MSRef<ClassCPEntry>> {
     resolve_class_symbol(index, cp, parsed_cp)?;
 
-    match cp[index].get() {
+    match cell_at(cp, index)?.get() {
         Some(CPEntry::Class(entry)) => unsafe { Ok(MSRef::from_raw(NonNull::from(entry))) },
         _ => Err(ResolveError::MismatchCPType),
     }
@@ -380,16 +864,20 @@ fn resolve_symbol(
     cp: &[OnceCell<CPEntry>],
     parsed_cp: &[ConstantPoolInfo],
 ) -> ResolveResult<SymbolHandle> {
-    match cp[idx].get() {
+    let cell = cell_at(cp, idx)?;
+    match cell.get() {
         Some(x) => match x {
             CPEntry::Utf8(handle) => Ok(handle.clone()),
             _ => Err(ResolveError::MismatchCPType),
         },
 
-        None => match &parsed_cp[idx] {
-            ConstantPoolInfo::Utf8Info { utf8 } => {
-                let handle = SymbolTable::intern(utf8.as_str());
-                cp[idx].set(CPEntry::Utf8(handle.clone())).unwrap();
+        None => match info_at(parsed_cp, idx)? {
+            ConstantPoolInfo::Utf8Info { utf8, wtf8 } => {
+                let handle = match wtf8 {
+                    Some(wtf8) => SymbolTable::intern_wtf8(wtf8),
+                    None => SymbolTable::intern(utf8.as_str()),
+                };
+                cell.get_or_init(|| CPEntry::Utf8(handle.clone()));
 
                 Ok(handle)
             }
@@ -405,7 +893,7 @@ impl CPEntry {
         cp: &[OnceCell<Self>],
         parsed_cp: &[ConstantPoolInfo],
     ) -> ResolveResult<()> {
-        let info = &parsed_cp[idx];
+        let info = info_at(parsed_cp, idx)?;
 
         let res = match info {
             ConstantPoolInfo::ClassInfo { name_index } => {
@@ -454,17 +942,68 @@ impl CPEntry {
                 Self::Utf8(handle)
             }
 
+            ConstantPoolInfo::MethodHandleInfo {
+                reference_kind,
+                reference_index,
+            } => Self::MethodHandle(MethodHandleCPEntry::build(
+                *reference_kind,
+                *reference_index as usize,
+                cp,
+                parsed_cp,
+            )?),
+
+            ConstantPoolInfo::MethodTypeInfo { descriptor_index } => {
+                let desc = resolve_symbol(*descriptor_index as usize, cp, parsed_cp)?;
+                Self::MethodType(MethodDesc::from(desc.utf8())?)
+            }
+
+            ConstantPoolInfo::DynamicInfo {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                let (name, desc) =
+                    resolve_name_and_type(*name_and_type_index as usize, cp, parsed_cp)?;
+                Self::Dynamic(DynamicCPEntry {
+                    bootstrap_method_attr_index: *bootstrap_method_attr_index,
+                    name,
+                    desc: FieldDesc::from(desc.utf8())?,
+                    value: OnceLock::new(),
+                })
+            }
+
+            ConstantPoolInfo::InvokeDynamicInfo {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                let (name, desc) =
+                    resolve_name_and_type(*name_and_type_index as usize, cp, parsed_cp)?;
+                Self::InvokeDynamic(InvokeDynamicCPEntry {
+                    bootstrap_method_attr_index: *bootstrap_method_attr_index,
+                    name,
+                    desc: MethodDesc::from(desc.utf8())?,
+                    call_sites: DashMap::new(),
+                })
+            }
+
+            ConstantPoolInfo::ModuleInfo { name_index } => {
+                Self::Module(resolve_symbol(*name_index as usize, cp, parsed_cp)?)
+            }
+
+            ConstantPoolInfo::PackageInfo { name_index } => {
+                Self::Package(resolve_symbol(*name_index as usize, cp, parsed_cp)?)
+            }
+
             ConstantPoolInfo::Unusable => return Ok(()),
         };
 
-        cp[idx].get_or_init(|| res);
+        cell_at(cp, idx)?.get_or_init(|| res);
 
         Ok(())
     }
 }
 
 pub fn get_utf8(cp: &[OnceCell<CPEntry>], idx: usize) -> ResolveResult<SymbolHandle> {
-    match cp[idx].get() {
+    match cell_at(cp, idx)?.get() {
         Some(CPEntry::Utf8(handle)) => Ok(handle.clone()),
         _ => Err(ResolveError::MismatchCPType),
     }
@@ -479,4 +1018,28 @@ pub enum Loadable {
     Class(MSRef<ClassCPEntry>),
     StringLoadable(MSRef<StringCPEntry>),
     MethodType(MethodDesc),
+    MethodHandle(MSRef<MethodHandleCPEntry>),
+    Dynamic(MSRef<DynamicCPEntry>),
+}
+
+impl Loadable {
+    /// JVMS 4.4: the constants `ldc` and bootstrap arguments may refer to.
+    pub fn build(idx: usize, cp: &[OnceCell<CPEntry>]) -> ResolveResult<Self> {
+        let entry = cp.get(idx).and_then(OnceCell::get).ok_or(ResolveError::InvalidCPIndex)?;
+
+        let loadable = match entry {
+            CPEntry::Integer(x) => Self::Integer(*x),
+            CPEntry::Float(x) => Self::Float(*x),
+            CPEntry::Long(x) => Self::Long(*x),
+            CPEntry::Double(x) => Self::Double(*x),
+            CPEntry::Class(x) => unsafe { Self::Class(MSRef::from_raw(NonNull::from(x))) },
+            CPEntry::StringConstant(x) => unsafe { Self::StringLoadable(MSRef::from_raw(NonNull::from(x))) },
+            CPEntry::MethodType(x) => Self::MethodType(x.clone()),
+            CPEntry::MethodHandle(x) => unsafe { Self::MethodHandle(MSRef::from_raw(NonNull::from(x))) },
+            CPEntry::Dynamic(x) => unsafe { Self::Dynamic(MSRef::from_raw(NonNull::from(x))) },
+            _ => return Err(ResolveError::MismatchCPType),
+        };
+
+        Ok(loadable)
+    }
 }
@@ -173,7 +173,11 @@ impl MethodDesc {
         let mut pos = 1; // right after '('
         while pos < close_paren_abs {
             let len = Self::field_desc_len(&utf8[pos..]);
-            let param_str = &utf8[pos..pos + len];
+            // 参数不能越过 ')'，切片也不能落在多字节字符中间。
+            let param_str = utf8
+                .get(pos..pos + len)
+                .filter(|_| pos + len <= close_paren_abs)
+                .ok_or_else(|| ResolveError::InvalidDesc(utf8.into()))?;
             let field_desc = FieldDesc::from(&param_str.to_string())?;
             params_desc.push(field_desc);
             pos += len;
@@ -186,7 +190,7 @@ impl MethodDesc {
         }
 
         let ret_str = &utf8[ret_start..];
-        let ret_desc = if ret_str.as_bytes()[0] == b'V' {
+        let ret_desc = if ret_str == "V" {
             ReturnDesc::Void
         } else {
             ReturnDesc::Type(FieldDesc::from(&ret_str.to_string())?)
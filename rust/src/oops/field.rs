@@ -4,6 +4,7 @@ use crate::{
     class_parser::{attr_info::AttrInfo, field_info::FieldInfo},
     oops::{
         acc_flags::AccFlags,
+        annotation::Annotations,
         attr::ConstantValue,
         cp_entry::{CPEntry, get_utf8},
         desc::FieldDesc,
@@ -20,6 +21,8 @@ pub struct Field {
     offs: OnceCell<usize>,
 
     pub constant_value: Option<ConstantValue>,
+
+    pub annotations: Annotations,
 }
 
 impl Field {
@@ -41,12 +44,15 @@ impl Field {
             }
         }
 
+        let annotations = Annotations::build(&info.attrs, &info.raw_attrs, cp)?;
+
         Ok(Self {
             acc_flags,
             name,
             desc,
             offs: OnceCell::new(),
             constant_value,
+            annotations,
         })
     }
 
@@ -1,4 +1,4 @@
-use std::{array, cell::OnceCell, marker::PhantomData, ptr};
+use std::{array, cell::OnceCell, marker::PhantomData, ptr, slice};
 
 use parking_lot::RwLock;
 
@@ -9,7 +9,7 @@ use crate::{
         exec_error::{ExecError, ExecResult},
         slot::Slot,
     },
-    gc_bindings::oop_handle::NObjPtr,
+    gc_bindings::{oop_codec::decode_oop, oop_handle::NObjPtr},
     oops::{
         acc_flags::AccFlags,
         attr::ConstantValue,
@@ -275,13 +275,83 @@ impl Fields {
             .read();
         let bytes = Self::field_bytes(&storage, field)?;
 
+        Ok(Self::decode_slots(field, bytes))
+    }
+
+    pub(super) fn write_static(&self, field: &Field, slots: &[Slot]) -> ExecResult<()> {
+        if !field.acc_flags.contains(AccFlags::ACC_STATIC) {
+            return Err(ExecError::IncompatibleStaticFieldAccess);
+        }
+
+        let value = Self::encode_slots(field, slots)?;
+
+        let mut storage = self
+            .static_storage
+            .as_ref()
+            .ok_or(ExecError::InvalidStaticFieldStorage)?
+            .write();
+        let destination = Self::field_bytes_mut(&mut storage, field)?;
+        if destination.len() != value.len() {
+            return Err(ExecError::InvalidStaticFieldStorage);
+        }
+        destination.copy_from_slice(&value);
+        Ok(())
+    }
+
+    /// Read an instance field from `obj`.  `layer_start` is the offset of the
+    /// declaring class's part inside the object (`ObjLayout::layer_start`).
+    pub(super) fn read_instance(
+        obj: NObjPtr,
+        layer_start: usize,
+        field: &Field,
+    ) -> ExecResult<Vec<Slot>> {
+        let bytes = Self::instance_field_bytes(obj, layer_start, field)?;
+        Ok(Self::decode_slots(field, bytes))
+    }
+
+    pub(super) fn write_instance(
+        obj: NObjPtr,
+        layer_start: usize,
+        field: &Field,
+        slots: &[Slot],
+    ) -> ExecResult<()> {
+        let value = Self::encode_slots(field, slots)?;
+        let destination = Self::instance_field_bytes(obj, layer_start, field)?;
+        destination.copy_from_slice(&value);
+        Ok(())
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn instance_field_bytes<'a>(
+        obj: NObjPtr,
+        layer_start: usize,
+        field: &Field,
+    ) -> ExecResult<&'a mut [u8]> {
+        if field.acc_flags.contains(AccFlags::ACC_STATIC) {
+            return Err(ExecError::IncompatibleInstanceFieldAccess);
+        }
+
+        let base = decode_oop(obj);
+        if base.is_null() {
+            return Err(ExecError::NullObjectReference);
+        }
+
+        // The object was allocated with the declaring class's full layout, so
+        // every declared field lies inside it.
+        unsafe {
+            let start = (base as *mut u8).add(layer_start + field.offs());
+            Ok(slice::from_raw_parts_mut(start, field.desc.byte_size()))
+        }
+    }
+
+    fn decode_slots(field: &Field, bytes: &[u8]) -> Vec<Slot> {
         if field.desc.is_ref_type() {
-            return Ok(vec![Slot::reference(u32::from_ne_bytes(
+            return vec![Slot::reference(u32::from_ne_bytes(
                 bytes.try_into().unwrap(),
-            ))]);
+            ))];
         }
 
-        let slots = match field.desc.elem {
+        match field.desc.elem {
             FieldElemType::Boolean => vec![Slot::int((bytes[0] != 0) as i32)],
             FieldElemType::Byte => vec![Slot::int(i8::from_ne_bytes([bytes[0]]) as i32)],
             FieldElemType::Char => vec![Slot::int(
@@ -303,16 +373,10 @@ impl Fields {
                 vec![Slot::double_high(value), Slot::double_low(value)]
             }
             FieldElemType::Class { .. } => unreachable!(),
-        };
-
-        Ok(slots)
-    }
-
-    pub(super) fn write_static(&self, field: &Field, slots: &[Slot]) -> ExecResult<()> {
-        if !field.acc_flags.contains(AccFlags::ACC_STATIC) {
-            return Err(ExecError::IncompatibleStaticFieldAccess);
         }
+    }
 
+    fn encode_slots(field: &Field, slots: &[Slot]) -> ExecResult<Vec<u8>> {
         let value = if field.desc.is_ref_type() {
             let [slot] = slots else {
                 return Err(ExecError::InvalidFieldValue);
@@ -372,17 +436,7 @@ impl Fields {
             }
         };
 
-        let mut storage = self
-            .static_storage
-            .as_ref()
-            .ok_or(ExecError::InvalidStaticFieldStorage)?
-            .write();
-        let destination = Self::field_bytes_mut(&mut storage, field)?;
-        if destination.len() != value.len() {
-            return Err(ExecError::InvalidStaticFieldStorage);
-        }
-        destination.copy_from_slice(&value);
-        Ok(())
+        Ok(value)
     }
 
     fn field_bytes<'a>(storage: &'a [u8], field: &Field) -> ExecResult<&'a [u8]> {
@@ -0,0 +1,43 @@
+//! 基本类型包装类 `Integer`、`Long`、`Float`、`Double` 的 VM 侧装箱。
+//!
+//! 布局约定见 `java/java.base/java/lang/Integer.java` 等：唯一的实例字段
+//! `value` 保存对应的基本类型值。
+
+use crate::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD,
+        load_error::{LoadError, LoadResult},
+    },
+    engine::{outcome::RetValue, slot::Slot},
+    gc_bindings::oop_handle::NObjPtr,
+};
+
+pub struct JavaBox;
+
+impl JavaBox {
+    /// Box a primitive `value` the way `valueOf` would, as an `Integer`,
+    /// `Long`, `Float` or `Double`.  `None` for a reference or `void`.
+    pub fn create(value: RetValue) -> LoadResult<Option<NObjPtr>> {
+        let (class_name, desc, slots) = match value {
+            RetValue::Int(x) => ("java/lang/Integer", "I", vec![Slot::int(x)]),
+            RetValue::Long(x) => ("java/lang/Long", "J", vec![Slot::long_high(x), Slot::long_low(x)]),
+            RetValue::Float(x) => ("java/lang/Float", "F", vec![Slot::float(x)]),
+            RetValue::Double(x) => ("java/lang/Double", "D", vec![Slot::double_high(x), Slot::double_low(x)]),
+            RetValue::Ref(_) | RetValue::Void => return Ok(None),
+        };
+
+        let klass = BootstrapCLD::find_class(class_name)?
+            .as_normal_ref()
+            .ok_or_else(|| LoadError::NotFound(class_name.into()))?;
+        let field = klass
+            .find_declared_field("value", desc)
+            .ok_or_else(|| LoadError::NotFound(format!("{class_name}.value")))?;
+
+        let obj = klass.allocate_instance();
+        klass
+            .write_instance_field(obj, &field, &slots)
+            .expect("the box's value field has the boxed type");
+
+        Ok(Some(obj))
+    }
+}
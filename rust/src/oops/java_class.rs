@@ -0,0 +1,71 @@
+//! `java.lang.Class` mirrors of loaded classes.
+//!
+//! The stub in `java/java.base/java/lang/Class.java` declares one VM field,
+//! `long native_klass_ptr`, pointing back at the mirrored `NormalKlass`.
+
+use std::ptr::NonNull;
+
+use parking_lot::Mutex;
+
+use crate::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD,
+        load_error::{LoadError, LoadResult},
+        ms_api::MSRef,
+    },
+    engine::{
+        exec_error::{ExecError, ExecResult},
+        slot::Slot,
+    },
+    gc_bindings::oop_handle::NObjPtr,
+    oops::{field::Field, normal_klass::NormalKlass},
+};
+
+const CLASS_CLASS: &str = "java/lang/Class";
+
+/// Serializes the first materialization of each mirror.
+static MIRROR_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct JavaClass;
+
+impl JavaClass {
+    fn class_klass() -> LoadResult<(MSRef<NormalKlass>, MSRef<Field>)> {
+        let klass = BootstrapCLD::find_class(CLASS_CLASS)?
+            .as_normal_ref()
+            .ok_or_else(|| LoadError::NotFound(CLASS_CLASS.into()))?;
+        let klass_ptr = klass
+            .find_declared_field("native_klass_ptr", "J")
+            .ok_or_else(|| LoadError::NotFound("java/lang/Class.native_klass_ptr".into()))?;
+
+        Ok((klass, klass_ptr))
+    }
+
+    /// The `Class` object for `klass`, created on first use.
+    pub fn mirror(klass: &NormalKlass) -> LoadResult<NObjPtr> {
+        let _guard = MIRROR_LOCK.lock();
+        let existing = klass.mirror_handle().get();
+        if existing != 0 {
+            return Ok(existing);
+        }
+
+        let (class_klass, klass_ptr) = Self::class_klass()?;
+        let obj = class_klass.allocate_instance();
+        let address = klass as *const NormalKlass as i64;
+        class_klass
+            .write_instance_field(obj, &klass_ptr, &[Slot::long_high(address), Slot::long_low(address)])
+            .expect("native_klass_ptr is a declared long instance field");
+
+        klass.mirror_handle().replace(obj);
+        Ok(obj)
+    }
+
+    /// The class a `Class` object mirrors.
+    pub fn klass_of(mirror: NObjPtr) -> ExecResult<MSRef<NormalKlass>> {
+        let (class_klass, klass_ptr) = Self::class_klass()?;
+        let slots = class_klass.read_instance_field(mirror, &klass_ptr)?;
+        let address = Slot::as_long(slots[0], slots[1])?;
+
+        let klass = NonNull::new(address as *mut NormalKlass).ok_or(ExecError::NullObjectReference)?;
+        Ok(unsafe { MSRef::from_raw(klass) })
+    }
+}
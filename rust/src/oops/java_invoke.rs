@@ -0,0 +1,331 @@
+//! VM-side objects of `java.lang.invoke`: method types, direct method
+//! handles, lookups and call sites.
+//!
+//! Layouts follow the stubs in `java/java.base/java/lang/invoke/`.  A
+//! `MethodType` keeps its descriptor string; a `DirectMethodHandle` keeps its
+//! reference kind, the declaring class's mirror and the address of the
+//! `Method` or `Field` it refers to.
+
+use std::{ptr::NonNull, sync::LazyLock};
+
+use dashmap::{DashMap, mapref::entry::Entry};
+
+use crate::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD,
+        load_error::{LoadError, LoadResult},
+        ms_api::MSRef,
+    },
+    engine::{
+        exec_error::{ExecError, ExecResult},
+        resolved_method::ResolvedMethod,
+        slot::Slot,
+    },
+    gc_bindings::{
+        oop_codec::klass_of,
+        oop_handle::{KLASS_OOP_STORAGE_ID, NObjPtr, OOPHandle},
+    },
+    oops::{
+        acc_flags::AccFlags,
+        cp_entry::{MethodHandleCPEntry, MethodHandleRef, RefKind, ResolvedFieldRef},
+        desc::{FieldDesc, MethodDesc, ReturnDesc},
+        field::Field,
+        java_class::JavaClass,
+        java_string::JavaString,
+        normal_klass::NormalKlass,
+        oops_errors::{ResolveError, ResolveResult},
+    },
+};
+
+pub const CALL_SITE_CLASS: &str = "java/lang/invoke/CallSite";
+const METHOD_TYPE_CLASS: &str = "java/lang/invoke/MethodType";
+pub const METHOD_HANDLE_CLASS: &str = "java/lang/invoke/MethodHandle";
+const DIRECT_METHOD_HANDLE_CLASS: &str = "java/lang/invoke/DirectMethodHandle";
+const LOOKUP_CLASS: &str = "java/lang/invoke/MethodHandles$Lookup";
+
+fn boot_class(name: &str) -> LoadResult<MSRef<NormalKlass>> {
+    BootstrapCLD::find_class(name)?
+        .as_normal_ref()
+        .ok_or_else(|| LoadError::NotFound(name.into()))
+}
+
+fn declared_field(klass: &NormalKlass, name: &str, desc: &str) -> LoadResult<MSRef<Field>> {
+    klass
+        .find_declared_field(name, desc)
+        .ok_or_else(|| LoadError::NotFound(format!("{}.{name}", klass.name().utf8())))
+}
+
+fn read_ref(klass: &NormalKlass, obj: NObjPtr, field: &Field) -> ExecResult<NObjPtr> {
+    klass.read_instance_field(obj, field)?[0].as_ref()
+}
+
+fn write_ref(klass: &NormalKlass, obj: NObjPtr, field: &Field, value: NObjPtr) {
+    klass
+        .write_instance_field(obj, field, &[Slot::reference(value)])
+        .expect("reference field of a freshly resolved class");
+}
+
+/// Does `obj` belong to the class called `class_name` or a subclass of it?
+fn is_instance_of(obj: NObjPtr, class_name: &str) -> bool {
+    let Ok(target) = boot_class(class_name) else {
+        return false;
+    };
+
+    klass_of(obj)
+        .and_then(|klass| klass.as_normal_ref())
+        .is_some_and(|klass| klass.is_subclass_of(&target))
+}
+
+/// The member a direct method handle invokes or accesses.
+#[derive(Debug, Clone)]
+pub enum DirectMember {
+    Method(ResolvedMethod),
+    Field(ResolvedFieldRef),
+}
+
+/// A method handle constant, resolved (JVMS 5.4.3.5).
+#[derive(Debug, Clone)]
+pub struct DirectMethodHandle {
+    pub kind: RefKind,
+    pub member: DirectMember,
+}
+
+impl DirectMethodHandle {
+    /// Resolve the member `entry` names, on behalf of `referrer`.
+    pub fn resolve(entry: &MethodHandleCPEntry, referrer: &NormalKlass) -> ResolveResult<Self> {
+        let member = match &entry.reference {
+            MethodHandleRef::Field(x) => DirectMember::Field(x.resolve(referrer)?),
+            MethodHandleRef::Method(x) => DirectMember::Method(x.resolve(referrer)?.into()),
+            MethodHandleRef::InterfaceMethod(x) => DirectMember::Method(x.resolve(referrer)?.into()),
+        };
+
+        let is_static = match &member {
+            DirectMember::Method(x) => x.method().acc_flags.contains(AccFlags::ACC_STATIC),
+            DirectMember::Field(x) => x.field.acc_flags.contains(AccFlags::ACC_STATIC),
+        };
+        let wants_static = matches!(
+            entry.kind,
+            RefKind::GetStatic | RefKind::PutStatic | RefKind::InvokeStatic
+        );
+        if is_static != wants_static {
+            let name = match &member {
+                DirectMember::Method(x) => format!("{}.{}", x.holder().name().utf8(), x.method().name.utf8()),
+                DirectMember::Field(x) => format!("{}.{}", x.holder.name().utf8(), x.field.name.utf8()),
+            };
+            return Err(ResolveError::WrongRefType(name));
+        }
+
+        Ok(Self {
+            kind: entry.kind,
+            member,
+        })
+    }
+
+    /// The class declaring the member.
+    pub fn holder(&self) -> &NormalKlass {
+        match &self.member {
+            DirectMember::Method(x) => x.holder(),
+            DirectMember::Field(x) => &x.holder,
+        }
+    }
+
+    fn member_address(&self) -> i64 {
+        match &self.member {
+            DirectMember::Method(x) => x.method() as *const _ as i64,
+            DirectMember::Field(x) => &*x.field as *const Field as i64,
+        }
+    }
+}
+
+/// An interned `MethodType`, kept alive as a GC root.
+struct InternedType(OOPHandle);
+
+unsafe impl Send for InternedType {}
+unsafe impl Sync for InternedType {}
+
+static METHOD_TYPES: LazyLock<DashMap<String, InternedType>> = LazyLock::new(DashMap::new);
+
+pub struct JavaMethodType;
+
+impl JavaMethodType {
+    /// The `MethodType` for the method descriptor `desc`.  Types are interned,
+    /// so equal descriptors always yield the same object.
+    pub fn create(desc: &str) -> LoadResult<NObjPtr> {
+        let entry = match METHOD_TYPES.entry(desc.to_string()) {
+            Entry::Occupied(x) => return Ok(x.get().0.get()),
+            Entry::Vacant(x) => x,
+        };
+
+        let klass = boot_class(METHOD_TYPE_CLASS)?;
+        let descriptor = declared_field(&klass, "descriptor", "Ljava/lang/String;")?;
+
+        let method_type = klass.allocate_instance();
+        write_ref(&klass, method_type, &descriptor, JavaString::create(desc)?);
+
+        let root = OOPHandle::new(KLASS_OOP_STORAGE_ID);
+        root.replace(method_type);
+        entry.insert(InternedType(root));
+
+        Ok(method_type)
+    }
+
+    /// Can a call site of type `call_type` invoke a handle of `handle_type`?
+    /// `invokeExact` needs identical types.  `invoke` also lets reference
+    /// types differ; the conversions of `asType`, such as boxing and
+    /// widening, are not supported.
+    pub fn accepts(handle_type: &MethodDesc, call_type: &MethodDesc, exact: bool) -> bool {
+        if exact || handle_type.raw.equals(&call_type.raw) {
+            return handle_type.raw.equals(&call_type.raw);
+        }
+
+        let same_kind = |a: &FieldDesc, b: &FieldDesc| {
+            (a.is_ref_type() && b.is_ref_type()) || a.raw.equals(&b.raw)
+        };
+        let same_return = match (&handle_type.ret_desc, &call_type.ret_desc) {
+            (ReturnDesc::Void, ReturnDesc::Void) => true,
+            (ReturnDesc::Type(a), ReturnDesc::Type(b)) => same_kind(a, b),
+            _ => false,
+        };
+
+        same_return
+            && handle_type.params_desc.len() == call_type.params_desc.len()
+            && handle_type
+                .params_desc
+                .iter()
+                .zip(&call_type.params_desc)
+                .all(|(a, b)| same_kind(a, b))
+    }
+
+    pub fn descriptor(method_type: NObjPtr) -> ExecResult<String> {
+        let klass = boot_class(METHOD_TYPE_CLASS)?;
+        let descriptor = declared_field(&klass, "descriptor", "Ljava/lang/String;")?;
+
+        JavaString::to_rust_string(read_ref(&klass, method_type, &descriptor)?)
+    }
+}
+
+pub struct JavaMethodHandle;
+
+impl JavaMethodHandle {
+    /// Allocate a `DirectMethodHandle` of type `type_desc` for `handle`.
+    pub fn create(handle: &DirectMethodHandle, type_desc: &str) -> LoadResult<NObjPtr> {
+        let base = boot_class(METHOD_HANDLE_CLASS)?;
+        let klass = boot_class(DIRECT_METHOD_HANDLE_CLASS)?;
+        let method_type = declared_field(&base, "type", "Ljava/lang/invoke/MethodType;")?;
+        let ref_kind = declared_field(&klass, "refKind", "I")?;
+        let declaring_class = declared_field(&klass, "declaringClass", "Ljava/lang/Class;")?;
+        let member_ptr = declared_field(&klass, "native_member_ptr", "J")?;
+
+        let obj = klass.allocate_instance();
+        write_ref(&base, obj, &method_type, JavaMethodType::create(type_desc)?);
+        write_ref(&klass, obj, &declaring_class, JavaClass::mirror(handle.holder())?);
+
+        let address = handle.member_address();
+        klass
+            .write_instance_field(obj, &ref_kind, &[Slot::int(handle.kind as i32)])
+            .expect("int field of a freshly resolved class");
+        klass
+            .write_instance_field(obj, &member_ptr, &[Slot::long_high(address), Slot::long_low(address)])
+            .expect("long field of a freshly resolved class");
+
+        Ok(obj)
+    }
+
+    /// Resolve a `CONSTANT_MethodHandle` on behalf of `referrer`.  The
+    /// constant resolves to the same object on every use.
+    pub fn constant(entry: &MethodHandleCPEntry, referrer: &NormalKlass) -> ExecResult<NObjPtr> {
+        if let Some(handle) = entry.handle() {
+            return Ok(handle);
+        }
+
+        let direct = DirectMethodHandle::resolve(entry, referrer)?;
+        let handle = Self::create(&direct, &entry.type_descriptor())?;
+
+        Ok(entry.set_handle(handle))
+    }
+
+    pub fn is_method_handle(obj: NObjPtr) -> bool {
+        is_instance_of(obj, METHOD_HANDLE_CLASS)
+    }
+
+    /// Descriptor of the handle's `MethodType`.
+    pub fn type_descriptor(handle: NObjPtr) -> ExecResult<String> {
+        let base = boot_class(METHOD_HANDLE_CLASS)?;
+        let method_type =
+            declared_field(&base, "type", "Ljava/lang/invoke/MethodType;")?;
+
+        JavaMethodType::descriptor(read_ref(&base, handle, &method_type)?)
+    }
+
+    /// The member behind a direct handle, or `None` for any other kind of
+    /// method handle.
+    pub fn direct(handle: NObjPtr) -> ExecResult<Option<DirectMethodHandle>> {
+        if !is_instance_of(handle, DIRECT_METHOD_HANDLE_CLASS) {
+            return Ok(None);
+        }
+
+        let klass = boot_class(DIRECT_METHOD_HANDLE_CLASS)?;
+        let ref_kind = declared_field(&klass, "refKind", "I")?;
+        let declaring_class =
+            declared_field(&klass, "declaringClass", "Ljava/lang/Class;")?;
+        let member_ptr = declared_field(&klass, "native_member_ptr", "J")?;
+
+        let kind = RefKind::from_u8(klass.read_instance_field(handle, &ref_kind)?[0].as_int()? as u8)?;
+        let holder = JavaClass::klass_of(read_ref(&klass, handle, &declaring_class)?)?;
+        let slots = klass.read_instance_field(handle, &member_ptr)?;
+        let address = Slot::as_long(slots[0], slots[1])?;
+
+        let member = if kind.is_field_access() {
+            let field = NonNull::new(address as *mut Field).ok_or(ExecError::NullObjectReference)?;
+            DirectMember::Field(ResolvedFieldRef {
+                holder,
+                field: unsafe { MSRef::from_raw(field) },
+            })
+        } else {
+            let method = NonNull::new(address as *mut _).ok_or(ExecError::NullObjectReference)?;
+            DirectMember::Method(ResolvedMethod::new(holder, unsafe { MSRef::from_raw(method) }))
+        };
+
+        Ok(Some(DirectMethodHandle { kind, member }))
+    }
+}
+
+pub struct JavaLookup;
+
+impl JavaLookup {
+    /// A `MethodHandles.Lookup` with full access to `lookup_class`.
+    pub fn create(lookup_class: &NormalKlass) -> LoadResult<NObjPtr> {
+        let klass = boot_class(LOOKUP_CLASS)?;
+        let field = declared_field(&klass, "lookupClass", "Ljava/lang/Class;")?;
+
+        let lookup = klass.allocate_instance();
+        write_ref(&klass, lookup, &field, JavaClass::mirror(lookup_class)?);
+
+        Ok(lookup)
+    }
+
+    /// The class whose access `lookup` carries.
+    pub fn lookup_class(lookup: NObjPtr) -> ExecResult<MSRef<NormalKlass>> {
+        let klass = boot_class(LOOKUP_CLASS)?;
+        let field = declared_field(&klass, "lookupClass", "Ljava/lang/Class;")?;
+
+        JavaClass::klass_of(read_ref(&klass, lookup, &field)?)
+    }
+}
+
+pub struct JavaCallSite;
+
+impl JavaCallSite {
+    pub fn is_call_site(obj: NObjPtr) -> bool {
+        is_instance_of(obj, CALL_SITE_CLASS)
+    }
+
+    /// The call site's current target; `null` if it was never set.
+    pub fn target(call_site: NObjPtr) -> ExecResult<NObjPtr> {
+        let klass = boot_class(CALL_SITE_CLASS)?;
+        let target =
+            declared_field(&klass, "target", "Ljava/lang/invoke/MethodHandle;")?;
+
+        read_ref(&klass, call_site, &target)
+    }
+}
@@ -0,0 +1,72 @@
+//! `java.lang.String` 对象的 VM 侧构造与读取。
+//!
+//! 布局约定见 `java/java.base/java/lang/String.java`：唯一的实例字段
+//! `char[] value` 保存 UTF-16 码元。
+
+use crate::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD,
+        load_error::{LoadError, LoadResult},
+        ms_api::MSRef,
+    },
+    engine::{exec_error::ExecResult, slot::Slot},
+    gc_bindings::oop_handle::NObjPtr,
+    oops::{
+        array_klass::ArrayKlass, field::Field, klass::Klass, normal_klass::NormalKlass, symbol_table::SymbolHandle,
+    },
+};
+
+const STRING_CLASS: &str = "java/lang/String";
+const CHAR_ARRAY_CLASS: &str = "[C";
+
+pub struct JavaString;
+
+impl JavaString {
+    fn string_klass() -> LoadResult<(MSRef<NormalKlass>, MSRef<Field>)> {
+        let klass = BootstrapCLD::find_class(STRING_CLASS)?
+            .as_normal_ref()
+            .ok_or_else(|| LoadError::NotFound(STRING_CLASS.into()))?;
+        let value = klass
+            .find_declared_field("value", "[C")
+            .ok_or_else(|| LoadError::NotFound("java/lang/String.value".into()))?;
+
+        Ok((klass, value))
+    }
+
+    /// Allocate a new `String` holding `text`.
+    pub fn create(text: &str) -> LoadResult<NObjPtr> {
+        Self::create_utf16(&text.encode_utf16().collect::<Vec<_>>())
+    }
+
+    /// Allocate a new `String` with the exact text of `symbol`, unpaired
+    /// surrogates included.
+    pub fn from_symbol(symbol: &SymbolHandle) -> LoadResult<NObjPtr> {
+        Self::create_utf16(&symbol.to_utf16())
+    }
+
+    pub fn create_utf16(chars: &[u16]) -> LoadResult<NObjPtr> {
+        let (klass, value_field) = Self::string_klass()?;
+        let char_array: MSRef<Klass> = BootstrapCLD::find_class(CHAR_ARRAY_CLASS)?;
+        let array_klass = char_array
+            .as_array()
+            .ok_or_else(|| LoadError::NotFound(CHAR_ARRAY_CLASS.into()))?;
+
+        let value = array_klass.allocate(&char_array, chars.len());
+        ArrayKlass::write_chars(value, chars).expect("fresh char[] fits its contents");
+
+        let string = klass.allocate_instance();
+        klass
+            .write_instance_field(string, &value_field, &[Slot::reference(value)])
+            .expect("String.value is a declared reference field");
+
+        Ok(string)
+    }
+
+    /// Decode a `String` back to Rust.  Unpaired surrogates become U+FFFD.
+    pub fn to_rust_string(string: NObjPtr) -> ExecResult<String> {
+        let (klass, value_field) = Self::string_klass()?;
+        let value = klass.read_instance_field(string, &value_field)?[0].as_ref()?;
+
+        Ok(String::from_utf16_lossy(&ArrayKlass::read_chars(value)?))
+    }
+}
@@ -0,0 +1,266 @@
+//! `java.lang.Throwable` 对象的 VM 侧访问。
+//!
+//! 布局约定见 `java/java.base/java/lang/Throwable.java` 与
+//! `StackTraceElement.java`：`detailMessage`、`cause`（等于自身表示未设置）
+//! 以及由 `fillInStackTrace` 填写的 `StackTraceElement[] stackTrace`。
+
+use crate::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD,
+        load_error::{LoadError, LoadResult},
+        ms_api::MSRef,
+    },
+    engine::{
+        exec_error::{ExecError, ExecResult},
+        slot::Slot,
+        stack_trace::{StackFrame, StackTrace, ThrowableReport},
+    },
+    gc_bindings::{oop_codec::klass_of, oop_handle::NObjPtr},
+    oops::{array_klass::ArrayKlass, field::Field, java_string::JavaString, normal_klass::NormalKlass},
+};
+
+pub const THROWABLE_CLASS: &str = "java/lang/Throwable";
+const ERROR_CLASS: &str = "java/lang/Error";
+const STACK_TRACE_ELEMENT_CLASS: &str = "java/lang/StackTraceElement";
+const STACK_TRACE_ARRAY_CLASS: &str = "[Ljava/lang/StackTraceElement;";
+
+fn boot_class(name: &str) -> LoadResult<MSRef<NormalKlass>> {
+    BootstrapCLD::find_class(name)?
+        .as_normal_ref()
+        .ok_or_else(|| LoadError::NotFound(name.into()))
+}
+
+fn declared_field(klass: &NormalKlass, name: &str, desc: &str) -> LoadResult<MSRef<Field>> {
+    klass
+        .find_declared_field(name, desc)
+        .ok_or_else(|| LoadError::NotFound(format!("{}.{name}", klass.name().utf8())))
+}
+
+fn read_ref(klass: &NormalKlass, obj: NObjPtr, field: &Field) -> ExecResult<NObjPtr> {
+    klass.read_instance_field(obj, field)?[0].as_ref()
+}
+
+fn read_string(klass: &NormalKlass, obj: NObjPtr, field: &Field) -> ExecResult<Option<String>> {
+    match read_ref(klass, obj, field)? {
+        0 => Ok(None),
+        string => JavaString::to_rust_string(string).map(Some),
+    }
+}
+
+fn write_string(klass: &NormalKlass, obj: NObjPtr, field: &Field, text: Option<&str>) -> LoadResult<()> {
+    let value = text.map(JavaString::create).transpose()?.unwrap_or(0);
+    klass
+        .write_instance_field(obj, field, &[Slot::reference(value)])
+        .expect("reference field of a freshly resolved class");
+    Ok(())
+}
+
+fn extends(klass: &NormalKlass, name: &str) -> bool {
+    if klass.name().utf8() == name {
+        return true;
+    }
+
+    let mut current = klass.super_klass_ref();
+    while let Some(k) = current {
+        if k.name().utf8() == name {
+            return true;
+        }
+        current = k.super_klass_ref();
+    }
+
+    false
+}
+
+/// Does `klass` extend `java.lang.Throwable`?
+pub fn is_throwable(klass: &NormalKlass) -> bool {
+    extends(klass, THROWABLE_CLASS)
+}
+
+/// Does `klass` extend `java.lang.Error`?
+pub fn is_error(klass: &NormalKlass) -> bool {
+    extends(klass, ERROR_CLASS)
+}
+
+struct ThrowableFields {
+    klass: MSRef<NormalKlass>,
+    detail_message: MSRef<Field>,
+    cause: MSRef<Field>,
+    stack_trace: MSRef<Field>,
+}
+
+impl ThrowableFields {
+    fn resolve() -> LoadResult<Self> {
+        let klass = boot_class(THROWABLE_CLASS)?;
+
+        Ok(Self {
+            detail_message: declared_field(&klass, "detailMessage", "Ljava/lang/String;")?,
+            cause: declared_field(&klass, "cause", "Ljava/lang/Throwable;")?,
+            stack_trace: declared_field(&klass, "stackTrace", "[Ljava/lang/StackTraceElement;")?,
+            klass,
+        })
+    }
+}
+
+struct ElementFields {
+    klass: MSRef<NormalKlass>,
+    declaring_class: MSRef<Field>,
+    method_name: MSRef<Field>,
+    file_name: MSRef<Field>,
+    line_number: MSRef<Field>,
+    bci: MSRef<Field>,
+}
+
+impl ElementFields {
+    fn resolve() -> LoadResult<Self> {
+        let klass = boot_class(STACK_TRACE_ELEMENT_CLASS)?;
+
+        Ok(Self {
+            declaring_class: declared_field(&klass, "declaringClass", "Ljava/lang/String;")?,
+            method_name: declared_field(&klass, "methodName", "Ljava/lang/String;")?,
+            file_name: declared_field(&klass, "fileName", "Ljava/lang/String;")?,
+            line_number: declared_field(&klass, "lineNumber", "I")?,
+            bci: declared_field(&klass, "bci", "I")?,
+            klass,
+        })
+    }
+
+    fn create(&self, frame: &StackFrame) -> LoadResult<NObjPtr> {
+        let klass = &self.klass;
+        let element = klass.allocate_instance();
+
+        write_string(klass, element, &self.declaring_class, Some(&frame.class_name.replace('/', ".")))?;
+        write_string(klass, element, &self.method_name, Some(&frame.method_name))?;
+        write_string(klass, element, &self.file_name, frame.file_name.as_deref())?;
+        // StackTraceElement 约定：-1 表示行号未知。
+        let line_number = frame.line_number.map_or(-1, |x| x as i32);
+        for (field, value) in [(&self.line_number, line_number), (&self.bci, frame.bci as i32)] {
+            klass
+                .write_instance_field(element, field, &[Slot::int(value)])
+                .expect("int field of a freshly resolved class");
+        }
+
+        Ok(element)
+    }
+
+    fn read(&self, element: NObjPtr) -> ExecResult<StackFrame> {
+        let klass = &self.klass;
+
+        Ok(StackFrame {
+            class_name: read_string(klass, element, &self.declaring_class)?
+                .unwrap_or_default()
+                .replace('.', "/"),
+            method_name: read_string(klass, element, &self.method_name)?.unwrap_or_default(),
+            bci: klass.read_instance_field(element, &self.bci)?[0].as_int()?.max(0) as usize,
+            file_name: read_string(klass, element, &self.file_name)?,
+            line_number: u32::try_from(klass.read_instance_field(element, &self.line_number)?[0].as_int()?).ok(),
+        })
+    }
+}
+
+pub struct JavaThrowable;
+
+impl JavaThrowable {
+    /// Allocate an instance of the throwable class `class_name` (internal
+    /// form) without running a constructor: the message is set and the
+    /// cause left uninitialized, as `Throwable(String)` would.
+    pub fn create(class_name: &str, message: Option<&str>) -> LoadResult<NObjPtr> {
+        let fields = ThrowableFields::resolve()?;
+        let klass = boot_class(class_name)?;
+        if !is_throwable(&klass) {
+            return Err(LoadError::NotFound(format!("{class_name} is not a Throwable")));
+        }
+
+        let obj = klass.allocate_instance();
+        write_string(&fields.klass, obj, &fields.detail_message, message)?;
+        fields
+            .klass
+            .write_instance_field(obj, &fields.cause, &[Slot::reference(obj)])
+            .expect("Throwable.cause is a declared reference field");
+
+        Ok(obj)
+    }
+
+    /// Replace the recorded stack trace with `trace`.
+    pub fn set_stack_trace(obj: NObjPtr, trace: &StackTrace) -> LoadResult<()> {
+        let fields = ThrowableFields::resolve()?;
+        let elements = ElementFields::resolve()?;
+
+        let array_class = BootstrapCLD::find_class(STACK_TRACE_ARRAY_CLASS)?;
+        let array_klass = array_class
+            .as_array()
+            .ok_or_else(|| LoadError::NotFound(STACK_TRACE_ARRAY_CLASS.into()))?;
+        let array = array_klass.allocate(&array_class, trace.len());
+        for (index, frame) in trace.frames.iter().enumerate() {
+            ArrayKlass::write_ref(array, index, elements.create(frame)?)
+                .expect("index is within the fresh array");
+        }
+
+        fields
+            .klass
+            .write_instance_field(obj, &fields.stack_trace, &[Slot::reference(array)])
+            .expect("Throwable.stackTrace is a declared reference field");
+        Ok(())
+    }
+
+    pub fn stack_trace(obj: NObjPtr) -> ExecResult<StackTrace> {
+        let fields = ThrowableFields::resolve()?;
+        let array = read_ref(&fields.klass, obj, &fields.stack_trace)?;
+        if array == 0 {
+            return Ok(StackTrace::default());
+        }
+
+        let elements = ElementFields::resolve()?;
+        let frames = (0..ArrayKlass::length(array)?)
+            .map(|index| elements.read(ArrayKlass::read_ref(array, index)?))
+            .collect::<ExecResult<_>>()?;
+
+        Ok(StackTrace { frames })
+    }
+
+    pub fn message(obj: NObjPtr) -> ExecResult<Option<String>> {
+        let fields = ThrowableFields::resolve()?;
+        read_string(&fields.klass, obj, &fields.detail_message)
+    }
+
+    /// `getCause()`: `None` when unset or explicitly `null`.
+    pub fn cause(obj: NObjPtr) -> ExecResult<Option<NObjPtr>> {
+        let fields = ThrowableFields::resolve()?;
+        let cause = read_ref(&fields.klass, obj, &fields.cause)?;
+
+        Ok((cause != 0 && cause != obj).then_some(cause))
+    }
+
+    pub fn set_cause(obj: NObjPtr, cause: Option<NObjPtr>) -> ExecResult<()> {
+        let fields = ThrowableFields::resolve()?;
+        fields
+            .klass
+            .write_instance_field(obj, &fields.cause, &[Slot::reference(cause.unwrap_or(0))])
+    }
+
+    /// Snapshot `obj` and its cause chain for printing.  A cause that is
+    /// already part of the chain ends it, so cycles cannot loop forever.
+    pub fn report(obj: NObjPtr) -> ExecResult<ThrowableReport> {
+        let mut chain = Vec::new();
+        let mut next = Some(obj);
+        while let Some(current) = next.filter(|x| !chain.contains(x)) {
+            chain.push(current);
+            next = Self::cause(current)?;
+        }
+
+        let mut report = None;
+        for &current in chain.iter().rev() {
+            let class_name = klass_of(current)
+                .map(|klass| klass.name().utf8().to_string())
+                .ok_or(ExecError::NullObjectReference)?;
+
+            report = Some(ThrowableReport {
+                class_name,
+                message: Self::message(current)?,
+                stack_trace: Self::stack_trace(current)?,
+                cause: report.map(Box::new),
+            });
+        }
+
+        Ok(report.expect("chain starts with obj"))
+    }
+}
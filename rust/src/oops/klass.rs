@@ -1,5 +1,8 @@
-use crate::{class_loader::ms_api::MSRef, oops::{array_klass::ArrayKlass, normal_klass::NormalKlass, prim_klass::PrimKlass}};
+use crate::{class_loader::ms_api::MSRef, oops::{array_klass::ArrayKlass, normal_klass::NormalKlass, prim_klass::PrimKlass, symbol_table::SymbolHandle}};
 
+/// Always allocated in metaspace behind an `MSBox`, so the size of the
+/// largest variant costs nothing per reference.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum Klass {
     Normal(NormalKlass),
@@ -8,6 +11,15 @@ pub enum Klass {
 }
 
 impl Klass {
+    /// Binary name in internal form; primitive classes use their keyword.
+    pub fn name(&self) -> &SymbolHandle {
+        match self {
+            Self::Normal(x) => x.name(),
+            Self::Primitive(x) => &x.name,
+            Self::Array(x) => &x.name,
+        }
+    }
+
     pub fn as_normal(&self) -> Option<&NormalKlass> {
         match self {
             Self::Normal(x) => Some(x),
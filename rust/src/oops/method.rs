@@ -1,13 +1,14 @@
 use std::cell::OnceCell;
 
-use crate::{class_loader::ms_api::MSAllocator, class_parser::{attr_info::AttrInfo, method_info::MethodInfo}, oops::{acc_flags::AccFlags, attr::Code, cp_entry::{CPEntry, get_utf8}, desc::MethodDesc, oops_errors::ResolveResult, symbol_table::SymbolHandle}};
+use crate::{class_loader::ms_api::MSAllocator, class_parser::{attr_info::AttrInfo, method_info::MethodInfo}, oops::{acc_flags::AccFlags, annotation::Annotations, attr::Code, cp_entry::{CPEntry, get_utf8}, desc::MethodDesc, java_invoke::METHOD_HANDLE_CLASS, normal_klass::NormalKlass, oops_errors::ResolveResult, symbol_table::SymbolHandle}};
 
 #[derive(Debug)]
 pub struct Method {
     pub acc_flags: AccFlags,
     pub name: SymbolHandle,
     pub desc: MethodDesc,
-    pub code: Option<Code>
+    pub code: Option<Code>,
+    pub annotations: Annotations,
 }
 
 impl Method {
@@ -16,10 +17,17 @@ impl Method {
         let name = get_utf8(cp, info.name_idx as usize)?;
         let desc = MethodDesc::from(get_utf8(cp, info.desc_idx as usize)?.utf8())?;
 
+        let mut annotations = Annotations::build(&info.attrs, &info.raw_attrs, cp)?;
+
         let mut code = None;
         for n in &info.attrs {
             match n {
-                AttrInfo::Code(info) => code = Some(Code::build(info, cp, msa)?),
+                AttrInfo::Code(info) => {
+                    code = Some(Code::build(info, cp, msa)?);
+                    for attr in &info.attrs {
+                        annotations.add_type_annotations(attr, cp)?;
+                    }
+                }
                 _ => continue
             }
         }
@@ -28,7 +36,17 @@ impl Method {
             acc_flags,
             name,
             desc,
-            code
+            code,
+            annotations,
         })
     }
+
+    /// JVMS 2.9.3: declared in `java.lang.invoke.MethodHandle` with a single
+    /// `Object[]` parameter, `Object` return type, and `ACC_VARARGS` and
+    /// `ACC_NATIVE` set.  Such a method accepts any call-site descriptor.
+    pub fn is_signature_polymorphic(&self, holder: &NormalKlass) -> bool {
+        holder.name().utf8() == METHOD_HANDLE_CLASS
+            && self.acc_flags.contains(AccFlags::ACC_VARARGS | AccFlags::ACC_NATIVE)
+            && self.desc.raw.utf8() == "([Ljava/lang/Object;)Ljava/lang/Object;"
+    }
 }
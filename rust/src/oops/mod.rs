@@ -1,10 +1,17 @@
 pub mod acc_flags;
+mod access;
+pub mod annotation;
 pub mod array_klass;
 pub mod attr;
 pub mod cp_entry;
 pub mod desc;
 pub mod field;
 mod fields;
+pub mod java_box;
+pub mod java_class;
+pub mod java_invoke;
+pub mod java_string;
+pub mod java_throwable;
 pub mod klass;
 pub mod method;
 pub mod normal_klass;
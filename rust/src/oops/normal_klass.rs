@@ -1,6 +1,7 @@
 use std::{
-    cell::OnceCell,
+    cell::{Cell, OnceCell},
     ptr::{NonNull, null},
+    sync::OnceLock,
 };
 
 use crate::{
@@ -9,12 +10,25 @@ use crate::{
         cld::ClassLoaderData,
         ms_api::{MSAllocator, MSBox, MSRef},
     },
-    class_parser::{class_file::ClassFile, cp_info::ConstantPoolInfo, method_info::MethodInfo},
+    class_parser::{
+        attr_info::AttrInfo, class_file::ClassFile, cp_info::ConstantPoolInfo, limits::ClassFileLimits,
+        method_info::MethodInfo,
+    },
     engine::{exec_error::ExecResult, slot::Slot},
-    gc_bindings::obj_layout::ObjLayout,
+    gc_bindings::{
+        gc_bindings::alloc_object,
+        obj_layout::{OBJ_HEADER_BYTES, ObjLayout},
+        oop_codec::encode_oop,
+        oop_handle::{KLASS_OOP_STORAGE_ID, NObjPtr, OOPHandle},
+    },
     oops::{
         acc_flags::AccFlags,
-        cp_entry::{CPEntry, ClassCPEntry, ResolvedFieldRef, ResolvedMethodRef},
+        annotation::Annotations,
+        attr::BootstrapMethod,
+        cp_entry::{
+            CPEntry, ClassCPEntry, DynamicCPEntry, InvokeDynamicCPEntry, ResolvedFieldRef, ResolvedInterfaceMethodRef,
+            ResolvedMethodRef, get_utf8,
+        },
         field::Field,
         fields::Fields,
         klass::Klass,
@@ -23,10 +37,13 @@ use crate::{
         symbol_table::{SymbolHandle, SymbolTable},
     },
     runtime::java_thread::JavaThreadID,
+    verifier::{self, verify_error::VerifyResult},
 };
 
 #[derive(Debug)]
 pub struct UnlinkedNormalKlass {
+    major_version: u16,
+
     acc_flags: AccFlags,
 
     this_klass: MSRef<ClassCPEntry>,
@@ -39,6 +56,16 @@ pub struct UnlinkedNormalKlass {
     fields: Fields,
 
     methods: MSBox<[Method]>,
+
+    /// The `SourceFile` attribute, e.g. `Main.java`.
+    source_file: Option<SymbolHandle>,
+
+    annotations: Box<Annotations>,
+
+    bootstrap_methods: Box<[BootstrapMethod]>,
+
+    nest_host: Option<MSRef<ClassCPEntry>>,
+    nest_members: Box<[SymbolHandle]>,
 }
 
 fn build_cp<'a>(
@@ -61,26 +88,25 @@ fn build_cp<'a>(
     Ok(cp)
 }
 
+/// First class-file version whose `NestHost` and `NestMembers` count.
+const NESTMATES_MAJOR: u16 = 55;
+
 pub fn cp_slice_get(cp_slice: &[OnceCell<CPEntry>], idx: usize) -> Option<&CPEntry> {
-    cp_slice[idx].get()
+    cp_slice.get(idx)?.get()
+}
+
+fn class_entry_at(cp_slice: &[OnceCell<CPEntry>], idx: u16) -> ResolveResult<MSRef<ClassCPEntry>> {
+    match cp_slice_get(cp_slice, idx as usize) {
+        Some(CPEntry::Class(entry)) => unsafe { Ok(MSRef::from_raw(entry.into())) },
+        _ => Err(ResolveError::MismatchCPType),
+    }
 }
 
 fn build_interfaces(
     parsed_ifaces: &[u16],
     cp_slice: &[OnceCell<CPEntry>],
 ) -> ResolveResult<Vec<MSRef<ClassCPEntry>>> {
-    let mut ifaces = Vec::with_capacity(parsed_ifaces.len());
-
-    for idx in parsed_ifaces {
-        match cp_slice_get(cp_slice, *idx as usize) {
-            Some(CPEntry::Class(entry)) => unsafe {
-                ifaces.push(MSRef::from_raw(entry.into()));
-            },
-            _ => return Err(ResolveError::MismatchCPType),
-        };
-    }
-
-    Ok(ifaces)
+    parsed_ifaces.iter().map(|&idx| class_entry_at(cp_slice, idx)).collect()
 }
 
 fn link_interfaces(
@@ -94,7 +120,7 @@ fn link_interfaces(
         let klass = entry.get(cld)?;
         let interface = klass.as_normal_ref().ok_or(ResolveError::NotANormal)?;
         if !interface.is_interface() {
-            return Err(ResolveError::WrongRefType);
+            return Err(ResolveError::WrongRefType(interface.name().utf8().into()));
         }
         uninit[i].write(interface);
     }
@@ -148,7 +174,47 @@ impl UnlinkedNormalKlass {
 
         let methods = build_methods(&cf.methods, &cp, msa)?;
 
+        // 重复的 SourceFile 已被 class_parser 拒绝。
+        let source_file = cf
+            .attrs
+            .iter()
+            .find_map(|x| match x {
+                AttrInfo::SourceFile { sourcefile_idx } => Some(*sourcefile_idx),
+                _ => None,
+            })
+            .map(|idx| get_utf8(&cp, idx as usize))
+            .transpose()?;
+
+        let annotations = Box::new(Annotations::build(&cf.attrs, &cf.raw_attrs, &cp)?);
+
+        let bootstrap_methods = cf
+            .bootstrap_methods()
+            .iter()
+            .map(|x| BootstrapMethod::build(x, &cp))
+            .collect::<ResolveResult<_>>()?;
+
+        // 版本 55 之前没有嵌套（nest），HotSpot 也忽略这两个属性。
+        let mut nest_host = None;
+        let mut nest_members = Box::default();
+        if cf.major_version >= NESTMATES_MAJOR {
+            for attr in &cf.attrs {
+                match attr {
+                    AttrInfo::NestHost { host_class_idx } => {
+                        nest_host = Some(class_entry_at(&cp, *host_class_idx)?);
+                    }
+                    AttrInfo::NestMembers(members) => {
+                        nest_members = members
+                            .iter()
+                            .map(|&x| class_entry_at(&cp, x).map(|entry| entry.name().clone()))
+                            .collect::<ResolveResult<_>>()?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         Ok(Self {
+            major_version: cf.major_version,
             acc_flags,
             this_klass: this_entry.clone(),
             super_klass: super_entry,
@@ -156,10 +222,44 @@ impl UnlinkedNormalKlass {
             interfaces,
             fields,
             methods,
+            source_file,
+            annotations,
+            bootstrap_methods,
+            nest_host,
+            nest_members,
         })
     }
 }
 
+thread_local! {
+    /// Classes this thread is linking, each waiting on the loading of a
+    /// supertype that is linked in turn.
+    static LINK_NESTING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// One level of `LINK_NESTING`, given back when dropped.
+struct LinkNesting;
+
+impl LinkNesting {
+    /// Bounds the recursion of loading supertypes, which would otherwise
+    /// follow a hostile hierarchy until the native stack overflows.
+    fn enter(name: &SymbolHandle, max_depth: usize) -> ResolveResult<Self> {
+        let nesting = LINK_NESTING.get();
+        if nesting > max_depth {
+            return Err(ResolveError::HierarchyTooDeep(name.utf8().into()));
+        }
+
+        LINK_NESTING.set(nesting + 1);
+        Ok(Self)
+    }
+}
+
+impl Drop for LinkNesting {
+    fn drop(&mut self) {
+        LINK_NESTING.set(LINK_NESTING.get() - 1);
+    }
+}
+
 #[derive(Debug)]
 enum ClassInitState {
     Uninitialized,
@@ -196,11 +296,18 @@ impl Default for ClassInit {
 
 #[derive(Debug)]
 pub struct NormalKlass {
+    /// Class-file major version; it selects the verifier.
+    major_version: u16,
+
     acc_flags: AccFlags,
 
     this_klass: MSRef<ClassCPEntry>,
     super_klass: Option<MSRef<NormalKlass>>,
 
+    /// Supertypes above this class along the longest path; 0 for
+    /// `java/lang/Object`.
+    hierarchy_depth: usize,
+
     // Points to rust memory space.
     cld: Option<NonNull<ClassLoaderData>>,
 
@@ -212,9 +319,34 @@ pub struct NormalKlass {
 
     methods: MSBox<[Method]>,
 
+    source_file: Option<SymbolHandle>,
+
+    /// Boxed: most classes have none, and `Klass` variants should stay small.
+    annotations: Box<Annotations>,
+
+    bootstrap_methods: Box<[BootstrapMethod]>,
+
+    /// `NestHost`, the host this class claims.
+    nest_host: Option<MSRef<ClassCPEntry>>,
+    /// `NestMembers`, the classes a host admits.
+    nest_members: Box<[SymbolHandle]>,
+    /// The validated nest host; `None` when this class hosts its own nest.
+    validated_nest_host: OnceLock<Option<MSRef<NormalKlass>>>,
+
+    /// The `java.lang.Class` instance, created on first use.
+    mirror: OOPHandle,
+
     obj_layout: ObjLayout,
 
     init: ClassInit,
+
+    /// For a class spun on behalf of another (a lambda class), that class:
+    /// access checks grant the spun class its host's privileges.
+    host: OnceCell<MSRef<NormalKlass>>,
+
+    /// JVMS 5.4.1: the outcome of verifying this class, computed once before
+    /// its code first runs.
+    verification: OnceLock<VerifyResult<()>>,
 }
 
 impl NormalKlass {
@@ -227,12 +359,19 @@ impl NormalKlass {
             None => BootstrapCLD::bs_msa(),
         };
 
+        let max_depth = ClassFileLimits::current().max_class_hierarchy_depth;
+        let nesting = LinkNesting::enter(unlinked.this_klass.name(), max_depth)?;
+
         let obj_layout;
         let super_klass;
         match unlinked.super_klass {
             Some(x) => {
                 let super_ref = x.get(cld)?;
-                let super_normal = super_ref.as_normal().unwrap();
+                let super_normal = super_ref.as_normal().ok_or(ResolveError::NotANormal)?;
+                // JVMS 5.4.3.1：超类不能是接口。
+                if super_normal.is_interface() {
+                    return Err(ResolveError::WrongRefType(super_normal.name().utf8().into()));
+                }
                 super_klass = unsafe { Some(MSRef::from_raw(super_normal.into())) };
 
                 obj_layout = ObjLayout {
@@ -246,7 +385,7 @@ impl NormalKlass {
                 super_klass = None;
                 obj_layout = ObjLayout {
                     super_layout: null(),
-                    byte_size: unlinked.fields.instance_size,
+                    byte_size: OBJ_HEADER_BYTES + unlinked.fields.instance_size,
                     ptrs_count: unlinked.fields.instance_ptrs_count,
                 }
             }
@@ -256,6 +395,17 @@ impl NormalKlass {
         // Field resolution can then traverse the interface graph without exposing
         // or re-reading symbolic constant-pool entries.
         let interfaces = link_interfaces(&unlinked.interfaces, cld, msa)?;
+        drop(nesting);
+
+        let hierarchy_depth = super_klass
+            .iter()
+            .chain(interfaces.iter())
+            .map(|x| x.hierarchy_depth + 1)
+            .max()
+            .unwrap_or(0);
+        if hierarchy_depth > max_depth {
+            return Err(ResolveError::HierarchyTooDeep(unlinked.this_klass.name().utf8().into()));
+        }
 
         let cld_ptr = match cld {
             Some(x) => Some(x.into()),
@@ -263,16 +413,27 @@ impl NormalKlass {
         };
 
         let klass = Self {
+            major_version: unlinked.major_version,
             acc_flags: unlinked.acc_flags,
             this_klass: unlinked.this_klass,
             super_klass,
+            hierarchy_depth,
             cld: cld_ptr,
             constant_pool: unlinked.constant_pool,
             interfaces,
             fields: unlinked.fields,
             methods: unlinked.methods,
+            source_file: unlinked.source_file,
+            annotations: unlinked.annotations,
+            bootstrap_methods: unlinked.bootstrap_methods,
+            nest_host: unlinked.nest_host,
+            nest_members: unlinked.nest_members,
+            validated_nest_host: OnceLock::new(),
+            mirror: OOPHandle::new(KLASS_OOP_STORAGE_ID),
             obj_layout,
             init: ClassInit::default(),
+            host: OnceCell::new(),
+            verification: OnceLock::new(),
         };
 
         let boxed = MSBox::new(msa, Klass::Normal(klass));
@@ -283,9 +444,52 @@ impl NormalKlass {
 }
 
 impl NormalKlass {
+    pub fn major_version(&self) -> u16 {
+        self.major_version
+    }
+
+    pub fn acc_flags(&self) -> &AccFlags {
+        &self.acc_flags
+    }
+
     pub fn is_interface(&self) -> bool {
         self.acc_flags.contains(AccFlags::ACC_INTERFACE)
     }
+
+    pub fn is_abstract(&self) -> bool {
+        self.acc_flags.contains(AccFlags::ACC_ABSTRACT)
+    }
+
+    /// Binary name in internal form, e.g. `java/lang/Object`.
+    pub fn name(&self) -> &SymbolHandle {
+        self.this_klass.name()
+    }
+
+    /// The `SourceFile` attribute, if the class was compiled with it.
+    pub fn source_file(&self) -> Option<&SymbolHandle> {
+        self.source_file.as_ref()
+    }
+
+    /// `RuntimeVisible*Annotations` of the class itself.
+    pub fn annotations(&self) -> &Annotations {
+        &self.annotations
+    }
+
+    /// Entry `index` of the `BootstrapMethods` attribute.
+    pub fn bootstrap_method(&self, index: usize) -> Option<&BootstrapMethod> {
+        self.bootstrap_methods.get(index)
+    }
+
+    pub(crate) fn mirror_handle(&self) -> &OOPHandle {
+        &self.mirror
+    }
+
+    /// The `Klass` wrapping this class.  Object headers encode this pointer.
+    pub fn klass_ref(&self) -> MSRef<Klass> {
+        self.this_klass
+            .resolved()
+            .expect("linked class has no self reference")
+    }
 }
 
 impl NormalKlass {
@@ -293,6 +497,11 @@ impl NormalKlass {
         &self.obj_layout
     }
 
+    /// Number of constant-pool slots, counting the unused slot 0.
+    pub fn constant_pool_len(&self) -> usize {
+        self.constant_pool.len()
+    }
+
     pub fn constant_pool_entry(&self, index: usize) -> Option<&CPEntry> {
         self.constant_pool.get(index)?.get()
     }
@@ -301,10 +510,131 @@ impl NormalKlass {
         self.cld.map(|x| unsafe { x.as_ref() })
     }
 
+    pub fn host(&self) -> Option<&NormalKlass> {
+        self.host.get().map(|x| &**x)
+    }
+
+    /// Mark this class as spun for `host`; the first host set stays.
+    pub(crate) fn set_host(&self, host: MSRef<NormalKlass>) {
+        let _ = self.host.set(host);
+    }
+
+    /// The host of this class's nest: the class `NestHost` names, which
+    /// must be in the same runtime package and list this class in
+    /// `NestMembers`.  As in Java 11, a host that cannot be loaded fails
+    /// with its load error and one that fails the checks with
+    /// `NotANestMember`; only a validated host is remembered.
+    pub fn nest_host(&self) -> ResolveResult<&NormalKlass> {
+        if let Some(host) = self.validated_nest_host.get() {
+            return Ok(host.as_deref().unwrap_or(self));
+        }
+        let Some(entry) = &self.nest_host else {
+            return Ok(self);
+        };
+
+        let host = entry.resolve(self)?;
+        let not_a_member = || ResolveError::NotANestMember {
+            member: self.name().utf8().into(),
+            host: host.name().utf8().into(),
+        };
+        let host = host.as_normal_ref().ok_or_else(not_a_member)?;
+        let admitted = host.nest_members.iter().any(|x| x == self.name());
+        if !admitted || !host.is_same_runtime_package(self) {
+            return Err(not_a_member());
+        }
+
+        Ok(self.validated_nest_host.get_or_init(|| Some(host)).as_deref().unwrap_or(self))
+    }
+
     pub fn super_klass_ref(&self) -> Option<MSRef<NormalKlass>> {
         self.super_klass.clone()
     }
 
+    /// Is this class `other` or one of its subclasses?  Interfaces are not
+    /// considered.
+    pub fn is_subclass_of(&self, other: &NormalKlass) -> bool {
+        if std::ptr::eq(self, other) {
+            return true;
+        }
+
+        let mut current = self.super_klass_ref();
+        while let Some(klass) = current {
+            if std::ptr::eq(&*klass, other) {
+                return true;
+            }
+            current = klass.super_klass_ref();
+        }
+
+        false
+    }
+
+    /// Is this class or interface `other`, a subclass of it, or one of its
+    /// implementations?  The subtype rules of JVMS 6.5 `checkcast` between
+    /// classes and interfaces.
+    pub fn is_subtype_of(&self, other: &NormalKlass) -> bool {
+        if !other.is_interface() || std::ptr::eq(self, other) {
+            return self.is_subclass_of(other);
+        }
+
+        self.superinterfaces()
+            .iter()
+            .any(|interface| std::ptr::eq(&**interface, other))
+    }
+
+    /// Every interface this class or interface implements or extends,
+    /// directly or through its superclasses, each listed once.
+    pub(crate) fn superinterfaces(&self) -> Vec<MSRef<NormalKlass>> {
+        let mut current = Some(self);
+        let mut pending = Vec::new();
+        while let Some(klass) = current {
+            pending.extend(klass.direct_interfaces().iter().cloned());
+            current = klass.super_klass.as_deref();
+        }
+
+        let mut visited: Vec<MSRef<NormalKlass>> = Vec::new();
+        while let Some(interface) = pending.pop() {
+            if visited.iter().any(|seen| seen.equals(&interface)) {
+                continue;
+            }
+            pending.extend(interface.direct_interfaces().iter().cloned());
+            visited.push(interface);
+        }
+
+        visited
+    }
+
+    /// JVMS 5.4.3.3: the maximally-specific superinterface methods named
+    /// `name` with descriptor `desc` — non-private, non-static declarations
+    /// in superinterfaces, dropping any declared in a superinterface of
+    /// another candidate's declaring interface.
+    pub(crate) fn maximally_specific_methods(
+        &self,
+        name: &SymbolHandle,
+        desc: &SymbolHandle,
+    ) -> Vec<(MSRef<NormalKlass>, MSRef<Method>)> {
+        let candidates: Vec<_> = self
+            .superinterfaces()
+            .into_iter()
+            .filter_map(|interface| {
+                let method = interface.find_declared_method_symbol(name, desc)?;
+                (!method
+                    .acc_flags
+                    .intersects(AccFlags::ACC_PRIVATE | AccFlags::ACC_STATIC))
+                .then_some((interface, method))
+            })
+            .collect();
+
+        candidates
+            .iter()
+            .filter(|(holder, _)| {
+                !candidates
+                    .iter()
+                    .any(|(other, _)| !other.equals(holder) && other.is_subtype_of(holder))
+            })
+            .cloned()
+            .collect()
+    }
+
     /// Acquire this class's initialization state for `owner`.
     ///
     /// This method only coordinates state and waiters.  Deciding whether and
@@ -401,6 +731,29 @@ impl NormalKlass {
         }
     }
 
+    pub fn find_declared_field(&self, name: &str, desc: &str) -> Option<MSRef<Field>> {
+        let name = SymbolTable::intern(name);
+        let desc = SymbolTable::intern(desc);
+
+        self.find_declared_field_symbol(&name, &desc)
+    }
+
+    /// Allocate a zeroed instance on the Java heap.  Constructors are not run.
+    pub fn allocate_instance(&self) -> NObjPtr {
+        let klass = self.klass_ref();
+        encode_oop(alloc_object(&*klass, self.obj_layout.byte_size))
+    }
+
+    /// `field` must be declared by this class.
+    pub fn read_instance_field(&self, obj: NObjPtr, field: &Field) -> ExecResult<Vec<Slot>> {
+        Fields::read_instance(obj, self.obj_layout.layer_start(), field)
+    }
+
+    /// `field` must be declared by this class.
+    pub fn write_instance_field(&self, obj: NObjPtr, field: &Field, slots: &[Slot]) -> ExecResult<()> {
+        Fields::write_instance(obj, self.obj_layout.layer_start(), field, slots)
+    }
+
     pub fn read_static_field(&self, field: &Field) -> ExecResult<Vec<Slot>> {
         self.fields.read_static(field)
     }
@@ -413,6 +766,18 @@ impl NormalKlass {
         self.fields.initialize_constant_values()
     }
 
+    pub fn methods(&self) -> &[Method] {
+        &self.methods
+    }
+
+    /// Verify the class unless it is trusted; the first outcome is kept, so
+    /// a class that failed verification fails again on every use.
+    pub fn verify(&self) -> VerifyResult<()> {
+        self.verification
+            .get_or_init(|| verifier::verify_class(self))
+            .clone()
+    }
+
     pub fn find_declared_method(&self, name: &str, desc: &str) -> Option<MSRef<Method>> {
         let name = SymbolTable::intern(name);
         let desc = SymbolTable::intern(desc);
@@ -433,6 +798,18 @@ impl NormalKlass {
         Some(unsafe { MSRef::from_raw(NonNull::from(method)) })
     }
 
+    /// The method called `name`, if it is the only one this class declares
+    /// under that name and it is signature polymorphic.
+    pub(crate) fn signature_polymorphic_method(&self, name: &SymbolHandle) -> Option<MSRef<Method>> {
+        let mut named = self.methods.iter().filter(|method| method.name.equals(name));
+        let method = named.next()?;
+        if named.next().is_some() || !method.is_signature_polymorphic(self) {
+            return None;
+        }
+
+        Some(unsafe { MSRef::from_raw(NonNull::from(method)) })
+    }
+
     pub fn resolve_method_ref(&self, index: usize) -> ResolveResult<ResolvedMethodRef> {
         let entry = self
             .constant_pool_entry(index)
@@ -443,4 +820,47 @@ impl NormalKlass {
             _ => Err(ResolveError::MismatchCPType),
         }
     }
+
+    pub fn resolve_interface_method_ref(&self, index: usize) -> ResolveResult<ResolvedInterfaceMethodRef> {
+        let entry = self
+            .constant_pool_entry(index)
+            .ok_or(ResolveError::InvalidCPIndex)?;
+
+        match entry {
+            CPEntry::InterfaceMethodRef(entry) => entry.resolve(self),
+            _ => Err(ResolveError::MismatchCPType),
+        }
+    }
+
+    /// Resolve the `CONSTANT_Class` at `index` against this class's loader.
+    pub fn resolve_class(&self, index: usize) -> ResolveResult<MSRef<Klass>> {
+        let entry = self
+            .constant_pool_entry(index)
+            .ok_or(ResolveError::InvalidCPIndex)?;
+
+        match entry {
+            CPEntry::Class(entry) => entry.resolve(self),
+            _ => Err(ResolveError::MismatchCPType),
+        }
+    }
+
+    /// The `CONSTANT_InvokeDynamic` at `index`.  Its call site is linked by
+    /// the execution engine, which owns the thread the bootstrap method runs on.
+    pub fn invoke_dynamic_entry(&self, index: usize) -> ResolveResult<MSRef<InvokeDynamicCPEntry>> {
+        match self.constant_pool_entry(index) {
+            Some(CPEntry::InvokeDynamic(entry)) => unsafe { Ok(MSRef::from_raw(NonNull::from(entry))) },
+            Some(_) => Err(ResolveError::MismatchCPType),
+            None => Err(ResolveError::InvalidCPIndex),
+        }
+    }
+
+    /// The `CONSTANT_Dynamic` at `index`, resolved by the execution engine
+    /// like a call site.
+    pub fn dynamic_entry(&self, index: usize) -> ResolveResult<MSRef<DynamicCPEntry>> {
+        match self.constant_pool_entry(index) {
+            Some(CPEntry::Dynamic(entry)) => unsafe { Ok(MSRef::from_raw(NonNull::from(entry))) },
+            Some(_) => Err(ResolveError::MismatchCPType),
+            None => Err(ResolveError::InvalidCPIndex),
+        }
+    }
 }
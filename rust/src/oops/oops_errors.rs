@@ -1,3 +1,23 @@
+use std::{error::Error, fmt};
+
+use crate::{class_loader::load_error::LoadError, engine::exec_error::JavaExceptionKind};
+
+/// A field or method as a symbolic reference names it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberName {
+    pub class: String,
+    pub name: String,
+    pub desc: String,
+}
+
+impl fmt::Display for MemberName {
+    /// `Foo.bar(I)V` for a method, `Foo.baz:J` for a field.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separator = if self.desc.starts_with('(') { "" } else { ":" };
+        write!(f, "{}.{}{separator}{}", self.class, self.name, self.desc)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ResolveError {
     MismatchCPType,
@@ -7,24 +27,103 @@ pub enum ResolveError {
     NotANormal,
 
     // 运行时解析错误（CP 引用解析阶段）
-    ClassNotFound,
-    MethodNotFound,
-    FieldNotFound,
+    /// No class file defines the class.
+    ClassNotFound(String),
+    /// The class file was found, but loading it failed.
+    ClassLoad(Box<LoadError>),
+    MethodNotFound(MemberName),
+    FieldNotFound(MemberName),
 
     DuplicatedAttr,
+    /// A `LineNumberTable` entry whose `start_pc` is outside the code array.
+    InvalidLineNumberTable,
+    /// A `LocalVariableTable` entry outside the code array or the locals.
+    InvalidLocalVariableTable,
 
-    WrongRefType,
+    /// A class where an interface is required, or the other way round; or
+    /// a method handle whose kind does not match its member.
+    WrongRefType(String),
+    /// JVMS 5.4.4: `referrer` may not access `target`, a class or member.
+    IllegalAccess { referrer: String, target: String },
+    /// `member` claims `host` as its nest host, but `host` is in another
+    /// runtime package, does not list it in `NestMembers`, or is not a class.
+    NotANestMember { member: String, host: String },
 
     InvalidCPIndex,
 
     IllegalMethodName(String),
+
+    /// The class sits deeper in its hierarchy than `ClassFileLimits` allows.
+    HierarchyTooDeep(String),
 }
 
 pub type ResolveResult<T> = Result<T, ResolveError>;
 
+impl ResolveError {
+    /// The linkage error JVMS 5.4.3 specifies for a failed resolution of a
+    /// symbolic reference; `None` for malformed class files and VM failures.
+    pub fn java_exception(&self) -> Option<JavaExceptionKind> {
+        match self {
+            Self::ClassNotFound(_) => Some(JavaExceptionKind::NoClassDefFoundError),
+            Self::ClassLoad(error) => Some(error.java_exception().unwrap_or(JavaExceptionKind::NoClassDefFoundError)),
+            Self::FieldNotFound(_) => Some(JavaExceptionKind::NoSuchFieldError),
+            Self::MethodNotFound(_) => Some(JavaExceptionKind::NoSuchMethodError),
+            Self::WrongRefType(_) => Some(JavaExceptionKind::IncompatibleClassChangeError),
+            Self::IllegalAccess { .. } => Some(JavaExceptionKind::IllegalAccessError),
+            Self::NotANestMember { .. } => Some(JavaExceptionKind::IncompatibleClassChangeError),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MismatchCPType => f.write_str("constant pool entry has the wrong type"),
+            Self::MismatchAttrType => f.write_str("attribute has the wrong type"),
+            Self::InvalidDesc(desc) => write!(f, "invalid descriptor {desc:?}"),
+            Self::UnknownRefKind(kind) => write!(f, "unknown method handle reference kind {kind}"),
+            Self::NotANormal => f.write_str("array or primitive class where a class or interface is required"),
+            Self::ClassNotFound(name) => write!(f, "class {name} not found"),
+            // Transparent: the load error names the class itself.
+            Self::ClassLoad(error) => error.fmt(f),
+            Self::MethodNotFound(member) => write!(f, "method {member} not found"),
+            Self::FieldNotFound(member) => write!(f, "field {member} not found"),
+            Self::DuplicatedAttr => f.write_str("duplicated attribute"),
+            Self::InvalidLineNumberTable => f.write_str("LineNumberTable entry outside the code array"),
+            Self::InvalidLocalVariableTable => f.write_str("LocalVariableTable entry outside the code array or locals"),
+            Self::WrongRefType(name) => write!(f, "{name} is the wrong kind of class, interface or member here"),
+            Self::IllegalAccess { referrer, target } => write!(f, "{referrer} cannot access {target}"),
+            Self::NotANestMember { member, host } => write!(f, "{member} is not a nest member of {host}"),
+            Self::InvalidCPIndex => f.write_str("invalid constant pool index"),
+            Self::IllegalMethodName(name) => write!(f, "illegal method name {name:?}"),
+            Self::HierarchyTooDeep(name) => write!(f, "class hierarchy of {name} is too deep"),
+        }
+    }
+}
+
+impl Error for ResolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ClassLoad(error) => error.source(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClassInitError {
     InvalidTransition,
 }
 
 pub type ClassInitResult<T> = Result<T, ClassInitError>;
+
+impl fmt::Display for ClassInitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidTransition => f.write_str("invalid class initialization state transition"),
+        }
+    }
+}
+
+impl Error for ClassInitError {}
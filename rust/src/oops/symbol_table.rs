@@ -6,16 +6,24 @@ use std::{
 
 use parking_lot::Mutex;
 
+use crate::class_parser::modified_utf8;
+
+/// 符号按 WTF-8 保存：Java 字符串可以含有不成对的代理项，这样的符号不是合法
+/// 的 Rust 字符串，另存一份把它们换成 U+FFFD 的文本供 `utf8()` 使用。
 struct Symbol {
     next: *mut Symbol,
     ref_cnt: AtomicU32,
 
-    utf8: String,
+    wtf8: Box<[u8]>,
+    lossy: Option<Box<str>>,
 }
 
 impl Symbol {
     fn utf8(&self) -> &str {
-        &self.utf8
+        match &self.lossy {
+            Some(lossy) => lossy,
+            None => unsafe { std::str::from_utf8_unchecked(&self.wtf8) },
+        }
     }
 }
 
@@ -38,11 +46,14 @@ unsafe impl Send for Bucket {}
 unsafe impl Sync for Bucket {}
 
 impl Bucket {
-    fn intern(&self, n: &str) -> SymbolHandle {
+    fn intern(&self, n: &[u8]) -> SymbolHandle {
         let s = Symbol {
             next: null_mut(),
             ref_cnt: AtomicU32::new(1),
-            utf8: n.into(),
+            wtf8: n.into(),
+            lossy: std::str::from_utf8(n)
+                .is_err()
+                .then(|| modified_utf8::to_lossy_string(n).into()),
         };
 
         let mut guard = self.symbols.lock();
@@ -51,7 +62,7 @@ impl Bucket {
         loop {
             if iter != null_mut() {
                 unsafe {
-                    if (*iter).utf8 == s.utf8 {
+                    if (*iter).wtf8 == s.wtf8 {
                         (*iter).inc_ref_cnt();
                         return SymbolHandle {
                             symbol: NonNull::new_unchecked(iter),
@@ -86,9 +97,9 @@ static BUCKETS: [Bucket; BUCKETS_COUNT] = [const {
 
 pub struct SymbolTable;
 
-fn hash(n: &str) -> usize {
+fn hash(n: &[u8]) -> usize {
     let mut hash: u32 = 0;
-    for &byte in n.as_bytes() {
+    for &byte in n {
         hash = hash.wrapping_mul(31).wrapping_add(byte as u32);
     }
 
@@ -97,7 +108,16 @@ fn hash(n: &str) -> usize {
 
 impl SymbolTable {
     pub fn intern(n: &str) -> SymbolHandle {
-        BUCKETS[hash(&n)].intern(n)
+        Self::intern_wtf8(n.as_bytes())
+    }
+
+    /// Intern WTF-8 text, which may hold unpaired surrogates.
+    pub fn intern_wtf8(n: &[u8]) -> SymbolHandle {
+        BUCKETS[hash(n)].intern(n)
+    }
+
+    pub fn intern_utf16(units: &[u16]) -> SymbolHandle {
+        Self::intern_wtf8(&modified_utf8::from_utf16(units))
     }
 }
 
@@ -150,9 +170,19 @@ impl SymbolHandle {
         self.symbol == n.symbol
     }
 
+    /// The text, with U+FFFD for each unpaired surrogate.
     pub fn utf8(&self) -> &str {
         unsafe { self.symbol.as_ref().utf8() }
     }
+
+    /// The exact text as WTF-8.
+    pub fn wtf8(&self) -> &[u8] {
+        unsafe { &self.symbol.as_ref().wtf8 }
+    }
+
+    pub fn to_utf16(&self) -> Vec<u16> {
+        modified_utf8::to_utf16(self.wtf8())
+    }
 }
 
 // ── tests ───────────────────────────────────────────────────────────────
@@ -230,6 +260,28 @@ mod tests {
         assert_eq!(a.utf8(), "你好世界");
     }
 
+    #[test]
+    fn intern_supplementary_characters() {
+        let a = SymbolTable::intern("pkg/\u{1d11e}");
+        let b = SymbolTable::intern_utf16(&[0x70, 0x6b, 0x67, 0x2f, 0xd834, 0xdd1e]);
+        assert!(a.equals(&b));
+        assert_eq!(b.utf8(), "pkg/\u{1d11e}");
+        assert_eq!(a.to_utf16(), [0x70, 0x6b, 0x67, 0x2f, 0xd834, 0xdd1e]);
+    }
+
+    #[test]
+    fn intern_unpaired_surrogates() {
+        let lone = SymbolTable::intern_utf16(&[0x61, 0xd834]);
+        let other = SymbolTable::intern_utf16(&[0x61, 0xdd1e]);
+        assert!(!lone.equals(&other));
+        assert_eq!(lone.utf8(), "a\u{fffd}");
+        assert_eq!(lone.wtf8(), [0x61, 0xed, 0xa0, 0xb4]);
+        assert_eq!(lone.to_utf16(), [0x61, 0xd834]);
+
+        // 有损文本不是符号的身份
+        assert!(!lone.equals(&SymbolTable::intern("a\u{fffd}")));
+    }
+
     #[test]
     fn intern_long_string() {
         let long = "a".repeat(10000);
@@ -1,9 +1,54 @@
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
+
+use crate::{class_loader::class_source::ClassSource, class_parser::limits::ClassFileLimits};
 
 #[derive(Debug)]
 pub struct Arguments {
+    /// Bootstrap class path: `:`-separated directories and jars that hold
+    /// `java.*` and the other boot classes.
     pub bs_class_path: String,
-    pub xmx: usize
+    /// Application class path (`-cp`), searched by the system class loader
+    /// after delegating to the bootstrap loader.
+    pub class_path: String,
+    /// Extra bootstrap sources, searched after `bs_class_path`.
+    pub boot_sources: Vec<Arc<dyn ClassSource>>,
+    /// Extra application sources, searched after `class_path`.
+    pub app_sources: Vec<Arc<dyn ClassSource>>,
+    pub xmx: usize,
+    /// Initial heap size.  Recorded only; the heap reserves `xmx` up front.
+    pub xms: usize,
+    /// Java thread stack size in bytes.
+    pub xss: usize,
+    /// `-D` system properties in command-line order; later ones win.
+    pub system_properties: Vec<(String, String)>,
+    /// `-verbose:class`: report every class as it is loaded.
+    pub verbose_class: bool,
+    /// Verify classes of the bootstrap loader.  Off by default, as in
+    /// HotSpot: boot classes are trusted.
+    pub bytecode_verification_local: bool,
+    /// Verify classes of every other loader.
+    pub bytecode_verification_remote: bool,
+    /// `-XX:Max*` bounds on the class files the VM accepts.
+    pub class_file_limits: ClassFileLimits,
+}
+
+impl Default for Arguments {
+    fn default() -> Self {
+        Self {
+            bs_class_path: String::new(),
+            class_path: String::new(),
+            boot_sources: Vec::new(),
+            app_sources: Vec::new(),
+            xmx: 64 * 1024 * 1024,
+            xms: 0,
+            xss: 1024 * 1024,
+            system_properties: Vec::new(),
+            verbose_class: false,
+            bytecode_verification_local: false,
+            bytecode_verification_remote: true,
+            class_file_limits: ClassFileLimits::default(),
+        }
+    }
 }
 
 static ARGUMENTS: OnceLock<Arguments> = OnceLock::new();
@@ -12,8 +57,21 @@ impl Arguments {
     pub fn init(args: Arguments) {
         ARGUMENTS.set(args).unwrap()
     }
-    
+
     pub fn get() -> &'static Arguments {
         ARGUMENTS.get().unwrap()
     }
+
+    /// The arguments, if the VM has been started.
+    pub fn try_get() -> Option<&'static Arguments> {
+        ARGUMENTS.get()
+    }
+
+    pub fn system_property(&self, key: &str) -> Option<&str> {
+        self.system_properties
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
 }
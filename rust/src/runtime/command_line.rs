@@ -0,0 +1,249 @@
+//! `java`-compatible command-line parsing for the `klover` launcher.
+
+use std::path::PathBuf;
+
+use crate::runtime::arguments::Arguments;
+
+/// Boot class path used when `-Xbootclasspath:` is not given.
+pub const BOOT_CLASS_PATH_ENV: &str = "KLOVER_BOOT_CLASS_PATH";
+
+pub const USAGE: &str = "\
+Usage: klover [options] <mainclass> [args...]
+           (to execute a class)
+   or  klover [options] -jar <jarfile> [args...]
+           (to execute a jar file)
+
+Options:
+    -cp <path>, -classpath <path>, --class-path <path>
+                      application class search path of directories and jars
+    -Xbootclasspath:<path>
+                      bootstrap class search path (default: $KLOVER_BOOT_CLASS_PATH)
+    -Xbootclasspath/a:<path>
+                      append to the bootstrap class search path
+    -D<name>=<value>  set a system property
+    -verbose:class    print a line for every loaded class
+    -Xverify:<mode>   bytecode verification: none, remote (default) or all
+    -Xmx<size>        maximum heap size, e.g. 64m
+    -Xms<size>        initial heap size
+    -Xss<size>        thread stack size
+    -XX:MaxConstantPoolSize=<n>
+    -XX:MaxCodeLength=<n>
+    -XX:MaxAttributeNesting=<n>
+    -XX:MaxClassHierarchyDepth=<n>
+                      limits on the class files the VM accepts
+    -help, -h, -?     print this message";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchTarget {
+    /// Main class in internal form (`com/example/Main`).
+    MainClass(String),
+    Jar(PathBuf),
+}
+
+#[derive(Debug)]
+pub struct CommandLine {
+    pub arguments: Arguments,
+    pub target: LaunchTarget,
+    pub program_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandLineError {
+    /// `-help` was given.
+    HelpRequested,
+    NoMainClass,
+    MissingValue(String),
+    InvalidSize(String),
+    /// A `-XX:<flag>=<n>` whose value is not a number.
+    InvalidValue(String),
+    UnrecognizedOption(String),
+}
+
+/// Parse `64m`, `512k`, `1g` or a plain byte count.
+pub fn parse_size(text: &str) -> Option<usize> {
+    let (digits, unit) = match text.as_bytes().last()? {
+        b'k' | b'K' => (&text[..text.len() - 1], 1024),
+        b'm' | b'M' => (&text[..text.len() - 1], 1024 * 1024),
+        b'g' | b'G' => (&text[..text.len() - 1], 1024 * 1024 * 1024),
+        _ => (text, 1),
+    };
+
+    digits.parse::<usize>().ok()?.checked_mul(unit)
+}
+
+impl CommandLine {
+    /// Parse the arguments after the program name.  Options must precede the
+    /// main class (or `-jar <file>`); everything after it goes to `main`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CommandLineError> {
+        let mut args = args.into_iter();
+        let mut arguments = Arguments {
+            bs_class_path: std::env::var(BOOT_CLASS_PATH_ENV).unwrap_or_default(),
+            ..Default::default()
+        };
+        let mut class_path = None;
+        let mut boot_append = Vec::new();
+
+        let target = loop {
+            let Some(arg) = args.next() else {
+                return Err(CommandLineError::NoMainClass);
+            };
+
+            match arg.as_str() {
+                "-cp" | "-classpath" | "--class-path" => {
+                    class_path = Some(args.next().ok_or(CommandLineError::MissingValue(arg))?);
+                }
+                "-jar" => {
+                    let jar = args.next().ok_or(CommandLineError::MissingValue(arg))?;
+                    break LaunchTarget::Jar(PathBuf::from(jar));
+                }
+                "-verbose:class" => arguments.verbose_class = true,
+                "-Xverify:none" | "-Xverify:remote" | "-Xverify:all" => {
+                    arguments.bytecode_verification_local = arg == "-Xverify:all";
+                    arguments.bytecode_verification_remote = arg != "-Xverify:none";
+                }
+                "-help" | "-h" | "-?" | "--help" => return Err(CommandLineError::HelpRequested),
+
+                _ if arg.starts_with("-Xbootclasspath/a:") => {
+                    boot_append.push(arg["-Xbootclasspath/a:".len()..].to_string());
+                }
+                _ if arg.starts_with("-Xbootclasspath:") => {
+                    arguments.bs_class_path = arg["-Xbootclasspath:".len()..].to_string();
+                }
+                _ if arg.starts_with("-D") => {
+                    let (key, value) = arg[2..].split_once('=').unwrap_or((&arg[2..], ""));
+                    if key.is_empty() {
+                        return Err(CommandLineError::UnrecognizedOption(arg));
+                    }
+                    arguments.system_properties.push((key.into(), value.into()));
+                }
+                _ if arg.starts_with("-Xmx") || arg.starts_with("-Xms") || arg.starts_with("-Xss") => {
+                    let size = parse_size(&arg[4..])
+                        .filter(|&x| x > 0)
+                        .ok_or_else(|| CommandLineError::InvalidSize(arg.clone()))?;
+                    match &arg[..4] {
+                        "-Xmx" => arguments.xmx = size,
+                        "-Xms" => arguments.xms = size,
+                        _ => arguments.xss = size,
+                    }
+                }
+                _ if arg.starts_with("-XX:") && arg.contains('=') => {
+                    let (flag, value) = arg[4..].split_once('=').unwrap_or_default();
+                    let limits = &mut arguments.class_file_limits;
+                    let limit = match flag {
+                        "MaxConstantPoolSize" => &mut limits.max_constant_pool_entries,
+                        "MaxCodeLength" => &mut limits.max_code_length,
+                        "MaxAttributeNesting" => &mut limits.max_attribute_nesting,
+                        "MaxClassHierarchyDepth" => &mut limits.max_class_hierarchy_depth,
+                        _ => return Err(CommandLineError::UnrecognizedOption(arg)),
+                    };
+                    *limit = value.parse().map_err(|_| CommandLineError::InvalidValue(arg.clone()))?;
+                }
+                _ if arg.starts_with('-') => return Err(CommandLineError::UnrecognizedOption(arg)),
+
+                _ => break LaunchTarget::MainClass(arg.replace('.', "/")),
+            }
+        };
+
+        // 与 java 一致：未指定 -cp 时使用 CLASSPATH 环境变量，否则为当前目录。
+        arguments.class_path = class_path
+            .or_else(|| std::env::var("CLASSPATH").ok())
+            .unwrap_or_else(|| ".".into());
+        for path in boot_append {
+            if !arguments.bs_class_path.is_empty() {
+                arguments.bs_class_path.push(':');
+            }
+            arguments.bs_class_path.push_str(&path);
+        }
+
+        Ok(Self {
+            arguments,
+            target,
+            program_args: args.collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class_parser::limits::ClassFileLimits;
+
+    fn parse(args: &[&str]) -> Result<CommandLine, CommandLineError> {
+        CommandLine::parse(args.iter().map(|x| x.to_string()))
+    }
+
+    #[test]
+    fn parses_options_main_class_and_program_arguments() {
+        let cl = parse(&[
+            "-cp", "a:b.jar", "-Xmx128m", "-Xss512k", "-Dfoo=bar", "-Dempty", "-verbose:class",
+            "-Xbootclasspath:boot", "-Xbootclasspath/a:more.jar", "com.example.Main", "-cp", "x",
+        ])
+        .unwrap();
+
+        assert_eq!(cl.target, LaunchTarget::MainClass("com/example/Main".into()));
+        assert_eq!(cl.program_args, ["-cp", "x"]);
+        assert_eq!(cl.arguments.class_path, "a:b.jar");
+        assert_eq!(cl.arguments.bs_class_path, "boot:more.jar");
+        assert_eq!(cl.arguments.xmx, 128 * 1024 * 1024);
+        assert_eq!(cl.arguments.xss, 512 * 1024);
+        assert_eq!(cl.arguments.system_property("foo"), Some("bar"));
+        assert_eq!(cl.arguments.system_property("empty"), Some(""));
+        assert!(cl.arguments.verbose_class);
+        assert!(!cl.arguments.bytecode_verification_local);
+        assert!(cl.arguments.bytecode_verification_remote);
+    }
+
+    #[test]
+    fn verify_modes_select_the_verified_loaders() {
+        let modes = |arg: &str| {
+            let arguments = parse(&[arg, "Main"]).unwrap().arguments;
+            (arguments.bytecode_verification_local, arguments.bytecode_verification_remote)
+        };
+
+        assert_eq!(modes("-Xverify:none"), (false, false));
+        assert_eq!(modes("-Xverify:remote"), (false, true));
+        assert_eq!(modes("-Xverify:all"), (true, true));
+        assert!(parse(&["-Xverify:some", "Main"]).is_err());
+    }
+
+    #[test]
+    fn class_file_limits_are_configurable() {
+        let limits = parse(&["-XX:MaxCodeLength=1000", "-XX:MaxClassHierarchyDepth=16", "Main"])
+            .unwrap()
+            .arguments
+            .class_file_limits;
+
+        assert_eq!(limits.max_code_length, 1000);
+        assert_eq!(limits.max_class_hierarchy_depth, 16);
+        assert_eq!(limits.max_attribute_nesting, ClassFileLimits::default().max_attribute_nesting);
+        assert_eq!(
+            parse(&["-XX:MaxCodeLength=big", "Main"]).unwrap_err(),
+            CommandLineError::InvalidValue("-XX:MaxCodeLength=big".into())
+        );
+        assert!(parse(&["-XX:MaxHeapSize=1", "Main"]).is_err());
+    }
+
+    #[test]
+    fn jar_target_consumes_the_next_argument() {
+        let cl = parse(&["-jar", "app.jar", "arg"]).unwrap();
+
+        assert_eq!(cl.target, LaunchTarget::Jar("app.jar".into()));
+        assert_eq!(cl.program_args, ["arg"]);
+    }
+
+    #[test]
+    fn reports_malformed_command_lines() {
+        assert_eq!(parse(&[]).unwrap_err(), CommandLineError::NoMainClass);
+        assert_eq!(parse(&["-cp"]).unwrap_err(), CommandLineError::MissingValue("-cp".into()));
+        assert_eq!(parse(&["-Xmx12q", "Main"]).unwrap_err(), CommandLineError::InvalidSize("-Xmx12q".into()));
+        assert_eq!(parse(&["-server", "Main"]).unwrap_err(), CommandLineError::UnrecognizedOption("-server".into()));
+    }
+
+    #[test]
+    fn sizes_accept_unit_suffixes() {
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("2K"), Some(2048));
+        assert_eq!(parse_size("1g"), Some(1 << 30));
+        assert_eq!(parse_size("m"), None);
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
     engine::{
+        call_site::CallSiteLinkFrame,
         class_init::ClassInitFrame,
         exec_error::{ExecError, ExecResult},
         interpreter::interpreter_frame::InterpreterFrame,
+        method_handle::ConstructFrame,
     },
+    oops::cp_entry::DynamicCPEntry,
     runtime::runtime_error::{StackError, StackResult},
 };
 
@@ -11,6 +14,8 @@ use crate::{
 pub(crate) enum JavaFrame {
     Interpreter(InterpreterFrame),
     ClassInit(ClassInitFrame),
+    CallSiteLink(CallSiteLinkFrame),
+    Construct(ConstructFrame),
 }
 
 impl JavaFrame {
@@ -19,7 +24,7 @@ impl JavaFrame {
             Self::Interpreter(x) => x.reserved_slots(),
             // Control frames still consume one logical slot so an initialization
             // cycle cannot bypass the stack limit with zero-sized frames.
-            Self::ClassInit(_) => 1,
+            Self::ClassInit(_) | Self::CallSiteLink(_) | Self::Construct(_) => 1,
         }
     }
 }
@@ -67,7 +72,19 @@ impl JavaStack {
     }
 
     pub(crate) fn push_class_init(&mut self, frame: ClassInitFrame) -> StackResult<()> {
-        let required = 1;
+        self.push_control(JavaFrame::ClassInit(frame))
+    }
+
+    pub(crate) fn push_call_site_link(&mut self, frame: CallSiteLinkFrame) -> StackResult<()> {
+        self.push_control(JavaFrame::CallSiteLink(frame))
+    }
+
+    pub(crate) fn push_construct(&mut self, frame: ConstructFrame) -> StackResult<()> {
+        self.push_control(JavaFrame::Construct(frame))
+    }
+
+    fn push_control(&mut self, frame: JavaFrame) -> StackResult<()> {
+        let required = frame.reserved_slots();
         let new_used = self
             .used_slots
             .checked_add(required)
@@ -78,7 +95,7 @@ impl JavaStack {
         }
 
         self.used_slots = new_used;
-        self.frames.push(JavaFrame::ClassInit(frame));
+        self.frames.push(frame);
         Ok(())
     }
 
@@ -129,10 +146,26 @@ impl JavaStack {
         }
     }
 
+    /// Interpreter frames from the innermost outwards.
+    pub fn interpreter_frames(&self) -> impl Iterator<Item = &InterpreterFrame> {
+        self.frames.iter().rev().filter_map(|frame| match frame {
+            JavaFrame::Interpreter(x) => Some(x),
+            JavaFrame::ClassInit(_) | JavaFrame::CallSiteLink(_) | JavaFrame::Construct(_) => None,
+        })
+    }
+
     pub(crate) fn current_is_class_init(&self) -> bool {
         matches!(self.frames.last(), Some(JavaFrame::ClassInit(_)))
     }
 
+    pub(crate) fn current_is_call_site_link(&self) -> bool {
+        matches!(self.frames.last(), Some(JavaFrame::CallSiteLink(_)))
+    }
+
+    pub(crate) fn current_is_construct(&self) -> bool {
+        matches!(self.frames.last(), Some(JavaFrame::Construct(_)))
+    }
+
     pub(crate) fn current_class_init(&self) -> StackResult<&ClassInitFrame> {
         match self.frames.last() {
             Some(JavaFrame::ClassInit(frame)) => Ok(frame),
@@ -146,4 +179,18 @@ impl JavaStack {
             _ => Err(StackError::Empty),
         }
     }
+
+    pub(crate) fn current_call_site_link(&self) -> StackResult<&CallSiteLinkFrame> {
+        match self.frames.last() {
+            Some(JavaFrame::CallSiteLink(frame)) => Ok(frame),
+            _ => Err(StackError::Empty),
+        }
+    }
+
+    /// Is a bootstrap method for `constant` already running on this stack?
+    pub(crate) fn is_linking(&self, constant: &DynamicCPEntry) -> bool {
+        self.frames
+            .iter()
+            .any(|frame| matches!(frame, JavaFrame::CallSiteLink(x) if x.is_linking(constant)))
+    }
 }
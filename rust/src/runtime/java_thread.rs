@@ -30,9 +30,14 @@ impl JavaThreadID {
 pub struct JavaThread {
     pub id: JavaThreadID,
     pub state: JavaThreadState,
+    /// Shown in `Exception in thread "<name>"` reports.
+    pub name: String,
     
     pub stack: JavaStack,
     pub pending_exception: Option<PendingException>,
+    /// Set by `System.exit`; the dispatcher unwinds the thread before the
+    /// next instruction.
+    pub exit_request: Option<i32>,
 
     pub interrupted: bool
 }
@@ -45,8 +50,10 @@ impl JavaThread {
         Self {
             id,
             state: JavaThreadState::New,
+            name: format!("Thread-{}", id.as_u64()),
             stack: JavaStack::new(stack_limit),
             pending_exception: None,
+            exit_request: None,
             interrupted: false,
         }
     }
@@ -55,6 +62,14 @@ impl JavaThread {
         self.id
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
     pub fn state(&self) -> JavaThreadState {
         self.state
     }
@@ -0,0 +1,167 @@
+//! Program entry: locating `main(String[])` and running it on a fresh thread.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD,
+        class_path::CLASS_PATH_SEPARATOR,
+        manifest::{MANIFEST_PATH, Manifest},
+        system_cld::SystemCLD,
+        zip::zip_archive::ZipArchive,
+    },
+    engine::{
+        call::Invocation,
+        exec_dispatcher::ExecDispatcher,
+        outcome::{RunOutcome, ThreadExit},
+        resolved_method::ResolvedMethod,
+        slot::Slot,
+    },
+    gc_bindings::oop_handle::NObjPtr,
+    oops::{acc_flags::AccFlags, array_klass::ArrayKlass, java_string::JavaString},
+    runtime::{
+        arguments::Arguments,
+        runtime_error::{LaunchError, LaunchResult},
+        thread_manager::ThreadManager,
+    },
+};
+
+const MAIN_NAME: &str = "main";
+const MAIN_DESC: &str = "([Ljava/lang/String;)V";
+const STRING_ARRAY_CLASS: &str = "[Ljava/lang/String;";
+
+/// Bytecodes executed per `run_quantum` call.
+const QUANTUM: usize = 1024;
+
+/// What `-jar app.jar` resolves to: the manifest's `Main-Class`, and the
+/// application class path made of the jar followed by its `Class-Path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JarLaunch {
+    pub main_class: String,
+    pub class_path: String,
+}
+
+impl JarLaunch {
+    pub fn from_jar(jar: &Path) -> LaunchResult<Self> {
+        let archive = ZipArchive::open(jar).map_err(LaunchError::InvalidJar)?;
+        let bytes = archive
+            .read(MANIFEST_PATH)
+            .map_err(LaunchError::InvalidJar)?
+            .ok_or(LaunchError::NoManifest)?;
+        let manifest = Manifest::parse(&bytes).map_err(LaunchError::InvalidManifest)?;
+
+        let main_class = manifest.main_class().ok_or(LaunchError::NoMainClass)?;
+
+        // Class-Path 中的相对 URL 以 jar 所在目录为基准。
+        let base = jar.parent().unwrap_or(Path::new(""));
+        let mut entries = vec![jar.to_path_buf()];
+        entries.extend(manifest.class_path().into_iter().map(|url| resolve_url(base, url)));
+
+        let class_path = entries
+            .iter()
+            .map(|x| x.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(&CLASS_PATH_SEPARATOR.to_string());
+
+        Ok(Self {
+            main_class,
+            class_path,
+        })
+    }
+}
+
+/// Turn a `Class-Path` URL into a file path: strip a `file:` scheme, decode
+/// `%XX` escapes, and resolve relative paths against `base`.
+fn resolve_url(base: &Path, url: &str) -> PathBuf {
+    let path = url.strip_prefix("file:").unwrap_or(url);
+    let path = PathBuf::from(percent_decode(path));
+
+    if path.is_absolute() {
+        path
+    } else {
+        base.join(path)
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+pub struct Launcher;
+
+impl Launcher {
+    /// Load `main_class` (internal form) through the system loader and find
+    /// its `public static void main(String[])`.
+    pub fn find_main(main_class: &str) -> LaunchResult<ResolvedMethod> {
+        let holder = SystemCLD::find_class(main_class)?
+            .as_normal_ref()
+            .ok_or_else(|| LaunchError::MainNotFound(main_class.into()))?;
+
+        let method = holder
+            .find_declared_method(MAIN_NAME, MAIN_DESC)
+            .filter(|m| m.acc_flags.contains(AccFlags::ACC_PUBLIC | AccFlags::ACC_STATIC))
+            .ok_or_else(|| LaunchError::MainNotFound(main_class.into()))?;
+
+        Ok(ResolvedMethod::new(holder, method))
+    }
+
+    /// Build the `String[]` handed to `main`.
+    pub fn main_args(args: &[String]) -> LaunchResult<NObjPtr> {
+        let klass = BootstrapCLD::find_class(STRING_ARRAY_CLASS)?;
+        let array_klass = klass
+            .as_array()
+            .expect("descriptor starting with '[' loads an array class");
+
+        let array = array_klass.allocate(&klass, args.len());
+        for (index, arg) in args.iter().enumerate() {
+            ArrayKlass::write_ref(array, index, JavaString::create(arg)?)?;
+        }
+
+        Ok(array)
+    }
+
+    /// Run `main_class.main(args)` to completion on a new thread.
+    pub fn run_main(main_class: &str, args: &[String]) -> LaunchResult<ThreadExit> {
+        let target = Self::find_main(main_class)?;
+        let args = vec![Slot::reference(Self::main_args(args)?)];
+
+        // -Xss 以字节计，Java 栈以 slot 计。
+        let stack_slots = Arguments::get().xss / size_of::<Slot>();
+        let mut manager = ThreadManager::new(stack_slots);
+        let mut thread = manager.create_thread()?;
+        thread.set_name("main");
+        thread.start()?;
+
+        let mut dispatcher = ExecDispatcher::new();
+        dispatcher.enter_root(&mut thread, Invocation { target, args })?;
+
+        loop {
+            match dispatcher.run_quantum(&mut thread, QUANTUM)? {
+                RunOutcome::QuantumExpired => continue,
+                RunOutcome::Terminated(exit) => return Ok(exit),
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 pub mod arguments;
+pub mod command_line;
 pub mod java_thread;
 pub mod java_stack;
+pub mod launcher;
 pub mod runtime_error;
 pub mod thread_manager;
 pub mod vm;
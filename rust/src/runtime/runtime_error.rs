@@ -1,3 +1,10 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    class_loader::{load_error::LoadError, manifest::ManifestError, zip::zip_error::ZipError},
+    engine::exec_error::ExecError,
+};
+
 #[derive(Debug)]
 pub enum ThreadError {
     InvalidID,
@@ -7,11 +14,95 @@ pub enum ThreadError {
 
 pub type ThreadResult<T> = Result<T, ThreadError>;
 
+impl fmt::Display for ThreadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidID => f.write_str("no thread with this id"),
+            Self::IDExhausted => f.write_str("thread ids exhausted"),
+            Self::AlreadyStarted => f.write_str("thread already started"),
+        }
+    }
+}
+
+impl Error for ThreadError {}
+
 #[derive(Debug)]
 pub enum StackError {
     Overflow,
     Empty,
 }
 
-
 pub type StackResult<T> = Result<T, StackError>;
+
+impl fmt::Display for StackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => f.write_str("Java stack overflow"),
+            Self::Empty => f.write_str("Java stack is empty"),
+        }
+    }
+}
+
+impl Error for StackError {}
+
+#[derive(Debug)]
+pub enum LaunchError {
+    /// The jar given to `-jar` could not be opened as an archive.
+    InvalidJar(ZipError),
+    NoManifest,
+    InvalidManifest(ManifestError),
+    NoMainClass,
+    Load(LoadError),
+    /// The main class has no `public static void main(String[])`.
+    MainNotFound(String),
+    Thread(ThreadError),
+    Exec(ExecError),
+}
+
+impl From<LoadError> for LaunchError {
+    fn from(value: LoadError) -> Self {
+        Self::Load(value)
+    }
+}
+
+impl From<ThreadError> for LaunchError {
+    fn from(value: ThreadError) -> Self {
+        Self::Thread(value)
+    }
+}
+
+impl From<ExecError> for LaunchError {
+    fn from(value: ExecError) -> Self {
+        Self::Exec(value)
+    }
+}
+
+pub type LaunchResult<T> = Result<T, LaunchError>;
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJar(_) => f.write_str("cannot read the archive"),
+            Self::NoManifest => f.write_str("jar file has no manifest"),
+            Self::InvalidManifest(_) => f.write_str("invalid jar manifest"),
+            Self::NoMainClass => f.write_str("no Main-Class manifest attribute"),
+            Self::Load(error) => error.fmt(f),
+            Self::MainNotFound(name) => write!(f, "main method not found in class {name}"),
+            Self::Thread(error) => error.fmt(f),
+            Self::Exec(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for LaunchError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidJar(error) => Some(error),
+            Self::InvalidManifest(error) => Some(error),
+            Self::Load(error) => error.source(),
+            Self::Thread(error) => error.source(),
+            Self::Exec(error) => error.source(),
+            Self::NoManifest | Self::NoMainClass | Self::MainNotFound(_) => None,
+        }
+    }
+}
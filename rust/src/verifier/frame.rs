@@ -0,0 +1,256 @@
+//! Type states: the verification types of the locals and the operand stack
+//! at one instruction (JVMS 4.10.1.3).
+
+use crate::{
+    oops::normal_klass::NormalKlass,
+    verifier::{
+        verification_type::{OBJECT_CLASS, VerificationType},
+        verify_error::VerifyErrorKind,
+    },
+};
+
+type Result<T> = std::result::Result<T, VerifyErrorKind>;
+
+fn bad_type(expected: impl ToString, found: &VerificationType) -> VerifyErrorKind {
+    VerifyErrorKind::BadType {
+        expected: expected.to_string(),
+        found: found.to_string(),
+    }
+}
+
+/// Locals are exactly `max_locals` slots.  The stack holds slots too, so on
+/// the stack `Top` only ever appears as the second half of a `long` or
+/// `double`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub locals: Vec<VerificationType>,
+    pub stack: Vec<VerificationType>,
+    /// `flagThisUninit`: a constructor has not called `super(...)` yet.
+    pub this_uninit: bool,
+}
+
+impl Frame {
+    /// Expand the JVMS list form, where `long` and `double` are written
+    /// once, into slots; the locals are padded to `max_locals` with `Top`.
+    pub fn from_lists(
+        locals: &[VerificationType],
+        stack: &[VerificationType],
+        max_locals: usize,
+    ) -> Result<Self> {
+        let expand = |types: &[VerificationType]| {
+            let mut slots = Vec::with_capacity(types.len());
+            for ty in types {
+                slots.push(ty.clone());
+                if ty.is_category2() {
+                    slots.push(VerificationType::Top);
+                }
+            }
+            slots
+        };
+
+        let mut local_slots = expand(locals);
+        if local_slots.len() > max_locals {
+            return Err(VerifyErrorKind::InvalidLocalIndex(local_slots.len() - 1));
+        }
+        local_slots.resize(max_locals, VerificationType::Top);
+
+        Ok(Self {
+            this_uninit: locals.contains(&VerificationType::UninitializedThis),
+            locals: local_slots,
+            stack: expand(stack),
+        })
+    }
+
+    /// JVMS 4.10.1.4 `frameIsAssignable`: every slot of this state may flow
+    /// into `target`, and `target` keeps `this` uninitialized if this does.
+    pub fn is_assignable_to(&self, target: &Frame, context: &NormalKlass) -> Result<bool> {
+        if self.stack.len() != target.stack.len() || (self.this_uninit && !target.this_uninit) {
+            return Ok(false);
+        }
+
+        let slots = self.locals.iter().zip(&target.locals);
+        for (from, to) in slots.chain(self.stack.iter().zip(&target.stack)) {
+            if !from.is_assignable_to(to, context)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// JVMS 4.10.2.2: the state where this and `other` meet.  Locals that
+    /// do not merge become `Top`; `None` if the stacks do not merge.
+    pub fn merge(&self, other: &Frame, context: &NormalKlass) -> Result<Option<Frame>> {
+        if self.stack.len() != other.stack.len() {
+            return Ok(None);
+        }
+
+        let mut stack = Vec::with_capacity(self.stack.len());
+        for (a, b) in self.stack.iter().zip(&other.stack) {
+            match a.merge(b, context)? {
+                Some(ty) => stack.push(ty),
+                None => return Ok(None),
+            }
+        }
+
+        let mut locals = Vec::with_capacity(self.locals.len());
+        for (a, b) in self.locals.iter().zip(&other.locals) {
+            locals.push(a.merge(b, context)?.unwrap_or(VerificationType::Top));
+        }
+
+        Ok(Some(Frame {
+            locals,
+            stack,
+            this_uninit: self.this_uninit || other.this_uninit,
+        }))
+    }
+
+    pub fn push(&mut self, ty: VerificationType) {
+        let wide = ty.is_category2();
+        self.stack.push(ty);
+        if wide {
+            self.stack.push(VerificationType::Top);
+        }
+    }
+
+    /// Pop one raw slot.
+    pub fn pop_slot(&mut self) -> Result<VerificationType> {
+        self.stack.pop().ok_or(VerifyErrorKind::StackUnderflow)
+    }
+
+    /// Pop the top `count` slots for a `pop`/`dup`/`swap` family instruction,
+    /// bottom first.  `cuts` are the positions in that list where the
+    /// instruction splits the slots apart; none may fall inside a `long` or
+    /// `double`.
+    pub fn pop_slots(&mut self, count: usize, cuts: &[usize]) -> Result<Vec<VerificationType>> {
+        let start = self
+            .stack
+            .len()
+            .checked_sub(count)
+            .ok_or(VerifyErrorKind::StackUnderflow)?;
+        let slots = self.stack.split_off(start);
+
+        for &cut in cuts.iter().chain(&[0]) {
+            if slots[cut] == VerificationType::Top {
+                return Err(bad_type("category 1 value", &VerificationType::Top));
+            }
+        }
+
+        Ok(slots)
+    }
+
+    /// Pop a value usable as `expected`, returning its actual type.
+    pub fn pop(&mut self, expected: &VerificationType, context: &NormalKlass) -> Result<VerificationType> {
+        if expected.is_category2() {
+            let upper = self.pop_slot()?;
+            if upper != VerificationType::Top {
+                return Err(bad_type(expected, &upper));
+            }
+        }
+
+        let actual = self.pop_slot()?;
+        if actual == VerificationType::Top || !actual.is_assignable_to(expected, context)? {
+            return Err(bad_type(expected, &actual));
+        }
+
+        Ok(actual)
+    }
+
+    /// Pop any reference, including an uninitialized one.
+    pub fn pop_reference(&mut self) -> Result<VerificationType> {
+        let actual = self.pop_slot()?;
+        if !actual.is_reference() {
+            return Err(bad_type("reference", &actual));
+        }
+
+        Ok(actual)
+    }
+
+    /// Pop the operand of `astore`: a reference or a return address.
+    pub fn pop_storable(&mut self) -> Result<VerificationType> {
+        let actual = self.pop_slot()?;
+        if !actual.is_reference() && !matches!(actual, VerificationType::ReturnAddress(_)) {
+            return Err(bad_type("reference or returnAddress", &actual));
+        }
+
+        Ok(actual)
+    }
+
+    /// Pop a constructed object.
+    pub fn pop_object(&mut self, context: &NormalKlass) -> Result<VerificationType> {
+        self.pop(&VerificationType::reference(OBJECT_CLASS), context)
+    }
+
+    /// Pop an array reference or `null`.
+    pub fn pop_array(&mut self) -> Result<VerificationType> {
+        let actual = self.pop_slot()?;
+        if actual != VerificationType::Null && actual.array_descriptor().is_none() {
+            return Err(bad_type("array", &actual));
+        }
+
+        Ok(actual)
+    }
+
+    fn check_local(&self, index: usize, ty: &VerificationType) -> Result<()> {
+        let last = index + ty.is_category2() as usize;
+        if last >= self.locals.len() {
+            return Err(VerifyErrorKind::InvalidLocalIndex(last));
+        }
+
+        Ok(())
+    }
+
+    /// The type of local `index`, which must be usable as `expected`.
+    pub fn load(&self, index: usize, expected: &VerificationType, context: &NormalKlass) -> Result<VerificationType> {
+        self.check_local(index, expected)?;
+
+        let actual = &self.locals[index];
+        if *actual == VerificationType::Top || !actual.is_assignable_to(expected, context)? {
+            return Err(bad_type(expected, actual));
+        }
+
+        Ok(actual.clone())
+    }
+
+    /// Local `index` holding a reference, for `aload`.
+    pub fn load_reference(&self, index: usize) -> Result<VerificationType> {
+        self.check_local(index, &VerificationType::Null)?;
+
+        let actual = &self.locals[index];
+        if !actual.is_reference() {
+            return Err(bad_type("reference", actual));
+        }
+
+        Ok(actual.clone())
+    }
+
+    /// Store into local `index`, invalidating a `long` or `double` whose
+    /// second half it overwrites.
+    pub fn store(&mut self, index: usize, ty: VerificationType) -> Result<()> {
+        self.check_local(index, &ty)?;
+
+        if index > 0 && self.locals[index - 1].is_category2() {
+            self.locals[index - 1] = VerificationType::Top;
+        }
+        if ty.is_category2() {
+            self.locals[index + 1] = VerificationType::Top;
+        }
+        self.locals[index] = ty;
+
+        Ok(())
+    }
+
+    /// A constructor call initialized `uninit`: every copy of it becomes
+    /// `init`.
+    pub fn initialize(&mut self, uninit: &VerificationType, init: VerificationType) {
+        for slot in self.locals.iter_mut().chain(self.stack.iter_mut()) {
+            if slot == uninit {
+                *slot = init.clone();
+            }
+        }
+
+        if *uninit == VerificationType::UninitializedThis {
+            self.this_uninit = false;
+        }
+    }
+}
@@ -0,0 +1,836 @@
+//! The effect of each instruction on a type state, shared by the type
+//! checker and the type inferencer.
+
+use crate::{
+    oops::{
+        acc_flags::AccFlags,
+        attr::Code,
+        cp_entry::CPEntry,
+        desc::{MethodDesc, ReturnDesc},
+        method::Method,
+        normal_klass::NormalKlass,
+        symbol_table::SymbolHandle,
+    },
+    verifier::{
+        frame::Frame,
+        verification_type::{OBJECT_CLASS, VerificationType as Ty},
+        verify_error::{VerifyError, VerifyErrorKind},
+    },
+};
+
+type Result<T> = std::result::Result<T, VerifyErrorKind>;
+
+const THROWABLE_CLASS: &str = "java/lang/Throwable";
+
+pub fn bad_type(expected: impl ToString, found: impl ToString) -> VerifyErrorKind {
+    VerifyErrorKind::BadType {
+        expected: expected.to_string(),
+        found: found.to_string(),
+    }
+}
+
+fn read_u16(code: &[u8], at: usize) -> Result<u16> {
+    match code.get(at..at + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => Err(VerifyErrorKind::TruncatedInstruction),
+    }
+}
+
+pub fn read_i32(code: &[u8], at: usize) -> Result<i32> {
+    match code.get(at..at + 4) {
+        Some(bytes) => Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err(VerifyErrorKind::TruncatedInstruction),
+    }
+}
+
+/// First byte after the padding of a `tableswitch` or `lookupswitch` at
+/// `bci`: its operands are 4-byte aligned from the start of the code.
+fn switch_operands(bci: usize) -> usize {
+    (bci + 4) & !3
+}
+
+/// Length of the instruction at `bci`, checking that it is complete.
+pub fn instruction_len(code: &[u8], bci: usize) -> Result<usize> {
+    let opcode = code[bci];
+    let len = match opcode {
+        0x10 | 0x12 | 0x15..=0x19 | 0x36..=0x3a | 0xa9 | 0xbc => 2,
+        0x11 | 0x13 | 0x14 | 0x84 | 0x99..=0xa8 | 0xb2..=0xb8 | 0xbb | 0xbd | 0xc0 | 0xc1 | 0xc6 | 0xc7 => 3,
+        0xc5 => 4,
+        0xb9 | 0xba | 0xc8 | 0xc9 => 5,
+        0xc4 => match code.get(bci + 1) {
+            Some(0x84) => 6,
+            Some(0x15..=0x19 | 0x36..=0x3a | 0xa9) => 4,
+            Some(&other) => return Err(VerifyErrorKind::InvalidOpcode(other)),
+            None => return Err(VerifyErrorKind::TruncatedInstruction),
+        },
+        0xaa => {
+            let operands = switch_operands(bci);
+            let low = read_i32(code, operands + 4)?;
+            let high = read_i32(code, operands + 8)?;
+            if low > high {
+                return Err(VerifyErrorKind::InvalidSwitch);
+            }
+            operands + 12 + (high as i64 - low as i64 + 1) as usize * 4 - bci
+        }
+        0xab => {
+            let operands = switch_operands(bci);
+            let npairs = read_i32(code, operands + 4)?;
+            if npairs < 0 {
+                return Err(VerifyErrorKind::InvalidSwitch);
+            }
+            operands + 8 + npairs as usize * 8 - bci
+        }
+        0x00..=0xc9 => 1,
+        _ => return Err(VerifyErrorKind::InvalidOpcode(opcode)),
+    };
+
+    if bci + len > code.len() {
+        return Err(VerifyErrorKind::TruncatedInstruction);
+    }
+    Ok(len)
+}
+
+/// What an instruction does to the type state.
+pub struct Step {
+    /// The state after the instruction.
+    pub next: Frame,
+    /// Whether control can reach the next instruction.
+    pub falls_through: bool,
+    /// Branch targets, which see `next` as well.
+    pub branches: Vec<usize>,
+}
+
+/// One method's code and the instruction being checked.
+pub struct Interpreter<'a> {
+    pub klass: &'a NormalKlass,
+    pub method: &'a Method,
+    pub code: &'a Code,
+    pub bytes: &'a [u8],
+    /// Offsets at which an instruction starts.
+    starts: Vec<bool>,
+    /// Offset of the instruction being checked, for errors.
+    pub bci: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(klass: &'a NormalKlass, method: &'a Method, code: &'a Code) -> Self {
+        Self {
+            klass,
+            method,
+            code,
+            bytes: &code.bytecodes,
+            starts: Vec::new(),
+            bci: 0,
+        }
+    }
+
+    /// `kind` at the current instruction.
+    pub fn error(&self, kind: VerifyErrorKind) -> VerifyError {
+        VerifyError {
+            class: self.klass.name().utf8().into(),
+            method: format!("{}{}", self.method.name.utf8(), self.method.desc.raw.utf8()),
+            bci: self.bci,
+            kind,
+        }
+    }
+
+    pub fn is_init(&self) -> bool {
+        self.method.name.utf8() == "<init>"
+    }
+
+    /// Check the code length and find where instructions start.
+    pub fn find_instructions(&mut self) -> Result<()> {
+        let len = self.bytes.len();
+        if len == 0 || len > u16::MAX as usize {
+            return Err(VerifyErrorKind::InvalidCodeLength(len));
+        }
+        self.starts = vec![false; len];
+
+        let mut bci = 0;
+        while bci < len {
+            self.bci = bci;
+            self.starts[bci] = true;
+            bci += instruction_len(self.bytes, bci)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_start(&self, offset: usize) -> bool {
+        self.starts.get(offset).copied().unwrap_or(false)
+    }
+
+    /// Stores change the locals an exception handler sees, so handlers are
+    /// checked against the state after them as well.
+    pub fn is_store(&self, bci: usize) -> bool {
+        match self.bytes[bci] {
+            0x36..=0x4e => true,
+            0xc4 => matches!(self.bytes[bci + 1], 0x36..=0x3a),
+            _ => false,
+        }
+    }
+
+    /// JVMS 4.10.1.6: `this` and the parameters, in slot form and in the
+    /// list form stack map frames are relative to.
+    pub fn initial_frame(&self) -> Result<(Frame, Vec<Ty>)> {
+        let mut list = Vec::new();
+        if !self.method.acc_flags.contains(AccFlags::ACC_STATIC) {
+            let class = self.klass.name();
+            list.push(if self.is_init() && class.utf8() != OBJECT_CLASS {
+                Ty::UninitializedThis
+            } else {
+                Ty::Reference(class.clone())
+            });
+        }
+        list.extend(
+            self.method
+                .desc
+                .params_desc
+                .iter()
+                .map(|x| Ty::from_descriptor(x.raw.utf8())),
+        );
+
+        let frame = Frame::from_lists(&list, &[], self.code.max_locals)?;
+        Ok((frame, list))
+    }
+
+    /// Handlers must cover whole instructions and catch a `Throwable`.
+    pub fn check_handler_ranges(&mut self) -> Result<()> {
+        for handler in self.code.exception_table.iter() {
+            let (start, end, pc) = (
+                handler.start_pc() as usize,
+                handler.end_pc() as usize,
+                handler.handler_pc() as usize,
+            );
+            self.bci = pc;
+
+            let end_ok = end == self.bytes.len() || self.is_start(end);
+            if start >= end || !self.is_start(start) || !end_ok || !self.is_start(pc) {
+                return Err(VerifyErrorKind::InvalidExceptionHandler);
+            }
+
+            let caught = self.caught_type(handler.catch_type().map(|x| x.name()));
+            if !caught.is_assignable_to(&Ty::reference(THROWABLE_CLASS), self.klass)? {
+                return Err(bad_type(THROWABLE_CLASS, caught));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn caught_type(&self, catch_type: Option<&SymbolHandle>) -> Ty {
+        match catch_type {
+            Some(name) => Ty::Reference(name.clone()),
+            None => Ty::reference(THROWABLE_CLASS),
+        }
+    }
+
+    /// The instruction a branch by `offset` from the current one lands on.
+    pub fn branch_target(&self, offset: i32) -> Result<usize> {
+        let target = self.bci as isize + offset as isize;
+        if target < 0 || !self.is_start(target as usize) {
+            return Err(VerifyErrorKind::InvalidBranchTarget(target));
+        }
+
+        Ok(target as usize)
+    }
+
+    pub fn u8_at(&self, at: usize) -> usize {
+        self.bytes[self.bci + at] as usize
+    }
+
+    pub fn u16_at(&self, at: usize) -> Result<usize> {
+        Ok(read_u16(self.bytes, self.bci + at)? as usize)
+    }
+
+    pub fn i16_at(&self, at: usize) -> Result<i32> {
+        Ok(read_u16(self.bytes, self.bci + at)? as i16 as i32)
+    }
+
+    fn cp_entry(&self, index: usize) -> Result<&'a CPEntry> {
+        self.klass
+            .constant_pool_entry(index)
+            .ok_or(VerifyErrorKind::InvalidConstant(index))
+    }
+
+    fn class_at(&self, index: usize) -> Result<SymbolHandle> {
+        match self.cp_entry(index)? {
+            CPEntry::Class(entry) => Ok(entry.name().clone()),
+            _ => Err(VerifyErrorKind::InvalidConstant(index)),
+        }
+    }
+
+    fn return_type(&self) -> Option<Ty> {
+        match &self.method.desc.ret_desc {
+            ReturnDesc::Void => None,
+            ReturnDesc::Type(desc) => Some(Ty::from_descriptor(desc.raw.utf8())),
+        }
+    }
+
+    /// Check the instruction at `self.bci` against `state`.
+    pub fn execute(&self, mut state: Frame) -> Result<Step> {
+        let klass = self.klass;
+        let frame = &mut state;
+        let opcode = self.bytes[self.bci];
+        let mut falls_through = true;
+        let mut branches = Vec::new();
+
+        match opcode {
+            0x00 => {}
+            0x01 => frame.push(Ty::Null),
+            0x02..=0x08 | 0x10 | 0x11 => frame.push(Ty::Integer),
+            0x09 | 0x0a => frame.push(Ty::Long),
+            0x0b..=0x0d => frame.push(Ty::Float),
+            0x0e | 0x0f => frame.push(Ty::Double),
+            0x12 => self.ldc(frame, self.u8_at(1))?,
+            0x13 => self.ldc(frame, self.u16_at(1)?)?,
+            0x14 => {
+                let index = self.u16_at(1)?;
+                match self.cp_entry(index)? {
+                    CPEntry::Long(_) => frame.push(Ty::Long),
+                    CPEntry::Double(_) => frame.push(Ty::Double),
+                    CPEntry::Dynamic(x) if x.desc.slot_count() == 2 => frame.push(Ty::from_descriptor(x.desc.raw.utf8())),
+                    _ => return Err(VerifyErrorKind::InvalidConstant(index)),
+                }
+            }
+
+            // Loads.
+            0x15..=0x18 => {
+                let ty = Self::typed(opcode - 0x15);
+                frame.load(self.u8_at(1), &ty, klass)?;
+                frame.push(ty);
+            }
+            0x19 => {
+                let ty = frame.load_reference(self.u8_at(1))?;
+                frame.push(ty);
+            }
+            0x1a..=0x29 => {
+                let ty = Self::typed((opcode - 0x1a) / 4);
+                frame.load(((opcode - 0x1a) % 4) as usize, &ty, klass)?;
+                frame.push(ty);
+            }
+            0x2a..=0x2d => {
+                let ty = frame.load_reference((opcode - 0x2a) as usize)?;
+                frame.push(ty);
+            }
+            0x2e..=0x35 => self.array_load(frame, opcode)?,
+
+            // Stores.
+            0x36..=0x39 => {
+                let ty = Self::typed(opcode - 0x36);
+                frame.pop(&ty, klass)?;
+                frame.store(self.u8_at(1), ty)?;
+            }
+            0x3a => {
+                let ty = frame.pop_storable()?;
+                frame.store(self.u8_at(1), ty)?;
+            }
+            0x3b..=0x4a => {
+                let ty = Self::typed((opcode - 0x3b) / 4);
+                frame.pop(&ty, klass)?;
+                frame.store(((opcode - 0x3b) % 4) as usize, ty)?;
+            }
+            0x4b..=0x4e => {
+                let ty = frame.pop_storable()?;
+                frame.store((opcode - 0x4b) as usize, ty)?;
+            }
+            0x4f..=0x56 => self.array_store(frame, opcode)?,
+
+            // Operand stack manipulation.
+            0x57 => {
+                frame.pop_slots(1, &[])?;
+            }
+            0x58 => {
+                frame.pop_slots(2, &[])?;
+            }
+            0x59..=0x5f => {
+                let (count, cuts, order): (usize, &[usize], &[usize]) = match opcode {
+                    0x59 => (1, &[], &[0, 0]),
+                    0x5a => (2, &[1], &[1, 0, 1]),
+                    0x5b => (3, &[2], &[2, 0, 1, 2]),
+                    0x5c => (2, &[], &[0, 1, 0, 1]),
+                    0x5d => (3, &[1], &[1, 2, 0, 1, 2]),
+                    0x5e => (4, &[2], &[2, 3, 0, 1, 2, 3]),
+                    _ => (2, &[1], &[1, 0]),
+                };
+                let slots = frame.pop_slots(count, cuts)?;
+                frame.stack.extend(order.iter().map(|&i| slots[i].clone()));
+            }
+
+            // Arithmetic.
+            0x60..=0x73 => {
+                let ty = Self::typed((opcode - 0x60) % 4);
+                frame.pop(&ty, klass)?;
+                frame.pop(&ty, klass)?;
+                frame.push(ty);
+            }
+            0x74..=0x77 => {
+                let ty = Self::typed(opcode - 0x74);
+                frame.pop(&ty, klass)?;
+                frame.push(ty);
+            }
+            0x78..=0x7d => {
+                // 移位量总是 int。
+                let ty = if opcode.is_multiple_of(2) { Ty::Integer } else { Ty::Long };
+                frame.pop(&Ty::Integer, klass)?;
+                frame.pop(&ty, klass)?;
+                frame.push(ty);
+            }
+            0x7e..=0x83 => {
+                let ty = if opcode.is_multiple_of(2) { Ty::Integer } else { Ty::Long };
+                frame.pop(&ty, klass)?;
+                frame.pop(&ty, klass)?;
+                frame.push(ty);
+            }
+            0x84 => {
+                frame.load(self.u8_at(1), &Ty::Integer, klass)?;
+            }
+
+            // Conversions.
+            0x85..=0x90 => {
+                let (from, to) = ((opcode - 0x85) / 3, (opcode - 0x85) % 3);
+                // to 依次跳过 from 自身：i2l i2f i2d / l2i l2f l2d / ...
+                let to = if to >= from { to + 1 } else { to };
+                frame.pop(&Self::typed(from), klass)?;
+                frame.push(Self::typed(to));
+            }
+            0x91..=0x93 => {
+                frame.pop(&Ty::Integer, klass)?;
+                frame.push(Ty::Integer);
+            }
+
+            // Comparisons.
+            0x94..=0x98 => {
+                let ty = match opcode {
+                    0x94 => Ty::Long,
+                    0x95 | 0x96 => Ty::Float,
+                    _ => Ty::Double,
+                };
+                frame.pop(&ty, klass)?;
+                frame.pop(&ty, klass)?;
+                frame.push(Ty::Integer);
+            }
+            0x99..=0x9e => {
+                frame.pop(&Ty::Integer, klass)?;
+                branches.push(self.branch_target(self.i16_at(1)?)?);
+            }
+            0x9f..=0xa4 => {
+                frame.pop(&Ty::Integer, klass)?;
+                frame.pop(&Ty::Integer, klass)?;
+                branches.push(self.branch_target(self.i16_at(1)?)?);
+            }
+            0xa5 | 0xa6 => {
+                frame.pop_reference()?;
+                frame.pop_reference()?;
+                branches.push(self.branch_target(self.i16_at(1)?)?);
+            }
+            0xc6 | 0xc7 => {
+                frame.pop_reference()?;
+                branches.push(self.branch_target(self.i16_at(1)?)?);
+            }
+
+            // Control transfer.
+            0xa7 => {
+                branches.push(self.branch_target(self.i16_at(1)?)?);
+                falls_through = false;
+            }
+            0xc8 => {
+                branches.push(self.branch_target(read_i32(self.bytes, self.bci + 1)?)?);
+                falls_through = false;
+            }
+            0xa8 | 0xa9 | 0xc9 => return Err(VerifyErrorKind::Subroutine),
+            0xaa | 0xab => {
+                frame.pop(&Ty::Integer, klass)?;
+                branches = self.switch_targets(opcode)?;
+                falls_through = false;
+            }
+            0xac..=0xb1 => {
+                self.check_return(frame, opcode)?;
+                falls_through = false;
+            }
+
+            // Fields.
+            0xb2..=0xb5 => self.field_access(frame, opcode)?,
+
+            // Invocation.
+            0xb6..=0xba => self.invoke(frame, opcode)?,
+
+            // Objects and arrays.
+            0xbb => {
+                let index = self.u16_at(1)?;
+                if self.class_at(index)?.utf8().starts_with('[') {
+                    return Err(VerifyErrorKind::InvalidConstant(index));
+                }
+                // JVMS 4.10.1.9 new：同一 new 的旧值不能仍在栈上，局部变量中的旧值作废。
+                let created = Ty::Uninitialized(self.bci as u16);
+                if frame.stack.contains(&created) {
+                    return Err(bad_type("no uninitialized object from this new", &created));
+                }
+                frame.initialize(&created, Ty::Top);
+                frame.push(created);
+            }
+            0xbc => {
+                let desc = match self.u8_at(1) {
+                    4 => "[Z",
+                    5 => "[C",
+                    6 => "[F",
+                    7 => "[D",
+                    8 => "[B",
+                    9 => "[S",
+                    10 => "[I",
+                    11 => "[J",
+                    _ => return Err(VerifyErrorKind::InvalidOpcode(opcode)),
+                };
+                frame.pop(&Ty::Integer, klass)?;
+                frame.push(Ty::reference(desc));
+            }
+            0xbd => {
+                let component = self.class_at(self.u16_at(1)?)?;
+                let component = component.utf8();
+                let desc = if component.starts_with('[') {
+                    format!("[{component}")
+                } else {
+                    format!("[L{component};")
+                };
+                frame.pop(&Ty::Integer, klass)?;
+                frame.push(Ty::reference(&desc));
+            }
+            0xbe => {
+                frame.pop_array()?;
+                frame.push(Ty::Integer);
+            }
+            0xbf => {
+                frame.pop(&Ty::reference(THROWABLE_CLASS), klass)?;
+                falls_through = false;
+            }
+            0xc0 => {
+                let class = self.class_at(self.u16_at(1)?)?;
+                frame.pop_object(klass)?;
+                frame.push(Ty::Reference(class));
+            }
+            0xc1 => {
+                self.class_at(self.u16_at(1)?)?;
+                frame.pop_object(klass)?;
+                frame.push(Ty::Integer);
+            }
+            0xc2 | 0xc3 => {
+                frame.pop_object(klass)?;
+            }
+            0xc4 => self.wide(frame)?,
+            0xc5 => {
+                let index = self.u16_at(1)?;
+                let class = self.class_at(index)?;
+                let dimensions = self.u8_at(3);
+                let array_dimensions = class.utf8().bytes().take_while(|&x| x == b'[').count();
+                if dimensions == 0 || dimensions > array_dimensions {
+                    return Err(VerifyErrorKind::InvalidConstant(index));
+                }
+                for _ in 0..dimensions {
+                    frame.pop(&Ty::Integer, klass)?;
+                }
+                frame.push(Ty::Reference(class));
+            }
+
+            _ => return Err(VerifyErrorKind::InvalidOpcode(opcode)),
+        }
+
+        Ok(Step {
+            next: state,
+            falls_through,
+            branches,
+        })
+    }
+
+    /// `int`, `long`, `float`, `double` by the order opcodes list them.
+    fn typed(order: u8) -> Ty {
+        match order {
+            0 => Ty::Integer,
+            1 => Ty::Long,
+            2 => Ty::Float,
+            _ => Ty::Double,
+        }
+    }
+
+    fn ldc(&self, frame: &mut Frame, index: usize) -> Result<()> {
+        let major = self.klass.major_version();
+        let ty = match self.cp_entry(index)? {
+            CPEntry::Integer(_) => Ty::Integer,
+            CPEntry::Float(_) => Ty::Float,
+            CPEntry::StringConstant(_) => Ty::reference("java/lang/String"),
+            CPEntry::Class(_) if major >= 49 => Ty::reference("java/lang/Class"),
+            CPEntry::MethodType(_) => Ty::reference("java/lang/invoke/MethodType"),
+            CPEntry::MethodHandle(_) => Ty::reference("java/lang/invoke/MethodHandle"),
+            CPEntry::Dynamic(x) if x.desc.slot_count() == 1 => Ty::from_descriptor(x.desc.raw.utf8()),
+            _ => return Err(VerifyErrorKind::InvalidConstant(index)),
+        };
+        frame.push(ty);
+
+        Ok(())
+    }
+
+    /// The array descriptor an array instruction needs for `opcode`;
+    /// `baload` and `bastore` also accept `boolean[]`.
+    fn array_kinds(opcode: u8) -> &'static [&'static str] {
+        match opcode {
+            0x2e | 0x4f => &["[I"],
+            0x2f | 0x50 => &["[J"],
+            0x30 | 0x51 => &["[F"],
+            0x31 | 0x52 => &["[D"],
+            0x33 | 0x54 => &["[B", "[Z"],
+            0x34 | 0x55 => &["[C"],
+            0x35 | 0x56 => &["[S"],
+            _ => &[],
+        }
+    }
+
+    fn pop_typed_array(frame: &mut Frame, opcode: u8) -> Result<Ty> {
+        let array = frame.pop_array()?;
+        let kinds = Self::array_kinds(opcode);
+
+        match array.array_descriptor() {
+            None => Ok(array),
+            Some(desc) if kinds.is_empty() && matches!(desc.as_bytes()[1], b'L' | b'[') => Ok(array),
+            Some(desc) if kinds.contains(&desc) => Ok(array),
+            Some(_) => Err(bad_type(kinds.first().unwrap_or(&"reference array"), array)),
+        }
+    }
+
+    fn array_load(&self, frame: &mut Frame, opcode: u8) -> Result<()> {
+        frame.pop(&Ty::Integer, self.klass)?;
+        let array = Self::pop_typed_array(frame, opcode)?;
+
+        let element = match opcode {
+            0x32 => match array.array_descriptor() {
+                Some(desc) => Ty::from_descriptor(&desc[1..]),
+                None => Ty::Null,
+            },
+            0x2f => Ty::Long,
+            0x30 => Ty::Float,
+            0x31 => Ty::Double,
+            _ => Ty::Integer,
+        };
+        frame.push(element);
+
+        Ok(())
+    }
+
+    fn array_store(&self, frame: &mut Frame, opcode: u8) -> Result<()> {
+        match opcode {
+            0x53 => frame.pop_object(self.klass)?,
+            0x50 => frame.pop(&Ty::Long, self.klass)?,
+            0x51 => frame.pop(&Ty::Float, self.klass)?,
+            0x52 => frame.pop(&Ty::Double, self.klass)?,
+            _ => frame.pop(&Ty::Integer, self.klass)?,
+        };
+        frame.pop(&Ty::Integer, self.klass)?;
+        Self::pop_typed_array(frame, opcode - 0x4f + 0x2e)?;
+
+        Ok(())
+    }
+
+    fn wide(&self, frame: &mut Frame) -> Result<()> {
+        let opcode = self.bytes[self.bci + 1];
+        let index = self.u16_at(2)?;
+
+        match opcode {
+            0x15..=0x18 => {
+                let ty = Self::typed(opcode - 0x15);
+                frame.load(index, &ty, self.klass)?;
+                frame.push(ty);
+            }
+            0x19 => {
+                let ty = frame.load_reference(index)?;
+                frame.push(ty);
+            }
+            0x36..=0x39 => {
+                let ty = Self::typed(opcode - 0x36);
+                frame.pop(&ty, self.klass)?;
+                frame.store(index, ty)?;
+            }
+            0x3a => {
+                let ty = frame.pop_storable()?;
+                frame.store(index, ty)?;
+            }
+            0x84 => {
+                frame.load(index, &Ty::Integer, self.klass)?;
+            }
+            _ => return Err(VerifyErrorKind::Subroutine),
+        }
+
+        Ok(())
+    }
+
+    pub fn switch_targets(&self, opcode: u8) -> Result<Vec<usize>> {
+        let operands = switch_operands(self.bci);
+        let offset_at = |at: usize| read_i32(self.bytes, operands + at);
+
+        let mut targets = vec![self.branch_target(offset_at(0)?)?];
+        if opcode == 0xaa {
+            let count = offset_at(8)? as i64 - offset_at(4)? as i64 + 1;
+            for i in 0..count as usize {
+                targets.push(self.branch_target(offset_at(12 + i * 4)?)?);
+            }
+        } else {
+            let count = offset_at(4)? as usize;
+            let mut previous = None;
+            for i in 0..count {
+                // JVMS 6.5 lookupswitch：键必须严格递增。
+                let key = offset_at(8 + i * 8)?;
+                if previous.is_some_and(|x| x >= key) {
+                    return Err(VerifyErrorKind::InvalidSwitch);
+                }
+                previous = Some(key);
+                targets.push(self.branch_target(offset_at(12 + i * 8)?)?);
+            }
+        }
+
+        Ok(targets)
+    }
+
+    fn check_return(&self, frame: &mut Frame, opcode: u8) -> Result<()> {
+        let expected = self.return_type();
+
+        match (opcode, &expected) {
+            (0xb1, None) => {
+                if self.is_init() && frame.this_uninit {
+                    return Err(VerifyErrorKind::UninitializedReturn);
+                }
+            }
+            (0xb0, Some(ty)) if ty.is_reference() => {
+                frame.pop(ty, self.klass)?;
+            }
+            (0xac..=0xaf, Some(ty)) if *ty == Self::typed(opcode - 0xac) => {
+                frame.pop(ty, self.klass)?;
+            }
+            _ => return Err(VerifyErrorKind::WrongReturn),
+        }
+
+        Ok(())
+    }
+
+    fn field_access(&self, frame: &mut Frame, opcode: u8) -> Result<()> {
+        let index = self.u16_at(1)?;
+        let CPEntry::FieldRef(field) = self.cp_entry(index)? else {
+            return Err(VerifyErrorKind::InvalidConstant(index));
+        };
+        let ty = Ty::from_descriptor(field.desc().utf8());
+        let holder = Ty::Reference(field.class_name().clone());
+
+        match opcode {
+            0xb2 => frame.push(ty),
+            0xb3 => {
+                frame.pop(&ty, self.klass)?;
+            }
+            0xb4 => {
+                frame.pop(&holder, self.klass)?;
+                frame.push(ty);
+            }
+            _ => {
+                frame.pop(&ty, self.klass)?;
+                // JVMS 4.10.1.9 putfield：构造器可在 super() 之前给本类声明的字段赋值。
+                let declared_here = field.class_name().equals(self.klass.name());
+                if declared_here && frame.stack.last() == Some(&Ty::UninitializedThis) {
+                    frame.pop_slot()?;
+                } else {
+                    frame.pop(&holder, self.klass)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn invoke(&self, frame: &mut Frame, opcode: u8) -> Result<()> {
+        let index = self.u16_at(1)?;
+        let entry = self.cp_entry(index)?;
+
+        let (class, name, desc) = match (opcode, entry) {
+            (0xba, CPEntry::InvokeDynamic(entry)) => {
+                if self.u16_at(3)? != 0 {
+                    return Err(VerifyErrorKind::InvalidOpcode(opcode));
+                }
+                (None, &entry.name, entry.desc.clone())
+            }
+            (0xb6..=0xb8, CPEntry::MethodRef(entry)) => {
+                (Some(entry.class_name()), entry.name(), MethodDesc::from(entry.desc().utf8())?)
+            }
+            // JVMS 4.9.1：版本 52 起 invokespecial/invokestatic 可引用接口方法。
+            (0xb7 | 0xb8, CPEntry::InterfaceMethodRef(entry)) if self.klass.major_version() >= 52 => {
+                (Some(entry.class_name()), entry.name(), MethodDesc::from(entry.desc().utf8())?)
+            }
+            (0xb9, CPEntry::InterfaceMethodRef(entry)) => {
+                (Some(entry.class_name()), entry.name(), MethodDesc::from(entry.desc().utf8())?)
+            }
+            _ => return Err(VerifyErrorKind::InvalidConstant(index)),
+        };
+
+        let is_init_call = opcode == 0xb7 && name.utf8() == "<init>";
+        if name.utf8().starts_with('<') && !is_init_call {
+            return Err(VerifyErrorKind::IllegalMethodName(name.utf8().into()));
+        }
+
+        for param in desc.params_desc.iter().rev() {
+            frame.pop(&Ty::from_descriptor(param.raw.utf8()), self.klass)?;
+        }
+
+        if let Some(class) = class {
+            let holder = Ty::Reference(class.clone());
+            match opcode {
+                0xb9 => {
+                    let count = self.u8_at(3);
+                    if count != 1 + desc.parameter_slot_count() || self.u8_at(4) != 0 {
+                        return Err(VerifyErrorKind::InvalidOpcode(opcode));
+                    }
+                    frame.pop(&holder, self.klass)?;
+                }
+                0xb6 => {
+                    frame.pop(&holder, self.klass)?;
+                }
+                0xb7 if is_init_call => {
+                    if !matches!(desc.ret_desc, ReturnDesc::Void) {
+                        return Err(VerifyErrorKind::BadConstructorCall);
+                    }
+                    self.initialize(frame, class)?;
+                }
+                0xb7 => {
+                    frame.pop(&Ty::Reference(self.klass.name().clone()), self.klass)?;
+                }
+                _ => {}
+            }
+        }
+
+        if let ReturnDesc::Type(ret) = &desc.ret_desc {
+            frame.push(Ty::from_descriptor(ret.raw.utf8()));
+        }
+
+        Ok(())
+    }
+
+    /// `invokespecial` of `class.<init>` on an uninitialized receiver.
+    fn initialize(&self, frame: &mut Frame, class: &SymbolHandle) -> Result<()> {
+        let receiver = frame.pop_slot()?;
+
+        let initialized = match &receiver {
+            // this(...) 或 super(...)。
+            Ty::UninitializedThis => {
+                let this = self.klass.name();
+                let is_super = self.klass.super_klass_ref().is_some_and(|x| x.name().equals(class));
+                if !class.equals(this) && !is_super {
+                    return Err(VerifyErrorKind::BadConstructorCall);
+                }
+                Ty::Reference(this.clone())
+            }
+            Ty::Uninitialized(offset) => {
+                let created = self.class_at(read_u16(self.bytes, *offset as usize + 1)? as usize)?;
+                if !created.equals(class) {
+                    return Err(VerifyErrorKind::BadConstructorCall);
+                }
+                Ty::Reference(created)
+            }
+            other => return Err(bad_type("uninitialized object", other)),
+        };
+        frame.initialize(&receiver, initialized);
+
+        Ok(())
+    }
+}
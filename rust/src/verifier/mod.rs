@@ -0,0 +1,50 @@
+//! Bytecode verification (JVMS 4.10).
+//!
+//! A class is verified once, before any of its code runs; the execution
+//! engine raises `VerifyError` for a class that fails.  Class files from
+//! version 50 on are type checked against their `StackMapTable`; older ones
+//! carry none and are verified by type inference.
+
+pub mod frame;
+pub mod interpreter;
+pub mod stack_map;
+pub mod type_checker;
+pub mod type_inferencer;
+pub mod verification_type;
+pub mod verify_error;
+
+use crate::{
+    oops::normal_klass::NormalKlass,
+    runtime::arguments::Arguments,
+    verifier::verify_error::VerifyResult,
+};
+
+/// First class-file version verified by type checking (JVMS 4.10.1).
+pub const TYPE_CHECKING_MAJOR: u16 = 50;
+
+/// Classes the VM spins itself are trusted, and so are boot classes unless
+/// `-Xverify:all` asks otherwise.
+fn is_trusted(klass: &NormalKlass) -> bool {
+    if klass.host().is_some() {
+        return true;
+    }
+
+    let arguments = Arguments::get();
+    match klass.cld() {
+        None => !arguments.bytecode_verification_local,
+        Some(_) => !arguments.bytecode_verification_remote,
+    }
+}
+
+pub fn verify_class(klass: &NormalKlass) -> VerifyResult<()> {
+    if is_trusted(klass) {
+        return Ok(());
+    }
+
+    match klass.major_version() {
+        major if major < TYPE_CHECKING_MAJOR => type_inferencer::verify_methods(klass),
+        // JVMS 4.10：版本 50 的类型检查失败后可以退回类型推导（同 HotSpot 的 FailOverToOldVerifier）。
+        TYPE_CHECKING_MAJOR => type_checker::verify_methods(klass).or_else(|_| type_inferencer::verify_methods(klass)),
+        _ => type_checker::verify_methods(klass),
+    }
+}
@@ -0,0 +1,97 @@
+//! Expanding a compressed `StackMapTable` into full type states
+//! (JVMS 4.7.4).
+
+use std::collections::BTreeMap;
+
+use crate::{
+    class_parser::stack_map_info::{StackMapFrameInfo, VerificationTypeInfo},
+    oops::{cp_entry::CPEntry, normal_klass::NormalKlass},
+    verifier::{frame::Frame, verification_type::VerificationType, verify_error::VerifyErrorKind},
+};
+
+/// The frames of `table` by offset.  `initial` is the method's implicit
+/// first frame in list form; `is_new` tells whether a `new` instruction
+/// starts at an offset.  Errors carry the offset of the offending frame.
+pub fn expand(
+    table: &[StackMapFrameInfo],
+    initial: Vec<VerificationType>,
+    max_locals: usize,
+    klass: &NormalKlass,
+    is_new: impl Fn(usize) -> bool,
+) -> Result<BTreeMap<usize, Frame>, (usize, VerifyErrorKind)> {
+    let mut frames = BTreeMap::new();
+    let mut locals = initial;
+    let mut previous: Option<usize> = None;
+
+    for info in table {
+        let offset = match previous {
+            None => info.offset_delta() as usize,
+            Some(x) => x + info.offset_delta() as usize + 1,
+        };
+        previous = Some(offset);
+
+        let fail = |kind| (offset, kind);
+        let convert_all = |types: &[VerificationTypeInfo]| {
+            types
+                .iter()
+                .map(|x| convert(x, klass, &is_new))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(fail(VerifyErrorKind::InvalidStackMapFrame))
+        };
+
+        let stack = match info {
+            StackMapFrameInfo::Same { .. } => Vec::new(),
+            StackMapFrameInfo::SameLocals1StackItem { stack, .. } => convert_all(std::slice::from_ref(stack))?,
+            StackMapFrameInfo::Chop { k, .. } => {
+                let kept = locals
+                    .len()
+                    .checked_sub(*k as usize)
+                    .ok_or(fail(VerifyErrorKind::InvalidStackMapFrame))?;
+                locals.truncate(kept);
+                Vec::new()
+            }
+            StackMapFrameInfo::Append { locals: appended, .. } => {
+                locals.extend(convert_all(appended)?);
+                Vec::new()
+            }
+            StackMapFrameInfo::Full { locals: full, stack, .. } => {
+                locals = convert_all(full)?;
+                convert_all(stack)?
+            }
+        };
+
+        // 栈上的 Top 只能是 long/double 的高半部分，不能单独出现。
+        if stack.contains(&VerificationType::Top) {
+            return Err(fail(VerifyErrorKind::InvalidStackMapFrame));
+        }
+
+        let frame = Frame::from_lists(&locals, &stack, max_locals).map_err(fail)?;
+        frames.insert(offset, frame);
+    }
+
+    Ok(frames)
+}
+
+fn convert(info: &VerificationTypeInfo, klass: &NormalKlass, is_new: impl Fn(usize) -> bool) -> Option<VerificationType> {
+    let ty = match info {
+        VerificationTypeInfo::Top => VerificationType::Top,
+        VerificationTypeInfo::Integer => VerificationType::Integer,
+        VerificationTypeInfo::Float => VerificationType::Float,
+        VerificationTypeInfo::Long => VerificationType::Long,
+        VerificationTypeInfo::Double => VerificationType::Double,
+        VerificationTypeInfo::Null => VerificationType::Null,
+        VerificationTypeInfo::UninitializedThis => VerificationType::UninitializedThis,
+        VerificationTypeInfo::Object { cpool_idx } => match klass.constant_pool_entry(*cpool_idx as usize)? {
+            CPEntry::Class(entry) => VerificationType::Reference(entry.name().clone()),
+            _ => return None,
+        },
+        VerificationTypeInfo::Uninitialized { offset } => {
+            if !is_new(*offset as usize) {
+                return None;
+            }
+            VerificationType::Uninitialized(*offset)
+        }
+    };
+
+    Some(ty)
+}
@@ -0,0 +1,154 @@
+//! JVMS 4.10.1: verification by type checking.  The code is walked once in
+//! order; the `StackMapTable` supplies the type state at every branch
+//! target and exception handler, so no iteration is needed.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    oops::normal_klass::NormalKlass,
+    verifier::{
+        frame::Frame,
+        interpreter::{Interpreter, instruction_len},
+        stack_map,
+        verification_type::VerificationType as Ty,
+        verify_error::{VerifyErrorKind, VerifyResult},
+    },
+};
+
+type Result<T> = std::result::Result<T, VerifyErrorKind>;
+
+/// Verify every method of `klass` that has code.
+pub fn verify_methods(klass: &NormalKlass) -> VerifyResult<()> {
+    for method in klass.methods() {
+        if let Some(code) = &method.code {
+            let mut checker = TypeChecker {
+                interpreter: Interpreter::new(klass, method, code),
+                frames: BTreeMap::new(),
+            };
+            checker.check_method().map_err(|kind| checker.interpreter.error(kind))?;
+        }
+    }
+
+    Ok(())
+}
+
+struct TypeChecker<'a> {
+    interpreter: Interpreter<'a>,
+    /// Stack map frames by offset.
+    frames: BTreeMap<usize, Frame>,
+}
+
+impl TypeChecker<'_> {
+    fn check_method(&mut self) -> Result<()> {
+        self.interpreter.find_instructions()?;
+        let (initial, initial_list) = self.interpreter.initial_frame()?;
+        self.expand_stack_map(initial_list)?;
+        self.interpreter.check_handler_ranges()?;
+
+        let klass = self.interpreter.klass;
+        let len = self.interpreter.bytes.len();
+        let mut current = Some(initial);
+        let mut bci = 0;
+        while bci < len {
+            self.interpreter.bci = bci;
+            let instruction_len = instruction_len(self.interpreter.bytes, bci)?;
+
+            if let Some(frame) = self.frames.get(&bci) {
+                if let Some(state) = &current
+                    && !state.is_assignable_to(frame, klass)?
+                {
+                    return Err(VerifyErrorKind::FrameMismatch(bci));
+                }
+                current = Some(frame.clone());
+            }
+            // 无条件跳转之后的指令必须有栈映射帧。
+            let Some(state) = current.take() else {
+                return Err(VerifyErrorKind::MissingStackMapFrame(bci));
+            };
+
+            self.check_handlers(&state)?;
+            let step = self.interpreter.execute(state)?;
+            if step.next.stack.len() > self.interpreter.code.max_stack {
+                return Err(VerifyErrorKind::StackOverflow);
+            }
+            for target in step.branches {
+                self.check_target(&step.next, target)?;
+            }
+            if self.interpreter.is_store(bci) {
+                self.check_handlers(&step.next)?;
+            }
+
+            current = step.falls_through.then_some(step.next);
+            bci += instruction_len;
+        }
+
+        if current.is_some() {
+            return Err(VerifyErrorKind::FallsOffCode);
+        }
+        Ok(())
+    }
+
+    fn expand_stack_map(&mut self, initial: Vec<Ty>) -> Result<()> {
+        let interpreter = &mut self.interpreter;
+        let bytes = interpreter.bytes;
+        let code = interpreter.code;
+        let is_new = |offset: usize| interpreter.is_start(offset) && bytes[offset] == 0xbb;
+
+        let frames = stack_map::expand(&code.stack_map_table, initial, code.max_locals, interpreter.klass, is_new);
+        let frames = frames.map_err(|(offset, kind)| {
+            interpreter.bci = offset;
+            kind
+        })?;
+
+        if let Some((&offset, _)) = frames.iter().find(|(offset, _)| !interpreter.is_start(**offset)) {
+            interpreter.bci = offset;
+            return Err(VerifyErrorKind::InvalidStackMapFrame);
+        }
+        if let Some((&offset, _)) = frames.iter().find(|(_, frame)| frame.stack.len() > code.max_stack) {
+            interpreter.bci = offset;
+            return Err(VerifyErrorKind::StackOverflow);
+        }
+
+        self.frames = frames;
+        Ok(())
+    }
+
+    /// JVMS 4.10.1.6: every handler covering the instruction must accept
+    /// its locals with just the exception on the stack.
+    fn check_handlers(&self, state: &Frame) -> Result<()> {
+        let interpreter = &self.interpreter;
+        for handler in interpreter.code.exception_table.iter() {
+            let range = handler.start_pc() as usize..handler.end_pc() as usize;
+            if !range.contains(&interpreter.bci) {
+                continue;
+            }
+
+            let pc = handler.handler_pc() as usize;
+            let target = self.frames.get(&pc).ok_or(VerifyErrorKind::MissingStackMapFrame(pc))?;
+            let thrown = Frame {
+                locals: state.locals.clone(),
+                stack: vec![interpreter.caught_type(handler.catch_type().map(|x| x.name()))],
+                this_uninit: state.this_uninit,
+            };
+            if !thrown.is_assignable_to(target, interpreter.klass)? {
+                return Err(VerifyErrorKind::FrameMismatch(pc));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A branch must land on an instruction with a frame `state` is
+    /// assignable to.
+    fn check_target(&self, state: &Frame, target: usize) -> Result<()> {
+        let frame = self
+            .frames
+            .get(&target)
+            .ok_or(VerifyErrorKind::MissingStackMapFrame(target))?;
+        if !state.is_assignable_to(frame, self.interpreter.klass)? {
+            return Err(VerifyErrorKind::FrameMismatch(target));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,386 @@
+//! JVMS 4.10.2: verification by type inference, for class files without a
+//! `StackMapTable`.  The type states flowing into each instruction are
+//! merged until none changes any more.
+//!
+//! Subroutines are handled as in HotSpot's old verifier: every instruction
+//! belongs to the main code or to exactly one subroutine, and `ret` returns
+//! to the instruction after each `jsr` of its subroutine with the locals
+//! the subroutine touched taken from the `ret` and the rest from the `jsr`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    oops::normal_klass::NormalKlass,
+    verifier::{
+        frame::Frame,
+        interpreter::{Interpreter, bad_type, instruction_len, read_i32},
+        verification_type::VerificationType as Ty,
+        verify_error::{VerifyErrorKind, VerifyResult},
+    },
+};
+
+type Result<T> = std::result::Result<T, VerifyErrorKind>;
+
+/// Verify every method of `klass` that has code.
+pub fn verify_methods(klass: &NormalKlass) -> VerifyResult<()> {
+    for method in klass.methods() {
+        if let Some(code) = &method.code {
+            let mut inferencer = TypeInferencer {
+                interpreter: Interpreter::new(klass, method, code),
+                states: Vec::new(),
+                pending: BTreeSet::new(),
+                owners: Vec::new(),
+                subroutines: BTreeMap::new(),
+            };
+            inferencer
+                .infer_method()
+                .map_err(|kind| inferencer.interpreter.error(kind))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Subroutine {
+    /// The `jsr` instructions calling it.
+    callers: Vec<usize>,
+    /// Locals it reads or writes, including those of the subroutines it
+    /// calls.
+    touched: BTreeSet<usize>,
+    /// The states merged at its `ret` instructions.
+    returned: Option<Frame>,
+}
+
+struct TypeInferencer<'a> {
+    interpreter: Interpreter<'a>,
+    /// Merged state on entry to each instruction reached so far.
+    states: Vec<Option<Frame>>,
+    /// Instructions whose entry state changed since they were last run.
+    pending: BTreeSet<usize>,
+    /// The subroutine each instruction belongs to, by entry offset; `None`
+    /// for the main code.
+    owners: Vec<Option<Option<usize>>>,
+    subroutines: BTreeMap<usize, Subroutine>,
+}
+
+impl TypeInferencer<'_> {
+    fn infer_method(&mut self) -> Result<()> {
+        self.interpreter.find_instructions()?;
+        self.interpreter.check_handler_ranges()?;
+        self.find_subroutines()?;
+
+        let (initial, _) = self.interpreter.initial_frame()?;
+        self.states = vec![None; self.interpreter.bytes.len()];
+        self.propagate(0, initial)?;
+
+        while let Some(bci) = self.pending.pop_first() {
+            self.interpreter.bci = bci;
+            let Some(state) = self.states[bci].clone() else {
+                continue;
+            };
+            self.propagate_to_handlers(bci, &state)?;
+
+            let bytes = self.interpreter.bytes;
+            match (bytes[bci], bytes.get(bci + 1)) {
+                (0xa8 | 0xc9, _) => self.jsr(bci, state)?,
+                (0xa9, _) => self.ret(bci, &state, self.interpreter.u8_at(1))?,
+                (0xc4, Some(0xa9)) => self.ret(bci, &state, self.interpreter.u16_at(2)?)?,
+                _ => {
+                    let step = self.interpreter.execute(state)?;
+                    if step.next.stack.len() > self.interpreter.code.max_stack {
+                        return Err(VerifyErrorKind::StackOverflow);
+                    }
+                    if self.interpreter.is_store(bci) {
+                        self.propagate_to_handlers(bci, &step.next)?;
+                    }
+                    for target in step.branches {
+                        self.propagate(target, step.next.clone())?;
+                    }
+                    if step.falls_through {
+                        self.propagate(self.next_instruction(bci)?, step.next)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn next_instruction(&self, bci: usize) -> Result<usize> {
+        let next = bci + instruction_len(self.interpreter.bytes, bci)?;
+        if next >= self.interpreter.bytes.len() {
+            return Err(VerifyErrorKind::FallsOffCode);
+        }
+
+        Ok(next)
+    }
+
+    /// Merge `state` into the entry state of `target`, scheduling it again
+    /// if that changed.
+    fn propagate(&mut self, target: usize, state: Frame) -> Result<()> {
+        let merged = match &self.states[target] {
+            None => state,
+            Some(old) => {
+                let merged = old
+                    .merge(&state, self.interpreter.klass)?
+                    .ok_or(VerifyErrorKind::FrameMismatch(target))?;
+                if merged == *old {
+                    return Ok(());
+                }
+                merged
+            }
+        };
+
+        self.states[target] = Some(merged);
+        self.pending.insert(target);
+        Ok(())
+    }
+
+    /// Every handler covering `bci` is entered with its locals and just the
+    /// exception on the stack.
+    fn propagate_to_handlers(&mut self, bci: usize, state: &Frame) -> Result<()> {
+        let interpreter = &self.interpreter;
+        let mut entries = Vec::new();
+        for handler in interpreter.code.exception_table.iter() {
+            let range = handler.start_pc() as usize..handler.end_pc() as usize;
+            if range.contains(&bci) {
+                let thrown = Frame {
+                    locals: state.locals.clone(),
+                    stack: vec![interpreter.caught_type(handler.catch_type().map(|x| x.name()))],
+                    this_uninit: state.this_uninit,
+                };
+                entries.push((handler.handler_pc() as usize, thrown));
+            }
+        }
+
+        for (pc, thrown) in entries {
+            self.propagate(pc, thrown)?;
+        }
+        Ok(())
+    }
+
+    /// `jsr` enters the subroutine with its return address pushed.  Once
+    /// the subroutine has returned, control also reaches the next
+    /// instruction.
+    fn jsr(&mut self, bci: usize, state: Frame) -> Result<()> {
+        let entry = self.jsr_target(bci)?;
+        if self.owners[bci] == Some(Some(entry)) {
+            return Err(VerifyErrorKind::Subroutine);
+        }
+
+        let mut called = state.clone();
+        called.push(Ty::ReturnAddress(entry as u16));
+        if called.stack.len() > self.interpreter.code.max_stack {
+            return Err(VerifyErrorKind::StackOverflow);
+        }
+        self.propagate(entry, called)?;
+
+        if let Some(returned) = &self.subroutines[&entry].returned {
+            let after = self.after_return(&state, returned, entry);
+            self.propagate(self.next_instruction(bci)?, after)?;
+        }
+        Ok(())
+    }
+
+    /// `ret` returns from the subroutine it belongs to, through a local
+    /// holding that subroutine's return address.
+    fn ret(&mut self, bci: usize, state: &Frame, index: usize) -> Result<()> {
+        let entry = match state.locals.get(index) {
+            None => return Err(VerifyErrorKind::InvalidLocalIndex(index)),
+            Some(Ty::ReturnAddress(entry)) if self.owners[bci] == Some(Some(*entry as usize)) => *entry as usize,
+            Some(other) => return Err(bad_type("return address of the enclosing subroutine", other)),
+        };
+
+        let subroutine = &self.subroutines[&entry];
+        let returned = match &subroutine.returned {
+            None => state.clone(),
+            Some(old) => old
+                .merge(state, self.interpreter.klass)?
+                .ok_or(VerifyErrorKind::FrameMismatch(bci))?,
+        };
+        if subroutine.returned.as_ref() == Some(&returned) {
+            return Ok(());
+        }
+
+        let callers = subroutine.callers.clone();
+        for caller in callers {
+            if let Some(called) = self.states[caller].clone() {
+                let after = self.after_return(&called, &returned, entry);
+                self.propagate(self.next_instruction(caller)?, after)?;
+            }
+        }
+        if let Some(subroutine) = self.subroutines.get_mut(&entry) {
+            subroutine.returned = Some(returned);
+        }
+        Ok(())
+    }
+
+    /// The state after the `jsr` that had `called` on entry, once the
+    /// subroutine at `entry` returned with `returned`.
+    fn after_return(&self, called: &Frame, returned: &Frame, entry: usize) -> Frame {
+        let touched = &self.subroutines[&entry].touched;
+        let mut locals: Vec<Ty> = (0..called.locals.len())
+            .map(|i| match touched.contains(&i) {
+                true => returned.locals[i].clone(),
+                false => called.locals[i].clone(),
+            })
+            .collect();
+
+        // 两边拼接可能拆开 long/double 的两半。
+        for i in 0..locals.len() {
+            if locals[i].is_category2() && locals.get(i + 1) != Some(&Ty::Top) {
+                locals[i] = Ty::Top;
+            }
+        }
+
+        Frame {
+            locals,
+            stack: returned.stack.clone(),
+            this_uninit: returned.this_uninit,
+        }
+    }
+
+    fn jsr_target(&self, bci: usize) -> Result<usize> {
+        let interpreter = &self.interpreter;
+        let offset = match interpreter.bytes[bci] {
+            0xa8 => interpreter.i16_at(1)?,
+            _ => read_i32(interpreter.bytes, bci + 1)?,
+        };
+
+        interpreter.branch_target(offset)
+    }
+
+    /// Assign every instruction to the main code or a subroutine, and find
+    /// the locals each subroutine touches.
+    fn find_subroutines(&mut self) -> Result<()> {
+        let len = self.interpreter.bytes.len();
+        self.owners = vec![None; len];
+
+        let mut bci = 0;
+        while bci < len {
+            self.interpreter.bci = bci;
+            if matches!(self.interpreter.bytes[bci], 0xa8 | 0xc9) {
+                let entry = self.jsr_target(bci)?;
+                self.subroutines.entry(entry).or_default().callers.push(bci);
+            }
+            bci += instruction_len(self.interpreter.bytes, bci)?;
+        }
+
+        self.mark_owner(0, None)?;
+        let entries: Vec<usize> = self.subroutines.keys().copied().collect();
+        for &entry in &entries {
+            self.mark_owner(entry, Some(entry))?;
+        }
+
+        // 子程序调用的子程序所访问的局部变量也算在调用者头上。
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &entry in &entries {
+                let mut touched = self.subroutines[&entry].touched.clone();
+                for (other, subroutine) in &self.subroutines {
+                    let calls_other = subroutine.callers.iter().any(|&x| self.owners[x] == Some(Some(entry)));
+                    if *other != entry && calls_other {
+                        touched.extend(subroutine.touched.iter().copied());
+                    }
+                }
+                if let Some(subroutine) = self.subroutines.get_mut(&entry)
+                    && touched.len() > subroutine.touched.len()
+                {
+                    subroutine.touched = touched;
+                    changed = true;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mark the instructions reachable from `start` without entering or
+    /// leaving a subroutine as belonging to `owner`.
+    fn mark_owner(&mut self, start: usize, owner: Option<usize>) -> Result<()> {
+        let mut work = vec![start];
+        while let Some(bci) = work.pop() {
+            match self.owners[bci] {
+                Some(x) if x == owner => continue,
+                Some(_) => {
+                    self.interpreter.bci = bci;
+                    return Err(VerifyErrorKind::Subroutine);
+                }
+                None => self.owners[bci] = Some(owner),
+            }
+
+            self.interpreter.bci = bci;
+            let touched = self.touched_locals(bci)?;
+            if let Some(subroutine) = owner.and_then(|x| self.subroutines.get_mut(&x)) {
+                subroutine.touched.extend(touched);
+            }
+            for handler in self.interpreter.code.exception_table.iter() {
+                if (handler.start_pc() as usize..handler.end_pc() as usize).contains(&bci) {
+                    work.push(handler.handler_pc() as usize);
+                }
+            }
+            work.extend(self.successors(bci)?);
+        }
+
+        Ok(())
+    }
+
+    /// Where control goes from `bci` within the same subroutine; a `jsr`
+    /// continues after itself and a `ret` goes nowhere.
+    fn successors(&self, bci: usize) -> Result<Vec<usize>> {
+        let interpreter = &self.interpreter;
+        let opcode = interpreter.bytes[bci];
+        let next = bci + instruction_len(interpreter.bytes, bci)?;
+        let falls_through = next < interpreter.bytes.len();
+
+        let successors = match opcode {
+            0x99..=0xa6 | 0xc6 | 0xc7 => {
+                let target = interpreter.branch_target(interpreter.i16_at(1)?)?;
+                let mut successors = vec![target];
+                successors.extend(falls_through.then_some(next));
+                successors
+            }
+            0xa7 => vec![interpreter.branch_target(interpreter.i16_at(1)?)?],
+            0xc8 => vec![interpreter.branch_target(read_i32(interpreter.bytes, bci + 1)?)?],
+            0xaa | 0xab => interpreter.switch_targets(opcode)?,
+            0xa9 | 0xac..=0xb1 | 0xbf => Vec::new(),
+            0xc4 if interpreter.bytes[bci + 1] == 0xa9 => Vec::new(),
+            _ => falls_through.then_some(next).into_iter().collect(),
+        };
+
+        Ok(successors)
+    }
+
+    /// The local variables the instruction at `bci` reads or writes.
+    fn touched_locals(&self, bci: usize) -> Result<Vec<usize>> {
+        let interpreter = &self.interpreter;
+        let opcode = interpreter.bytes[bci];
+        // 局部变量下标，以及是否为 long/double（占两个槽）。
+        let (index, wide) = match opcode {
+            0x15..=0x19 | 0x36..=0x3a | 0x84 | 0xa9 => {
+                let wide = matches!(opcode, 0x16 | 0x18 | 0x37 | 0x39);
+                (interpreter.u8_at(1), wide)
+            }
+            0x1a..=0x2d => {
+                let order = (opcode - 0x1a) / 4;
+                ((opcode - 0x1a) as usize % 4, order == 1 || order == 3)
+            }
+            0x3b..=0x4e => {
+                let order = (opcode - 0x3b) / 4;
+                ((opcode - 0x3b) as usize % 4, order == 1 || order == 3)
+            }
+            0xc4 => {
+                let wide = matches!(interpreter.bytes[bci + 1], 0x16 | 0x18 | 0x37 | 0x39);
+                (interpreter.u16_at(2)?, wide)
+            }
+            _ => return Ok(Vec::new()),
+        };
+
+        Ok(match wide {
+            true => vec![index, index + 1],
+            false => vec![index],
+        })
+    }
+}
@@ -0,0 +1,211 @@
+//! Verification types (JVMS 4.10.1.2) and the assignability relation
+//! between them.
+
+use std::fmt;
+
+use crate::{
+    class_loader::{bootstrap_cld::BootstrapCLD, load_error::LoadError, ms_api::MSRef},
+    oops::{
+        normal_klass::NormalKlass,
+        oops_errors::{ResolveError, ResolveResult},
+        symbol_table::{SymbolHandle, SymbolTable},
+    },
+};
+
+pub const OBJECT_CLASS: &str = "java/lang/Object";
+
+/// One local variable or operand stack slot.  A `long` or `double` takes two
+/// slots, the second of them `Top`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationType {
+    Top,
+    Integer,
+    Float,
+    Long,
+    Double,
+    Null,
+    /// `this` in a constructor before `super(...)` or `this(...)`.
+    UninitializedThis,
+    /// An object created by the `new` at this offset, not yet constructed.
+    Uninitialized(u16),
+    /// A class name in internal form, or an array descriptor.
+    Reference(SymbolHandle),
+    /// The return address a `jsr` to the subroutine at this offset pushed;
+    /// only the type inferencer produces it.
+    ReturnAddress(u16),
+}
+
+impl VerificationType {
+    pub fn reference(name: &str) -> Self {
+        Self::Reference(SymbolTable::intern(name))
+    }
+
+    /// The type a value of field descriptor `desc` has on the stack;
+    /// `boolean`, `byte`, `char` and `short` widen to `int`.
+    pub fn from_descriptor(desc: &str) -> Self {
+        match desc.as_bytes().first() {
+            Some(b'[') => Self::reference(desc),
+            Some(b'L') => Self::reference(&desc[1..desc.len() - 1]),
+            Some(b'J') => Self::Long,
+            Some(b'D') => Self::Double,
+            Some(b'F') => Self::Float,
+            _ => Self::Integer,
+        }
+    }
+
+    pub fn is_category2(&self) -> bool {
+        matches!(self, Self::Long | Self::Double)
+    }
+
+    /// Any reference, constructed or not.
+    pub fn is_reference(&self) -> bool {
+        matches!(
+            self,
+            Self::Null | Self::UninitializedThis | Self::Uninitialized(_) | Self::Reference(_)
+        )
+    }
+
+    /// The array descriptor, for an array type.
+    pub fn array_descriptor(&self) -> Option<&str> {
+        match self {
+            Self::Reference(name) if name.utf8().starts_with('[') => Some(name.utf8()),
+            _ => None,
+        }
+    }
+
+    /// Can a value of this type be used where `to` is expected?  Classes are
+    /// loaded through `context`'s loader when the names alone cannot tell.
+    pub fn is_assignable_to(&self, to: &Self, context: &NormalKlass) -> ResolveResult<bool> {
+        if self == to || *to == Self::Top {
+            return Ok(true);
+        }
+
+        match (self, to) {
+            (Self::Null, Self::Reference(_)) => Ok(true),
+            (Self::Reference(from), Self::Reference(to)) => is_java_assignable(from.utf8(), to.utf8(), context),
+            _ => Ok(false),
+        }
+    }
+
+    /// JVMS 4.10.2.2: the type two values merge into where control flows
+    /// meet, or `None` if they have none in common.  References merge into
+    /// their first common superclass.
+    pub fn merge(&self, other: &Self, context: &NormalKlass) -> ResolveResult<Option<Self>> {
+        if self == other {
+            return Ok(Some(self.clone()));
+        }
+
+        match (self, other) {
+            (Self::Null, Self::Reference(_)) => Ok(Some(other.clone())),
+            (Self::Reference(_), Self::Null) => Ok(Some(self.clone())),
+            (Self::Reference(a), Self::Reference(b)) => {
+                let common = common_superclass(a.utf8(), b.utf8(), context)?;
+                Ok(Some(Self::reference(&common)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// JVMS 4.10.1.2 `isJavaAssignable` between class and array types.
+fn is_java_assignable(from: &str, to: &str, context: &NormalKlass) -> ResolveResult<bool> {
+    if from == to || to == OBJECT_CLASS {
+        return Ok(true);
+    }
+
+    match (from.strip_prefix('['), to.strip_prefix('[')) {
+        (Some(from), Some(to)) => match (component_class(from), component_class(to)) {
+            (Some(from), Some(to)) => is_java_assignable(from, to, context),
+            // 基本类型数组只与自身兼容。
+            _ => Ok(false),
+        },
+        (Some(_), None) => Ok(to == "java/lang/Cloneable" || to == "java/io/Serializable"),
+        (None, Some(_)) => Ok(false),
+        (None, None) => {
+            // 接口在校验时视同 Object（JVMS 4.10.1.2）。
+            let target = load(to, context)?;
+            if target.is_interface() {
+                return Ok(true);
+            }
+
+            Ok(load(from, context)?.is_subclass_of(&target))
+        }
+    }
+}
+
+/// The first class or array type both `a` and `b` are assignable to.
+fn common_superclass(a: &str, b: &str, context: &NormalKlass) -> ResolveResult<String> {
+    if a == b {
+        return Ok(a.to_owned());
+    }
+
+    match (a.strip_prefix('['), b.strip_prefix('[')) {
+        (Some(a), Some(b)) => match (component_class(a), component_class(b)) {
+            (Some(a), Some(b)) => {
+                let common = common_superclass(a, b, context)?;
+                Ok(match common.starts_with('[') {
+                    true => format!("[{common}"),
+                    false => format!("[L{common};"),
+                })
+            }
+            _ => Ok(OBJECT_CLASS.to_owned()),
+        },
+        (None, None) => {
+            let (a, b) = (load(a, context)?, load(b, context)?);
+            // 接口在校验时视同 Object。
+            if a.is_interface() || b.is_interface() {
+                return Ok(OBJECT_CLASS.to_owned());
+            }
+
+            let mut current = Some(a);
+            while let Some(klass) = current {
+                if b.is_subclass_of(&klass) {
+                    return Ok(klass.name().utf8().to_owned());
+                }
+                current = klass.super_klass_ref();
+            }
+            Ok(OBJECT_CLASS.to_owned())
+        }
+        _ => Ok(OBJECT_CLASS.to_owned()),
+    }
+}
+
+/// The class name or array descriptor a reference array component
+/// descriptor stands for; `None` for primitive components.
+fn component_class(desc: &str) -> Option<&str> {
+    match desc.as_bytes().first()? {
+        b'[' => Some(desc),
+        b'L' => Some(&desc[1..desc.len() - 1]),
+        _ => None,
+    }
+}
+
+fn load(name: &str, context: &NormalKlass) -> ResolveResult<MSRef<NormalKlass>> {
+    let klass = match context.cld() {
+        Some(cld) => cld.load_class(name),
+        None => BootstrapCLD::find_class(name),
+    }
+    .map_err(|error| match error {
+        LoadError::NotFound(name) => ResolveError::ClassNotFound(name),
+        other => ResolveError::ClassLoad(Box::new(other)),
+    })?;
+
+    klass.as_normal_ref().ok_or(ResolveError::NotANormal)
+}
+
+impl fmt::Display for VerificationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Top => f.write_str("top"),
+            Self::Integer => f.write_str("int"),
+            Self::Float => f.write_str("float"),
+            Self::Long => f.write_str("long"),
+            Self::Double => f.write_str("double"),
+            Self::Null => f.write_str("null"),
+            Self::UninitializedThis => f.write_str("uninitializedThis"),
+            Self::Uninitialized(offset) => write!(f, "uninitialized({offset})"),
+            Self::Reference(name) => f.write_str(name.utf8()),
+            Self::ReturnAddress(entry) => write!(f, "returnAddress({entry})"),
+        }
+    }
+}
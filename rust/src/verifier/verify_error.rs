@@ -0,0 +1,119 @@
+use std::{error::Error, fmt};
+
+use crate::{engine::exec_error::JavaExceptionKind, oops::oops_errors::ResolveError};
+
+/// Why a method failed verification.
+#[derive(Debug, Clone)]
+pub enum VerifyErrorKind {
+    InvalidCodeLength(usize),
+    /// Not an opcode a class file may contain.
+    InvalidOpcode(u8),
+    /// The code ends inside an instruction.
+    TruncatedInstruction,
+    /// A `tableswitch` range or `lookupswitch` key list that is malformed.
+    InvalidSwitch,
+    /// `jsr` and `ret` are not allowed where type checking applies.
+    Subroutine,
+    /// A branch target outside the code or inside an instruction.
+    InvalidBranchTarget(isize),
+    /// Control reaches the instruction at this offset without a stack map
+    /// frame to describe its type state.
+    MissingStackMapFrame(usize),
+    /// A stack map frame that cannot be expanded, or placed where no
+    /// instruction starts.
+    InvalidStackMapFrame,
+    /// The type state does not match the stack map frame at this offset.
+    FrameMismatch(usize),
+    InvalidExceptionHandler,
+    FallsOffCode,
+    StackOverflow,
+    StackUnderflow,
+    /// A local variable index at or beyond `max_locals`.
+    InvalidLocalIndex(usize),
+    BadType { expected: String, found: String },
+    /// A constant-pool entry of the wrong kind for the instruction.
+    InvalidConstant(usize),
+    /// A method name the instruction may not invoke.
+    IllegalMethodName(String),
+    /// `invokespecial` of an `<init>` that cannot initialize the receiver.
+    BadConstructorCall,
+    /// A constructor returning before it called `super(...)` or `this(...)`.
+    UninitializedReturn,
+    /// A return instruction that does not match the method's return type.
+    WrongReturn,
+    /// Loading a class named by the code failed.
+    Resolve(Box<ResolveError>),
+}
+
+impl From<ResolveError> for VerifyErrorKind {
+    fn from(value: ResolveError) -> Self {
+        Self::Resolve(Box::new(value))
+    }
+}
+
+/// A method of `class` failed verification at `bci`.
+#[derive(Debug, Clone)]
+pub struct VerifyError {
+    pub class: String,
+    /// `name(desc)`.
+    pub method: String,
+    pub bci: usize,
+    pub kind: VerifyErrorKind,
+}
+
+pub type VerifyResult<T> = Result<T, VerifyError>;
+
+impl VerifyError {
+    /// `VerifyError`, or the linkage error of a class that could not be
+    /// loaded to check assignability.
+    pub fn java_exception(&self) -> JavaExceptionKind {
+        match &self.kind {
+            VerifyErrorKind::Resolve(error) => error.java_exception().unwrap_or(JavaExceptionKind::VerifyError),
+            _ => JavaExceptionKind::VerifyError,
+        }
+    }
+}
+
+impl fmt::Display for VerifyErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCodeLength(len) => write!(f, "invalid code length {len}"),
+            Self::InvalidOpcode(opcode) => write!(f, "invalid opcode {opcode:#04x}"),
+            Self::TruncatedInstruction => f.write_str("code ends inside the instruction"),
+            Self::InvalidSwitch => f.write_str("malformed switch"),
+            Self::Subroutine => f.write_str("jsr/ret in a class file verified by type checking"),
+            Self::InvalidBranchTarget(target) => write!(f, "invalid branch target {target}"),
+            Self::MissingStackMapFrame(offset) => write!(f, "no stack map frame at {offset}"),
+            Self::InvalidStackMapFrame => f.write_str("invalid stack map frame"),
+            Self::FrameMismatch(offset) => write!(f, "type state does not match the stack map frame at {offset}"),
+            Self::InvalidExceptionHandler => f.write_str("invalid exception handler"),
+            Self::FallsOffCode => f.write_str("control falls off the end of the code"),
+            Self::StackOverflow => f.write_str("operand stack exceeds max_stack"),
+            Self::StackUnderflow => f.write_str("operand stack underflow"),
+            Self::InvalidLocalIndex(index) => write!(f, "local variable {index} exceeds max_locals"),
+            Self::BadType { expected, found } => write!(f, "expected {expected}, found {found}"),
+            Self::InvalidConstant(index) => write!(f, "constant {index} has the wrong type"),
+            Self::IllegalMethodName(name) => write!(f, "illegal call of {name}"),
+            Self::BadConstructorCall => f.write_str("<init> cannot initialize the receiver"),
+            Self::UninitializedReturn => f.write_str("constructor returns without initializing this"),
+            Self::WrongReturn => f.write_str("return does not match the return type"),
+            // Transparent: the resolve error names the class.
+            Self::Resolve(error) => error.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "verification of {}.{} failed at bci {}: {}", self.class, self.method, self.bci, self.kind)
+    }
+}
+
+impl Error for VerifyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            VerifyErrorKind::Resolve(error) => error.source(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+mod test_harness;
+
+use klover::{
+    assembler::{class_builder::ClassBuilder, code_builder::CodeBuilder},
+    class_loader::ms_api::MSRef,
+    engine::{
+        exec_error::JavaExceptionKind,
+        outcome::ThreadExit,
+    },
+    oops::normal_klass::NormalKlass,
+};
+use test_harness::{define, expect_exception, expect_int, load_class, run};
+
+const TARGETS: &str = "AccessTargets";
+
+/// A class in a loader of its own, so in another runtime package than
+/// `AccessTargets`, whose static `call()I` runs `code`.
+fn caller(name: &str, code: impl FnOnce(&mut CodeBuilder)) -> MSRef<NormalKlass> {
+    let mut class = ClassBuilder::new(name);
+    let mut method = class.method(0x0009, "call", "()I");
+    code(&mut method);
+    method.finish().unwrap();
+
+    define(class)
+}
+
+fn invoke_static(name: &str, method: &str) -> MSRef<NormalKlass> {
+    caller(name, |x| {
+        x.invoke(0xb8, TARGETS, method, "()I").op(0xac); // invokestatic; ireturn
+    })
+}
+
+fn get_static(name: &str, field: &str) -> MSRef<NormalKlass> {
+    caller(name, |x| {
+        x.field(0xb2, TARGETS, field, "I").op(0xac); // getstatic; ireturn
+    })
+}
+
+/// A subclass of `AccessTargets` in another runtime package whose `call()`
+/// invokes the protected `guarded()` on a new instance of `receiver`.
+fn subclass(name: &str, receiver: &str) -> MSRef<NormalKlass> {
+    let mut class = ClassBuilder::new(name);
+    class.extend(TARGETS);
+
+    let mut init = class.method(0x0001, "<init>", "()V");
+    init.op(0x2a).invoke(0xb7, TARGETS, "<init>", "()V").op(0xb1); // aload_0; invokespecial; return
+    init.finish().unwrap();
+
+    let mut call = class.method(0x0009, "call", "()I");
+    call.class_op(0xbb, receiver).op(0x59); // new; dup
+    call.invoke(0xb7, receiver, "<init>", "()V");
+    call.invoke(0xb6, TARGETS, "guarded", "()I").op(0xac); // invokevirtual; ireturn
+    call.finish().unwrap();
+
+    load_class(TARGETS);
+    define(class)
+}
+
+fn call(klass: &MSRef<NormalKlass>) -> ThreadExit {
+    run(klass, "call", "()I", vec![])
+}
+
+#[test]
+fn test_same_runtime_package_is_accessible() {
+    assert_eq!(expect_int(run(&load_class(TARGETS), "samePackage", "()I", vec![])), 22);
+}
+
+#[test]
+fn test_public_members_are_accessible() {
+    assert_eq!(expect_int(call(&invoke_static("CallsVisible", "visible"))), 30);
+    assert_eq!(expect_int(call(&get_static("ReadsOpen", "open"))), 3);
+}
+
+#[test]
+fn test_private_members_are_inaccessible() {
+    expect_exception(call(&invoke_static("CallsSecret", "secret")), JavaExceptionKind::IllegalAccessError);
+    expect_exception(call(&get_static("ReadsSecret", "secret")), JavaExceptionKind::IllegalAccessError);
+}
+
+#[test]
+fn test_package_members_need_the_same_loader() {
+    expect_exception(call(&invoke_static("CallsPackageOnly", "packageOnly")), JavaExceptionKind::IllegalAccessError);
+    expect_exception(call(&get_static("ReadsShared", "shared")), JavaExceptionKind::IllegalAccessError);
+}
+
+#[test]
+fn test_failed_resolution_fails_again() {
+    let klass = invoke_static("CallsSecretTwice", "secret");
+    expect_exception(call(&klass), JavaExceptionKind::IllegalAccessError);
+    expect_exception(call(&klass), JavaExceptionKind::IllegalAccessError);
+}
+
+#[test]
+fn test_package_private_class_is_inaccessible() {
+    let klass = caller("NewsHidden", |x| {
+        x.class_op(0xbb, "AccessTargets$Hidden").op(0x57).op(0x03).op(0xac); // new; pop; iconst_0; ireturn
+    });
+    expect_exception(call(&klass), JavaExceptionKind::IllegalAccessError);
+}
+
+#[test]
+fn test_protected_static_needs_a_subclass() {
+    expect_exception(call(&invoke_static("CallsGuardedStatic", "guardedStatic")), JavaExceptionKind::IllegalAccessError);
+
+    let mut class = ClassBuilder::new("SubCallsGuardedStatic");
+    class.extend(TARGETS);
+    let mut code = class.method(0x0009, "call", "()I");
+    code.invoke(0xb8, TARGETS, "guardedStatic", "()I").op(0xac); // invokestatic; ireturn
+    code.finish().unwrap();
+    load_class(TARGETS);
+    assert_eq!(expect_int(call(&define(class))), 50);
+}
+
+#[test]
+fn test_protected_receiver_must_be_a_subclass() {
+    assert_eq!(expect_int(call(&subclass("GuardsOwn", "GuardsOwn"))), 40);
+    expect_exception(call(&subclass("GuardsForeign", TARGETS)), JavaExceptionKind::IllegalAccessError);
+}
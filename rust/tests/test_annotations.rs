@@ -0,0 +1,195 @@
+mod test_harness;
+
+use klover::{
+    class_loader::ms_api::MSRef,
+    class_parser::annotation_info::TargetInfo,
+    engine::{
+        exec_error::JavaExceptionKind,
+        native::registry::NativeRegistry,
+        outcome::{PendingException, RetValue},
+        slot::Slot,
+    },
+    oops::{
+        annotation::{Annotation, ElementValue},
+        array_klass::ArrayKlass,
+        java_class::JavaClass,
+        java_string::JavaString,
+        normal_klass::NormalKlass,
+    },
+    runtime::thread_manager::ThreadManager,
+};
+use test_harness::load_class;
+
+fn string(value: &ElementValue) -> &str {
+    match value {
+        ElementValue::String(x) => x.utf8(),
+        other => panic!("expected a string, got {other:?}"),
+    }
+}
+
+/// Call the native `name desc` of `class` directly, as the interpreter would.
+fn call_native(class: &str, name: &str, desc: &str, args: &[Slot]) -> (RetValue, Option<PendingException>) {
+    let holder = load_class(class);
+    let method = holder.find_declared_method(name, desc).unwrap();
+    let native = NativeRegistry::lookup(&holder, &method).expect("native is registered");
+
+    let mut manager = ThreadManager::new(1024);
+    let mut thread = manager.create_thread().unwrap();
+    let value = native(&mut thread, args).unwrap();
+
+    (value, thread.pending_exception.take())
+}
+
+fn mirror(klass: &MSRef<NormalKlass>) -> u32 {
+    JavaClass::mirror(klass).unwrap()
+}
+
+#[test]
+fn test_class_annotation_tree() {
+    let klass = load_class("Annotated");
+    let info = klass.annotations().find("LAnnotated$Info;").unwrap();
+
+    assert_eq!(string(info.element("name").unwrap()), "klass");
+    assert_eq!(info.element("level"), Some(&ElementValue::Int(3)));
+    assert_eq!(
+        info.element("color"),
+        Some(&ElementValue::Enum {
+            type_name: "LAnnotated$Color;".into(),
+            const_name: "RED".into(),
+        })
+    );
+    assert_eq!(info.element("type"), Some(&ElementValue::Class("Ljava/lang/String;".into())));
+    assert_eq!(
+        info.element("ids"),
+        Some(&ElementValue::Array(vec![ElementValue::Long(1), ElementValue::Long(2)]))
+    );
+
+    let Some(ElementValue::Annotation(tag)) = info.element("tag") else {
+        panic!("tag should be a nested annotation");
+    };
+    assert_eq!(tag.type_name.utf8(), "LAnnotated$Tag;");
+    assert_eq!(string(tag.element("value").unwrap()), "outer");
+}
+
+#[test]
+fn test_field_and_method_annotations() {
+    let klass = load_class("Annotated");
+
+    let counter = klass.find_declared_field("counter", "I").unwrap();
+    let types: Vec<_> = counter.annotations.declared.iter().map(|x| x.type_name.utf8()).collect();
+    assert_eq!(types, ["LAnnotated$Tag;", "Ljava/lang/Deprecated;"]);
+
+    // List<@NonNull String>: the annotation sits on type argument 0.
+    let names = klass.find_declared_field("names", "Ljava/util/List;").unwrap();
+    let [type_annotation] = names.annotations.type_annotations.as_slice() else {
+        panic!("expected one type annotation");
+    };
+    assert_eq!(type_annotation.target, TargetInfo::Empty);
+    assert_eq!(type_annotation.type_path.len(), 1);
+    assert_eq!(type_annotation.type_path[0].type_path_kind, 3);
+
+    let add = klass.find_declared_method("add", "(II)I").unwrap();
+    assert!(add.annotations.declared.is_empty(), "CLASS retention is not runtime visible");
+    assert!(add.annotations.raw("RuntimeVisibleAnnotations").is_none());
+
+    let [a, b] = add.annotations.parameters.as_slice() else {
+        panic!("expected annotations for two parameters");
+    };
+    assert_eq!(string(a[0].element("value").unwrap()), "a");
+    assert!(b.is_empty());
+
+    // The cast's annotation comes from the Code attribute.
+    let cast = &add.annotations.type_annotations[0];
+    assert!(matches!(cast.target, TargetInfo::TypeArgument { type_argument_idx: 0, .. }));
+    assert_eq!(cast.annotation.type_name.utf8(), "LAnnotated$NonNull;");
+}
+
+#[test]
+fn test_annotation_defaults() {
+    let info = load_class("Annotated$Info");
+
+    let default = |name: &str, desc: &str| {
+        let method = info.find_declared_method(name, desc).unwrap();
+        method.annotations.default_value.clone()
+    };
+
+    assert_eq!(default("name", "()Ljava/lang/String;"), None);
+    assert_eq!(default("level", "()I"), Some(ElementValue::Int(1)));
+    assert_eq!(default("ids", "()[J"), Some(ElementValue::Array(vec![])));
+    assert_eq!(
+        default("tag", "()LAnnotated$Tag;"),
+        Some(ElementValue::Annotation(Annotation {
+            type_name: "LAnnotated$Tag;".into(),
+            elements: vec![("value".into(), ElementValue::String("none".into()))],
+        }))
+    );
+}
+
+#[test]
+fn test_raw_annotations_native() {
+    let klass = load_class("Annotated");
+    let this = Slot::reference(mirror(&klass));
+
+    let (value, exception) = call_native("java/lang/Class", "getRawAnnotations", "()[B", &[this]);
+    assert!(exception.is_none());
+    let RetValue::Ref(array) = value else {
+        panic!("expected a byte[]");
+    };
+    assert_eq!(
+        ArrayKlass::read_bytes(array).unwrap(),
+        klass.annotations().raw("RuntimeVisibleAnnotations").unwrap()
+    );
+
+    // A class without annotations gets null.
+    let plain = Slot::reference(mirror(&load_class("SimpleAddition")));
+    let (value, _) = call_native("java/lang/Class", "getRawAnnotations", "()[B", &[plain]);
+    assert!(matches!(value, RetValue::Ref(0)));
+
+    let (value, _) = call_native("java/lang/Class", "getName", "()Ljava/lang/String;", &[this]);
+    let RetValue::Ref(name) = value else { panic!() };
+    assert_eq!(JavaString::to_rust_string(name).unwrap(), "Annotated");
+}
+
+#[test]
+fn test_constant_pool_native() {
+    let klass = load_class("Annotated");
+    let this = Slot::reference(mirror(&klass));
+
+    let (value, _) = call_native(
+        "java/lang/Class",
+        "getConstantPool",
+        "()Lsun/reflect/ConstantPool;",
+        &[this],
+    );
+    let RetValue::Ref(pool) = value else { panic!() };
+
+    // The raw bytes start with num_annotations and the type_index of @Info.
+    let raw = klass.annotations().raw("RuntimeVisibleAnnotations").unwrap();
+    let type_index = i32::from(u16::from_be_bytes([raw[2], raw[3]]));
+
+    let pool = Slot::reference(pool);
+    let (value, exception) = call_native(
+        "sun/reflect/ConstantPool",
+        "getUTF8At0",
+        "(Ljava/lang/Object;I)Ljava/lang/String;",
+        &[pool, this, Slot::int(type_index)],
+    );
+    assert!(exception.is_none());
+    let RetValue::Ref(string) = value else { panic!() };
+    assert_eq!(JavaString::to_rust_string(string).unwrap(), "LAnnotated$Info;");
+
+    let (value, _) = call_native("sun/reflect/ConstantPool", "getSize0", "(Ljava/lang/Object;)I", &[pool, this]);
+    assert!(matches!(value, RetValue::Int(x) if x as usize == klass.constant_pool_len()));
+
+    // A Utf8 entry is not an int.
+    let (_, exception) = call_native(
+        "sun/reflect/ConstantPool",
+        "getIntAt0",
+        "(Ljava/lang/Object;I)I",
+        &[pool, this, Slot::int(type_index)],
+    );
+    assert!(matches!(
+        exception,
+        Some(PendingException::JVMGen(JavaExceptionKind::IllegalArgumentException))
+    ));
+}
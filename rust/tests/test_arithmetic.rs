@@ -21,6 +21,7 @@ fn init_vm() {
         vm_init(Arguments {
             bs_class_path: format!("{}/../test_data/classes", env!("CARGO_MANIFEST_DIR")),
             xmx: 64 * 1024 * 1024,
+            ..Default::default()
         });
     });
 }
@@ -0,0 +1,193 @@
+use klover::class_parser::{
+    attr_info::{AttrLocation, HasAttrs},
+    class_file::ClassFile,
+    cp_info::ConstantPoolInfo,
+    method_info::MethodInfo,
+    parse_error::ParseError,
+};
+
+fn parse(name: &str) -> ClassFile {
+    let path = format!("{}/../test_data/classes/{name}.class", env!("CARGO_MANIFEST_DIR"));
+    let bytes = std::fs::read(&path).unwrap_or_else(|err| panic!("{path}: {err}"));
+    ClassFile::from(&bytes).unwrap_or_else(|err| panic!("{path}: {err:?}"))
+}
+
+fn utf8(cf: &ClassFile, idx: u16) -> &str {
+    match &cf.constant_pool[idx as usize] {
+        ConstantPoolInfo::Utf8Info { utf8, .. } => utf8,
+        _ => panic!("#{idx} is not Utf8"),
+    }
+}
+
+fn class_name(cf: &ClassFile, idx: u16) -> &str {
+    match &cf.constant_pool[idx as usize] {
+        ConstantPoolInfo::ClassInfo { name_index } => utf8(cf, *name_index),
+        _ => panic!("#{idx} is not Class"),
+    }
+}
+
+fn method<'a>(cf: &'a ClassFile, name: &str) -> &'a MethodInfo {
+    cf.methods.iter().find(|x| utf8(cf, x.name_idx) == name).unwrap()
+}
+
+#[test]
+fn test_class_level_attributes() {
+    let cf = parse("AttrSamples");
+
+    assert!(cf.is_deprecated());
+    assert_eq!(
+        utf8(&cf, cf.signature_idx().unwrap()),
+        "<T::Ljava/lang/Comparable<TT;>;>Ljava/lang/Object;"
+    );
+
+    let mut inner: Vec<_> = cf
+        .inner_classes()
+        .iter()
+        .map(|x| class_name(&cf, x.inner_class_info_idx))
+        .collect();
+    inner.sort();
+    assert_eq!(inner, ["AttrSamples$1Local", "AttrSamples$Inner", "AttrSamples$Marker"]);
+
+    // 局部类没有外部类，也就没有 outer_class_info。
+    let local = cf
+        .inner_classes()
+        .iter()
+        .find(|x| class_name(&cf, x.inner_class_info_idx) == "AttrSamples$1Local")
+        .unwrap();
+    assert_eq!(local.outer_class_info_idx, 0);
+    assert_eq!(utf8(&cf, local.inner_name_idx), "Local");
+
+    assert!(cf.raw_attr("SourceFile").is_some());
+    assert!(cf.raw_attr("RuntimeVisibleAnnotations").is_some());
+}
+
+#[test]
+fn test_enclosing_method_of_a_local_class() {
+    let cf = parse("AttrSamples$1Local");
+    let (class_idx, method_idx) = cf.enclosing_method().unwrap();
+
+    assert_eq!(class_name(&cf, class_idx), "AttrSamples");
+    match &cf.constant_pool[method_idx as usize] {
+        ConstantPoolInfo::NameAndTypeInfo { name_index, .. } => assert_eq!(utf8(&cf, *name_index), "local"),
+        _ => panic!("EnclosingMethod should name a NameAndType"),
+    }
+}
+
+#[test]
+fn test_field_signature() {
+    let cf = parse("AttrSamples");
+    let field = &cf.fields[0];
+
+    assert_eq!(utf8(&cf, field.signature_idx().unwrap()), "Ljava/util/List<Ljava/lang/String;>;");
+    assert!(!field.is_deprecated());
+}
+
+#[test]
+fn test_method_exceptions_and_parameters() {
+    let cf = parse("AttrSamples");
+    let throwing = method(&cf, "throwing");
+
+    let exceptions: Vec<_> = throwing.exceptions().iter().map(|x| class_name(&cf, *x)).collect();
+    assert_eq!(exceptions, ["java/io/IOException", "java/lang/InterruptedException"]);
+
+    let params: Vec<_> = throwing
+        .method_parameters()
+        .iter()
+        .map(|x| utf8(&cf, x.name_idx))
+        .collect();
+    assert_eq!(params, ["count", "label"]);
+
+    assert!(method(&cf, "local").exceptions().is_empty());
+}
+
+#[test]
+fn test_annotation_default() {
+    let cf = parse("AttrSamples$Marker");
+    let value = method(&cf, "value").annotation_default().unwrap();
+
+    // element_value: tag 'I', const_value_index -> CONSTANT_Integer 7
+    assert_eq!(value[0], b'I');
+    let idx = u16::from_be_bytes([value[1], value[2]]);
+    assert!(matches!(cf.constant_pool[idx as usize], ConstantPoolInfo::IntegerInfo { value: 7 }));
+}
+
+/// A minimal `public class A` with the given class-level attributes, each
+/// `(name, payload)`.  The pool holds `#5 Utf8 "A.java"` for payloads to use.
+fn class_with_attrs(attrs: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut pool: Vec<&str> = vec!["A", "java/lang/Object", "A.java"];
+    for (name, _) in attrs {
+        if !pool.contains(name) {
+            pool.push(name);
+        }
+    }
+    // #1 A, #2 Class #1, #3 java/lang/Object, #4 Class #3, #5.. 其余 Utf8
+    let utf8_idx = |s: &str| -> u16 {
+        match pool.iter().position(|x| *x == s).unwrap() {
+            0 => 1,
+            1 => 3,
+            n => n as u16 + 3,
+        }
+    };
+
+    let mut out = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52];
+    out.extend_from_slice(&(pool.len() as u16 + 3).to_be_bytes());
+    let push_utf8 = |out: &mut Vec<u8>, s: &str| {
+        out.push(1);
+        out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+        out.extend_from_slice(s.as_bytes());
+    };
+    push_utf8(&mut out, pool[0]);
+    out.extend_from_slice(&[7, 0, 1]);
+    push_utf8(&mut out, pool[1]);
+    out.extend_from_slice(&[7, 0, 3]);
+    for s in &pool[2..] {
+        push_utf8(&mut out, s);
+    }
+
+    out.extend_from_slice(&[0x00, 0x21, 0, 2, 0, 4, 0, 0, 0, 0, 0, 0]);
+    out.extend_from_slice(&(attrs.len() as u16).to_be_bytes());
+    for (name, payload) in attrs {
+        out.extend_from_slice(&utf8_idx(name).to_be_bytes());
+        out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        out.extend_from_slice(payload);
+    }
+
+    out
+}
+
+#[test]
+fn test_minimal_class_parses() {
+    let cf = ClassFile::from(&class_with_attrs(&[("SourceFile", &[0, 5]), ("Custom", &[1, 2, 3])])).unwrap();
+
+    assert_eq!(cf.raw_attr("Custom"), Some(&[1u8, 2, 3][..]));
+    assert_eq!(cf.raw_attrs.len(), 2);
+    assert_eq!(cf.attrs.len(), 1);
+}
+
+#[test]
+fn test_duplicated_attribute_is_rejected() {
+    let res = ClassFile::from(&class_with_attrs(&[("SourceFile", &[0, 5]), ("SourceFile", &[0, 5])]));
+    assert!(matches!(res, Err(ParseError::DuplicatedAttr(name)) if name == "SourceFile"));
+}
+
+#[test]
+fn test_misplaced_attribute_is_rejected() {
+    let res = ClassFile::from(&class_with_attrs(&[("ConstantValue", &[0, 5])]));
+    assert!(matches!(
+        res,
+        Err(ParseError::MisplacedAttr { name, location: AttrLocation::ClassFile }) if name == "ConstantValue"
+    ));
+}
+
+#[test]
+fn test_attribute_length_and_index_are_checked() {
+    let res = ClassFile::from(&class_with_attrs(&[("Deprecated", &[0])]));
+    assert!(matches!(res, Err(ParseError::InvalidAttrLen(1))));
+
+    // Signature must name a Utf8, not a Class.
+    let res = ClassFile::from(&class_with_attrs(&[("Signature", &[0, 2])]));
+    assert!(matches!(res, Err(ParseError::InvalidCPType)));
+
+    // Unknown attributes are skipped whatever they contain.
+    assert!(ClassFile::from(&class_with_attrs(&[("Custom", &[])])).is_ok());
+}
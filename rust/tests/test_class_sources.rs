@@ -0,0 +1,43 @@
+mod test_harness;
+
+use std::{fs, sync::Arc};
+
+use klover::{
+    class_loader::{
+        bootstrap_cld::BootstrapCLD, class_path::ClassPath, class_source::MemorySource,
+        system_cld::SystemCLD,
+    },
+    engine::slot::Slot,
+    runtime::{arguments::Arguments, vm::vm_init},
+};
+use test_harness::{expect_int, run};
+
+fn read_test_class(path: &str) -> Vec<u8> {
+    fs::read(format!("{}/../test_data/{path}", env!("CARGO_MANIFEST_DIR"))).unwrap()
+}
+
+/// Neither JarBoot nor JarApp is on a file class path in this process; both
+/// loaders can only see them through the in-memory sources.
+#[test]
+fn memory_sources_feed_both_loaders() {
+    let boot = Arc::new(MemorySource::new("generated-boot"));
+    boot.insert_class("JarBoot", read_test_class("jars/boot/JarBoot.class"));
+    let app = Arc::new(MemorySource::new("generated-app"));
+    app.insert_class("JarApp", read_test_class("jars/app/JarApp.class"));
+
+    vm_init(Arguments {
+        bs_class_path: format!("{}/../test_data/classes", env!("CARGO_MANIFEST_DIR")),
+        boot_sources: vec![boot],
+        app_sources: vec![app],
+        ..Default::default()
+    });
+
+    assert_eq!(ClassPath::boot().entries().len(), 2);
+    assert!(BootstrapCLD::find_class("JarApp").is_err());
+
+    let holder = SystemCLD::find_class("JarApp").unwrap().as_normal_ref().unwrap();
+    assert!(std::ptr::eq(holder.cld().unwrap(), SystemCLD::get()));
+
+    let exit = run(&holder, "plusBootOffset", "(I)I", vec![Slot::int(1)]);
+    assert_eq!(expect_int(exit), 1001);
+}
@@ -0,0 +1,264 @@
+mod test_harness;
+
+use std::path::{Path, PathBuf};
+
+use klover::{
+    assembler::{build_error::BuildError, class_builder::ClassBuilder, code_builder::CodeBuilder},
+    class_loader::{cld::ClassLoaderData, ms_api::MSRef},
+    class_parser::{
+        attr_info::{AttrInfo, CodeAttrInfo, HasAttrs},
+        class_file::ClassFile,
+        cp_info::ConstantPoolInfo,
+    },
+    engine::slot::Slot,
+    oops::normal_klass::NormalKlass,
+};
+use test_harness::{expect_int, expect_long, run, start_vm};
+
+fn class_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            class_files(&path, out);
+        } else if path.extension().is_some_and(|x| x == "class") {
+            out.push(path);
+        }
+    }
+}
+
+fn test_data(dir: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../test_data").join(dir)
+}
+
+fn code_of<'a>(cf: &'a ClassFile, name: &str) -> &'a CodeAttrInfo {
+    let method = cf
+        .methods
+        .iter()
+        .find(|x| matches!(&cf.constant_pool[x.name_idx as usize], ConstantPoolInfo::Utf8Info { utf8, .. } if utf8 == name))
+        .unwrap();
+    method
+        .attrs
+        .iter()
+        .find_map(|x| match x {
+            AttrInfo::Code(code) => Some(code),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn define(bytes: &[u8], name: &str) -> MSRef<NormalKlass> {
+    start_vm();
+    let cld = unsafe { ClassLoaderData::new(Some(format!("{name} loader"))).as_ref() };
+    cld.define_class(bytes).unwrap().as_normal_ref().unwrap()
+}
+
+#[test]
+fn test_test_classes_round_trip() {
+    let mut paths = Vec::new();
+    for dir in ["classes", "app_classes", "module_classes"] {
+        class_files(&test_data(dir), &mut paths);
+    }
+    assert!(paths.len() > 50);
+
+    for path in paths {
+        let bytes = std::fs::read(&path).unwrap();
+        let cf = ClassFile::from(&bytes).unwrap();
+        assert!(cf.to_bytes().unwrap() == bytes, "{} does not round-trip", path.display());
+    }
+}
+
+#[test]
+fn test_edited_attributes_are_written() {
+    let bytes = std::fs::read(test_data("classes/SimpleAddition.class")).unwrap();
+    let mut cf = ClassFile::from(&bytes).unwrap();
+
+    cf.attrs.retain(|x| !matches!(x, AttrInfo::SourceFile { .. }));
+    for method in &mut cf.methods {
+        for attr in &mut method.attrs {
+            if let AttrInfo::Code(code) = attr {
+                code.max_stack += 1;
+            }
+        }
+    }
+
+    let cf = ClassFile::from(&cf.to_bytes().unwrap()).unwrap();
+    assert!(cf.raw_attr("SourceFile").is_none());
+    assert!(cf.methods.iter().all(|x| x.attrs.iter().all(|x| match x {
+        AttrInfo::Code(code) => code.max_stack >= 1,
+        _ => true,
+    })));
+}
+
+#[test]
+fn test_built_class_runs() {
+    let mut class = ClassBuilder::new("BuiltMax");
+    let mut code = class.method(0x0009, "max", "(II)I");
+    let second = code.label();
+    code.op(0x1a).op(0x1b).branch(0xa2, second); // iload_0; iload_1; if_icmpge
+    code.op(0x1b).op(0xac).bind(second); //          iload_1; ireturn
+    code.op(0x1a).op(0xac); //                       iload_0; ireturn
+    code.finish().unwrap();
+    let klass = define(&class.to_bytes().unwrap(), "BuiltMax");
+
+    assert_eq!(expect_int(run(&klass, "max", "(II)I", vec![Slot::int(3), Slot::int(9)])), 9);
+    assert_eq!(expect_int(run(&klass, "max", "(II)I", vec![Slot::int(8), Slot::int(-2)])), 8);
+}
+
+#[test]
+fn test_max_stack_and_locals_are_computed() {
+    let mut class = ClassBuilder::new("BuiltSizes");
+    let mut code = class.method(0x0009, "sum", "(JI)J");
+    // long total = a; for (int i = n; i > 0; i--) total++; return total;
+    let head = code.label();
+    let done = code.label();
+    code.op(0x1e).local(0x37, 4); //                   lload_0; lstore 4
+    code.op(0x1c).op(0x3e).bind(head); //              iload_2; istore_3
+    code.op(0x1d).branch(0x9e, done); //               iload_3; ifle
+    code.local(0x16, 4).op(0x0a).op(0x61); //         lload 4; lconst_1; ladd
+    code.local(0x37, 4).iinc(3, -1).branch(0xa7, head);
+    code.bind(done).local(0x16, 4).op(0xad); //        lload 4; lreturn
+    code.finish().unwrap();
+
+    let bytes = class.to_bytes().unwrap();
+    let cf = ClassFile::from(&bytes).unwrap();
+    assert_eq!(code_of(&cf, "sum").max_stack, 4);
+    assert_eq!(code_of(&cf, "sum").max_locals, 6);
+
+    let klass = define(&bytes, "BuiltSizes");
+    let args = vec![Slot::long_high(100), Slot::long_low(100), Slot::int(4)];
+    assert_eq!(expect_long(run(&klass, "sum", "(JI)J", args)), 104);
+}
+
+#[test]
+fn test_wide_local_variables() {
+    let mut class = ClassBuilder::new("BuiltWide");
+    let mut code = class.method(0x0009, "wide", "()V");
+    code.op(0x0a).local(0x37, 300).iinc(300, 1000).op(0xb1); // lconst_1; lstore 300; iinc 300 1000; return
+    code.finish().unwrap();
+
+    let cf = class.build().unwrap();
+    let code = code_of(&cf, "wide");
+    assert_eq!(code.code, [0x0a, 0xc4, 0x37, 0x01, 0x2c, 0xc4, 0x84, 0x01, 0x2c, 0x03, 0xe8, 0xb1]);
+    assert_eq!(code.max_locals, 302);
+    assert_eq!(code.max_stack, 2);
+}
+
+fn i32_at(code: &[u8], pos: usize) -> i32 {
+    i32::from_be_bytes(code[pos..pos + 4].try_into().unwrap())
+}
+
+#[test]
+fn test_switches_resolve_labels() {
+    let mut class = ClassBuilder::new("BuiltSwitch");
+
+    let mut code = class.method(0x0009, "table", "(I)I");
+    let cases = [code.label(), code.label(), code.label()];
+    let default = code.label();
+    code.op(0x1a).tableswitch(1, default, &cases); // iload_0
+    for (case, value) in cases.into_iter().zip([10, 200, 3000]) {
+        code.bind(case).iconst(value).op(0xac);
+    }
+    code.bind(default).iconst(-1).op(0xac);
+    code.finish().unwrap();
+
+    let mut code = class.method(0x0009, "lookup", "(I)I");
+    let (big, small, default) = (code.label(), code.label(), code.label());
+    code.op(0x1a).lookupswitch(default, &[(1_000_000, big), (-5, small)]);
+    code.bind(big).ldc_int(1_000_000).op(0xac);
+    code.bind(small).iconst(5).op(0xac);
+    code.bind(default).op(0x03).op(0xac); // iconst_0; ireturn
+    code.finish().unwrap();
+
+    let bytes = class.to_bytes().unwrap();
+    define(&bytes, "BuiltSwitch");
+    let cf = ClassFile::from(&bytes).unwrap();
+
+    // iload_0; tableswitch at 1, padded to 4; cases at 28 (bipush), 31 and
+    // 35 (sipush); default at 39.
+    let table = &code_of(&cf, "table").code;
+    assert_eq!(table[1], 0xaa);
+    let offsets: Vec<_> = [4, 8, 12, 16, 20, 24].iter().map(|x| i32_at(table, *x)).collect();
+    assert_eq!(offsets, [38, 1, 3, 27, 30, 34]);
+    assert_eq!(code_of(&cf, "table").max_stack, 1);
+
+    // Keys sorted; `big` at 28 (ldc), `small` at 31, default at 33.
+    let lookup = &code_of(&cf, "lookup").code;
+    let entries: Vec<_> = (4..28).step_by(4).map(|x| i32_at(lookup, x)).collect();
+    assert_eq!(entries, [32, 2, -5, 30, 1_000_000, 27]);
+}
+
+#[test]
+fn test_calls_and_fields() {
+    let mut class = ClassBuilder::new("BuiltCalls");
+    class.field(0x000a, "counter", "J");
+
+    let mut code = class.method(0x0009, "bump", "()J");
+    code.field(0xb2, "BuiltCalls", "counter", "J").op(0x0a).op(0x61); // getstatic; lconst_1; ladd
+    code.op(0x5c).field(0xb3, "BuiltCalls", "counter", "J").op(0xad); // dup2; putstatic; lreturn
+    code.finish().unwrap();
+
+    let mut code = class.method(0x0009, "twice", "()J");
+    code.invoke(0xb8, "BuiltCalls", "bump", "()J").op(0x58); // invokestatic; pop2
+    code.invoke(0xb8, "BuiltCalls", "bump", "()J").op(0xad); // invokestatic; lreturn
+    code.finish().unwrap();
+
+    let bytes = class.to_bytes().unwrap();
+    let cf = ClassFile::from(&bytes).unwrap();
+    assert_eq!(code_of(&cf, "bump").max_stack, 4);
+    assert_eq!(code_of(&cf, "twice").max_locals, 0);
+
+    let klass = define(&bytes, "BuiltCalls");
+    assert_eq!(expect_long(run(&klass, "twice", "()J", vec![])), 2);
+}
+
+#[test]
+fn test_handlers_start_with_one_stack_slot() {
+    let mut class = ClassBuilder::new("BuiltHandler");
+    let mut code = class.method(0x0009, "guarded", "()V");
+    let (start, end, handler) = (code.label(), code.label(), code.label());
+    code.bind(start).op(0xb1).bind(end); // return
+    code.bind(handler).op(0x59).op(0x57).op(0xbf); // dup; pop; athrow
+    code.handler(start, end, handler, Some("java/lang/Throwable"));
+    code.finish().unwrap();
+
+    let cf = class.build().unwrap();
+    assert_eq!(code_of(&cf, "guarded").max_stack, 2);
+    assert_eq!(code_of(&cf, "guarded").exception_table[0].handler_pc, 1);
+}
+
+fn finish_error(build: impl FnOnce(&mut CodeBuilder)) -> BuildError {
+    let mut class = ClassBuilder::new("Broken");
+    let mut code = class.method(0x0009, "broken", "()V");
+    build(&mut code);
+    code.finish().unwrap_err()
+}
+
+#[test]
+fn test_build_errors() {
+    let unbound = finish_error(|x| {
+        let label = x.label();
+        x.branch(0xa7, label);
+    });
+    assert_eq!(unbound, BuildError::UnboundLabel);
+
+    assert_eq!(finish_error(|x| {
+        x.op(0x57).op(0xb1); // pop; return
+    }), BuildError::StackUnderflow { pc: 0 });
+
+    assert_eq!(finish_error(|x| {
+        x.op(0x00); // nop
+    }), BuildError::FallsOffEnd);
+
+    let inconsistent = finish_error(|x| {
+        let join = x.label();
+        x.op(0x03).branch(0x99, join); // iconst_0; ifeq
+        x.op(0x03).bind(join).op(0xb1); // iconst_0; return
+    });
+    assert_eq!(inconsistent, BuildError::InconsistentStack { pc: 5, depths: (1, 0) });
+
+    assert!(matches!(finish_error(|x| {
+        x.invoke(0xb8, "Broken", "broken", "(Q)V");
+    }), BuildError::InvalidDescriptor(_)));
+
+    assert_eq!(finish_error(|_| {}), BuildError::InvalidCodeLength(0));
+}
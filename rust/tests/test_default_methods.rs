@@ -0,0 +1,77 @@
+mod test_harness;
+
+use klover::{
+    assembler::class_builder::ClassBuilder,
+    class_loader::ms_api::MSRef,
+    engine::exec_error::JavaExceptionKind,
+    oops::normal_klass::NormalKlass,
+};
+use test_harness::{define, expect_exception, expect_int, load_class, run};
+
+const SAMPLES: &str = "DefaultMethods";
+
+fn run_sample(name: &str) -> i32 {
+    expect_int(run(&load_class(SAMPLES), name, "()I", vec![]))
+}
+
+/// javac rejects a class inheriting two unrelated defaults, so such classes
+/// are assembled here: `name` implements `DefaultMethods$<interface>` for
+/// each of `interfaces`, and its static `call()` returns `new name().side()`.
+fn implementor(name: &str, interfaces: &[&str]) -> MSRef<NormalKlass> {
+    let mut class = ClassBuilder::new(name);
+    for interface in interfaces {
+        class.implement(&format!("{SAMPLES}${interface}"));
+    }
+
+    let mut init = class.method(0x0001, "<init>", "()V");
+    init.op(0x2a).invoke(0xb7, "java/lang/Object", "<init>", "()V").op(0xb1); // aload_0; invokespecial; return
+    init.finish().unwrap();
+
+    let mut call = class.method(0x0009, "call", "()I");
+    call.class_op(0xbb, name).op(0x59); // new; dup
+    call.invoke(0xb7, name, "<init>", "()V");
+    call.invoke(0xb6, name, "side", "()I").op(0xac); // invokevirtual; ireturn
+    call.finish().unwrap();
+
+    define(class)
+}
+
+#[test]
+fn test_invokestatic_interface_method() {
+    assert_eq!(run_sample("staticCall"), 10);
+}
+
+#[test]
+fn test_default_method_is_inherited() {
+    assert_eq!(run_sample("inherited"), 11);
+}
+
+#[test]
+fn test_most_specific_default_is_selected() {
+    assert_eq!(run_sample("mostSpecific"), 22);
+    assert_eq!(run_sample("throughInterface"), 22);
+    assert_eq!(run_sample("superclassInterfaces"), 22);
+}
+
+#[test]
+fn test_invokespecial_interface_super_call() {
+    assert_eq!(run_sample("superCall"), 122);
+}
+
+#[test]
+fn test_single_default_beside_abstract_is_selected() {
+    let klass = implementor("DefaultBesideAbstract", &["Abstract", "Left"]);
+    assert_eq!(expect_int(run(&klass, "call", "()I", vec![])), 1);
+}
+
+#[test]
+fn test_conflicting_defaults_are_incompatible() {
+    let klass = implementor("ConflictingDefaults", &["Left", "Right"]);
+    expect_exception(run(&klass, "call", "()I", vec![]), JavaExceptionKind::IncompatibleClassChangeError);
+}
+
+#[test]
+fn test_only_abstract_method_is_abstract_method_error() {
+    let klass = implementor("OnlyAbstract", &["Abstract"]);
+    expect_exception(run(&klass, "call", "()I", vec![]), JavaExceptionKind::AbstractMethodError);
+}
@@ -0,0 +1,145 @@
+mod test_harness;
+
+use klover::{
+    class_parser::{class_file::ClassFile, parse_error::ParseError},
+    engine::slot::Slot,
+    oops::{
+        cp_entry::{CPEntry, MethodHandleRef, RefKind},
+        normal_klass::{NormalKlass, UnlinkedNormalKlass},
+        oops_errors::ResolveError,
+    },
+};
+use test_harness::{expect_int, load_class, run, start_vm};
+
+fn class_bytes(name: &str) -> Vec<u8> {
+    std::fs::read(format!("{}/../test_data/classes/{name}.class", env!("CARGO_MANIFEST_DIR"))).unwrap()
+}
+
+fn entries(klass: &NormalKlass) -> impl Iterator<Item = &CPEntry> {
+    (1..klass.constant_pool_len()).filter_map(|x| klass.constant_pool_entry(x))
+}
+
+/// Byte offset of the first constant-pool entry with `tag`.
+fn find_tag(bytes: &[u8], tag: u8) -> usize {
+    let count = u16::from_be_bytes([bytes[8], bytes[9]]);
+    let mut pos = 10;
+    let mut slot = 1;
+
+    while slot < count {
+        if bytes[pos] == tag {
+            return pos;
+        }
+        let (len, slots) = match bytes[pos] {
+            1 => (3 + u16::from_be_bytes([bytes[pos + 1], bytes[pos + 2]]) as usize, 1),
+            5 | 6 => (9, 2),
+            7 | 8 | 16 => (3, 1),
+            15 => (4, 1),
+            _ => (5, 1),
+        };
+        pos += len;
+        slot += slots;
+    }
+
+    panic!("no constant with tag {tag}");
+}
+
+fn build(bytes: &[u8]) -> Result<UnlinkedNormalKlass, ResolveError> {
+    start_vm();
+    UnlinkedNormalKlass::build(ClassFile::from(bytes).unwrap(), None)
+}
+
+#[test]
+fn test_class_with_lambdas_loads_and_runs() {
+    let klass = load_class("Lambdas");
+    assert_eq!(expect_int(run(&klass, "plain", "(II)I", vec![Slot::int(2), Slot::int(3)])), 5);
+}
+
+#[test]
+fn test_invoke_dynamic_entries() {
+    let klass = load_class("Lambdas");
+
+    let mut call_sites: Vec<_> = entries(&klass)
+        .filter_map(|x| match x {
+            CPEntry::InvokeDynamic(x) => Some((x.name.utf8(), x.desc.raw.utf8())),
+            _ => None,
+        })
+        .collect();
+    call_sites.sort();
+
+    assert_eq!(
+        call_sites,
+        [
+            ("applyAsInt", "()Ljava/util/function/IntBinaryOperator;"),
+            ("applyAsInt", "()Ljava/util/function/IntBinaryOperator;"),
+            ("get", "()Ljava/util/function/Supplier;"),
+        ]
+    );
+}
+
+#[test]
+fn test_method_handle_and_method_type_entries() {
+    let klass = load_class("Lambdas");
+
+    let handles: Vec<_> = entries(&klass)
+        .filter_map(|x| match x {
+            CPEntry::MethodHandle(x) => Some((x.kind, x.class_name().utf8(), x.name().utf8())),
+            _ => None,
+        })
+        .collect();
+
+    assert!(handles.contains(&(
+        RefKind::InvokeStatic,
+        "java/lang/invoke/LambdaMetafactory",
+        "metafactory"
+    )));
+    assert!(handles.contains(&(RefKind::InvokeStatic, "Lambdas", "plain")));
+    assert!(handles.contains(&(RefKind::NewInvokeSpecial, "java/lang/String", "<init>")));
+
+    let plain = entries(&klass)
+        .find_map(|x| match x {
+            CPEntry::MethodHandle(x) if x.name().utf8() == "plain" => Some(x),
+            _ => None,
+        })
+        .unwrap();
+    assert!(matches!(plain.reference, MethodHandleRef::Method(_)));
+    assert_eq!(plain.desc().utf8(), "(II)I");
+
+    let types: Vec<_> = entries(&klass)
+        .filter_map(|x| match x {
+            CPEntry::MethodType(x) => Some(x.raw.utf8()),
+            _ => None,
+        })
+        .collect();
+    assert!(types.contains(&"(II)I"));
+    assert!(types.contains(&"()Ljava/lang/Object;"));
+}
+
+#[test]
+fn test_unknown_reference_kind_is_rejected() {
+    let mut bytes = class_bytes("Lambdas");
+    let pos = find_tag(&bytes, 15);
+
+    bytes[pos + 1] = 10;
+    assert!(matches!(build(&bytes), Err(ResolveError::UnknownRefKind(10))));
+
+    bytes[pos + 1] = 0;
+    assert!(matches!(build(&bytes), Err(ResolveError::UnknownRefKind(0))));
+}
+
+#[test]
+fn test_reference_kind_must_match_the_member() {
+    let mut bytes = class_bytes("Lambdas");
+    let pos = find_tag(&bytes, 15);
+
+    // A getfield handle cannot name a method.
+    bytes[pos + 1] = RefKind::GetField as u8;
+    assert!(matches!(build(&bytes), Err(ResolveError::MismatchCPType)));
+}
+
+#[test]
+fn test_dynamic_constants_need_a_recent_class_file() {
+    let mut bytes = class_bytes("Lambdas");
+    bytes[7] = 50;
+
+    assert!(matches!(ClassFile::from(&bytes), Err(ParseError::UnsupportedCPTag(_))));
+}
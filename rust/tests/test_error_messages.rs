@@ -0,0 +1,89 @@
+mod test_harness;
+
+use std::error::Error;
+
+use klover::{
+    assembler::class_builder::ClassBuilder,
+    class_loader::cld::ClassLoaderData,
+    engine::exec_error::{ExecError, JavaExceptionKind},
+    oops::oops_errors::ResolveError,
+};
+use test_harness::{define_in, start_vm, try_run};
+
+/// `err` and its sources, outermost first.
+fn chain(err: &dyn Error) -> Vec<String> {
+    let mut messages = vec![err.to_string()];
+    let mut next = err.source();
+    while let Some(cause) = next {
+        messages.push(cause.to_string());
+        next = cause.source();
+    }
+    messages
+}
+
+fn loader(name: &str) -> &'static ClassLoaderData {
+    start_vm();
+    unsafe { ClassLoaderData::new(Some(name.into())).as_ref() }
+}
+
+#[test]
+fn test_parse_error_names_the_problem() {
+    let error = loader("garbage").define_class(&[0xde, 0xad, 0xbe, 0xef]).unwrap_err();
+    assert_eq!(chain(&error), ["invalid class file", "bad magic number 0xdeadbeef"]);
+}
+
+#[test]
+fn test_link_error_names_both_classes() {
+    let mut class = ClassBuilder::new("Orphan");
+    class.extend("NoSuchSuperclass");
+    let error = loader("orphans").define_class(&class.to_bytes().unwrap()).unwrap_err();
+
+    assert_eq!(
+        chain(&error),
+        ["cannot link class Orphan", "class NoSuchSuperclass not found"]
+    );
+}
+
+#[test]
+fn test_resolution_errors_name_the_member() {
+    let mut class = ClassBuilder::new("Referrer");
+    let missing = class.constant_pool().field_ref("AccessTargets", "missing", "I");
+    let secret = class.constant_pool().method_ref("AccessTargets", "secret", "()I");
+    let klass = define_in(class, loader("referrer"));
+
+    let error = klass.resolve_field_ref(missing as usize).unwrap_err();
+    assert!(matches!(&error, ResolveError::FieldNotFound(x) if x.name == "missing"));
+    assert_eq!(error.to_string(), "field AccessTargets.missing:I not found");
+
+    let error = klass.resolve_method_ref(secret as usize).unwrap_err();
+    assert_eq!(error.to_string(), "Referrer cannot access AccessTargets.secret()I");
+}
+
+#[test]
+fn test_engine_error_names_method_and_bci() {
+    let mut class = ClassBuilder::new("Unsupported");
+    let mut code = class.method(0x0009, "call", "()I");
+    // iconst_1; i2l; pop2; iconst_0; ireturn: i2l is not implemented.
+    code.op(0x04).op(0x85).op(0x58).op(0x03).op(0xac);
+    code.finish().unwrap();
+    let klass = define_in(class, loader("unsupported"));
+
+    let error = try_run(&klass, "call", "()I", vec![]).unwrap_err();
+    assert!(matches!(&error, ExecError::At { bci: 1, .. }));
+    assert_eq!(
+        chain(&error),
+        ["in Unsupported.call()I at bci 1", "unsupported opcode 0x85 at bci 1"]
+    );
+}
+
+/// The verifier rejects such code before it runs; trusted classes that skip
+/// it get a VerifyError, not the ICCE of a static field.
+#[test]
+fn test_receiver_without_the_field_is_a_verify_error() {
+    let error = ExecError::ReceiverLacksField;
+    assert_eq!(error.java_exception(), Some(JavaExceptionKind::VerifyError));
+    assert_eq!(error.to_string(), "field access on an object without the field");
+
+    let error = ExecError::IncompatibleInstanceFieldAccess;
+    assert_eq!(error.java_exception(), Some(JavaExceptionKind::IncompatibleClassChangeError));
+}
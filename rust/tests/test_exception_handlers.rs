@@ -0,0 +1,49 @@
+mod test_harness;
+
+use klover::{
+    engine::{exec_error::JavaExceptionKind, slot::Slot},
+    oops::java_throwable::JavaThrowable,
+};
+use test_harness::{expect_exception, expect_int, load_class, run};
+
+#[test]
+fn test_vm_raised_exception_is_caught_by_its_class() {
+    let holder = load_class("Catches");
+
+    assert_eq!(expect_int(run(&holder, "divideOr", "(II)I", vec![Slot::int(5), Slot::int(-7)])), 2);
+    assert_eq!(expect_int(run(&holder, "divideOr", "(II)I", vec![Slot::int(0), Slot::int(-7)])), -7);
+    assert_eq!(expect_int(run(&holder, "nested", "(I)I", vec![Slot::int(0)])), -2);
+}
+
+#[test]
+fn test_handler_for_another_class_is_skipped() {
+    let holder = load_class("Catches");
+    let exit = run(&holder, "wrongCatch", "(I)I", vec![Slot::int(0)]);
+
+    expect_exception(exit, JavaExceptionKind::ArithmeticException);
+}
+
+#[test]
+fn test_finally_rethrows_with_the_original_trace() {
+    let holder = load_class("Catches");
+    let exit = run(&holder, "divideWithCleanup", "(I)I", vec![Slot::int(0)]);
+
+    let obj = expect_exception(exit, JavaExceptionKind::ArithmeticException).expect("rethrown as an object");
+    assert_eq!(JavaThrowable::message(obj).unwrap().as_deref(), Some("/ by zero"));
+
+    // 轨迹是抛出点的栈，而不是 athrow 重新抛出时的栈。
+    let trace = JavaThrowable::stack_trace(obj).unwrap();
+    let methods: Vec<_> = trace.frames.iter().map(|frame| frame.method_name.as_str()).collect();
+    assert_eq!(methods, ["divide", "divideWithCleanup"]);
+    assert_eq!(trace.frames[1].bci, 3);
+
+    assert_eq!(expect_int(run(&holder, "cleanups", "()I", vec![])), 1);
+}
+
+#[test]
+fn test_athrow_of_a_constructed_exception() {
+    let holder = load_class("Catches");
+
+    assert_eq!(expect_int(run(&holder, "throwAndCatch", "(I)I", vec![Slot::int(0)])), 0);
+    assert_eq!(expect_int(run(&holder, "throwAndCatch", "(I)I", vec![Slot::int(1)])), 1);
+}
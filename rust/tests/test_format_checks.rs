@@ -0,0 +1,189 @@
+mod test_harness;
+
+use klover::{
+    assembler::class_builder::ClassBuilder,
+    class_loader::{cld::ClassLoaderData, load_error::LoadError},
+    class_parser::{class_file::ClassFile, cp_info::ConstantPoolInfo, parse_error::ParseError},
+    engine::exec_error::JavaExceptionKind,
+};
+use test_harness::{load_class, splice_utf8};
+
+/// A class file of version 52: some checks do not apply to older ones.
+fn java8(name: &str) -> ClassBuilder {
+    let mut class = ClassBuilder::new(name);
+    class.version(52);
+    class
+}
+
+fn parse(class: ClassBuilder) -> Result<ClassFile, ParseError> {
+    ClassFile::from(&class.to_bytes().unwrap())
+}
+
+fn with_field(class_name: &str, flags: u16, name: &str, desc: &str) -> ClassBuilder {
+    let mut class = java8(class_name);
+    class.field(flags, name, desc);
+    class
+}
+
+/// `max_locals` is given, so `desc` may be malformed.
+fn add_method(class: &mut ClassBuilder, flags: u16, name: &str, desc: &str) {
+    let mut code = class.method(flags, name, desc);
+    code.max_locals(2).op(0xb1); // return
+    code.finish().unwrap();
+}
+
+fn with_method(class_name: &str, flags: u16, name: &str, desc: &str) -> ClassBuilder {
+    let mut class = java8(class_name);
+    add_method(&mut class, flags, name, desc);
+    class
+}
+
+#[test]
+fn test_well_formed_classes_pass() {
+    let mut class = with_field("pkg/Good", 0x0012, "value", "[[Ljava/lang/String;");
+    add_method(&mut class, 0x0001, "<init>", "()V");
+    add_method(&mut class, 0x0008, "<clinit>", "()V");
+    class.abstract_method(0x0109, "nap", "(JD)V");
+    assert!(parse(class).is_ok());
+}
+
+#[test]
+fn test_names_are_checked() {
+    assert!(matches!(parse(java8("pkg.Dotted")), Err(ParseError::InvalidName(_))));
+    assert!(matches!(parse(java8("pkg//Empty")), Err(ParseError::InvalidName(_))));
+
+    let res = parse(with_field("BadField", 0x0001, "a;b", "I"));
+    assert!(matches!(res, Err(ParseError::InvalidName(name)) if name == "a;b"));
+
+    let res = parse(with_method("BadMethod", 0x0001, "a<b", "()V"));
+    assert!(matches!(res, Err(ParseError::InvalidName(_))));
+
+    let res = parse(with_method("StaticInit", 0x0009, "<init>", "()V"));
+    assert!(matches!(res, Err(ParseError::InvalidMethodFlags { .. })));
+
+    let res = parse(with_method("ReturningInit", 0x0001, "<init>", "()I"));
+    assert!(matches!(res, Err(ParseError::InvalidDescriptor(_))));
+}
+
+#[test]
+fn test_descriptors_are_checked() {
+    for desc in ["Q", "V", "Ljava/lang/String", "L;", "II"] {
+        let res = parse(with_field("BadFieldDesc", 0x0001, "x", desc));
+        assert!(matches!(res, Err(ParseError::InvalidDescriptor(_))), "{desc}");
+    }
+    for desc in ["(I", "(V)V", "()", "(I)VV"] {
+        let res = parse(with_method("BadMethodDesc", 0x0009, "m", desc));
+        assert!(matches!(res, Err(ParseError::InvalidDescriptor(_))), "{desc}");
+    }
+
+    let too_deep = "[".repeat(256) + "I";
+    let res = parse(with_field("DeepArray", 0x0001, "x", &too_deep));
+    assert!(matches!(res, Err(ParseError::InvalidDescriptor(_))));
+
+    // 128 个 long 占 256 个槽位
+    let too_wide = format!("({})V", "J".repeat(128));
+    let res = parse(with_method("WideMethod", 0x0009, "m", &too_wide));
+    assert!(matches!(res, Err(ParseError::InvalidDescriptor(_))));
+}
+
+#[test]
+fn test_flag_combinations_are_checked() {
+    for flags in [0x0200, 0x0630, 0x0411] {
+        let mut class = java8("BadFlags");
+        class.flags(flags);
+        assert!(matches!(parse(class), Err(ParseError::InvalidClassFlags(_))), "{flags:#x}");
+    }
+
+    for flags in [0x0003, 0x0050] {
+        let res = parse(with_field("BadFieldFlags", flags, "x", "I"));
+        assert!(matches!(res, Err(ParseError::InvalidFieldFlags { .. })), "{flags:#x}");
+    }
+
+    let mut class = java8("AbstractStatic");
+    class.abstract_method(0x0408, "m", "()V");
+    assert!(matches!(parse(class), Err(ParseError::InvalidMethodFlags { .. })));
+}
+
+#[test]
+fn test_code_must_match_method_kind() {
+    let mut class = java8("NoCode");
+    class.abstract_method(0x0009, "m", "()V");
+    assert!(matches!(parse(class), Err(ParseError::MissingCode(name)) if name == "m"));
+
+    let res = parse(with_method("NativeCode", 0x0109, "m", "()V"));
+    assert!(matches!(res, Err(ParseError::UnexpectedCode(name)) if name == "m"));
+}
+
+#[test]
+fn test_duplicates_are_rejected() {
+    let mut class = with_field("TwoFields", 0x0001, "x", "I");
+    class.field(0x0002, "x", "I");
+    assert!(matches!(parse(class), Err(ParseError::DuplicatedField { .. })));
+
+    // 同名不同描述符的字段是允许的
+    let mut class = with_field("Overloaded", 0x0001, "x", "I");
+    class.field(0x0001, "x", "J");
+    assert!(parse(class).is_ok());
+
+    let mut class = with_method("TwoMethods", 0x0009, "m", "()V");
+    add_method(&mut class, 0x0001, "m", "()V");
+    assert!(matches!(parse(class), Err(ParseError::DuplicatedMethod { .. })));
+
+    let mut class = java8("TwoInterfaces");
+    class.implement("java/lang/Runnable");
+    class.implement("java/lang/Runnable");
+    assert!(matches!(parse(class), Err(ParseError::DuplicatedInterface(_))));
+}
+
+#[test]
+fn test_hierarchy_references_are_checked() {
+    let mut class = java8("ArrayParent");
+    class.extend("[I");
+    assert!(matches!(parse(class), Err(ParseError::InvalidSuperClass(_))));
+
+    let mut class = java8("pkg/Iface");
+    class.flags(0x0601).extend("java/lang/String");
+    assert!(matches!(parse(class), Err(ParseError::InvalidSuperClass(_))));
+
+    let mut class = java8("SelfParent");
+    class.extend("SelfParent");
+    assert!(matches!(parse(class), Err(ParseError::InvalidSuperClass(_))));
+}
+
+#[test]
+fn test_constant_pool_cross_references_are_checked() {
+    // Fieldref 的 class_index 指向 Utf8
+    let mut class = java8("BadFieldref");
+    let cp = class.constant_pool();
+    let class_index = cp.utf8("java/lang/Object");
+    let name_and_type_index = cp.name_and_type("x", "I");
+    cp.entry(ConstantPoolInfo::FieldrefInfo {
+        class_index,
+        name_and_type_index,
+    });
+    assert!(matches!(parse(class), Err(ParseError::InvalidCPType)));
+
+    let mut class = java8("FieldDescInMethodref");
+    class.constant_pool().method_ref("java/lang/Object", "x", "I");
+    assert!(matches!(parse(class), Err(ParseError::InvalidDescriptor(_))));
+
+    let mut class = java8("NulInUtf8");
+    class.constant_pool().utf8("nul byte");
+    let mut bytes = class.to_bytes().unwrap();
+    splice_utf8(&mut bytes, "nul byte", b"nul\0byte");
+    assert!(matches!(ClassFile::from(&bytes), Err(ParseError::InvalidUtf8(_))));
+}
+
+#[test]
+fn test_malformed_class_is_class_format_error() {
+    load_class("ControlFlow");
+    let cld = unsafe { ClassLoaderData::new(Some("format loader".to_owned())).as_ref() };
+    let bytes = with_method("FormatBroken", 0x0009, "<init>", "()V").to_bytes().unwrap();
+    match cld.define_class(&bytes) {
+        Err(error @ LoadError::Parse { .. }) => {
+            assert_eq!(error.java_exception(), Some(JavaExceptionKind::ClassFormatError));
+        }
+        Err(other) => panic!("expected a parse error, got {other}"),
+        Ok(_) => panic!("malformed class defined"),
+    }
+}
@@ -1,40 +1,139 @@
 #![allow(dead_code)]
 
-use std::sync::Once;
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    sync::{Arc, Once},
+};
 
 use klover::{
-    class_loader::{bootstrap_cld::BootstrapCLD, ms_api::MSRef},
+    assembler::{class_builder::ClassBuilder, jasmin},
+    class_loader::{
+        bootstrap_cld::BootstrapCLD, class_source::ClassSource, cld::ClassLoaderData, ms_api::MSRef,
+        system_cld::SystemCLD,
+    },
     engine::{
         call::Invocation,
         exec_dispatcher::ExecDispatcher,
-        exec_error::ExecResult,
-        outcome::{RetValue, RunOutcome, ThreadExit},
+        exec_error::{ExecResult, JavaExceptionKind},
+        outcome::{PendingException, RetValue, RunOutcome, ThreadExit},
         resolved_method::ResolvedMethod,
         slot::Slot,
     },
+    gc_bindings::oop_codec::klass_of,
     oops::normal_klass::NormalKlass,
     runtime::{arguments::Arguments, thread_manager::ThreadManager, vm::vm_init},
 };
 
 static VM_INIT: Once = Once::new();
 
-fn init_vm() {
+/// A directory of Jasmin-style sources: class `p/A` is assembled from
+/// `p/A.j` when it is looked up.
+#[derive(Debug)]
+struct AssemblySource {
+    root: PathBuf,
+}
+
+impl fmt::Display for AssemblySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.root.display())
+    }
+}
+
+impl ClassSource for AssemblySource {
+    fn read_resource(&self, resource: &str) -> Option<Vec<u8>> {
+        fs::read(self.root.join(resource)).ok()
+    }
+
+    /// A source that does not assemble is reported here and then treated as
+    /// missing, so the test sees `NoClassDefFoundError` next to the line
+    /// that is wrong.
+    fn read_class(&self, name: &str) -> Option<Vec<u8>> {
+        let path = self.root.join(format!("{name}.j"));
+        let source = fs::read_to_string(&path).ok()?;
+        jasmin::assemble(&source)
+            .inspect_err(|err| eprintln!("{}: {err}", path.display()))
+            .ok()
+    }
+}
+
+/// Bring up the VM, once per test binary.  Tests that only need the class
+/// loaders or the heap call this directly.
+pub fn start_vm() {
     VM_INIT.call_once(|| {
         vm_init(Arguments {
-            bs_class_path: format!("{}/../test_data/classes", env!("CARGO_MANIFEST_DIR")),
+            bs_class_path: format!(
+                "{0}/../test_data/classes:{0}/../test_data/jars/boot.jar",
+                env!("CARGO_MANIFEST_DIR")
+            ),
+            class_path: format!(
+                "{0}/../test_data/app_classes:{0}/../test_data/jars/app.jar",
+                env!("CARGO_MANIFEST_DIR")
+            ),
+            // `.j` sources under test_data/jasmin are assembled on lookup.
+            boot_sources: vec![Arc::new(AssemblySource {
+                root: format!("{}/../test_data/jasmin", env!("CARGO_MANIFEST_DIR")).into(),
+            })],
             xmx: 64 * 1024 * 1024,
+            system_properties: vec![
+                ("klover.test.name".into(), "first".into()),
+                ("klover.test.name".into(), "klover".into()),
+            ],
+            // The test classes are on the boot class path; verify them too.
+            bytecode_verification_local: true,
+            ..Default::default()
         });
     });
 }
 
 pub fn load_class(name: &str) -> MSRef<NormalKlass> {
-    init_vm();
+    start_vm();
     BootstrapCLD::find_class(name)
         .unwrap()
         .as_normal_ref()
         .unwrap()
 }
 
+pub fn load_app_class(name: &str) -> MSRef<NormalKlass> {
+    start_vm();
+    SystemCLD::find_class(name)
+        .unwrap()
+        .as_normal_ref()
+        .unwrap()
+}
+
+/// Define the class `class` assembles in a loader of its own, after
+/// bringing up the VM.
+pub fn define(class: ClassBuilder) -> MSRef<NormalKlass> {
+    start_vm();
+    let cld = unsafe { ClassLoaderData::new(Some("test loader".into())).as_ref() };
+    define_in(class, cld)
+}
+
+pub fn define_in(class: ClassBuilder, cld: &ClassLoaderData) -> MSRef<NormalKlass> {
+    cld.define_class(&class.to_bytes().unwrap())
+        .unwrap()
+        .as_normal_ref()
+        .unwrap()
+}
+
+/// Replace the contents of the `CONSTANT_Utf8` entry holding `text` in the
+/// class file `bytes` with `raw`, which need not be valid modified UTF-8.
+pub fn splice_utf8(bytes: &mut Vec<u8>, text: &str, raw: &[u8]) {
+    let mut entry = vec![1];
+    entry.extend((text.len() as u16).to_be_bytes());
+    entry.extend(text.as_bytes());
+    let at = bytes
+        .windows(entry.len())
+        .position(|x| x == entry)
+        .unwrap_or_else(|| panic!("no Utf8 entry {text:?}"));
+
+    let mut replacement = vec![1];
+    replacement.extend((raw.len() as u16).to_be_bytes());
+    replacement.extend(raw);
+    bytes.splice(at..at + entry.len(), replacement);
+}
+
 pub fn run(
     holder: &MSRef<NormalKlass>,
     name: &str,
@@ -104,3 +203,17 @@ pub fn expect_ref(exit: ThreadExit) -> u32 {
         other => panic!("expected reference return, got {other:?}"),
     }
 }
+
+/// The thread died of `kind`, raised by the VM or thrown as an object of
+/// that class.  Returns the object, if there is one.
+pub fn expect_exception(exit: ThreadExit, kind: JavaExceptionKind) -> Option<u32> {
+    match exit {
+        ThreadExit::UncaughtException(PendingException::JVMGen(x)) if x == kind => None,
+        ThreadExit::UncaughtException(PendingException::JavaObj(obj))
+            if klass_of(obj).is_some_and(|klass| klass.name().utf8() == kind.class_name()) =>
+        {
+            Some(obj)
+        }
+        other => panic!("expected {kind:?}, got {other:?}"),
+    }
+}
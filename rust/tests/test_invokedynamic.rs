@@ -0,0 +1,304 @@
+mod test_harness;
+
+use klover::{
+    assembler::class_builder::ClassBuilder,
+    class_loader::ms_api::MSRef,
+    engine::{
+        exec_error::JavaExceptionKind,
+        slot::Slot,
+    },
+    gc_bindings::oop_codec::klass_of,
+    oops::{
+        array_klass::ArrayKlass, java_class::JavaClass, java_invoke::JavaMethodType, java_string::JavaString,
+        java_throwable::JavaThrowable, normal_klass::NormalKlass,
+    },
+};
+use test_harness::{define, expect_exception, expect_int, load_class, run};
+
+const BOOTSTRAPS: &str = "IndyBootstraps";
+const BSM_PREFIX: &str = "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;";
+
+/// javac only emits `invokedynamic` for lambdas and string concatenation, so
+/// the callers are assembled here; the bootstrap methods live in
+/// `IndyBootstraps.java`.
+struct IndyClass(ClassBuilder);
+
+impl IndyClass {
+    fn new(name: &str) -> Self {
+        let mut class = ClassBuilder::new(name);
+        class.version(52);
+        Self(class)
+    }
+
+    fn integer(&mut self, value: i32) -> u16 {
+        self.0.constant_pool().integer(value)
+    }
+
+    /// `REF_invokeStatic` handle on `IndyBootstraps.name`.
+    fn static_handle(&mut self, name: &str, desc: &str) -> u16 {
+        let cp = self.0.constant_pool();
+        let method_ref = cp.method_ref(BOOTSTRAPS, name, desc);
+        cp.method_handle(6, method_ref)
+    }
+
+    /// The bootstrap method `IndyBootstraps.bsm` with `args`.
+    fn call_site(&mut self, bsm: &str, bsm_params: &str, args: &[u16]) -> u16 {
+        let handle = self.static_handle(bsm, &format!("{BSM_PREFIX}{bsm_params})Ljava/lang/invoke/CallSite;"));
+        self.0.bootstrap_method(handle, args)
+    }
+
+    /// A static method `name` that passes its int arguments to a call site
+    /// of the same name and type, linked by `bootstrap`, and returns the int
+    /// result.
+    fn caller(&mut self, name: &str, int_params: u8, bootstrap: u16) {
+        let desc = format!("({})I", "I".repeat(int_params as usize));
+        let mut code = self.0.method(0x0009, name, &desc);
+        for x in 0..int_params {
+            code.op(0x1a + x); // iload_<n>
+        }
+        code.invokedynamic(bootstrap, name, &desc).op(0xac); // ireturn
+        code.finish().unwrap();
+    }
+
+    fn define(self) -> MSRef<NormalKlass> {
+        define(self.0)
+    }
+}
+
+fn static_ref(klass: &NormalKlass, name: &str, desc: &str) -> u32 {
+    let field = klass.find_declared_field(name, desc).unwrap();
+    klass.read_static_field(&field).unwrap()[0].as_ref().unwrap()
+}
+
+#[test]
+fn test_invokedynamic_invokes_linked_target() {
+    let mut class = IndyClass::new("IndyAdd");
+    let target = class.static_handle("add", "(II)I");
+    let site = class.call_site("constant", "Ljava/lang/invoke/MethodHandle;", &[target]);
+    class.caller("add", 2, site);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "add", "(II)I", vec![Slot::int(2), Slot::int(3)])), 5);
+    assert_eq!(expect_int(run(&klass, "add", "(II)I", vec![Slot::int(-7), Slot::int(4)])), -3);
+}
+
+#[test]
+fn test_call_site_is_linked_once() {
+    let mut class = IndyClass::new("IndyCounted");
+    let target = class.static_handle("answer", "()I");
+    let site = class.call_site("counted", "Ljava/lang/invoke/MethodHandle;", &[target]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "answer", "()I", vec![])), 42);
+    assert_eq!(expect_int(run(&klass, "answer", "()I", vec![])), 42);
+
+    let bootstraps = load_class(BOOTSTRAPS);
+    assert_eq!(expect_int(run(&bootstraps, "countedLinks", "()I", vec![])), 1);
+}
+
+#[test]
+fn test_each_instruction_is_its_own_call_site() {
+    let mut class = IndyClass::new("IndyTwoInstructions");
+    let target = class.static_handle("add", "(II)I");
+    let site = class.call_site("countedPerInstruction", "Ljava/lang/invoke/MethodHandle;", &[target]);
+    // 两条 invokedynamic 共用同一个常量池项。
+    let entry = class.0.constant_pool().invoke_dynamic(site, "add", "(II)I");
+    assert_eq!(class.0.constant_pool().invoke_dynamic(site, "add", "(II)I"), entry);
+
+    let mut code = class.0.method(0x0009, "twice", "(II)I");
+    code.op(0x1a).op(0x1b).invokedynamic(site, "add", "(II)I"); // iload_0; iload_1
+    code.op(0x1a).op(0x1b).invokedynamic(site, "add", "(II)I");
+    code.op(0x60).op(0xac); // iadd; ireturn
+    code.finish().unwrap();
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "twice", "(II)I", vec![Slot::int(2), Slot::int(3)])), 10);
+    assert_eq!(expect_int(run(&klass, "twice", "(II)I", vec![Slot::int(1), Slot::int(1)])), 4);
+
+    let bootstraps = load_class(BOOTSTRAPS);
+    assert_eq!(expect_int(run(&bootstraps, "perInstructionLinks", "()I", vec![])), 2);
+}
+
+#[test]
+fn test_bootstrap_method_receives_lookup_name_and_type() {
+    let mut class = IndyClass::new("IndyRecording");
+    let target = class.static_handle("sub", "(II)I");
+    let site = class.call_site("recording", "Ljava/lang/invoke/MethodHandle;", &[target]);
+    class.caller("minus", 2, site);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "minus", "(II)I", vec![Slot::int(9), Slot::int(4)])), 5);
+
+    let bootstraps = load_class(BOOTSTRAPS);
+    let name = static_ref(&bootstraps, "lastName", "Ljava/lang/String;");
+    let method_type = static_ref(&bootstraps, "lastType", "Ljava/lang/invoke/MethodType;");
+    assert_eq!(JavaString::to_rust_string(name).unwrap(), "minus");
+    assert_eq!(JavaMethodType::descriptor(method_type).unwrap(), "(II)I");
+
+    let lookup = static_ref(&bootstraps, "lastLookup", "Ljava/lang/invoke/MethodHandles$Lookup;");
+    let lookup_klass = load_class("java/lang/invoke/MethodHandles$Lookup");
+    let field = lookup_klass.find_declared_field("lookupClass", "Ljava/lang/Class;").unwrap();
+    let mirror = lookup_klass.read_instance_field(lookup, &field).unwrap()[0].as_ref().unwrap();
+    assert_eq!(JavaClass::klass_of(mirror).unwrap().name().utf8(), "IndyRecording");
+}
+
+#[test]
+fn test_static_arguments_reach_bootstrap_method() {
+    let mut class = IndyClass::new("IndyChoosing");
+    let add = class.static_handle("add", "(II)I");
+    let sub = class.static_handle("sub", "(II)I");
+    let first = class.integer(0);
+    let second = class.integer(1);
+    let params = "Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodHandle;I";
+    let add_site = class.call_site("choosing", params, &[add, sub, first]);
+    let sub_site = class.call_site("choosing", params, &[add, sub, second]);
+    class.caller("first", 2, add_site);
+    class.caller("second", 2, sub_site);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "first", "(II)I", vec![Slot::int(6), Slot::int(2)])), 8);
+    assert_eq!(expect_int(run(&klass, "second", "(II)I", vec![Slot::int(6), Slot::int(2)])), 4);
+}
+
+#[test]
+fn test_mismatched_target_type_is_bootstrap_method_error() {
+    let mut class = IndyClass::new("IndyMismatch");
+    let target = class.static_handle("answer", "()I");
+    let site = class.call_site("constant", "Ljava/lang/invoke/MethodHandle;", &[target]);
+    class.caller("add", 2, site);
+    let klass = class.define();
+
+    let exit = run(&klass, "add", "(II)I", vec![Slot::int(1), Slot::int(2)]);
+    expect_exception(exit, JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_null_call_site_is_bootstrap_method_error() {
+    let mut class = IndyClass::new("IndyNull");
+    let site = class.call_site("returnsNull", "", &[]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+
+    expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_missing_bootstrap_method_is_bootstrap_method_error() {
+    let mut class = IndyClass::new("IndyMissing");
+    let site = class.call_site("missing", "", &[]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+
+    expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_failed_linkage_is_remembered() {
+    let mut class = IndyClass::new("IndyThrowing");
+    let divisor = class.integer(0);
+    let site = class.call_site("throwing", "I", &[divisor]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+
+    // JVMS 6.5: the bootstrap method's exception reaches the caller wrapped;
+    // later executions see the recorded linkage error.
+    let error = expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::BootstrapMethodError)
+        .expect("wrapped in an object");
+    let cause = JavaThrowable::cause(error).unwrap().expect("the original exception");
+    assert_eq!(klass_of(cause).unwrap().name().utf8(), "java/lang/ArithmeticException");
+    assert_eq!(JavaThrowable::message(cause).unwrap().as_deref(), Some("/ by zero"));
+
+    expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_errors_from_bootstrap_methods_are_not_wrapped() {
+    let mut class = IndyClass::new("IndyErroring");
+    let site = class.call_site("erroring", "", &[]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+
+    let error = expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::IllegalAccessError);
+    assert!(error.is_some());
+    expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+/// The `value` slots of the box `obj`, checking its class.
+fn unbox(obj: u32, class: &str, desc: &str) -> Vec<Slot> {
+    let klass = klass_of(obj).unwrap().as_normal_ref().unwrap();
+    assert_eq!(klass.name().utf8(), class);
+    let field = klass.find_declared_field("value", desc).unwrap();
+    klass.read_instance_field(obj, &field).unwrap()
+}
+
+#[test]
+fn test_variable_arity_bootstrap_method_collects_static_arguments() {
+    let mut class = IndyClass::new("IndyCollecting");
+    let add = class.static_handle("add", "(II)I");
+    let seven = class.integer(7);
+    let big = class.0.constant_pool().long(1 << 40);
+    let text = class.0.constant_pool().string("text");
+    let params = "Ljava/lang/invoke/MethodHandle;[Ljava/lang/Object;";
+    let site = class.call_site("collecting", params, &[add, seven, big, text]);
+    class.caller("add", 2, site);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "add", "(II)I", vec![Slot::int(6), Slot::int(2)])), 8);
+
+    let bootstraps = load_class(BOOTSTRAPS);
+    let rest = static_ref(&bootstraps, "lastCollected", "[Ljava/lang/Object;");
+    assert_eq!(ArrayKlass::length(rest).unwrap(), 3);
+    let int = unbox(ArrayKlass::read_ref(rest, 0).unwrap(), "java/lang/Integer", "I");
+    assert_eq!(int[0].as_int().unwrap(), 7);
+    let long = unbox(ArrayKlass::read_ref(rest, 1).unwrap(), "java/lang/Long", "J");
+    assert_eq!(Slot::as_long(long[0], long[1]).unwrap(), 1 << 40);
+    let text = JavaString::to_rust_string(ArrayKlass::read_ref(rest, 2).unwrap()).unwrap();
+    assert_eq!(text, "text");
+}
+
+#[test]
+fn test_variable_arity_bootstrap_method_without_trailing_arguments() {
+    let mut class = IndyClass::new("IndyCollectingNothing");
+    let sub = class.static_handle("sub", "(II)I");
+    let params = "Ljava/lang/invoke/MethodHandle;[Ljava/lang/Object;";
+    let site = class.call_site("collectingNothing", params, &[sub]);
+    class.caller("sub", 2, site);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "sub", "(II)I", vec![Slot::int(6), Slot::int(2)])), 4);
+    let bootstraps = load_class(BOOTSTRAPS);
+    let rest = static_ref(&bootstraps, "lastCollectedNothing", "[Ljava/lang/Object;");
+    assert_eq!(ArrayKlass::length(rest).unwrap(), 0);
+
+    // 固定参数不够时无法链接。
+    let mut class = IndyClass::new("IndyCollectingTooFew");
+    let site = class.call_site("collectingNothing", params, &[]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+    expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_primitive_static_arguments_are_boxed() {
+    let mut class = IndyClass::new("IndyBoxing");
+    let answer = class.static_handle("answer", "()I");
+    let half = class.0.constant_pool().float(0.5);
+    let site = class.call_site("boxing", "Ljava/lang/invoke/MethodHandle;Ljava/lang/Number;", &[answer, half]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "answer", "()I", vec![])), 42);
+    let bootstraps = load_class(BOOTSTRAPS);
+    let boxed = unbox(static_ref(&bootstraps, "lastBoxed", "Ljava/lang/Number;"), "java/lang/Float", "F");
+    assert_eq!(boxed[0].as_float().unwrap(), 0.5);
+
+    // 字符串不是 Number。
+    let mut class = IndyClass::new("IndyBoxingString");
+    let answer = class.static_handle("answer", "()I");
+    let text = class.0.constant_pool().string("0.5");
+    let site = class.call_site("boxing", "Ljava/lang/invoke/MethodHandle;Ljava/lang/Number;", &[answer, text]);
+    class.caller("answer", 0, site);
+    let klass = class.define();
+    expect_exception(run(&klass, "answer", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
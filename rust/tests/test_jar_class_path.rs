@@ -0,0 +1,36 @@
+mod test_harness;
+
+use klover::{
+    class_loader::{bootstrap_cld::BootstrapCLD, class_path::ClassPath, system_cld::SystemCLD},
+    engine::slot::Slot,
+};
+use test_harness::{expect_int, load_app_class, load_class, run};
+
+#[test]
+fn bootstrap_loader_reads_classes_from_a_jar() {
+    let holder = load_class("JarBoot");
+    let exit = run(&holder, "offset", "()I", vec![]);
+
+    assert_eq!(expect_int(exit), 1000);
+}
+
+#[test]
+fn system_loader_reads_classes_from_a_jar() {
+    let holder = load_app_class("JarApp");
+
+    assert!(std::ptr::eq(holder.cld().unwrap(), SystemCLD::get()));
+    assert!(BootstrapCLD::find_class("JarApp").is_err());
+
+    let exit = run(&holder, "plusBootOffset", "(I)I", vec![Slot::int(7)]);
+    assert_eq!(expect_int(exit), 1007);
+}
+
+#[test]
+fn jar_resources_are_readable_through_the_class_path() {
+    let _ = load_class("JarBoot");
+    let manifest = ClassPath::boot()
+        .read_resource("META-INF/MANIFEST.MF")
+        .expect("jar writes a manifest");
+
+    assert!(manifest.starts_with(b"Manifest-Version: 1.0"));
+}
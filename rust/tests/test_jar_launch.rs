@@ -0,0 +1,105 @@
+use std::{path::PathBuf, sync::Once};
+
+use klover::{
+    class_loader::system_cld::SystemCLD,
+    engine::{
+        outcome::{RetValue, ThreadExit},
+        slot::Slot,
+    },
+    oops::{array_klass::ArrayKlass, java_string::JavaString},
+    runtime::{
+        arguments::Arguments,
+        launcher::{JarLaunch, Launcher},
+        runtime_error::LaunchError,
+        vm::vm_init,
+    },
+};
+
+static VM_INIT: Once = Once::new();
+
+fn launch_jar() -> PathBuf {
+    PathBuf::from(format!("{}/../test_data/jars/launch.jar", env!("CARGO_MANIFEST_DIR")))
+}
+
+/// `-jar` replaces the application class path, so the VM is initialized
+/// from the jar's manifest rather than through the shared test harness.
+fn init_vm() -> JarLaunch {
+    let launch = JarLaunch::from_jar(&launch_jar()).unwrap();
+
+    VM_INIT.call_once(|| {
+        vm_init(Arguments {
+            bs_class_path: format!("{}/../test_data/classes", env!("CARGO_MANIFEST_DIR")),
+            class_path: launch.class_path.clone(),
+            ..Default::default()
+        });
+    });
+
+    launch
+}
+
+fn static_int(class: &str, field: &str) -> i32 {
+    let holder = SystemCLD::find_class(class).unwrap().as_normal_ref().unwrap();
+    let field = holder.find_declared_field(field, "I").unwrap();
+    let slots = holder.read_static_field(&field).unwrap();
+    Slot::as_int(slots[0]).unwrap()
+}
+
+#[test]
+fn manifest_supplies_main_class_and_relative_class_path() {
+    let launch = init_vm();
+    let jar = launch_jar();
+    let lib = jar.parent().unwrap().join("lib/launch-lib.jar");
+
+    assert_eq!(launch.main_class, "LaunchMain");
+    assert_eq!(
+        launch.class_path,
+        format!("{}:{}", jar.display(), lib.display())
+    );
+}
+
+#[test]
+fn main_runs_with_string_array_and_class_path_jar() {
+    let launch = init_vm();
+    let args = ["one", "two", "three"].map(String::from);
+
+    let exit = Launcher::run_main(&launch.main_class, &args).unwrap();
+
+    assert!(matches!(exit, ThreadExit::Returned(RetValue::Void)));
+    assert_eq!(static_int("LaunchMain", "argCount"), 3);
+    assert_eq!(static_int("LaunchMain", "libValue"), 4242);
+}
+
+#[test]
+fn main_args_are_java_strings() {
+    init_vm();
+    let args = ["plain", "héllo, 世界"].map(String::from);
+
+    let array = Launcher::main_args(&args).unwrap();
+
+    assert_eq!(ArrayKlass::length(array).unwrap(), 2);
+    for (index, arg) in args.iter().enumerate() {
+        let string = ArrayKlass::read_ref(array, index).unwrap();
+        assert_eq!(&JavaString::to_rust_string(string).unwrap(), arg);
+    }
+}
+
+#[test]
+fn missing_main_method_is_reported() {
+    init_vm();
+
+    assert!(matches!(
+        Launcher::run_main("LaunchLib", &[]),
+        Err(LaunchError::MainNotFound(name)) if name == "LaunchLib"
+    ));
+}
+
+#[test]
+fn jar_without_main_class_is_rejected() {
+    let jar = format!("{}/../test_data/jars/boot.jar", env!("CARGO_MANIFEST_DIR"));
+
+    // `jar cf` always writes a manifest, just without Main-Class.
+    assert!(matches!(
+        JarLaunch::from_jar(jar.as_ref()),
+        Err(LaunchError::NoMainClass)
+    ));
+}
@@ -0,0 +1,176 @@
+mod test_harness;
+
+use std::path::Path;
+
+use klover::{
+    assembler::{
+        asm_error::{AsmError, AsmErrorKind},
+        build_error::BuildError,
+        jasmin::assemble,
+    },
+    class_parser::{attr_info::AttrInfo, class_file::ClassFile, cp_info::ConstantPoolInfo},
+    engine::slot::Slot,
+    verifier::verify_error::VerifyErrorKind,
+};
+use test_harness::{expect_int, expect_long, load_class, run};
+
+fn verify_failure(name: &str) -> VerifyErrorKind {
+    match load_class(name).verify() {
+        Err(error) => error.kind,
+        Ok(()) => panic!("{name} verified"),
+    }
+}
+
+fn error_of(source: &str) -> AsmError {
+    assemble(source).unwrap_err()
+}
+
+/// The class path only reports sources that do not assemble; check them all
+/// here.
+#[test]
+fn test_sources_assemble() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../test_data/jasmin");
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let source = std::fs::read_to_string(&path).unwrap();
+        let bytes = assemble(&source).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        ClassFile::from(&bytes).unwrap();
+        count += 1;
+    }
+    assert!(count >= 7);
+}
+
+#[test]
+fn test_dup2_x2_forms() {
+    let klass = load_class("DupShapes");
+    klass.verify().unwrap();
+
+    assert_eq!(expect_int(run(&klass, "singles", "()I", vec![])), 341234);
+    assert_eq!(expect_long(run(&klass, "longOverInts", "()J", vec![])), 200);
+    assert_eq!(expect_int(run(&klass, "intsOverLong", "()I", vec![])), 16);
+    assert_eq!(expect_long(run(&klass, "longs", "()J", vec![])), -9);
+}
+
+#[test]
+fn test_dup2_x2_splitting_a_long_fails_verification() {
+    verify_failure("DupSplitsLong");
+}
+
+#[test]
+fn test_branches_into_handler_ranges() {
+    let klass = load_class("HandlerJump");
+    klass.verify().unwrap();
+
+    assert_eq!(expect_int(run(&klass, "intoRange", "(I)I", vec![Slot::int(0)])), 0);
+    assert_eq!(expect_int(run(&klass, "intoRange", "(I)I", vec![Slot::int(1)])), 22);
+    assert_eq!(expect_int(run(&klass, "loopIntoRange", "(I)I", vec![Slot::int(4)])), 12);
+}
+
+#[test]
+fn test_branch_into_handler_fails_verification() {
+    assert!(matches!(verify_failure("HandlerEntry"), VerifyErrorKind::StackUnderflow));
+}
+
+#[test]
+fn test_malformed_switches_fail_verification() {
+    load_class("Switches").verify().unwrap();
+    assert!(matches!(verify_failure("InvertedTableSwitch"), VerifyErrorKind::InvalidSwitch));
+    assert!(matches!(verify_failure("UnsortedLookupSwitch"), VerifyErrorKind::InvalidSwitch));
+}
+
+#[test]
+fn test_code_is_kept_as_written() {
+    let source = "\
+.bytecode 50.0
+.class public Written
+.method public static run()J
+    .limit stack 9
+    .limit locals 4
+    bipush 1
+    ldc_w 2
+    ldc2_w 3
+    lreturn
+.end method
+";
+    let cf = ClassFile::from(&assemble(source).unwrap()).unwrap();
+    assert_eq!(cf.major_version, 50);
+
+    let AttrInfo::Code(code) = &cf.methods[0].attrs[0] else {
+        panic!("no code");
+    };
+    assert_eq!((code.max_stack, code.max_locals), (9, 4));
+    assert_eq!(code.code[..4], [0x10, 0x01, 0x13, 0x00]);
+    assert_eq!(code.code[5], 0x14);
+}
+
+#[test]
+fn test_syntax_errors_name_the_line() {
+    let unknown = error_of(".class A\n.method static f()V\n    frobnicate\n.end method\n");
+    assert_eq!(unknown, AsmError { line: 3, kind: AsmErrorKind::UnknownInstruction("frobnicate".into()) });
+
+    let undefined = error_of(".class A\n.method static f()V\n    goto Nowhere\n.end method\n");
+    assert_eq!(undefined, AsmError { line: 3, kind: AsmErrorKind::UndefinedLabel("Nowhere".into()) });
+
+    let twice = error_of(".class A\n.method static f()V\nL:\nL:\n    return\n.end method\n");
+    assert_eq!(twice, AsmError { line: 4, kind: AsmErrorKind::DuplicateLabel("L".into()) });
+
+    let unterminated = error_of(".class A\n.method static f()V\n    return\n");
+    assert_eq!(unterminated, AsmError { line: 2, kind: AsmErrorKind::UnterminatedMethod });
+
+    let operand = error_of(".class A\n.method static f()V\n    bipush 300\n    return\n.end method\n");
+    assert_eq!(operand, AsmError { line: 3, kind: AsmErrorKind::InvalidOperand("300".into()) });
+
+    assert_eq!(error_of(".super B\n").kind, AsmErrorKind::MissingClass);
+    assert_eq!(error_of(".class A\n.method static f()V\n    ldc \"open\n").kind, AsmErrorKind::UnterminatedString);
+}
+
+#[test]
+fn test_stack_is_checked_without_limit() {
+    let underflow = error_of(".class A\n.method static f()V\n    pop\n    return\n.end method\n");
+    assert_eq!(underflow, AsmError { line: 5, kind: AsmErrorKind::Build(BuildError::StackUnderflow { pc: 0 }) });
+
+    assert!(assemble(".class A\n.method static f()V\n    .limit stack 0\n    pop\n    return\n.end method\n").is_ok());
+}
+
+/// The constant `ldc` or `ldc2_w` loads from `operand`.
+fn loaded_constant(op: &str, operand: &str) -> ConstantPoolInfo {
+    let source = format!(".class A\n.method static f()V\n    {op} {operand}\n    return\n.end method\n");
+    let mut cf = ClassFile::from(&assemble(&source).unwrap()).unwrap();
+    let AttrInfo::Code(code) = &cf.methods[0].attrs[0] else {
+        panic!("no code");
+    };
+    let index = match op {
+        "ldc" => code.code[1] as usize,
+        _ => u16::from_be_bytes([code.code[1], code.code[2]]) as usize,
+    };
+
+    cf.constant_pool.swap_remove(index)
+}
+
+#[test]
+fn test_ldc_floats_need_a_decimal_point_exponent_or_suffix() {
+    assert!(matches!(loaded_constant("ldc", "-7"), ConstantPoolInfo::IntegerInfo { value: -7 }));
+    assert!(matches!(loaded_constant("ldc", "1.5"), ConstantPoolInfo::FloatInfo { value } if value == 1.5));
+    assert!(matches!(loaded_constant("ldc", "2f"), ConstantPoolInfo::FloatInfo { value } if value == 2.0));
+    assert!(matches!(loaded_constant("ldc", "-1e3"), ConstantPoolInfo::FloatInfo { value } if value == -1000.0));
+    assert!(matches!(loaded_constant("ldc2_w", "3"), ConstantPoolInfo::LongInfo { value: 3 }));
+    assert!(matches!(loaded_constant("ldc2_w", "0.25"), ConstantPoolInfo::DoubleInfo { value } if value == 0.25));
+    assert!(matches!(loaded_constant("ldc2_w", "4d"), ConstantPoolInfo::DoubleInfo { value } if value == 4.0));
+
+    // 不是数字的单词都是类名。
+    for name in ["NaN", "Infinity", "inf", "java/lang/Error"] {
+        assert!(matches!(loaded_constant("ldc", name), ConstantPoolInfo::ClassInfo { .. }), "{name}");
+    }
+}
+
+#[test]
+fn test_ldc_integers_out_of_range_are_errors() {
+    let source = |op: &str, operand: &str| format!(".class A\n.method static f()V\n    {op} {operand}\n    return\n.end method\n");
+
+    let int = error_of(&source("ldc", "4294967296"));
+    assert_eq!(int, AsmError { line: 3, kind: AsmErrorKind::InvalidOperand("4294967296".into()) });
+    let long = error_of(&source("ldc2_w", "99999999999999999999"));
+    assert_eq!(long, AsmError { line: 3, kind: AsmErrorKind::InvalidOperand("99999999999999999999".into()) });
+    assert_eq!(error_of(&source("ldc2_w", "NaN")).kind, AsmErrorKind::InvalidOperand("NaN".into()));
+}
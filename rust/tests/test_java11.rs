@@ -0,0 +1,335 @@
+mod test_harness;
+
+use klover::{
+    assembler::class_builder::ClassBuilder,
+    class_loader::{cld::ClassLoaderData, load_error::LoadError, ms_api::MSRef},
+    class_parser::{attr_info::AttrInfo, class_file::ClassFile, cp_info::ConstantPoolInfo, parse_error::ParseError},
+    engine::{
+        exec_error::JavaExceptionKind,
+        outcome::ThreadExit,
+    },
+    oops::{java_class::JavaClass, java_string::JavaString, java_throwable::JavaThrowable, normal_klass::NormalKlass},
+};
+use test_harness::{define, define_in, expect_exception, expect_int, expect_long, expect_ref, load_class, run, start_vm};
+
+const BOOTSTRAPS: &str = "CondyBootstraps";
+const BSM_PREFIX: &str = "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/Class;";
+
+fn static_ref(klass: &NormalKlass, name: &str, desc: &str) -> u32 {
+    let field = klass.find_declared_field(name, desc).unwrap();
+    klass.read_static_field(&field).unwrap()[0].as_ref().unwrap()
+}
+
+/// javac never emits `CONSTANT_Dynamic`, so the constants are assembled here;
+/// the bootstrap methods live in `CondyBootstraps.java`.
+struct CondyClass(ClassBuilder);
+
+impl CondyClass {
+    fn new(name: &str) -> Self {
+        let mut class = ClassBuilder::new(name);
+        class.version(55);
+        Self(class)
+    }
+
+    /// `REF_invokeStatic` handle on `CondyBootstraps.bsm`.
+    fn handle(&mut self, bsm: &str, params: &str, ret: &str) -> u16 {
+        let cp = self.0.constant_pool();
+        let method_ref = cp.method_ref(BOOTSTRAPS, bsm, &format!("{BSM_PREFIX}{params}){ret}"));
+        cp.method_handle(6, method_ref)
+    }
+
+    /// A dynamic constant of type `desc` produced by `CondyBootstraps.bsm`.
+    fn constant(&mut self, bsm: &str, params: &str, ret: &str, args: &[u16], desc: &str) -> u16 {
+        let handle = self.handle(bsm, params, ret);
+        let bootstrap = self.0.bootstrap_method(handle, args);
+        self.0.constant_pool().dynamic(bootstrap, bsm, desc)
+    }
+
+    fn integer(&mut self, value: i32) -> u16 {
+        self.0.constant_pool().integer(value)
+    }
+
+    /// A static method returning `constant`, loaded with `ldc_w` or, for
+    /// `long`s, `ldc2_w`.
+    fn getter(&mut self, name: &str, desc: &str, constant: u16) {
+        let (load, ret) = match desc {
+            "J" => (0x14, 0xad),
+            "I" => (0x13, 0xac),
+            _ => (0x13, 0xb0),
+        };
+        let mut code = self.0.method(0x0009, name, &format!("(){desc}"));
+        code.load_constant(load, constant).op(ret);
+        code.finish().unwrap();
+    }
+
+    fn define(self) -> MSRef<NormalKlass> {
+        define(self.0)
+    }
+}
+
+#[test]
+fn test_dynamic_constant_is_loaded_by_ldc() {
+    let mut class = CondyClass::new("CondyAnswer");
+    let answer = class.constant("answer", "", "I", &[], "I");
+    class.getter("answer", "I", answer);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "answer", "()I", vec![])), 42);
+}
+
+#[test]
+fn test_long_dynamic_constant_is_loaded_by_ldc2_w() {
+    let mut class = CondyClass::new("CondyWide");
+    let wide = class.constant("wide", "", "J", &[], "J");
+    class.getter("wide", "J", wide);
+    let klass = class.define();
+
+    assert_eq!(expect_long(run(&klass, "wide", "()J", vec![])), 1 << 40);
+}
+
+#[test]
+fn test_dynamic_constant_is_resolved_once() {
+    let mut class = CondyClass::new("CondyCounted");
+    let counted = class.constant("counted", "", "I", &[], "I");
+    class.getter("counted", "I", counted);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "counted", "()I", vec![])), 7);
+    assert_eq!(expect_int(run(&klass, "counted", "()I", vec![])), 7);
+
+    let bootstraps = load_class(BOOTSTRAPS);
+    assert_eq!(expect_int(run(&bootstraps, "countedLinks", "()I", vec![])), 1);
+}
+
+#[test]
+fn test_bootstrap_method_receives_name_and_type() {
+    let mut class = CondyClass::new("CondyRecording");
+    let recorded = class.constant("recording", "", "Ljava/lang/Object;", &[], "Ljava/lang/String;");
+    class.getter("recorded", "Ljava/lang/String;", recorded);
+    let klass = class.define();
+
+    let value = expect_ref(run(&klass, "recorded", "()Ljava/lang/String;", vec![]));
+    assert_eq!(JavaString::to_rust_string(value).unwrap(), "recording");
+
+    let bootstraps = load_class(BOOTSTRAPS);
+    let name = static_ref(&bootstraps, "lastName", "Ljava/lang/String;");
+    assert_eq!(JavaString::to_rust_string(name).unwrap(), "recording");
+    let mirror = static_ref(&bootstraps, "lastType", "Ljava/lang/Class;");
+    let string_klass = load_class("java/lang/String");
+    assert_eq!(JavaClass::klass_of(mirror).unwrap().name(), string_klass.name());
+}
+
+#[test]
+fn test_dynamic_constants_can_be_static_arguments() {
+    let mut class = CondyClass::new("CondySum");
+    let answer = class.constant("answer", "", "I", &[], "I");
+    let eight = class.integer(8);
+    let sum = class.constant("sum", "II", "I", &[answer, eight], "I");
+    class.getter("sum", "I", sum);
+    let klass = class.define();
+
+    assert_eq!(expect_int(run(&klass, "sum", "()I", vec![])), 50);
+}
+
+#[test]
+fn test_reference_dynamic_constant() {
+    let mut class = CondyClass::new("CondyLookup");
+    let lookup = class.constant("lookup", "", "Ljava/lang/Object;", &[], "Ljava/lang/Object;");
+    class.getter("lookup", "Ljava/lang/Object;", lookup);
+    let klass = class.define();
+
+    assert_ne!(expect_ref(run(&klass, "lookup", "()Ljava/lang/Object;", vec![])), 0);
+}
+
+#[test]
+fn test_failed_resolution_is_sticky() {
+    let mut class = CondyClass::new("CondyThrowing");
+    let zero = class.integer(0);
+    let throwing = class.constant("throwing", "I", "I", &[zero], "I");
+    class.getter("throwing", "I", throwing);
+    let klass = class.define();
+
+    // The bootstrap method's exception is wrapped as for `invokedynamic`;
+    // later executions see the recorded resolution error.
+    let error = expect_exception(run(&klass, "throwing", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+    assert!(JavaThrowable::cause(error.unwrap()).unwrap().is_some());
+    expect_exception(run(&klass, "throwing", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_mismatched_return_type_is_bootstrap_method_error() {
+    let mut class = CondyClass::new("CondyNarrow");
+    let wide = class.constant("wide", "", "J", &[], "I");
+    class.getter("narrow", "I", wide);
+    let klass = class.define();
+
+    expect_exception(run(&klass, "narrow", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_self_referencing_constant_is_bootstrap_method_error() {
+    let mut class = CondyClass::new("CondyCyclic");
+    let answer = class.constant("answer", "", "I", &[], "I");
+    let handle = class.handle("sum", "II", "I");
+    let cp = class.0.constant_pool();
+    cp.name_and_type("cyclic", "I");
+    let itself = cp.next_index();
+    let bootstrap = class.0.bootstrap_method(handle, &[answer, itself]);
+    let cyclic = class.0.constant_pool().dynamic(bootstrap, "cyclic", "I");
+    assert_eq!(cyclic, itself);
+    class.getter("cyclic", "I", cyclic);
+    let klass = class.define();
+
+    expect_exception(run(&klass, "cyclic", "()I", vec![]), JavaExceptionKind::BootstrapMethodError);
+}
+
+#[test]
+fn test_ldc_of_long_constant_is_rejected() {
+    let mut class = CondyClass::new("CondyLdcLong");
+    let wide = class.constant("wide", "", "J", &[], "J");
+    let mut code = class.0.method(0x0009, "wide", "()J");
+    code.max_stack(2).load_constant(0x13, wide).op(0xad); // ldc_w; lreturn
+    code.finish().unwrap();
+    let klass = class.define();
+
+    expect_exception(run(&klass, "wide", "()J", vec![]), JavaExceptionKind::VerifyError);
+}
+
+#[test]
+fn test_nestmates_share_private_members() {
+    let klass = load_class("Nestmates");
+
+    assert_eq!(expect_int(run(&klass, "peek", "()I", vec![])), 81);
+    assert_eq!(expect_int(run(&klass, "fromHost", "()I", vec![])), 7);
+    assert_eq!(expect_int(run(&klass, "fromOther", "()I", vec![])), 11);
+}
+
+#[test]
+fn test_private_interface_methods() {
+    let klass = load_class("PrivateInterfaceMethods");
+
+    assert_eq!(expect_int(run(&klass, "area", "()I", vec![])), 9);
+    assert_eq!(expect_int(run(&klass, "scaled", "()I", vec![])), 9);
+}
+
+/// Runs `NestHost$<member>.call()`, which calls the host's private
+/// `secret()`; the host lists `NestHost$Member` and `NestHost$Old` as its
+/// members.
+fn call_host(member: &str, major: u16) -> ThreadExit {
+    start_vm();
+    let cld = unsafe { ClassLoaderData::new(Some(format!("{member} loader"))).as_ref() };
+
+    let mut host = ClassBuilder::new("NestHost");
+    host.version(55);
+    let mut code = host.method(0x000a, "secret", "()I");
+    code.op(0x08).op(0xac); // iconst_5; ireturn
+    code.finish().unwrap();
+    let members = ["NestHost$Member", "NestHost$Old"].map(|x| host.constant_pool().class(x));
+    host.attribute(AttrInfo::NestMembers(members.to_vec()));
+    define_in(host, cld);
+
+    let mut class = ClassBuilder::new(&format!("NestHost${member}"));
+    class.version(major);
+    let mut code = class.method(0x0009, "call", "()I");
+    code.invoke(0xb8, "NestHost", "secret", "()I").op(0xac); // invokestatic; ireturn
+    code.finish().unwrap();
+    let host_class_idx = class.constant_pool().class("NestHost");
+    class.attribute(AttrInfo::NestHost { host_class_idx });
+    let klass = define_in(class, cld);
+
+    run(&klass, "call", "()I", vec![])
+}
+
+#[test]
+fn test_listed_nest_member_calls_private_method() {
+    assert_eq!(expect_int(call_host("Member", 55)), 5);
+}
+
+#[test]
+fn test_unlisted_class_is_not_a_nestmate() {
+    // Java 11：宿主不收录该类是 ICCE，而不是拒绝访问。
+    expect_exception(call_host("Stranger", 55), JavaExceptionKind::IncompatibleClassChangeError);
+}
+
+/// `Orphan$Member` names the missing `Orphan` as its nest host and calls
+/// `OrphanPeer`'s public `open()` and private `secret()`.
+fn orphan_member() -> MSRef<NormalKlass> {
+    start_vm();
+    let cld = unsafe { ClassLoaderData::new(Some("orphan loader".to_owned())).as_ref() };
+
+    let mut peer = ClassBuilder::new("OrphanPeer");
+    peer.version(55);
+    for (flags, name) in [(0x0009, "open"), (0x000a, "secret")] {
+        let mut code = peer.method(flags, name, "()I");
+        code.op(0x08).op(0xac); // iconst_5; ireturn
+        code.finish().unwrap();
+    }
+    define_in(peer, cld);
+
+    let mut class = ClassBuilder::new("Orphan$Member");
+    class.version(55);
+    for name in ["open", "secret"] {
+        let mut code = class.method(0x0009, name, "()I");
+        code.invoke(0xb8, "OrphanPeer", name, "()I").op(0xac); // invokestatic; ireturn
+        code.finish().unwrap();
+    }
+    let host_class_idx = class.constant_pool().class("Orphan");
+    class.attribute(AttrInfo::NestHost { host_class_idx });
+    define_in(class, cld)
+}
+
+#[test]
+fn test_missing_nest_host_fails_private_access() {
+    let klass = orphan_member();
+
+    // 只有私有访问才需要宿主。
+    assert_eq!(expect_int(run(&klass, "open", "()I", vec![])), 5);
+    expect_exception(run(&klass, "secret", "()I", vec![]), JavaExceptionKind::NoClassDefFoundError);
+}
+
+#[test]
+fn test_nest_attributes_need_version_55() {
+    expect_exception(call_host("Old", 54), JavaExceptionKind::IllegalAccessError);
+}
+
+fn module_info_bytes() -> Vec<u8> {
+    std::fs::read(format!("{}/../test_data/module_classes/module-info.class", env!("CARGO_MANIFEST_DIR"))).unwrap()
+}
+
+#[test]
+fn test_module_info_is_parsed() {
+    let cf = ClassFile::from(&module_info_bytes()).unwrap();
+    assert!(cf.declares_module());
+
+    let module = cf
+        .attrs
+        .iter()
+        .find_map(|x| match x {
+            AttrInfo::Module(x) => Some(x),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(module.requires.len(), 1);
+    assert_eq!(module.exports.len(), 1);
+}
+
+#[test]
+fn test_module_info_cannot_be_defined() {
+    start_vm();
+    let cld = unsafe { ClassLoaderData::new(Some("module-info loader".to_owned())).as_ref() };
+
+    let err = cld.define_class(&module_info_bytes()).unwrap_err();
+    assert!(matches!(&err, LoadError::ModuleInfo(name) if name == "module-info"));
+    assert_eq!(err.java_exception(), Some(JavaExceptionKind::NoClassDefFoundError));
+}
+
+#[test]
+fn test_module_constants_outside_module_info_are_rejected() {
+    let mut class = ClassBuilder::new("NotAModule");
+    class.version(55);
+    let cp = class.constant_pool();
+    let name_index = cp.utf8("sample");
+    cp.entry(ConstantPoolInfo::ModuleInfo { name_index });
+
+    assert!(matches!(ClassFile::from(&class.to_bytes().unwrap()), Err(ParseError::InvalidCPTag(19))));
+}
@@ -0,0 +1,106 @@
+use std::process::{Command, Output};
+
+fn test_data(path: &str) -> String {
+    format!("{}/../test_data/{path}", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn klover(args: &[&str]) -> Output {
+    let boot = format!("-Xbootclasspath:{}", test_data("classes"));
+
+    Command::new(env!("CARGO_BIN_EXE_klover"))
+        .arg(boot)
+        .args(args)
+        .env_remove("CLASSPATH")
+        .output()
+        .expect("failed to spawn klover")
+}
+
+fn app(args: &[&str]) -> Output {
+    let cp = test_data("app_classes");
+    let mut full = vec!["-cp", cp.as_str()];
+    full.extend_from_slice(args);
+    klover(&full)
+}
+
+#[test]
+fn test_exit_status_comes_from_system_exit() {
+    let out = app(&["ExitWith"]);
+    assert_eq!(out.status.code(), Some(40));
+
+    let out = app(&["ExitWith", "a", "b"]);
+    assert_eq!(out.status.code(), Some(42));
+}
+
+#[test]
+fn test_normal_return_exits_with_zero() {
+    let out = app(&["DivideByArgs", "x"]);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+}
+
+#[test]
+fn test_uncaught_exception_is_reported() {
+    let out = app(&["DivideByArgs"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert_eq!(out.status.code(), Some(1));
+    assert_eq!(
+        stderr,
+        "Exception in thread \"main\" java.lang.ArithmeticException: / by zero\n\
+         \tat DivideByArgs.main(DivideByArgs.java:5)\n"
+    );
+}
+
+#[test]
+fn test_print_stack_trace_from_java() {
+    let out = app(&["PrintsTrace"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+    assert_eq!(
+        stderr,
+        "java.lang.IllegalStateException\n\
+         \tat PrintsTrace.report(PrintsTrace.java:7)\n\
+         \tat PrintsTrace.main(PrintsTrace.java:3)\n"
+    );
+}
+
+#[test]
+fn test_missing_main_class() {
+    let out = app(&["no.such.Main"]);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert_eq!(out.status.code(), Some(1));
+    assert!(stderr.contains("Could not find or load main class no.such.Main"), "{stderr}");
+}
+
+#[test]
+fn test_jar_launch() {
+    let out = klover(&["-jar", &test_data("jars/launch.jar"), "one"]);
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+}
+
+#[test]
+fn test_verbose_class_names_the_source() {
+    let out = app(&["-verbose:class", "ExitWith"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+
+    assert_eq!(out.status.code(), Some(40));
+    assert!(
+        stdout.contains(&format!("[Loaded java.lang.System from {}]", test_data("classes"))),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!("[Loaded ExitWith from {}]", test_data("app_classes"))),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_bad_options() {
+    let out = klover(&["-Xmx12q", "Main"]);
+    assert_eq!(out.status.code(), Some(1));
+
+    let out = klover(&["-help"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("Usage: klover"));
+}
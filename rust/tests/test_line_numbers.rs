@@ -0,0 +1,58 @@
+mod test_harness;
+
+use klover::{
+    engine::{
+        exec_error::JavaExceptionKind,
+        outcome::{PendingException, ThreadExit},
+        slot::Slot,
+    },
+    oops::attr::LineNumberTableEntry,
+};
+use test_harness::{load_class, run};
+
+#[test]
+fn test_source_file_attribute() {
+    let holder = load_class("StackTraces");
+    assert_eq!(holder.source_file().unwrap().utf8(), "StackTraces.java");
+
+    let bare = load_class("NoDebugInfo");
+    assert!(bare.source_file().is_none());
+}
+
+#[test]
+fn test_line_number_table_maps_every_bci_to_its_line() {
+    let holder = load_class("StackTraces");
+    let method = holder.find_declared_method("multiLine", "(I)I").unwrap();
+    let code = method.code.as_ref().unwrap();
+
+    assert_eq!(
+        &code.line_number_table[..],
+        [
+            LineNumberTableEntry { start_pc: 0, line_number: 15 },
+            LineNumberTableEntry { start_pc: 4, line_number: 16 },
+            LineNumberTableEntry { start_pc: 8, line_number: 17 },
+        ]
+    );
+
+    let lines: Vec<_> = (0..code.bytecodes.len()).map(|bci| code.line_number(bci)).collect();
+    assert_eq!(
+        lines,
+        [15, 15, 15, 15, 16, 16, 16, 16, 17, 17, 17, 17].map(Some)
+    );
+}
+
+#[test]
+fn test_methods_without_debug_info_have_no_lines() {
+    let holder = load_class("NoDebugInfo");
+    let method = holder.find_declared_method("divide", "(II)I").unwrap();
+    let code = method.code.as_ref().unwrap();
+
+    assert!(code.line_number_table.is_empty());
+    assert_eq!(code.line_number(0), None);
+
+    let exit = run(&holder, "divide", "(II)I", vec![Slot::int(1), Slot::int(0)]);
+    assert!(matches!(
+        exit,
+        ThreadExit::UncaughtException(PendingException::JVMGen(JavaExceptionKind::ArithmeticException))
+    ));
+}
@@ -0,0 +1,135 @@
+mod test_harness;
+
+use klover::{
+    assembler::{class_builder::ClassBuilder, code_builder::CodeBuilder},
+    class_loader::ms_api::MSRef,
+    engine::{
+        exec_error::JavaExceptionKind,
+        outcome::ThreadExit,
+        slot::Slot,
+    },
+    gc_bindings::oop_codec::klass_of,
+    oops::normal_klass::NormalKlass,
+};
+use test_harness::{define, define_in, expect_exception, expect_int, expect_ref, load_class, run};
+
+const TARGETS: &str = "AccessTargets";
+
+fn build(name: &str, code: impl FnOnce(&mut CodeBuilder)) -> ClassBuilder {
+    let mut class = ClassBuilder::new(name);
+    let mut method = class.method(0x0009, "call", "()I");
+    code(&mut method);
+    method.finish().unwrap();
+    class
+}
+
+/// A class whose static `call()I` runs `code`.
+fn caller(name: &str, code: impl FnOnce(&mut CodeBuilder)) -> MSRef<NormalKlass> {
+    define(build(name, code))
+}
+
+/// `new name; pop; iconst_1; ireturn`
+fn instantiate(name: &str) -> impl FnOnce(&mut CodeBuilder) + '_ {
+    move |x| {
+        x.class_op(0xbb, name).op(0x57).op(0x04).op(0xac);
+    }
+}
+
+fn call(klass: &MSRef<NormalKlass>) -> ThreadExit {
+    run(klass, "call", "()I", vec![])
+}
+
+#[test]
+fn test_missing_field_is_no_such_field_error() {
+    let klass = caller("ReadsMissingField", |x| {
+        x.field(0xb2, TARGETS, "missing", "I").op(0xac); // getstatic; ireturn
+    });
+    expect_exception(call(&klass), JavaExceptionKind::NoSuchFieldError);
+}
+
+#[test]
+fn test_missing_method_is_no_such_method_error() {
+    let klass = caller("CallsMissingMethod", |x| {
+        x.invoke(0xb8, TARGETS, "absent", "()I").op(0xac); // invokestatic; ireturn
+    });
+    expect_exception(call(&klass), JavaExceptionKind::NoSuchMethodError);
+}
+
+#[test]
+fn test_missing_class_is_no_class_def_found_error() {
+    let klass = caller("NewsMissingClass", instantiate("NoSuchClassAnywhere"));
+    expect_exception(call(&klass), JavaExceptionKind::NoClassDefFoundError);
+}
+
+#[test]
+fn test_instance_call_of_static_method_is_incompatible() {
+    let klass = caller("CallsStaticVirtually", |x| {
+        x.class_op(0xbb, TARGETS).op(0x59); // new; dup
+        x.invoke(0xb7, TARGETS, "<init>", "()V");
+        x.invoke(0xb6, TARGETS, "visible", "()I").op(0xac); // invokevirtual; ireturn
+    });
+    expect_exception(call(&klass), JavaExceptionKind::IncompatibleClassChangeError);
+}
+
+#[test]
+fn test_methodref_to_interface_is_incompatible() {
+    let klass = caller("CallsInterfaceAsClass", |x| {
+        x.invoke(0xb8, "LambdaSamples$Counter", "next", "()I").op(0xac); // invokestatic; ireturn
+    });
+    expect_exception(call(&klass), JavaExceptionKind::IncompatibleClassChangeError);
+}
+
+#[test]
+fn test_class_resolution_failure_is_cached() {
+    let early = caller("NewsLateClass", instantiate("LateClass"));
+    expect_exception(call(&early), JavaExceptionKind::NoClassDefFoundError);
+
+    let loader = early.cld().unwrap();
+    let mut late_class = ClassBuilder::new("LateClass");
+    let mut init = late_class.method(0x0001, "<init>", "()V");
+    init.op(0x2a).invoke(0xb7, "java/lang/Object", "<init>", "()V").op(0xb1); // aload_0; invokespecial; return
+    init.finish().unwrap();
+    define_in(late_class, loader);
+
+    // The entry that failed keeps failing; a fresh one sees the new class.
+    expect_exception(call(&early), JavaExceptionKind::NoClassDefFoundError);
+    let late = define_in(build("NewsLateClassAgain", instantiate("LateClass")), loader);
+    assert_eq!(expect_int(call(&late)), 1);
+}
+
+/// A `Catches$Probe` whose `probe()I` runs `code`.
+fn probe(name: &str, code: impl FnOnce(&mut CodeBuilder)) -> MSRef<NormalKlass> {
+    let mut class = ClassBuilder::new(name);
+    class.implement("Catches$Probe");
+    let mut method = class.method(0x0001, "probe", "()I");
+    code(&mut method);
+    method.finish().unwrap();
+    define(class)
+}
+
+/// The error `Catches.linkageFrom` catches from an instance of `probe`.
+fn caught_in_java(probe: &MSRef<NormalKlass>) -> String {
+    let catches = load_class("Catches");
+    let arg = Slot::reference(probe.allocate_instance());
+    let exit = run(&catches, "linkageFrom", "(LCatches$Probe;)Ljava/lang/Throwable;", vec![arg]);
+
+    klass_of(expect_ref(exit)).unwrap().name().utf8().to_string()
+}
+
+#[test]
+fn test_linkage_errors_are_caught_in_java() {
+    let missing_field = probe("ProbesMissingField", |x| {
+        x.field(0xb2, TARGETS, "missing", "I").op(0xac); // getstatic; ireturn
+    });
+    assert_eq!(caught_in_java(&missing_field), "java/lang/NoSuchFieldError");
+    // 失败的解析被记住，再次执行抛出同样的错误。
+    assert_eq!(caught_in_java(&missing_field), "java/lang/NoSuchFieldError");
+
+    let missing_method = probe("ProbesMissingMethod", |x| {
+        x.invoke(0xb8, TARGETS, "absent", "()I").op(0xac); // invokestatic; ireturn
+    });
+    assert_eq!(caught_in_java(&missing_method), "java/lang/NoSuchMethodError");
+
+    let missing_class = probe("ProbesMissingClass", instantiate("NoSuchClassAnywhere"));
+    assert_eq!(caught_in_java(&missing_class), "java/lang/NoClassDefFoundError");
+}
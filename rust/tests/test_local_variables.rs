@@ -0,0 +1,117 @@
+mod test_harness;
+
+use klover::{
+    engine::{
+        call::Invocation,
+        exec_dispatcher::ExecDispatcher,
+        outcome::RunOutcome,
+        resolved_method::ResolvedMethod,
+        slot::Slot,
+    },
+    oops::attr::Code,
+    runtime::{java_thread::JavaThread, thread_manager::ThreadManager},
+};
+use test_harness::load_class;
+
+fn names_at(code: &Code, pc: usize) -> Vec<&str> {
+    code.live_locals(pc).map(|x| x.name.utf8()).collect()
+}
+
+#[test]
+fn test_local_variable_table_scopes() {
+    let holder = load_class("LocalVars");
+    let method = holder.find_declared_method("scopes", "(IJ)I").unwrap();
+    let code = method.code.as_ref().unwrap();
+
+    assert_eq!(code.local_variable_table.len(), 5);
+    assert_eq!(names_at(code, 0), ["a", "b"]);
+    assert_eq!(names_at(code, 12), ["a", "b", "sum", "i"]);
+    // `i` and `doubled` share slot 4 in disjoint ranges.
+    assert_eq!(names_at(code, 30), ["a", "b", "sum", "doubled"]);
+    assert_eq!(names_at(code, 32), Vec::<&str>::new());
+
+    let doubled = code.live_locals(30).find(|x| x.name.utf8() == "doubled").unwrap();
+    assert_eq!((doubled.index, doubled.descriptor.utf8()), (4, "I"));
+    let b = code.live_locals(30).find(|x| x.name.utf8() == "b").unwrap();
+    assert_eq!(b.slot_count(), 2);
+}
+
+#[test]
+fn test_local_variable_type_table_adds_generic_signatures() {
+    let holder = load_class("LocalVars");
+    let method = holder.find_declared_method("first", "([Ljava/lang/Object;I)Ljava/lang/Object;").unwrap();
+    let code = method.code.as_ref().unwrap();
+
+    let described: Vec<_> = code
+        .local_variable_table
+        .iter()
+        .map(|x| (x.name.utf8(), x.descriptor.utf8(), x.signature.as_ref().map(|s| s.utf8())))
+        .collect();
+    assert_eq!(
+        described,
+        [
+            ("items", "[Ljava/lang/Object;", Some("[TT;")),
+            ("index", "I", None),
+            ("item", "Ljava/lang/Object;", Some("TT;")),
+        ]
+    );
+}
+
+#[test]
+fn test_classes_without_debug_info_have_no_local_names() {
+    let holder = load_class("StackTraces");
+    let method = holder.find_declared_method("multiLine", "(I)I").unwrap();
+
+    assert!(method.code.as_ref().unwrap().local_variable_table.is_empty());
+}
+
+/// Step `scopes(5, 100L)` one instruction at a time until the frame is about
+/// to execute `pc`.
+fn pause_at(dispatcher: &mut ExecDispatcher, thread: &mut JavaThread, pc: usize) {
+    loop {
+        if let Ok(frame) = thread.stack().current_interpreter() {
+            if frame.pc() == pc {
+                return;
+            }
+        }
+        match dispatcher.run_quantum(thread, 1).unwrap() {
+            RunOutcome::QuantumExpired => {}
+            RunOutcome::Terminated(exit) => panic!("finished before pc {pc}: {exit:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_frame_pairs_locals_with_their_names() {
+    let holder = load_class("LocalVars");
+    let method = holder.find_declared_method("scopes", "(IJ)I").unwrap();
+    let target = ResolvedMethod::new(holder.clone(), method);
+
+    let mut manager = ThreadManager::new(1024);
+    let mut thread = manager.create_thread().unwrap();
+    thread.start().unwrap();
+    let mut dispatcher = ExecDispatcher::new();
+    let args = vec![Slot::int(5), Slot::long_high(100), Slot::long_low(100)];
+    dispatcher.enter_root(&mut thread, Invocation { target, args }).unwrap();
+
+    // 执行 pc 11 之后：循环第一轮，sum 与 i 都已赋值。
+    pause_at(&mut dispatcher, &mut thread, 12);
+    let frame = thread.stack().current_interpreter().unwrap();
+    let locals = frame.named_locals();
+    let names: Vec<_> = locals.iter().map(|x| (x.name(), x.descriptor())).collect();
+    assert_eq!(names, [("a", "I"), ("b", "J"), ("sum", "I"), ("i", "I")]);
+    assert_eq!(locals[0].slots[0].as_int().unwrap(), 5);
+    assert_eq!(Slot::as_long(locals[1].slots[0], locals[1].slots[1]).unwrap(), 100);
+    assert_eq!(locals[2].slots[0].as_int().unwrap(), 5);
+    assert_eq!(locals[3].slots[0].as_int().unwrap(), 0);
+
+    pause_at(&mut dispatcher, &mut thread, 29);
+    let frame = thread.stack().current_interpreter().unwrap();
+    let locals = frame.named_locals();
+    let values: Vec<_> = locals
+        .iter()
+        .filter(|x| x.slots.len() == 1)
+        .map(|x| (x.name(), x.slots[0].as_int().unwrap()))
+        .collect();
+    assert_eq!(values, [("a", 5), ("sum", 8), ("doubled", 16)]);
+}
@@ -0,0 +1,317 @@
+mod test_harness;
+
+use klover::{
+    assembler::{class_builder::ClassBuilder, constant_pool::ConstantPool},
+    class_loader::ms_api::MSRef,
+    engine::{
+        exec_error::JavaExceptionKind,
+        resolved_method::ResolvedMethod,
+        slot::Slot,
+    },
+    gc_bindings::oop_codec::klass_of,
+    oops::{
+        cp_entry::{RefKind, ResolvedFieldRef},
+        java_invoke::{DirectMember, DirectMethodHandle, JavaMethodHandle, JavaMethodType},
+        java_throwable::JavaThrowable,
+        normal_klass::NormalKlass,
+    },
+};
+use test_harness::{define, expect_exception, expect_int, expect_long, expect_ref, load_class, run};
+
+const CALLS: &str = "MethodHandleCalls";
+const LAMBDAS: &str = "LambdaSamples";
+
+/// A direct handle of type `type_desc` on method `name` of `class`.
+fn method_handle(kind: RefKind, class: &str, name: &str, desc: &str, type_desc: &str) -> Slot {
+    let holder = load_class(class);
+    let method = holder.find_declared_method(name, desc).unwrap();
+    let handle = DirectMethodHandle {
+        kind,
+        member: DirectMember::Method(ResolvedMethod::new(holder, method)),
+    };
+    Slot::reference(JavaMethodHandle::create(&handle, type_desc).unwrap())
+}
+
+/// A direct handle of type `type_desc` on field `name` of `class`.
+fn field_handle(kind: RefKind, class: &str, name: &str, type_desc: &str) -> Slot {
+    let holder = load_class(class);
+    let field = holder.find_declared_field(name, "I").unwrap();
+    let handle = DirectMethodHandle {
+        kind,
+        member: DirectMember::Field(ResolvedFieldRef { holder, field }),
+    };
+    Slot::reference(JavaMethodHandle::create(&handle, type_desc).unwrap())
+}
+
+/// A `MethodHandleCalls` whose `value` is `value`.
+fn receiver(value: i32) -> Slot {
+    let klass = load_class(CALLS);
+    let field = klass.find_declared_field("value", "I").unwrap();
+    let obj = klass.allocate_instance();
+    klass.write_instance_field(obj, &field, &[Slot::int(value)]).unwrap();
+    Slot::reference(obj)
+}
+
+fn run_lambda(name: &str, desc: &str, args: Vec<Slot>) -> i32 {
+    expect_int(run(&load_class(LAMBDAS), name, desc, args))
+}
+
+#[test]
+fn test_invoke_exact_static_method() {
+    let calls = load_class(CALLS);
+    let add = method_handle(RefKind::InvokeStatic, CALLS, "add", "(II)I", "(II)I");
+
+    let exit = run(&calls, "exactInts", "(Ljava/lang/invoke/MethodHandle;II)I", vec![add, Slot::int(4), Slot::int(5)]);
+    assert_eq!(expect_int(exit), 9);
+}
+
+#[test]
+fn test_invoke_exact_passes_wide_arguments() {
+    let calls = load_class(CALLS);
+    let widen = method_handle(RefKind::InvokeStatic, CALLS, "widen", "(JJ)J", "(JJ)J");
+    let big = 3_000_000_000i64;
+
+    let exit = run(
+        &calls,
+        "exactLongs",
+        "(Ljava/lang/invoke/MethodHandle;JJ)J",
+        vec![widen, Slot::long_high(2), Slot::long_low(2), Slot::long_high(big), Slot::long_low(big)],
+    );
+    assert_eq!(expect_long(exit), 6_000_000_000);
+}
+
+#[test]
+fn test_invoke_exact_rejects_other_type() {
+    let calls = load_class(CALLS);
+    let widen = method_handle(RefKind::InvokeStatic, CALLS, "widen", "(JJ)J", "(JJ)J");
+
+    let exit = run(&calls, "exactInts", "(Ljava/lang/invoke/MethodHandle;II)I", vec![widen, Slot::int(1), Slot::int(2)]);
+    expect_exception(exit, JavaExceptionKind::WrongMethodTypeException);
+}
+
+#[test]
+fn test_invoke_exact_on_null_handle() {
+    let calls = load_class(CALLS);
+
+    let exit = run(
+        &calls,
+        "exactInts",
+        "(Ljava/lang/invoke/MethodHandle;II)I",
+        vec![Slot::reference(0), Slot::int(1), Slot::int(2)],
+    );
+    expect_exception(exit, JavaExceptionKind::NullPointerException);
+}
+
+#[test]
+fn test_invoke_exact_void_static_method() {
+    let calls = load_class(CALLS);
+    let bump = method_handle(RefKind::InvokeStatic, CALLS, "bump", "(I)V", "(I)V");
+
+    let exit = run(&calls, "exactVoid", "(Ljava/lang/invoke/MethodHandle;I)I", vec![bump, Slot::int(0)]);
+    let before = expect_int(exit);
+    let bump = method_handle(RefKind::InvokeStatic, CALLS, "bump", "(I)V", "(I)V");
+    let exit = run(&calls, "exactVoid", "(Ljava/lang/invoke/MethodHandle;I)I", vec![bump, Slot::int(5)]);
+    assert_eq!(expect_int(exit), before + 5);
+}
+
+#[test]
+fn test_invoke_exact_virtual_method() {
+    let calls = load_class(CALLS);
+    let plus = method_handle(RefKind::InvokeVirtual, CALLS, "plus", "(I)I", "(LMethodHandleCalls;I)I");
+
+    let exit = run(
+        &calls,
+        "exactInstance",
+        "(Ljava/lang/invoke/MethodHandle;LMethodHandleCalls;I)I",
+        vec![plus, receiver(10), Slot::int(5)],
+    );
+    assert_eq!(expect_int(exit), 15);
+}
+
+#[test]
+fn test_invoke_exact_null_receiver() {
+    let calls = load_class(CALLS);
+    let plus = method_handle(RefKind::InvokeVirtual, CALLS, "plus", "(I)I", "(LMethodHandleCalls;I)I");
+
+    let exit = run(
+        &calls,
+        "exactInstance",
+        "(Ljava/lang/invoke/MethodHandle;LMethodHandleCalls;I)I",
+        vec![plus, Slot::reference(0), Slot::int(5)],
+    );
+    expect_exception(exit, JavaExceptionKind::NullPointerException);
+}
+
+#[test]
+fn test_invoke_casts_reference_arguments() {
+    let calls = load_class(CALLS);
+    let desc = "(Ljava/lang/invoke/MethodHandle;Ljava/lang/Object;I)I";
+
+    let plus = method_handle(RefKind::InvokeVirtual, CALLS, "plus", "(I)I", "(LMethodHandleCalls;I)I");
+    assert_eq!(expect_int(run(&calls, "invokeObject", desc, vec![plus, receiver(1), Slot::int(2)])), 3);
+
+    let plus = method_handle(RefKind::InvokeVirtual, CALLS, "plus", "(I)I", "(LMethodHandleCalls;I)I");
+    let other = Slot::reference(load_class(LAMBDAS).allocate_instance());
+    let exit = run(&calls, "invokeObject", desc, vec![plus, other, Slot::int(2)]);
+    expect_exception(exit, JavaExceptionKind::ClassCastException);
+}
+
+#[test]
+fn test_invoke_exact_rejects_what_invoke_accepts() {
+    let calls = load_class(CALLS);
+    let plus = method_handle(RefKind::InvokeVirtual, CALLS, "plus", "(I)I", "(LMethodHandleCalls;I)I");
+
+    let exit = run(
+        &calls,
+        "invokeObject",
+        "(Ljava/lang/invoke/MethodHandle;Ljava/lang/Object;I)I",
+        vec![plus, receiver(1), Slot::int(2)],
+    );
+    assert_eq!(expect_int(exit), 3);
+
+    // `invoke` does not widen int to long.
+    let widen = method_handle(RefKind::InvokeStatic, CALLS, "widen", "(JJ)J", "(JJ)J");
+    let exit = run(&calls, "invokeInts", "(Ljava/lang/invoke/MethodHandle;II)I", vec![widen, Slot::int(1), Slot::int(2)]);
+    expect_exception(exit, JavaExceptionKind::WrongMethodTypeException);
+}
+
+#[test]
+fn test_field_handles() {
+    let calls = load_class(CALLS);
+    let target = receiver(3);
+
+    let setter = field_handle(RefKind::PutField, CALLS, "value", "(LMethodHandleCalls;I)V");
+    let exit = run(
+        &calls,
+        "exactSetter",
+        "(Ljava/lang/invoke/MethodHandle;LMethodHandleCalls;I)I",
+        vec![setter, target, Slot::int(11)],
+    );
+    assert_eq!(expect_int(exit), 11);
+
+    let getter = field_handle(RefKind::GetField, CALLS, "value", "(LMethodHandleCalls;)I");
+    let exit = run(&calls, "exactGetter", "(Ljava/lang/invoke/MethodHandle;LMethodHandleCalls;)I", vec![getter, target]);
+    assert_eq!(expect_int(exit), 11);
+
+    let counter = field_handle(RefKind::GetStatic, CALLS, "counter", "()I");
+    let exit = run(&calls, "exactStaticGetter", "(Ljava/lang/invoke/MethodHandle;)I", vec![counter]);
+    let field = calls.find_declared_field("counter", "I").unwrap();
+    assert_eq!(expect_int(exit), calls.read_static_field(&field).unwrap()[0].as_int().unwrap());
+}
+
+#[test]
+fn test_constructor_handle() {
+    let calls = load_class(CALLS);
+    let constructor = method_handle(RefKind::NewInvokeSpecial, CALLS, "<init>", "(I)V", "(I)LMethodHandleCalls;");
+
+    let exit = run(&calls, "exactConstructor", "(Ljava/lang/invoke/MethodHandle;I)I", vec![constructor, Slot::int(21)]);
+    assert_eq!(expect_int(exit), 21);
+}
+
+/// A class whose `load()` pushes constant `index` and returns it.
+fn constant_loader(name: &str, constant: impl FnOnce(&mut ConstantPool) -> u16) -> MSRef<NormalKlass> {
+    let mut class = ClassBuilder::new(name);
+    class.version(52);
+    let index = constant(class.constant_pool());
+    let mut code = class.method(0x0009, "load", "()Ljava/lang/Object;");
+    code.load_constant(0x13, index).op(0xb0); // ldc_w; areturn
+    code.finish().unwrap();
+    define(class)
+}
+
+#[test]
+fn test_ldc_method_type_is_interned() {
+    let first = constant_loader("LdcMethodTypeA", |x| x.method_type("(IJ)V"));
+    let second = constant_loader("LdcMethodTypeB", |x| x.method_type("(IJ)V"));
+
+    let a = expect_ref(run(&first, "load", "()Ljava/lang/Object;", vec![]));
+    let b = expect_ref(run(&second, "load", "()Ljava/lang/Object;", vec![]));
+    assert_ne!(a, 0);
+    assert_eq!(a, b);
+    assert_eq!(JavaMethodType::descriptor(a).unwrap(), "(IJ)V");
+    assert_eq!(JavaMethodType::create("(IJ)V").unwrap(), a);
+}
+
+#[test]
+fn test_ldc_method_handle() {
+    let klass = constant_loader("LdcMethodHandle", |x| {
+        let add = x.method_ref(CALLS, "add", "(II)I");
+        x.method_handle(6, add)
+    });
+
+    let first = expect_ref(run(&klass, "load", "()Ljava/lang/Object;", vec![]));
+    let second = expect_ref(run(&klass, "load", "()Ljava/lang/Object;", vec![]));
+    assert_eq!(first, second);
+    assert_eq!(JavaMethodHandle::type_descriptor(first).unwrap(), "(II)I");
+
+    let calls = load_class(CALLS);
+    let exit = run(
+        &calls,
+        "exactInts",
+        "(Ljava/lang/invoke/MethodHandle;II)I",
+        vec![Slot::reference(first), Slot::int(20), Slot::int(22)],
+    );
+    assert_eq!(expect_int(exit), 42);
+}
+
+#[test]
+fn test_lambdas() {
+    assert_eq!(run_lambda("nonCapturing", "(II)I", vec![Slot::int(6), Slot::int(7)]), 42);
+    assert_eq!(run_lambda("capturing", "(III)I", vec![Slot::int(2), Slot::int(3), Slot::int(4)]), 10);
+    assert_eq!(run_lambda("capturingThis", "(III)I", vec![Slot::int(100), Slot::int(9), Slot::int(4)]), 105);
+
+    let wide = |x: i64| [Slot::long_high(x), Slot::long_low(x)];
+    let args = [wide(1 << 32), wide(3), wide(-1)].concat();
+    let exit = run(&load_class(LAMBDAS), "capturingWide", "(JJJ)J", args);
+    assert_eq!(expect_long(exit), (3 << 32) - 1);
+}
+
+#[test]
+fn test_method_references() {
+    assert_eq!(run_lambda("staticReference", "(II)I", vec![Slot::int(9), Slot::int(4)]), 5);
+    assert_eq!(run_lambda("boundThis", "(III)I", vec![Slot::int(10), Slot::int(1), Slot::int(2)]), 13);
+    assert_eq!(run_lambda("virtualReference", "(II)I", vec![Slot::int(3), Slot::int(4)]), 7);
+    assert_eq!(run_lambda("interfaceReference", "(II)I", vec![Slot::int(3), Slot::int(4)]), 12);
+    assert_eq!(run_lambda("constructorReference", "(II)I", vec![Slot::int(5), Slot::int(6)]), 30);
+}
+
+#[test]
+fn test_void_interface_method_discards_result() {
+    assert_eq!(run_lambda("discardedResult", "(I)I", vec![Slot::int(8)]), 8);
+}
+
+#[test]
+fn test_lambda_call_site_is_reused() {
+    assert_eq!(run_lambda("repeated", "(I)I", vec![Slot::int(3)]), 21);
+}
+
+#[test]
+fn test_unsupported_conversion_is_wrapped_lambda_conversion_exception() {
+    // `add(II)I` cannot implement `next()I`: the interface method supplies
+    // no arguments.
+    let mut class = ClassBuilder::new("BadLambda");
+    class.version(52);
+    let cp = class.constant_pool();
+    let metafactory = cp.method_ref(
+        "java/lang/invoke/LambdaMetafactory",
+        "metafactory",
+        "(Ljava/lang/invoke/MethodHandles$Lookup;Ljava/lang/String;Ljava/lang/invoke/MethodType;\
+         Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodHandle;Ljava/lang/invoke/MethodType;)\
+         Ljava/lang/invoke/CallSite;",
+    );
+    let handle = cp.method_handle(6, metafactory);
+    let sam = cp.method_type("()I");
+    let add = cp.method_ref(CALLS, "add", "(II)I");
+    let add = cp.method_handle(6, add);
+    let bsm = class.bootstrap_method(handle, &[sam, add, sam]);
+    let mut code = class.method(0x0009, "make", "()Ljava/lang/Object;");
+    code.invokedynamic(bsm, "next", "()LLambdaSamples$Counter;").op(0xb0); // areturn
+    code.finish().unwrap();
+    load_class(CALLS);
+    let klass = define(class);
+
+    let exit = run(&klass, "make", "()Ljava/lang/Object;", vec![]);
+    let error = expect_exception(exit, JavaExceptionKind::BootstrapMethodError).unwrap();
+    let cause = JavaThrowable::cause(error).unwrap().unwrap();
+    assert_eq!(klass_of(cause).unwrap().name().utf8(), "java/lang/invoke/LambdaConversionException");
+}
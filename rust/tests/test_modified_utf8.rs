@@ -0,0 +1,111 @@
+mod test_harness;
+
+use std::sync::Arc;
+
+use klover::{
+    assembler::class_builder::ClassBuilder,
+    class_loader::{class_path::ClassPath, class_source::MemorySource, cld::ClassLoaderData},
+    class_parser::{class_file::ClassFile, cp_info::ConstantPoolInfo, modified_utf8, parse_error::ParseError},
+    oops::symbol_table::SymbolTable,
+};
+use proptest::prelude::*;
+use test_harness::{expect_int, load_class, run, splice_utf8};
+
+const CLEF: &str = "pkg/Clef\u{1d11e}";
+
+/// Parse a class whose constant pool holds `raw` as the bytes of a Utf8
+/// entry, and return that entry.
+fn parse_raw(raw: &[u8]) -> Result<ConstantPoolInfo, ParseError> {
+    let mut class = ClassBuilder::new("RawUtf8");
+    let idx = class.constant_pool().utf8("raw");
+    let mut bytes = class.to_bytes().unwrap();
+    splice_utf8(&mut bytes, "raw", raw);
+
+    let mut cf = ClassFile::from(&bytes)?;
+    Ok(cf.constant_pool.swap_remove(idx as usize))
+}
+
+#[test]
+fn test_encoded_nul_and_surrogate_pairs_are_decoded() {
+    let Ok(ConstantPoolInfo::Utf8Info { utf8, wtf8: None }) = parse_raw(b"a\xc0\x80b") else {
+        panic!("C0 80 is NUL");
+    };
+    assert_eq!(utf8, "a\0b");
+
+    // U+1D11E 的代理对 D834 DD1E，各自编码成 3 字节
+    let Ok(ConstantPoolInfo::Utf8Info { utf8, wtf8: None }) = parse_raw(b"\xed\xa0\xb4\xed\xb4\x9e") else {
+        panic!("surrogate pair");
+    };
+    assert_eq!(utf8, "\u{1d11e}");
+}
+
+#[test]
+fn test_standard_utf8_only_forms_are_rejected() {
+    for raw in [
+        &b"\0"[..],
+        b"\xf0\x9d\x84\x9e", // U+1D11E 的标准 UTF-8 形式
+        b"\xc1\x81",          // 超长的 'A'
+        b"\xe0\x80\x80",      // 超长的 NUL
+        b"\xc3",
+        b"\xe4\xb8",
+        b"\x80",
+    ] {
+        assert!(matches!(parse_raw(raw), Err(ParseError::InvalidUtf8(_))), "{raw:x?}");
+    }
+}
+
+#[test]
+fn test_unpaired_surrogates_keep_exact_text() {
+    let Ok(ConstantPoolInfo::Utf8Info { utf8, wtf8: Some(wtf8) }) = parse_raw(b"a\xed\xa0\xb4") else {
+        panic!("lone high surrogate");
+    };
+    assert_eq!(utf8, "a\u{fffd}");
+    assert_eq!(modified_utf8::to_utf16(&wtf8), [0x61, 0xd834]);
+    assert_eq!(SymbolTable::intern_wtf8(&wtf8).to_utf16(), [0x61, 0xd834]);
+
+    // 低代理项在前时不能组成代理对
+    let Ok(ConstantPoolInfo::Utf8Info { wtf8: Some(wtf8), .. }) = parse_raw(b"\xed\xb4\x9e\xed\xa0\xb4") else {
+        panic!("reversed pair");
+    };
+    assert_eq!(modified_utf8::to_utf16(&wtf8), [0xdd1e, 0xd834]);
+}
+
+#[test]
+fn test_supplementary_class_names_resolve() {
+    load_class("ControlFlow");
+
+    let mut clef = ClassBuilder::new(CLEF);
+    let mut code = clef.method(0x0009, "note", "()I");
+    code.bipush(7).op(0xac); // ireturn
+    code.finish().unwrap();
+
+    let mut caller = ClassBuilder::new("CallsClef");
+    let mut code = caller.method(0x0009, "call", "()I");
+    code.invoke(0xb8, CLEF, "note", "()I").op(0xac); // invokestatic; ireturn
+    code.finish().unwrap();
+
+    let source = Arc::new(MemorySource::new("supplementary names"));
+    source.insert_class(CLEF, clef.to_bytes().unwrap());
+    source.insert_class("CallsClef", caller.to_bytes().unwrap());
+    let class_path = ClassPath::from_sources(vec![source]);
+    let cld = unsafe { ClassLoaderData::with_class_path(Some("clef loader".into()), class_path).as_ref() };
+
+    let caller = cld.load_class("CallsClef").unwrap().as_normal_ref().unwrap();
+    assert_eq!(expect_int(run(&caller, "call", "()I", vec![])), 7);
+
+    let clef = cld.find_loaded_class(CLEF).unwrap();
+    assert_eq!(clef.name().utf8(), CLEF);
+    assert_eq!(clef.name().to_utf16(), CLEF.encode_utf16().collect::<Vec<_>>());
+}
+
+proptest! {
+    #[test]
+    fn test_modified_utf8_round_trips(units in prop::collection::vec(any::<u16>(), 0..32)) {
+        let wtf8 = modified_utf8::from_utf16(&units);
+        let encoded = modified_utf8::encode(&wtf8);
+        prop_assert!(!encoded.contains(&0));
+        prop_assert!(encoded.iter().all(|&x| x < 0xf0));
+        prop_assert_eq!(modified_utf8::decode(&encoded), Some(wtf8.clone()));
+        prop_assert_eq!(modified_utf8::to_utf16(&wtf8), units);
+    }
+}
@@ -20,6 +20,7 @@ fn init_vm() {
                 env!("CARGO_MANIFEST_DIR")
             ),
             xmx: 64 * 1024 * 1024,
+            ..Default::default()
         });
     });
 }
@@ -0,0 +1,148 @@
+mod test_harness;
+
+use klover::{
+    class_loader::ms_api::MSRef,
+    engine::{
+        call::Invocation,
+        exec_dispatcher::ExecDispatcher,
+        exec_error::JavaExceptionKind,
+        outcome::{PendingException, RunOutcome, ThreadExit},
+        resolved_method::ResolvedMethod,
+        slot::Slot,
+        stack_trace::{StackFrame, StackTrace, ThrowableReport},
+    },
+    oops::{java_throwable::JavaThrowable, normal_klass::NormalKlass},
+    runtime::{java_thread::JavaThread, thread_manager::ThreadManager},
+};
+use parking_lot::Mutex;
+use test_harness::{expect_int, expect_ref, load_class, run};
+
+static REPORTS: Mutex<Vec<(String, ThrowableReport)>> = Mutex::new(Vec::new());
+
+fn record(thread: &JavaThread, report: &ThrowableReport) {
+    REPORTS.lock().push((thread.name().to_string(), report.clone()));
+}
+
+/// Run with a recording uncaught-exception handler and return the report.
+fn run_recorded(holder: &MSRef<NormalKlass>, name: &str, desc: &str, args: Vec<Slot>) -> (ThreadExit, ThrowableReport) {
+    let method = holder.find_declared_method(name, desc).unwrap();
+    let target = ResolvedMethod::new(holder.clone(), method);
+
+    let mut manager = ThreadManager::new(1024);
+    let mut thread = manager.create_thread().unwrap();
+    thread.set_name(format!("trace-{name}"));
+    thread.start().unwrap();
+
+    let mut dispatcher = ExecDispatcher::new();
+    dispatcher.set_uncaught_exception_handler(record);
+    dispatcher.enter_root(&mut thread, Invocation { target, args }).unwrap();
+
+    let exit = loop {
+        match dispatcher.run_quantum(&mut thread, 64).unwrap() {
+            RunOutcome::QuantumExpired => continue,
+            RunOutcome::Terminated(exit) => break exit,
+        }
+    };
+
+    let mut reports = REPORTS.lock();
+    let index = reports
+        .iter()
+        .position(|(thread, _)| *thread == format!("trace-{name}"))
+        .expect("handler was called");
+    (exit, reports.remove(index).1)
+}
+
+fn frame(method: &str, bci: usize, line: u32) -> StackFrame {
+    StackFrame {
+        class_name: "StackTraces".into(),
+        method_name: method.into(),
+        bci,
+        file_name: Some("StackTraces.java".into()),
+        line_number: Some(line),
+    }
+}
+
+#[test]
+fn test_vm_raised_exception_records_every_caller_with_its_bci() {
+    let holder = load_class("StackTraces");
+    let (exit, report) = run_recorded(&holder, "run", "(I)I", vec![Slot::int(0)]);
+
+    assert!(matches!(
+        exit,
+        ThreadExit::UncaughtException(PendingException::JVMGen(JavaExceptionKind::ArithmeticException))
+    ));
+    assert_eq!(report.class_name, "java/lang/ArithmeticException");
+    assert_eq!(report.message.as_deref(), Some("/ by zero"));
+    assert_eq!(
+        report.stack_trace.frames,
+        [frame("divide", 2, 3), frame("compute", 3, 7), frame("run", 1, 11)]
+    );
+    assert_eq!(
+        report.to_string(),
+        "java.lang.ArithmeticException: / by zero\n\
+         \tat StackTraces.divide(StackTraces.java:3)\n\
+         \tat StackTraces.compute(StackTraces.java:7)\n\
+         \tat StackTraces.run(StackTraces.java:11)\n"
+    );
+}
+
+#[test]
+fn test_handler_is_not_called_on_normal_return() {
+    let holder = load_class("StackTraces");
+    assert_eq!(expect_int(run(&holder, "run", "(I)I", vec![Slot::int(5)])), 3);
+}
+
+#[test]
+fn test_java_constructed_throwable_records_its_creator() {
+    let holder = load_class("StackTraces");
+    let obj = expect_ref(run(&holder, "create", "()Ljava/lang/Throwable;", vec![]));
+
+    // fillInStackTrace 与各层构造器的帧不计入轨迹。
+    assert_eq!(
+        JavaThrowable::stack_trace(obj).unwrap().frames,
+        [frame("make", 4, 25), frame("create", 0, 21)]
+    );
+    assert_eq!(
+        JavaThrowable::report(obj).unwrap().to_string(),
+        "java.lang.IllegalStateException\n\
+         \tat StackTraces.make(StackTraces.java:25)\n\
+         \tat StackTraces.create(StackTraces.java:21)\n"
+    );
+}
+
+#[test]
+fn test_throwable_objects_keep_their_trace_and_cause_chain() {
+    load_class("StackTraces");
+
+    let cause = JavaThrowable::create("java/lang/Throwable", Some("root")).unwrap();
+    let outer = JavaThrowable::create("java/lang/Throwable", None).unwrap();
+    let shared = StackTrace {
+        frames: vec![frame("run", 1, 11)],
+    };
+    let inner = StackTrace {
+        frames: vec![frame("divide", 2, 3), frame("compute", 3, 7), frame("run", 1, 11)],
+    };
+    JavaThrowable::set_stack_trace(outer, &shared).unwrap();
+    JavaThrowable::set_stack_trace(cause, &inner).unwrap();
+
+    assert_eq!(JavaThrowable::cause(outer).unwrap(), None);
+    JavaThrowable::set_cause(outer, Some(cause)).unwrap();
+
+    assert_eq!(JavaThrowable::stack_trace(cause).unwrap(), inner);
+    assert_eq!(JavaThrowable::message(cause).unwrap().as_deref(), Some("root"));
+    assert_eq!(JavaThrowable::cause(outer).unwrap(), Some(cause));
+    assert_eq!(
+        JavaThrowable::report(outer).unwrap().to_string(),
+        "java.lang.Throwable\n\
+         \tat StackTraces.run(StackTraces.java:11)\n\
+         Caused by: java.lang.Throwable: root\n\
+         \tat StackTraces.divide(StackTraces.java:3)\n\
+         \tat StackTraces.compute(StackTraces.java:7)\n\
+         \t... 1 more\n"
+    );
+
+    // 环形 cause 链在重复处截断。
+    JavaThrowable::set_cause(cause, Some(outer)).unwrap();
+    let report = JavaThrowable::report(outer).unwrap();
+    assert!(report.cause.unwrap().cause.is_none());
+}
@@ -0,0 +1,50 @@
+mod test_harness;
+
+use std::ptr;
+
+use klover::{
+    class_loader::{bootstrap_cld::BootstrapCLD, load_error::LoadError, system_cld::SystemCLD},
+    engine::slot::Slot,
+};
+use test_harness::{expect_int, expect_ref, load_app_class, run};
+
+#[test]
+fn system_loader_defines_classes_from_the_application_class_path() {
+    let holder = load_app_class("AppMain");
+
+    let cld = holder.cld().expect("application classes have a defining loader");
+    assert!(ptr::eq(cld, SystemCLD::get()));
+    assert!(matches!(
+        BootstrapCLD::find_class("AppMain"),
+        Err(LoadError::NotFound(_))
+    ));
+}
+
+#[test]
+fn system_loader_delegates_to_bootstrap_first() {
+    let _ = load_app_class("AppMain");
+    let callee = SystemCLD::find_class("StaticCallee").unwrap();
+    let boot = BootstrapCLD::find_class("StaticCallee").unwrap();
+
+    assert!(ptr::eq(&*callee, &*boot));
+    assert!(callee.as_normal().unwrap().cld().is_none());
+}
+
+#[test]
+fn application_class_calls_into_bootstrap_class() {
+    let holder = load_app_class("AppMain");
+    let exit = run(&holder, "twiceViaBootstrap", "(I)I", vec![Slot::int(21)]);
+
+    assert_eq!(expect_int(exit), 42);
+}
+
+#[test]
+fn get_system_class_loader_returns_a_stable_instance() {
+    let holder = load_app_class("AppMain");
+    let first = expect_ref(run(&holder, "systemLoader", "()Ljava/lang/ClassLoader;", vec![]));
+    let second = expect_ref(run(&holder, "systemLoader", "()Ljava/lang/ClassLoader;", vec![]));
+
+    assert_ne!(first, 0);
+    assert_eq!(first, second);
+    assert_eq!(SystemCLD::mirror().unwrap(), first);
+}
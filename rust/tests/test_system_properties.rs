@@ -0,0 +1,45 @@
+mod test_harness;
+
+use klover::{
+    engine::{exec_error::JavaExceptionKind, slot::Slot},
+    oops::java_string::JavaString,
+};
+use test_harness::{expect_exception, expect_int, expect_ref, load_class, run};
+
+fn string(text: &str) -> Slot {
+    Slot::reference(JavaString::create(text).unwrap())
+}
+
+#[test]
+fn test_get_property_reads_the_command_line() {
+    let holder = load_class("Properties");
+
+    let value = expect_ref(run(&holder, "get", "(Ljava/lang/String;)Ljava/lang/String;", vec![string("klover.test.name")]));
+    // 同一个键出现两次时后者生效。
+    assert_eq!(JavaString::to_rust_string(value).unwrap(), "klover");
+    assert_eq!(expect_int(run(&holder, "length", "(Ljava/lang/String;)I", vec![string("klover.test.name")])), 6);
+}
+
+#[test]
+fn test_unset_property_is_null_or_the_default() {
+    let holder = load_class("Properties");
+
+    let value = expect_ref(run(&holder, "get", "(Ljava/lang/String;)Ljava/lang/String;", vec![string("klover.test.unset")]));
+    assert_eq!(value, 0);
+
+    let value = expect_ref(run(
+        &holder,
+        "getOrDefault",
+        "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/String;",
+        vec![string("klover.test.unset"), string("fallback")],
+    ));
+    assert_eq!(JavaString::to_rust_string(value).unwrap(), "fallback");
+}
+
+#[test]
+fn test_null_key_throws() {
+    let holder = load_class("Properties");
+    let exit = run(&holder, "get", "(Ljava/lang/String;)Ljava/lang/String;", vec![Slot::reference(0)]);
+
+    expect_exception(exit, JavaExceptionKind::NullPointerException);
+}
@@ -0,0 +1,155 @@
+mod test_harness;
+
+use std::{error::Error, fs, sync::Arc};
+
+use klover::{
+    assembler::class_builder::ClassBuilder,
+    class_loader::{
+        class_path::ClassPath, class_source::MemorySource, cld::ClassLoaderData, load_error::LoadError,
+    },
+    class_parser::{attr_info::AttrInfo, class_file::ClassFile, limits::ClassFileLimits, parse_error::ParseError},
+    oops::oops_errors::ResolveError,
+};
+use proptest::prelude::*;
+use test_harness::load_class;
+
+/// javac output covering constants, lambdas, annotations and stack maps.
+const SEEDS: [&str; 6] = [
+    "VerifierSamples",
+    "ControlFlow",
+    "LambdaSamples",
+    "DefaultMethods",
+    "Annotated",
+    "StaticFields",
+];
+
+fn class_bytes(name: &str) -> Vec<u8> {
+    let path = format!("{}/../test_data/classes/{name}.class", env!("CARGO_MANIFEST_DIR"));
+    fs::read(&path).unwrap_or_else(|err| panic!("{path}: {err}"))
+}
+
+fn limits(change: impl FnOnce(&mut ClassFileLimits)) -> ClassFileLimits {
+    let mut limits = ClassFileLimits::default();
+    change(&mut limits);
+    limits
+}
+
+#[test]
+fn test_constant_pool_size_is_limited() {
+    let bytes = class_bytes("ControlFlow");
+    assert!(ClassFile::parse(&bytes, ClassFileLimits::default()).is_ok());
+
+    let res = ClassFile::parse(&bytes, limits(|x| x.max_constant_pool_entries = 8));
+    assert!(matches!(res, Err(ParseError::TooManyConstants(_))));
+}
+
+#[test]
+fn test_code_length_is_limited() {
+    let bytes = class_bytes("ControlFlow");
+    let res = ClassFile::parse(&bytes, limits(|x| x.max_code_length = 4));
+    assert!(matches!(res, Err(ParseError::InvalidCodeLength(len)) if len > 4));
+
+    // The builder refuses empty code, so empty the code it built.
+    let mut class = ClassBuilder::new("EmptyCode");
+    let mut code = class.method(0x0009, "empty", "()V");
+    code.op(0xb1); // return
+    code.finish().unwrap();
+    let mut cf = class.build().unwrap();
+    if let AttrInfo::Code(code) = &mut cf.methods[0].attrs[0] {
+        code.code.clear();
+    }
+    assert!(matches!(ClassFile::from(&cf.to_bytes().unwrap()), Err(ParseError::InvalidCodeLength(0))));
+}
+
+#[test]
+fn test_attribute_nesting_is_limited() {
+    // LineNumberTable 位于 Code 之内，深度为 2。
+    let bytes = class_bytes("ControlFlow");
+    let res = ClassFile::parse(&bytes, limits(|x| x.max_attribute_nesting = 1));
+    assert!(matches!(res, Err(ParseError::NestingTooDeep(2))));
+}
+
+/// A loader whose class path holds `Deep0` extends `Deep1` extends ...
+/// extends `Deep{depth - 1}`.
+fn deep_hierarchy(depth: usize) -> &'static ClassLoaderData {
+    let source = Arc::new(MemorySource::new("deep hierarchy"));
+    for i in 0..depth {
+        let mut class = ClassBuilder::new(&format!("Deep{i}"));
+        if i + 1 < depth {
+            class.extend(&format!("Deep{}", i + 1));
+        }
+        source.insert_class(&format!("Deep{i}"), class.to_bytes().unwrap());
+    }
+    let class_path = ClassPath::from_sources(vec![source]);
+    unsafe { ClassLoaderData::with_class_path(Some("deep loader".into()), class_path).as_ref() }
+}
+
+#[test]
+fn test_class_hierarchy_depth_is_limited() {
+    load_class("ControlFlow");
+    let max_depth = ClassFileLimits::default().max_class_hierarchy_depth;
+    let depth = max_depth + 44;
+
+    // 链接 Deep0 会递归加载整条继承链；超过上限时必须报错而不是耗尽栈。
+    let Err(error) = deep_hierarchy(depth).load_class("Deep0") else {
+        panic!("loaded a hierarchy {depth} deep");
+    };
+    let mut cause: &dyn Error = &error;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    assert!(cause.to_string().contains("too deep"), "{cause}");
+
+    // 从顶端逐个加载时没有递归，深度由已链接的父类算出。
+    let cld = deep_hierarchy(depth);
+    let deepest = depth - max_depth;
+    for i in (deepest..depth).rev() {
+        assert!(cld.load_class(&format!("Deep{i}")).is_ok(), "Deep{i}");
+    }
+    match cld.load_class(&format!("Deep{}", deepest - 1)) {
+        Err(LoadError::Resolve { source: ResolveError::HierarchyTooDeep(_), .. }) => {}
+        other => panic!("expected a too deep hierarchy, got {other:?}"),
+    }
+}
+
+/// A seed class with `mutations` applied: each `(position, byte)` overwrites
+/// the byte at `position` modulo the length, and `truncate` cuts the tail.
+fn mutated(seed: usize, mutations: &[(usize, u8)], truncate: Option<usize>) -> Vec<u8> {
+    let mut bytes = class_bytes(SEEDS[seed]);
+    let len = bytes.len();
+    for &(position, byte) in mutations {
+        bytes[position % len] = byte;
+    }
+    if let Some(cut) = truncate {
+        bytes.truncate(cut % len);
+    }
+    bytes
+}
+
+/// Parse, define, link and verify `bytes`; any outcome but a panic is fine.
+fn load_untrusted(bytes: &[u8]) {
+    let Ok(_) = ClassFile::from(bytes) else {
+        return;
+    };
+
+    let cld = unsafe { ClassLoaderData::new(Some("untrusted loader".into())).as_ref() };
+    if let Ok(klass) = cld.define_class(bytes)
+        && let Some(klass) = klass.as_normal_ref()
+    {
+        let _ = klass.verify();
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn test_mutated_class_files_never_panic(
+        seed in 0..SEEDS.len(),
+        mutations in prop::collection::vec((any::<usize>(), any::<u8>()), 1..8),
+        truncate in prop::option::weighted(0.1, any::<usize>()),
+    ) {
+        load_class("ControlFlow");
+        load_untrusted(&mutated(seed, &mutations, truncate));
+    }
+}
@@ -0,0 +1,283 @@
+mod test_harness;
+
+use klover::{
+    assembler::{class_builder::ClassBuilder, code_builder::CodeBuilder},
+    class_loader::ms_api::MSRef,
+    class_parser::stack_map_info::{StackMapFrameInfo, VerificationTypeInfo},
+    engine::{
+        exec_error::JavaExceptionKind,
+        outcome::{PendingException, ThreadExit},
+    },
+    oops::normal_klass::NormalKlass,
+    verifier::verify_error::VerifyErrorKind,
+};
+use test_harness::{define, load_class, run};
+
+const THROWABLE: &str = "java/lang/Throwable";
+
+/// A class file of version `major` whose static method `desc` runs `code`.
+fn versioned(name: &str, major: u16, desc: &str, code: impl FnOnce(&mut CodeBuilder)) -> MSRef<NormalKlass> {
+    let mut class = ClassBuilder::new(name);
+    class.version(major);
+    let mut method = class.method(0x0009, "call", desc);
+    code(&mut method);
+    method.finish().unwrap();
+
+    load_class("ControlFlow");
+    define(class)
+}
+
+/// A class whose static method `desc` runs `code`, verified by type checking.
+fn with_static(name: &str, desc: &str, code: impl FnOnce(&mut CodeBuilder)) -> MSRef<NormalKlass> {
+    versioned(name, 52, desc, code)
+}
+
+fn expect_failure(klass: &MSRef<NormalKlass>) -> VerifyErrorKind {
+    match klass.verify() {
+        Err(error) => error.kind,
+        Ok(()) => panic!("{} verified", klass.name().utf8()),
+    }
+}
+
+/// `return x == 0 ? 1 : 0;`
+fn branch_on_zero(code: &mut CodeBuilder) {
+    // 0: iload_0  1: ifeq 6  4: iconst_0  5: ireturn  6: iconst_1  7: ireturn
+    let zero = code.label();
+    code.op(0x1a).branch(0x99, zero).op(0x03).op(0xac);
+    code.bind(zero).op(0x04).op(0xac);
+}
+
+fn float_as_int(code: &mut CodeBuilder) {
+    code.op(0x0b).op(0xac); // fconst_0; ireturn
+}
+
+/// `static int call(int x) { return x == 0 ? 1 : 0; }` with `frames`.
+fn branching(name: &str, frames: Vec<StackMapFrameInfo>) -> MSRef<NormalKlass> {
+    with_static(name, "(I)I", |x| {
+        branch_on_zero(x);
+        if !frames.is_empty() {
+            x.stack_map_table(frames);
+        }
+    })
+}
+
+/// `static int call()` whose `iconst_0; ireturn` is covered by a handler
+/// catching `catch_type` at 2, where the frame holds `handler_stack`.
+fn catching(name: &str, catch_type: &str, handler_stack: &str) -> MSRef<NormalKlass> {
+    with_static(name, "()I", |x| {
+        // 0: iconst_0  1: ireturn  2: pop  3: iconst_1  4: ireturn
+        let (start, end) = (x.label(), x.label());
+        x.bind(start).op(0x03).op(0xac);
+        x.bind(end).op(0x57).op(0x04).op(0xac);
+        x.handler(start, end, end, Some(catch_type));
+
+        let cpool_idx = x.constant_pool().class(handler_stack);
+        x.stack_map_table(vec![StackMapFrameInfo::SameLocals1StackItem {
+            offset_delta: 2,
+            stack: VerificationTypeInfo::Object { cpool_idx },
+        }]);
+    })
+}
+
+#[test]
+fn test_javac_classes_verify() {
+    for name in ["VerifierSamples", "ControlFlow", "LambdaSamples", "DefaultMethods", THROWABLE] {
+        let klass = load_class(name);
+        if let Err(error) = klass.verify() {
+            panic!("{error}");
+        }
+    }
+}
+
+#[test]
+fn test_exceeding_max_stack_fails() {
+    let klass = with_static("VerifyDeepStack", "()I", |x| {
+        x.max_stack(1).op(0x04).op(0x04).op(0x60).op(0xac); // iconst_1; iconst_1; iadd; ireturn
+    });
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::StackOverflow));
+}
+
+#[test]
+fn test_local_beyond_max_locals_fails() {
+    let klass = with_static("VerifyFarLocal", "(I)I", |x| {
+        x.max_locals(1).op(0x1d).op(0xac); // iload_3; ireturn
+    });
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::InvalidLocalIndex(3)));
+}
+
+#[test]
+fn test_mistyped_return_fails() {
+    let klass = with_static("VerifyFloatAsInt", "()I", float_as_int);
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::BadType { .. }));
+}
+
+#[test]
+fn test_reference_assignability_follows_the_hierarchy() {
+    let identity = |x: &mut CodeBuilder| {
+        x.op(0x2a).op(0xb0); // aload_0; areturn
+    };
+    let widening = with_static("VerifyWidening", "(Ljava/lang/String;)Ljava/lang/Object;", identity);
+    assert!(widening.verify().is_ok());
+
+    let narrowing = with_static("VerifyNarrowing", "(Ljava/lang/Object;)Ljava/lang/String;", identity);
+    assert!(matches!(expect_failure(&narrowing), VerifyErrorKind::BadType { .. }));
+}
+
+#[test]
+fn test_uninitialized_object_cannot_escape() {
+    let klass = with_static("VerifyUninitialized", "()Ljava/lang/Object;", |x| {
+        x.class_op(0xbb, "java/lang/Object").op(0xb0); // new; areturn
+    });
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::BadType { .. }));
+}
+
+#[test]
+fn test_constructor_must_call_super() {
+    let mut class = ClassBuilder::new("VerifyNoSuperCall");
+    class.version(52);
+    let mut code = class.method(0x0001, "<init>", "()V");
+    code.op(0xb1); // return
+    code.finish().unwrap();
+    let klass = define(class);
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::UninitializedReturn));
+}
+
+#[test]
+fn test_branch_targets_need_matching_frames() {
+    let missing = branching("VerifyNoFrame", vec![]);
+    assert!(matches!(expect_failure(&missing), VerifyErrorKind::MissingStackMapFrame(6)));
+
+    let same = branching("VerifySameFrame", vec![StackMapFrameInfo::Same { offset_delta: 6 }]);
+    assert!(same.verify().is_ok());
+
+    let extra = branching(
+        "VerifyExtraStack",
+        vec![StackMapFrameInfo::SameLocals1StackItem {
+            offset_delta: 6,
+            stack: VerificationTypeInfo::Integer,
+        }],
+    );
+    assert!(matches!(expect_failure(&extra), VerifyErrorKind::FrameMismatch(6)));
+
+    let float = branching(
+        "VerifyFloatLocal",
+        vec![StackMapFrameInfo::Full {
+            offset_delta: 6,
+            locals: vec![VerificationTypeInfo::Float],
+            stack: vec![],
+        }],
+    );
+    assert!(matches!(expect_failure(&float), VerifyErrorKind::FrameMismatch(6)));
+}
+
+#[test]
+fn test_exception_handlers_are_checked() {
+    assert!(catching("VerifyCatches", THROWABLE, THROWABLE).verify().is_ok());
+
+    let not_throwable = catching("VerifyCatchesString", "java/lang/String", "java/lang/String");
+    assert!(matches!(expect_failure(&not_throwable), VerifyErrorKind::BadType { .. }));
+
+    let narrow_frame = catching("VerifyNarrowHandler", THROWABLE, "java/lang/String");
+    assert!(matches!(expect_failure(&narrow_frame), VerifyErrorKind::FrameMismatch(2)));
+}
+
+#[test]
+fn test_invoking_unverifiable_class_throws_verify_error() {
+    let klass = with_static("VerifyOnCall", "()I", float_as_int);
+    for _ in 0..2 {
+        match run(&klass, "call", "()I", vec![]) {
+            ThreadExit::UncaughtException(PendingException::JVMGen(JavaExceptionKind::VerifyError)) => {}
+            other => panic!("expected VerifyError, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_old_class_files_are_verified_by_inference() {
+    let klass = versioned("InferBranching", 49, "(I)I", branch_on_zero);
+    assert!(klass.verify().is_ok());
+
+    let klass = versioned("InferFloatAsInt", 49, "()I", float_as_int);
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::BadType { .. }));
+}
+
+#[test]
+fn test_version_50_fails_over_to_inference() {
+    let klass = versioned("InferVersion50", 50, "(I)I", branch_on_zero);
+    assert!(klass.verify().is_ok());
+}
+
+#[test]
+fn test_references_merge_into_common_superclass() {
+    // 0: iload_0  1: ifeq 8  4: aload_1  5: goto 9  8: aload_2  9: areturn
+    let code = |x: &mut CodeBuilder| {
+        let (second, done) = (x.label(), x.label());
+        x.op(0x1a).branch(0x99, second).op(0x2b).branch(0xa7, done);
+        x.bind(second).op(0x2c);
+        x.bind(done).op(0xb0);
+    };
+    let params = "(ILjava/lang/String;Ljava/lang/Throwable;)";
+
+    let object = versioned("InferMergeObject", 49, &format!("{params}Ljava/lang/Object;"), code);
+    assert!(object.verify().is_ok());
+
+    let string = versioned("InferMergeString", 49, &format!("{params}Ljava/lang/String;"), code);
+    assert!(matches!(expect_failure(&string), VerifyErrorKind::BadType { .. }));
+}
+
+#[test]
+fn test_stacks_of_different_depth_do_not_merge() {
+    // 0: iconst_0  1: iload_0  2: ifeq 6  5: iconst_1  6: ireturn
+    let klass = versioned("InferDepthMismatch", 49, "(I)I", |x| {
+        let done = x.label();
+        x.max_stack(2).op(0x03).op(0x1a).branch(0x99, done).op(0x04);
+        x.bind(done).op(0xac);
+    });
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::FrameMismatch(6)));
+}
+
+#[test]
+fn test_subroutines_keep_untouched_locals() {
+    // 0: iload_0  1: istore_1  2: jsr 7  5: iload_1  6: ireturn  7: astore_2
+    let with_subroutine = |body: fn(&mut CodeBuilder)| {
+        move |x: &mut CodeBuilder| {
+            let subroutine = x.label();
+            x.op(0x1a).op(0x3c).branch(0xa8, subroutine).op(0x1b).op(0xac);
+            x.bind(subroutine).op(0x4d);
+            body(x);
+            x.local(0xa9, 2); // ret 2
+        }
+    };
+
+    let klass = versioned("InferFinally", 49, "(I)I", with_subroutine(|x| {
+        x.iinc(0, 1);
+    }));
+    assert!(klass.verify().is_ok());
+
+    let klass = versioned("InferFinallyClobbers", 49, "(I)I", with_subroutine(|x| {
+        x.op(0x0b).op(0x44); // fconst_0; fstore_1
+    }));
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::BadType { .. }));
+}
+
+#[test]
+fn test_malformed_subroutines_fail() {
+    // ret 0, with an int in local 0
+    let klass = versioned("InferRetInt", 49, "(I)V", |x| {
+        x.local(0xa9, 0);
+    });
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::BadType { .. }));
+
+    // 0: jsr 4  3: return  4: astore_0  5: jsr 4  8: ret 0
+    let code = |x: &mut CodeBuilder| {
+        let subroutine = x.label();
+        x.branch(0xa8, subroutine).op(0xb1);
+        x.bind(subroutine).op(0x4b).branch(0xa8, subroutine).local(0xa9, 0);
+    };
+    let klass = versioned("InferRecursiveJsr", 49, "()V", code);
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::Subroutine));
+
+    // jsr in a class file verified by type checking
+    let klass = versioned("CheckJsr", 52, "()V", code);
+    assert!(matches!(expect_failure(&klass), VerifyErrorKind::Subroutine));
+}
@@ -0,0 +1,9 @@
+public class AppMain {
+    public static int twiceViaBootstrap(int value) {
+        return StaticCallee.twice(value);
+    }
+
+    public static ClassLoader systemLoader() {
+        return ClassLoader.getSystemClassLoader();
+    }
+}
@@ -0,0 +1,7 @@
+public class DivideByArgs {
+    public static int result;
+
+    public static void main(String[] args) {
+        result = 100 / args.length;
+    }
+}
@@ -0,0 +1,5 @@
+public class ExitWith {
+    public static void main(String[] args) {
+        System.exit(args.length + 40);
+    }
+}
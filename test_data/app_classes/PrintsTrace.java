@@ -0,0 +1,9 @@
+public class PrintsTrace {
+    public static void main(String[] args) {
+        report();
+    }
+
+    static void report() {
+        new IllegalStateException().printStackTrace();
+    }
+}
@@ -0,0 +1,43 @@
+/**
+ * Members of every access level, used from classes in other loaders by
+ * test_access_control.rs.
+ */
+public class AccessTargets {
+    private static int secret = 1;
+    static int shared = 2;
+    public static int open = 3;
+
+    public AccessTargets() {
+    }
+
+    private static int secret() {
+        return 10;
+    }
+
+    static int packageOnly() {
+        return 20;
+    }
+
+    public static int visible() {
+        return 30;
+    }
+
+    protected int guarded() {
+        return 40;
+    }
+
+    protected static int guardedStatic() {
+        return 50;
+    }
+
+    /** Package-private members used from the same runtime package. */
+    public static int samePackage() {
+        return Hidden.peek() + shared;
+    }
+
+    static class Hidden {
+        static int peek() {
+            return packageOnly();
+        }
+    }
+}
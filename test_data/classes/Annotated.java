@@ -0,0 +1,52 @@
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+import java.util.List;
+
+@Annotated.Info(
+        name = "klass",
+        level = 3,
+        color = Annotated.Color.RED,
+        type = String.class,
+        ids = {1L, 2L},
+        tag = @Annotated.Tag("outer"))
+public class Annotated {
+    public enum Color { RED, GREEN }
+
+    @Retention(RetentionPolicy.RUNTIME)
+    public @interface Info {
+        String name();
+        int level() default 1;
+        Color color() default Color.GREEN;
+        Class<?> type() default Object.class;
+        long[] ids() default {};
+        Tag tag() default @Tag("none");
+    }
+
+    @Retention(RetentionPolicy.RUNTIME)
+    public @interface Tag {
+        String value();
+    }
+
+    @Retention(RetentionPolicy.RUNTIME)
+    @Target(ElementType.TYPE_USE)
+    public @interface NonNull {
+    }
+
+    // Not visible at run time: kept only as raw bytes.
+    public @interface Invisible {
+    }
+
+    @Tag("field")
+    @Deprecated
+    public int counter;
+
+    public List<@NonNull String> names;
+
+    @Invisible
+    public static int add(@Tag("a") int a, int b) {
+        Object o = (@NonNull Object) null;
+        return a + b;
+    }
+}
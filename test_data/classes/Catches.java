@@ -0,0 +1,73 @@
+public class Catches {
+    static int divide(int a, int b) {
+        return a / b;
+    }
+
+    public static int divideOr(int n, int fallback) {
+        try {
+            return divide(10, n);
+        } catch (ArithmeticException e) {
+            return fallback;
+        }
+    }
+
+    public static int wrongCatch(int n) {
+        try {
+            return divide(10, n);
+        } catch (NullPointerException e) {
+            return -1;
+        }
+    }
+
+    static int cleanups;
+
+    public static int divideWithCleanup(int n) {
+        try {
+            return divide(10, n);
+        } finally {
+            cleanups++;
+        }
+    }
+
+    public static int cleanups() {
+        return cleanups;
+    }
+
+    public static int throwAndCatch(int n) {
+        try {
+            if (n > 0) {
+                throw new IllegalStateException();
+            }
+            return 0;
+        } catch (RuntimeException e) {
+            return 1;
+        }
+    }
+
+    public static int nested(int n) {
+        try {
+            try {
+                return divide(10, n);
+            } catch (NullPointerException e) {
+                return -1;
+            }
+        } catch (ArithmeticException e) {
+            return -2;
+        }
+    }
+
+    // Implemented by classes the tests define at run time, whose probe()
+    // fails to link.
+    public interface Probe {
+        int probe();
+    }
+
+    public static Throwable linkageFrom(Probe probe) {
+        try {
+            probe.probe();
+            return null;
+        } catch (LinkageError e) {
+            return e;
+        }
+    }
+}
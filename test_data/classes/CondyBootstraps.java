@@ -0,0 +1,45 @@
+import java.lang.invoke.MethodHandles;
+
+// Bootstrap methods for the hand-assembled dynamic constants in
+// test_java11.rs.  Each test resolves through its own bootstrap method, so the
+// counters stay meaningful when tests run in parallel.
+public class CondyBootstraps {
+    static int countedLinks;
+    static String lastName;
+    static Class<?> lastType;
+
+    public static int answer(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        return 42;
+    }
+
+    public static int counted(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        countedLinks = countedLinks + 1;
+        return 7;
+    }
+
+    public static Object recording(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        lastName = name;
+        lastType = type;
+        return name;
+    }
+
+    public static long wide(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        return 1L << 40;
+    }
+
+    public static int sum(MethodHandles.Lookup lookup, String name, Class<?> type, int a, int b) {
+        return a + b;
+    }
+
+    public static int throwing(MethodHandles.Lookup lookup, String name, Class<?> type, int divisor) {
+        return 1 / divisor;
+    }
+
+    public static Object lookup(MethodHandles.Lookup lookup, String name, Class<?> type) {
+        return lookup;
+    }
+
+    public static int countedLinks() {
+        return countedLinks;
+    }
+}
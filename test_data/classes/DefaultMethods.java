@@ -0,0 +1,77 @@
+// Interface static and default methods; see test_default_methods.rs.
+public class DefaultMethods {
+    interface Greeter {
+        static int base() {
+            return 10;
+        }
+
+        default int greet() {
+            return base() + 1;
+        }
+    }
+
+    interface Loud extends Greeter {
+        default int greet() {
+            return Greeter.super.greet() * 2;
+        }
+    }
+
+    interface Quiet extends Greeter {
+    }
+
+    interface Left {
+        default int side() {
+            return 1;
+        }
+    }
+
+    interface Right {
+        default int side() {
+            return 2;
+        }
+    }
+
+    interface Abstract {
+        int side();
+    }
+
+    static class Plain implements Greeter {
+    }
+
+    static class Both implements Quiet, Loud {
+    }
+
+    static class Overriding implements Loud {
+        public int greet() {
+            return Loud.super.greet() + 100;
+        }
+    }
+
+    static class Inheriting extends Plain implements Loud {
+    }
+
+    public static int staticCall() {
+        return Greeter.base();
+    }
+
+    public static int inherited() {
+        return new Plain().greet();
+    }
+
+    public static int mostSpecific() {
+        return new Both().greet();
+    }
+
+    public static int throughInterface() {
+        Greeter greeter = new Both();
+        return greeter.greet();
+    }
+
+    public static int superCall() {
+        return new Overriding().greet();
+    }
+
+    public static int superclassInterfaces() {
+        return new Inheriting().greet();
+    }
+}
@@ -0,0 +1,106 @@
+import java.lang.invoke.CallSite;
+import java.lang.invoke.ConstantCallSite;
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.MethodType;
+import java.lang.invoke.MutableCallSite;
+
+// Bootstrap methods for the hand-assembled call sites in test_invokedynamic.rs.
+// Each test links through its own bootstrap method, so the counters stay
+// meaningful when tests run in parallel.
+public class IndyBootstraps {
+    static int countedLinks;
+    static int perInstructionLinks;
+    static MethodHandles.Lookup lastLookup;
+    static String lastName;
+    static MethodType lastType;
+    static Object[] lastCollected;
+    static Object[] lastCollectedNothing;
+    static Number lastBoxed;
+
+    public static CallSite counted(MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target) {
+        countedLinks = countedLinks + 1;
+        return new ConstantCallSite(target);
+    }
+
+    public static CallSite countedPerInstruction(
+            MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target) {
+        perInstructionLinks = perInstructionLinks + 1;
+        return new ConstantCallSite(target);
+    }
+
+    public static CallSite recording(MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target) {
+        lastLookup = lookup;
+        lastName = name;
+        lastType = type;
+        return new MutableCallSite(target);
+    }
+
+    public static CallSite constant(MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target) {
+        return new ConstantCallSite(target);
+    }
+
+    public static CallSite choosing(
+            MethodHandles.Lookup lookup, String name, MethodType type,
+            MethodHandle first, MethodHandle second, int which) {
+        if (which == 0) {
+            return new ConstantCallSite(first);
+        }
+        return new ConstantCallSite(second);
+    }
+
+    public static CallSite collecting(
+            MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target, Object... rest) {
+        lastCollected = rest;
+        return new ConstantCallSite(target);
+    }
+
+    public static CallSite collectingNothing(
+            MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target, Object... rest) {
+        lastCollectedNothing = rest;
+        return new ConstantCallSite(target);
+    }
+
+    public static CallSite boxing(
+            MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target, Number value) {
+        lastBoxed = value;
+        return new ConstantCallSite(target);
+    }
+
+    public static CallSite returnsNull(MethodHandles.Lookup lookup, String name, MethodType type) {
+        return null;
+    }
+
+    public static CallSite throwing(MethodHandles.Lookup lookup, String name, MethodType type, int divisor) {
+        return constant(lookup, name, type, null, 1 / divisor);
+    }
+
+    public static CallSite erroring(MethodHandles.Lookup lookup, String name, MethodType type) {
+        throw new IllegalAccessError();
+    }
+
+    private static CallSite constant(
+            MethodHandles.Lookup lookup, String name, MethodType type, MethodHandle target, int ignored) {
+        return new ConstantCallSite(target);
+    }
+
+    public static int countedLinks() {
+        return countedLinks;
+    }
+
+    public static int perInstructionLinks() {
+        return perInstructionLinks;
+    }
+
+    public static int add(int a, int b) {
+        return a + b;
+    }
+
+    public static int sub(int a, int b) {
+        return a - b;
+    }
+
+    public static int answer() {
+        return 42;
+    }
+}
@@ -0,0 +1,138 @@
+// Lambdas and method references linked through LambdaMetafactory; see
+// test_method_handles.rs.  The functional interfaces are declared here
+// because java.util.function is not on the test boot class path.
+public class LambdaSamples {
+    interface IntOp {
+        int apply(int a, int b);
+    }
+
+    interface LongOp {
+        long apply(long a, long b);
+    }
+
+    public interface Counter {
+        int next();
+    }
+
+    interface Maker {
+        Point make(int x, int y);
+    }
+
+    interface Measure {
+        int measure(Point p);
+    }
+
+    interface Sink {
+        void accept(int value);
+    }
+
+    interface Shape {
+        int area();
+    }
+
+    static class Point implements Shape {
+        final int x;
+        final int y;
+
+        Point(int x, int y) {
+            this.x = x;
+            this.y = y;
+        }
+
+        public int area() {
+            return x * y;
+        }
+
+        int sum() {
+            return x + y;
+        }
+    }
+
+    static int sunk;
+    final int base;
+
+    LambdaSamples(int base) {
+        this.base = base;
+    }
+
+    static int minus(int a, int b) {
+        return a - b;
+    }
+
+    static int store(int value) {
+        sunk = value;
+        return value + 1;
+    }
+
+    int offset(int a, int b) {
+        return a + b + base;
+    }
+
+    public static int nonCapturing(int a, int b) {
+        IntOp op = (x, y) -> x * y;
+        return op.apply(a, b);
+    }
+
+    public static int capturing(int a, int b, int c) {
+        IntOp op = (x, y) -> x * y + c;
+        return op.apply(a, b);
+    }
+
+    public static long capturingWide(long big, long a, long b) {
+        LongOp op = (x, y) -> big * x + y;
+        return op.apply(a, b);
+    }
+
+    public static int capturingThis(int base, int a, int b) {
+        return new LambdaSamples(base).viaThis(a, b);
+    }
+
+    int viaThis(int a, int b) {
+        IntOp op = (x, y) -> x - y + base;
+        return op.apply(a, b);
+    }
+
+    public static int boundThis(int base, int a, int b) {
+        return new LambdaSamples(base).viaBoundReference(a, b);
+    }
+
+    int viaBoundReference(int a, int b) {
+        IntOp op = this::offset;
+        return op.apply(a, b);
+    }
+
+    public static int staticReference(int a, int b) {
+        IntOp op = LambdaSamples::minus;
+        return op.apply(a, b);
+    }
+
+    public static int virtualReference(int x, int y) {
+        Measure measure = Point::sum;
+        return measure.measure(new Point(x, y));
+    }
+
+    public static int interfaceReference(int x, int y) {
+        Measure measure = Shape::area;
+        return measure.measure(new Point(x, y));
+    }
+
+    public static int constructorReference(int x, int y) {
+        Maker maker = Point::new;
+        return maker.make(x, y).area();
+    }
+
+    public static int discardedResult(int value) {
+        Sink sink = LambdaSamples::store;
+        sink.accept(value);
+        return sunk;
+    }
+
+    public static int repeated(int times) {
+        int total = 0;
+        for (int i = 0; i < times; i++) {
+            Counter counter = () -> 7;
+            total += counter.next();
+        }
+        return total;
+    }
+}
@@ -0,0 +1,20 @@
+import java.util.function.IntBinaryOperator;
+import java.util.function.Supplier;
+
+public class Lambdas {
+    public static int plain(int a, int b) {
+        return a + b;
+    }
+
+    public static IntBinaryOperator lambda() {
+        return (a, b) -> a * b;
+    }
+
+    public static IntBinaryOperator methodRef() {
+        return Lambdas::plain;
+    }
+
+    public static Supplier<String> constructorRef() {
+        return String::new;
+    }
+}
@@ -0,0 +1,71 @@
+import java.lang.invoke.MethodHandle;
+
+// Signature polymorphic call sites for test_method_handles.rs.  The handles
+// are created by the tests and passed in.
+public class MethodHandleCalls {
+    static int counter;
+    int value;
+
+    MethodHandleCalls(int value) {
+        this.value = value;
+    }
+
+    public static int add(int a, int b) {
+        return a + b;
+    }
+
+    static long widen(long a, long b) {
+        return a * b;
+    }
+
+    static void bump(int by) {
+        counter += by;
+    }
+
+    int plus(int a) {
+        return value + a;
+    }
+
+    public static int exactInts(MethodHandle handle, int a, int b) throws Throwable {
+        return (int) handle.invokeExact(a, b);
+    }
+
+    public static long exactLongs(MethodHandle handle, long a, long b) throws Throwable {
+        return (long) handle.invokeExact(a, b);
+    }
+
+    public static int invokeInts(MethodHandle handle, int a, int b) throws Throwable {
+        return (int) handle.invoke(a, b);
+    }
+
+    public static int exactVoid(MethodHandle handle, int by) throws Throwable {
+        handle.invokeExact(by);
+        return counter;
+    }
+
+    public static int exactInstance(MethodHandle handle, MethodHandleCalls receiver, int a) throws Throwable {
+        return (int) handle.invokeExact(receiver, a);
+    }
+
+    public static int invokeObject(MethodHandle handle, Object receiver, int a) throws Throwable {
+        return (int) handle.invoke(receiver, a);
+    }
+
+    public static int exactGetter(MethodHandle handle, MethodHandleCalls receiver) throws Throwable {
+        return (int) handle.invokeExact(receiver);
+    }
+
+    public static int exactStaticGetter(MethodHandle handle) throws Throwable {
+        return (int) handle.invokeExact();
+    }
+
+    public static int exactConstructor(MethodHandle handle, int value) throws Throwable {
+        MethodHandleCalls created = (MethodHandleCalls) handle.invokeExact(value);
+        return created.value;
+    }
+
+    public static int exactSetter(MethodHandle handle, MethodHandleCalls receiver, int value) throws Throwable {
+        handle.invokeExact(receiver, value);
+        return receiver.value;
+    }
+}
@@ -0,0 +1,13 @@
+public class Properties {
+    public static String get(String key) {
+        return System.getProperty(key);
+    }
+
+    public static String getOrDefault(String key, String def) {
+        return System.getProperty(key, def);
+    }
+
+    public static int length(String key) {
+        return System.getProperty(key).length();
+    }
+}
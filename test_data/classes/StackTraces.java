@@ -0,0 +1,27 @@
+public class StackTraces {
+    public static int divide(int a, int b) {
+        return a / b;
+    }
+
+    public static int compute(int n) {
+        return divide(10, n) + 1;
+    }
+
+    public static int run(int n) {
+        return compute(n);
+    }
+
+    public static int multiLine(int a) {
+        int b = a + 1;
+        int c = b * 2;
+        return c - a;
+    }
+
+    public static Throwable create() {
+        return make();
+    }
+
+    static Throwable make() {
+        return new IllegalStateException();
+    }
+}
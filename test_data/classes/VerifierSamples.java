@@ -0,0 +1,86 @@
+// 校验器样例：javac 生成的 StackMapTable 覆盖的各种控制流。
+public class VerifierSamples {
+    private final int value;
+
+    // 先写字段再调用 super()：构造器中的 uninitializedThis
+    public VerifierSamples(int value) {
+        this.value = value > 0 ? value : -value;
+    }
+
+    public VerifierSamples() {
+        this(1);
+    }
+
+    // try/catch：异常处理器的帧
+    public static int parse(String text) {
+        try {
+            return text.length();
+        } catch (RuntimeException e) {
+            return -1;
+        }
+    }
+
+    // tableswitch
+    public static int dense(int x) {
+        switch (x) {
+            case 0: return 10;
+            case 1: return 11;
+            case 2: return 12;
+            default: return -1;
+        }
+    }
+
+    // lookupswitch
+    public static int sparse(int x) {
+        switch (x) {
+            case -100: return 1;
+            case 7: return 2;
+            case 100000: return 3;
+            default: return 0;
+        }
+    }
+
+    // long/double 局部变量跨分支
+    public static double wide(long a, double b) {
+        long total = 0;
+        for (long i = 0; i < a; i++) {
+            total += i;
+        }
+        double scaled = b;
+        if (total > 10) {
+            scaled *= 2;
+        }
+        return scaled + total;
+    }
+
+    // 分支合并出公共父类型
+    public static Object pick(boolean flag) {
+        Object result = flag ? "text" : new Object();
+        return result;
+    }
+
+    // 数组与 instanceof/checkcast
+    public static int arrays(Object o) {
+        int[][] grid = new int[2][3];
+        String[] names = { "a", "b" };
+        Object[] objects = names;
+        int n = grid[1].length + objects.length;
+        if (o instanceof String) {
+            n += ((String) o).length();
+        }
+        return n;
+    }
+
+    // 循环中 new 出来的对象
+    public static VerifierSamples make(int count) {
+        VerifierSamples last = null;
+        while (count-- > 0) {
+            last = new VerifierSamples(count);
+        }
+        return last;
+    }
+
+    public int value() {
+        return value;
+    }
+}
@@ -0,0 +1,25 @@
+import java.io.IOException;
+import java.util.List;
+
+// Compiled with -g -parameters; exercises the class, field and method
+// attributes the parser decodes beyond Code.
+@Deprecated
+public class AttrSamples<T extends Comparable<T>> {
+    public List<String> names;
+
+    public @interface Marker {
+        int value() default 7;
+    }
+
+    public class Inner {
+    }
+
+    public Object local() {
+        class Local {
+        }
+        return null;
+    }
+
+    public void throwing(int count, String label) throws IOException, InterruptedException {
+    }
+}
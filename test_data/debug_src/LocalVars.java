@@ -0,0 +1,17 @@
+// Compiled with -g so the class carries LocalVariableTable and
+// LocalVariableTypeTable.
+public class LocalVars {
+    public static int scopes(int a, long b) {
+        int sum = a;
+        for (int i = 0; i < 3; i++) {
+            sum += i;
+        }
+        int doubled = sum + sum;
+        return doubled + a;
+    }
+
+    public static <T> T first(T[] items, int index) {
+        T item = items[index];
+        return item;
+    }
+}
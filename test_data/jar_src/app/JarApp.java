@@ -0,0 +1,5 @@
+public class JarApp {
+    public static int plusBootOffset(int value) {
+        return value + JarBoot.offset();
+    }
+}
@@ -0,0 +1,5 @@
+public class JarBoot {
+    public static int offset() {
+        return 1000;
+    }
+}
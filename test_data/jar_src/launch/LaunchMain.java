@@ -0,0 +1,9 @@
+public class LaunchMain {
+    static int argCount = -1;
+    static int libValue;
+
+    public static void main(String[] args) {
+        argCount = args.length;
+        libValue = LaunchLib.value();
+    }
+}
@@ -0,0 +1,5 @@
+public class LaunchLib {
+    public static int value() {
+        return 4242;
+    }
+}
@@ -0,0 +1,41 @@
+// Compiled with --release 11, so javac relies on NestHost/NestMembers instead
+// of synthetic accessors for the private members below.
+public class Nestmates {
+    private int secret = 40;
+
+    private int bump() {
+        return secret + 1;
+    }
+
+    private static int twice(int x) {
+        return x * 2;
+    }
+
+    public static int peek() {
+        return Peer.peek(new Nestmates());
+    }
+
+    public static int fromHost() {
+        return Peer.hidden() + twice(3);
+    }
+
+    public static int fromOther() {
+        return Other.call();
+    }
+
+    static class Peer {
+        static int peek(Nestmates host) {
+            return host.secret + host.bump();
+        }
+
+        private static int hidden() {
+            return 1;
+        }
+    }
+
+    static class Other {
+        static int call() {
+            return twice(5) + Peer.hidden();
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// Private interface methods arrived with Java 9; javac calls the instance one
+// with invokeinterface once nestmates are available.
+public class PrivateInterfaceMethods {
+    interface Shape {
+        int side();
+
+        default int area() {
+            return square(side());
+        }
+
+        private int square(int x) {
+            return x * x;
+        }
+
+        static int scaled(int x) {
+            return twice(x) + 1;
+        }
+
+        private static int twice(int x) {
+            return x * 2;
+        }
+    }
+
+    static class Box implements Shape {
+        public int side() {
+            return 3;
+        }
+    }
+
+    public static int area() {
+        return new Box().area();
+    }
+
+    public static int scaled() {
+        return Shape.scaled(4);
+    }
+}
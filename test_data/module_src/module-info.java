@@ -0,0 +1,4 @@
+module sample {
+    requires java.base;
+    exports sample;
+}
@@ -0,0 +1,7 @@
+package sample;
+
+public class Api {
+    public static int version() {
+        return 11;
+    }
+}
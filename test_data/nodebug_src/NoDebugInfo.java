@@ -0,0 +1,6 @@
+// Compiled with -g:none: no SourceFile, LineNumberTable or LocalVariableTable.
+public class NoDebugInfo {
+    public static int divide(int a, int b) {
+        return a / b;
+    }
+}